@@ -1,7 +1,17 @@
 use deno_core::{JsRuntime, RuntimeOptions};
 use std::env;
 use std::fs::{read_to_string, write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+#[allow(dead_code)]
+#[path = "src/renderer/shaderconstants.rs"]
+mod shaderconstants;
+#[allow(dead_code)]
+#[path = "src/renderer/shaderpreprocessor.rs"]
+mod shaderpreprocessor;
+
+use shaderpreprocessor::{ShaderPreprocessor, ShaderTarget};
+
 pub struct TimerPermission;
 
 impl deno_web::TimersPermission for TimerPermission {
@@ -11,9 +21,57 @@ impl deno_web::TimersPermission for TimerPermission {
 
     fn check_unstable(&self, state: &deno_core::OpState, api_name: &'static str) {}
 }
+/// Generates the native and the web variant of every shader in `src/shaders` into `OUT_DIR/shaders`.
+/// The files of `src/shaders/include` are only included by the others.
+/// `OUT_DIR/shaders/native_sources.rs` embeds the native variants by file name for the shader hot reload.
+fn generate_shaders(out_dir: &Path) {
+    let shader_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/shaders");
+    // a directory is scanned for modifications including its subdirectories
+    println!("cargo:rerun-if-changed={}", shader_dir.display());
+    println!("cargo:rerun-if-changed=src/renderer/shaderconstants.rs");
+    println!("cargo:rerun-if-changed=src/renderer/shaderpreprocessor.rs");
+    let load = |file_name: &str| read_to_string(shader_dir.join(file_name)).ok();
+    let mut native_sources = String::from("static NATIVE_SOURCES: &[(&str, &str)] = &[\n");
+    for target in [ShaderTarget::Native, ShaderTarget::Web] {
+        let mut preprocessor = ShaderPreprocessor::new(target);
+        for (name, value) in shaderconstants::shader_constants(target == ShaderTarget::Web) {
+            preprocessor.define(name, value.as_str());
+        }
+        let target_dir = out_dir.join("shaders").join(target.directory());
+        std::fs::create_dir_all(&target_dir).unwrap();
+        for entry in std::fs::read_dir(&shader_dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path
+                .extension()
+                .map_or(true, |extension| extension != "wgsl")
+            {
+                continue;
+            }
+            let file_name = path.file_name().unwrap().to_str().unwrap();
+            let shader = preprocessor
+                .process(file_name, &load)
+                .unwrap_or_else(|e| panic!("failed to preprocess {}: {}", file_name, e));
+            write(target_dir.join(file_name), shader.source).unwrap();
+            if target == ShaderTarget::Native {
+                native_sources.push_str(
+                    format!(
+                        "    ({:?}, include_str!(concat!(env!(\"OUT_DIR\"), \"/shaders/native/{}\"))),\n",
+                        file_name, file_name
+                    )
+                    .as_str(),
+                );
+            }
+        }
+    }
+    native_sources.push_str("];\n");
+    write(out_dir.join("shaders").join("native_sources.rs"), native_sources).unwrap();
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 fn main() {
     let o = PathBuf::from(env::var_os("OUT_DIR").unwrap());
+    println!("cargo:rerun-if-changed=build.rs");
+    generate_shaders(&o);
     let snapshot_path = o.join("HORIZON_SNAPSHOT.bin");
     let options = RuntimeOptions {
         will_snapshot: true,
@@ -1,9 +1,16 @@
+pub mod animator;
 pub mod assetidentifier;
 pub mod collisionshape;
 pub mod componentparser;
 pub mod componenttypes;
+pub mod custommaterial;
 pub mod gltfmodel;
+pub mod hierarchy;
+pub mod materialoverride;
 pub mod modelcollider;
+pub mod modelnode;
+pub mod morphweights;
 pub mod physicshandle;
 pub mod scriptingcallback;
+pub mod skin;
 pub mod transform;
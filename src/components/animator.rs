@@ -0,0 +1,108 @@
+use std::collections::HashMap;
+
+use specs::*;
+
+/// Playback state of a single animation clip.
+#[derive(Clone, Debug)]
+pub struct AnimationPlayback {
+    /// Index into `HorizonModel::animations`.
+    pub animation: usize,
+    /// Playback position in seconds.
+    pub time: f32,
+    pub speed: f32,
+    pub looping: bool,
+    /// Set once a non-looping clip reaches its end, the last pose is kept.
+    pub finished: bool,
+}
+
+impl AnimationPlayback {
+    /// Clips played backwards start at their end.
+    pub fn new(animation: usize, duration: f32, looping: bool, speed: f32) -> Self {
+        Self {
+            animation,
+            time: if speed < 0.0 { duration } else { 0.0 },
+            speed,
+            looping,
+            finished: false,
+        }
+    }
+    /// Moves the playback position forward, returns true on the frame a non-looping clip finishes.
+    pub fn advance(&mut self, delta: f32, duration: f32) -> bool {
+        if self.finished {
+            return false;
+        }
+        self.time += delta * self.speed;
+        if self.looping {
+            if duration > 0.0 {
+                self.time = self.time.rem_euclid(duration);
+            }
+            return false;
+        }
+        // clips played backwards finish at their start.
+        let reached_end = (self.speed >= 0.0 && self.time >= duration)
+            || (self.speed < 0.0 && self.time <= 0.0);
+        self.time = self.time.clamp(0.0, duration);
+        self.finished = reached_end;
+        reached_end
+    }
+}
+
+/// Plays the animations of a model instance by writing the transforms of its spawned nodes.
+/// When a clip is started with a blend time the previous one keeps playing and is faded out.
+#[derive(Component, Clone, Debug)]
+#[storage(DenseVecStorage)]
+pub struct Animator {
+    /// The model entity the animations belong to.
+    pub model: Entity,
+    /// Spawned node entities by glTF node index.
+    pub nodes: HashMap<usize, Entity>,
+    pub current: Option<AnimationPlayback>,
+    /// The clip being blended out.
+    pub previous: Option<AnimationPlayback>,
+    /// Seconds elapsed since the blend started.
+    pub blend_time: f32,
+    pub blend_duration: f32,
+}
+
+impl Animator {
+    pub fn new(model: Entity, nodes: HashMap<usize, Entity>) -> Self {
+        Self {
+            model,
+            nodes,
+            current: None,
+            previous: None,
+            blend_time: 0.0,
+            blend_duration: 0.0,
+        }
+    }
+    /// Starts playing the clip, blending from the current one over `blend_duration` seconds.
+    pub fn play(
+        &mut self,
+        animation: usize,
+        duration: f32,
+        looping: bool,
+        speed: f32,
+        blend_duration: f32,
+    ) {
+        self.previous = if blend_duration > 0.0 {
+            self.current.take()
+        } else {
+            None
+        };
+        self.current = Some(AnimationPlayback::new(animation, duration, looping, speed));
+        self.blend_time = 0.0;
+        self.blend_duration = blend_duration;
+    }
+    pub fn stop(&mut self) {
+        self.current = None;
+        self.previous = None;
+    }
+    /// Weight of the current clip, 1 once blending is over.
+    pub fn blend_weight(&self) -> f32 {
+        if self.previous.is_none() || self.blend_duration <= 0.0 {
+            1.0
+        } else {
+            (self.blend_time / self.blend_duration).min(1.0)
+        }
+    }
+}
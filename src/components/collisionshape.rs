@@ -1,3 +1,4 @@
+use crate::scripting::util::entityinfo::ColliderInfo;
 use rapier3d::prelude::ColliderHandle;
 use specs::*;
 
@@ -5,4 +6,5 @@ use specs::*;
 #[storage(VecStorage)]
 pub struct CollisionShape {
     pub collider: ColliderHandle,
+    pub shape_info: ColliderInfo,
 }
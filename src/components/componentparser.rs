@@ -1,12 +1,18 @@
 use crate::components::collisionshape::CollisionShape;
-use crate::components::physicshandle::PhysicsHandle;
+use crate::components::gltfmodel::RawModel;
+use crate::components::hierarchy::Parent;
+use crate::components::materialoverride::MaterialOverride;
+use crate::components::modelnode::ModelNode;
+use crate::components::physicshandle::{PhysicsBodySettings, PhysicsHandle};
 use crate::components::transform::Transform;
 use crate::renderer::model::HorizonModel;
 use crate::renderer::primitives::lights::pointlight::PointLight;
+use crate::renderer::primitives::lights::spotlight::SpotLight;
+use crate::renderer::primitives::material::MaterialTextureSlot;
 use crate::renderer::primitives::mesh::{VertexAttribValues, VertexAttributeType};
 use crate::scripting::util::entityinfo::Component;
-use crate::systems::physics::PhysicsWorld;
-use rapier3d::na::{Isometry3, Matrix4, Point3, Quaternion, UnitQuaternion, Vector3};
+use crate::systems::physics::{Physics, PhysicsWorld};
+use rapier3d::na::{Matrix4, Point3, Quaternion, UnitQuaternion, Vector3};
 use rapier3d::parry::transformation::vhacd::VHACDParameters;
 use rapier3d::prelude::*;
 use specs::{Builder, Entity, EntityBuilder, Join, World, WorldExt};
@@ -37,7 +43,11 @@ impl Default for ComponentParser {
             next: Box::new(TransformComponentParser {
                 next: Some(Box::new(PhysicsComponentParser {
                     next: Some(Box::new(PointLightComponentParser {
-                        next: Some(Box::new(CollisionShapeParser { next: None })),
+                        next: Some(Box::new(SpotLightComponentParser {
+                            next: Some(Box::new(CollisionShapeParser {
+                                next: Some(Box::new(MaterialComponentParser { next: None })),
+                            })),
+                        })),
                     })),
                 })),
             }),
@@ -84,8 +94,26 @@ impl ParseComponent for TransformComponentParser {
                     .into(),
                 model_id,
             );
-            let mut transform_storage = world.write_storage::<Transform>();
-            transform_storage.insert(entity, transform_val).unwrap();
+            let previous_model = {
+                let mut transform_storage = world.write_storage::<Transform>();
+                transform_storage
+                    .insert(entity, transform_val)
+                    .unwrap()
+                    .and_then(|previous| previous.model)
+            };
+            if let Some(parent) = component_data.parent {
+                world
+                    .write_storage::<Parent>()
+                    .insert(entity, Parent(world.entities().entity(parent)))
+                    .unwrap();
+            }
+            // the model's nodes are drawn instead of the entity itself, keep them if the model didn't change.
+            if previous_model != model_id {
+                ModelNode::despawn_nodes(world, entity);
+                if let Some(model_ent) = model_id {
+                    ModelNode::spawn_nodes(world, entity, model_ent);
+                }
+            }
 
             Ok(())
         } else if let Some(ref next) = self.next {
@@ -118,6 +146,14 @@ impl ParseComponent for PhysicsComponentParser {
                     .get(world.entities().entity(model))
                     .ok_or(ComponentParserError::InvalidData("model"))?;
 
+                let body_settings = PhysicsBodySettings {
+                    body_type: component_data
+                        .body_type
+                        .clone()
+                        .ok_or(ComponentParserError::InvalidData("bodyType"))?,
+                    mass,
+                    damping: component_data.damping.clone().unwrap_or_default(),
+                };
                 let mut rigid_body_handle: Option<RigidBodyHandle> = None;
                 let mut collider_handles: Vec<ColliderHandle> = Vec::new();
 
@@ -125,17 +161,16 @@ impl ParseComponent for PhysicsComponentParser {
                 let transform = transform_storage
                     .get(entity)
                     .ok_or(ComponentParserError::MissingDependantComponent("Transform"))?;
+                // bodies are simulated in world space, the transform of a child is relative to its parent.
+                let parent_matrix = world
+                    .read_storage::<Parent>()
+                    .get(entity)
+                    .and_then(|parent| transform_storage.get(parent.0))
+                    .map(|parent| parent.world_matrix);
                 match component_data.body_type {
                     Some(crate::scripting::util::RigidBodyType::Dynamic) => {
                         let mut rigid_body_builder = RigidBodyBuilder::new_dynamic()
-                            .position(Isometry3::new(
-                                Vector3::new(
-                                    transform.position.x,
-                                    transform.position.y,
-                                    transform.position.z,
-                                ),
-                                transform.rotation.scaled_axis(),
-                            ))
+                            .position(Physics::body_isometry(transform, parent_matrix))
                             .user_data(entity.id() as u128)
                             .additional_mass(mass as f32);
                         if let Some(damping_values) = component_data.damping {
@@ -156,8 +191,8 @@ impl ParseComponent for PhysicsComponentParser {
                         let rigid_body = rigid_body_builder.build();
                         let body_handle = physics_world.add_rigid_body(rigid_body);
                         let mut convex_decs = Vec::new();
-                        for mesh in &model.meshes {
-                            for primitive in &mesh.primitives {
+                        for (mesh_index, mesh_transform) in model.mesh_transforms() {
+                            for primitive in &model.meshes[mesh_index].primitives {
                                 if let Some(VertexAttribValues::Float32x3 { 0: values }) =
                                     primitive.mesh.attribute(VertexAttributeType::Position)
                                 {
@@ -169,7 +204,9 @@ impl ParseComponent for PhysicsComponentParser {
                                         .iter()
                                         .map(|v| {
                                             let vertex = values[*v as usize];
-                                            Point3::new(vertex[0], vertex[1], vertex[2])
+                                            mesh_transform.transform_point(&Point3::new(
+                                                vertex[0], vertex[1], vertex[2],
+                                            ))
                                         })
                                         .collect::<Vec<_>>();
                                     if let Some(builder) = ColliderBuilder::convex_hull(&vertices) {
@@ -195,14 +232,7 @@ impl ParseComponent for PhysicsComponentParser {
                     // use tri-mesh for static rigid Bodies.
                     Some(crate::scripting::util::RigidBodyType::Static) => {
                         let rigid_body = RigidBodyBuilder::new_kinematic_velocity_based()
-                            .position(Isometry3::new(
-                                Vector3::new(
-                                    transform.position.x,
-                                    transform.position.y,
-                                    transform.position.z,
-                                ),
-                                transform.rotation.scaled_axis(),
-                            ))
+                            .position(Physics::body_isometry(transform, parent_matrix))
                             .user_data(entity.id() as u128)
                             .additional_mass(mass as f32)
                             .build();
@@ -210,8 +240,8 @@ impl ParseComponent for PhysicsComponentParser {
 
                         let mut triangles_meshes = Vec::new();
 
-                        for mesh in &model.meshes {
-                            for primitive in &mesh.primitives {
+                        for (mesh_index, mesh_transform) in model.mesh_transforms() {
+                            for primitive in &model.meshes[mesh_index].primitives {
                                 if let Some(VertexAttribValues::Float32x3 { 0: values }) =
                                     primitive.mesh.attribute(VertexAttributeType::Position)
                                 {
@@ -223,7 +253,9 @@ impl ParseComponent for PhysicsComponentParser {
                                         .iter()
                                         .map(|v| {
                                             let vertex = values[*v as usize];
-                                            Point3::new(vertex[0], vertex[1], vertex[2])
+                                            mesh_transform.transform_point(&Point3::new(
+                                                vertex[0], vertex[1], vertex[2],
+                                            ))
                                         })
                                         .collect::<Vec<_>>();
                                     if let Some(collider_builder) =
@@ -260,6 +292,7 @@ impl ParseComponent for PhysicsComponentParser {
                             PhysicsHandle {
                                 collider_handles,
                                 rigid_body_handle: body_handle,
+                                body_settings,
                             },
                         )
                         .unwrap();
@@ -296,19 +329,75 @@ impl ParseComponent for PointLightComponentParser {
             let color = component_data.color.unwrap();
             let mut point_light_storage = world.write_component::<PointLight>();
 
-            let attached_entity = component_data
-                .attached_to
-                .map(|val| world.entities().entity(val));
             point_light_storage
                 .insert(
                     entity,
                     PointLight::new(
                         Vector3::new(color.x as f32, color.y as f32, color.z as f32),
                         radius,
-                        attached_entity,
+                        component_data.casts_shadow.unwrap_or(false),
+                    ),
+                )
+                .unwrap();
+            // attached lights are children of the entity, their transform becomes an offset from it.
+            if let Some(attached_to) = component_data.attached_to {
+                world
+                    .write_storage::<Parent>()
+                    .insert(entity, Parent(world.entities().entity(attached_to)))
+                    .unwrap();
+            }
+            Ok(())
+        } else if let Some(ref next) = self.next {
+            next.parse(component_data, entity, world)
+        } else {
+            Err(ComponentParserError::NotFound(
+                component_data.component_type,
+            ))
+        }
+    }
+}
+pub struct SpotLightComponentParser {
+    next: Option<Box<dyn ParseComponent>>,
+}
+impl ParseComponent for SpotLightComponentParser {
+    fn parse(
+        &self,
+        component_data: Component,
+        entity: Entity,
+        world: &World,
+    ) -> Result<(), ComponentParserError> {
+        if component_data.component_type == "spotLight" {
+            let radius = component_data
+                .radius
+                .ok_or(ComponentParserError::InvalidData("radius"))?;
+            let color = component_data
+                .color
+                .ok_or(ComponentParserError::InvalidData("color"))?;
+            let outer_cone_angle = component_data
+                .outer_cone_angle
+                .ok_or(ComponentParserError::InvalidData("outerConeAngle"))?;
+            // without an inner cone the light fades out over the whole cone.
+            let inner_cone_angle = component_data.inner_cone_angle.unwrap_or(0.0);
+            world
+                .write_component::<SpotLight>()
+                .insert(
+                    entity,
+                    SpotLight::from_cone_angles(
+                        color.into(),
+                        radius,
+                        inner_cone_angle,
+                        outer_cone_angle,
+                        component_data.casts_shadow.unwrap_or(false),
                     ),
                 )
                 .unwrap();
+            // the light's position and direction come from the transform, attaching makes it relative to the entity.
+            if let Some(attached_to) = component_data.attached_to {
+                world
+                    .write_storage::<Parent>()
+                    .insert(entity, Parent(world.entities().entity(attached_to)))
+                    .unwrap();
+            }
             Ok(())
         } else if let Some(ref next) = self.next {
             next.parse(component_data, entity, world)
@@ -335,18 +424,25 @@ impl ParseComponent for CollisionShapeParser {
             } else {
                 return Err(ComponentParserError::MissingDependantComponent("Transform"));
             };
+            let parent_matrix = world
+                .read_storage::<Parent>()
+                .get(entity)
+                .and_then(|parent| world.read_storage::<Transform>().get(parent.0).copied())
+                .map(|parent| parent.world_matrix);
             if let Some(collider_info) = component_data.collision_shape {
                 let shape = match collider_info
                     .collision_shape_type
+                    .clone()
                     .unwrap_or_default()
                     .as_str()
                 {
                     "Cuboid" => {
-                        let half_extents = if let Some(extents) = collider_info.half_extents {
-                            extents
-                        } else {
-                            return Err(ComponentParserError::InvalidData("halfExtents"));
-                        };
+                        let half_extents =
+                            if let Some(extents) = collider_info.half_extents.clone() {
+                                extents
+                            } else {
+                                return Err(ComponentParserError::InvalidData("halfExtents"));
+                            };
                         SharedShape::new(Cuboid::new(half_extents.into()))
                     }
                     "Ball" => {
@@ -366,6 +462,7 @@ impl ParseComponent for CollisionShapeParser {
                             };
                             let shape = match collider_info
                                 .capsule_type
+                                .clone()
                                 .unwrap_or_default()
                                 .as_str()
                             {
@@ -383,10 +480,7 @@ impl ParseComponent for CollisionShapeParser {
                 };
 
                 let collision = ColliderBuilder::new(shape)
-                    .position(Isometry3::new(
-                        transform.position,
-                        transform.rotation.scaled_axis(),
-                    ))
+                    .position(Physics::body_isometry(&transform, parent_matrix))
                     .active_events(ActiveEvents::CONTACT_EVENTS | ActiveEvents::INTERSECTION_EVENTS)
                     .user_data(entity.id() as u128)
                     .sensor(true)
@@ -400,6 +494,7 @@ impl ParseComponent for CollisionShapeParser {
                         entity,
                         CollisionShape {
                             collider: collider_handle,
+                            shape_info: collider_info,
                         },
                     )
                     .unwrap();
@@ -416,3 +511,133 @@ impl ParseComponent for CollisionShapeParser {
         }
     }
 }
+/// Changes the glTF materials drawn for the entity.
+/// Without a material index the values override every material for this entity only,
+/// with one that material of the model is changed for every entity drawing it.
+pub struct MaterialComponentParser {
+    next: Option<Box<dyn ParseComponent>>,
+}
+impl ParseComponent for MaterialComponentParser {
+    fn parse(
+        &self,
+        component_data: Component,
+        entity: Entity,
+        world: &World,
+    ) -> Result<(), ComponentParserError> {
+        if component_data.component_type.as_str() == "material" {
+            let material_info = component_data
+                .material
+                .ok_or(ComponentParserError::InvalidData("material"))?;
+            let model_entity = RawModel::model_entity(world, entity)
+                .ok_or(ComponentParserError::MissingDependantComponent("RawModel"))?;
+            let mut horizon_models = world.write_storage::<HorizonModel>();
+            let mut raw_models = world.write_storage::<RawModel>();
+            let (horizon_model, raw_model) = match (
+                horizon_models.get_mut(model_entity),
+                raw_models.get_mut(model_entity),
+            ) {
+                (Some(horizon_model), Some(raw_model)) => (horizon_model, raw_model),
+                _ => return Err(ComponentParserError::MissingDependantComponent("RawModel")),
+            };
+            let textures = [
+                (
+                    MaterialTextureSlot::BaseColor,
+                    material_info.base_color_texture,
+                ),
+                (
+                    MaterialTextureSlot::MetallicRoughness,
+                    material_info.metallic_roughness_texture,
+                ),
+                (MaterialTextureSlot::Normal, material_info.normal_texture),
+                (
+                    MaterialTextureSlot::Occlusion,
+                    material_info.occlusion_texture,
+                ),
+                (
+                    MaterialTextureSlot::Emissive,
+                    material_info.emissive_texture,
+                ),
+            ];
+            if textures.iter().any(|(_, texture)| {
+                matches!(texture, Some(index) if !horizon_model.textures.contains_key(index))
+            }) {
+                return Err(ComponentParserError::InvalidData("texture"));
+            }
+            let index = match material_info.material_index {
+                Some(index) if horizon_model.materials.contains_key(&index) => index,
+                Some(_) => return Err(ComponentParserError::InvalidData("materialIndex")),
+                None => {
+                    let mut material_overrides = world.write_storage::<MaterialOverride>();
+                    if !material_overrides.contains(entity) {
+                        material_overrides
+                            .insert(entity, MaterialOverride::new(model_entity))
+                            .map_err(|_| ComponentParserError::InvalidData("material"))?;
+                    }
+                    let material_override = material_overrides.get_mut(entity).unwrap();
+                    if let Some(base_color) = &material_info.base_color {
+                        material_override.base_color = Some([
+                            base_color.x(),
+                            base_color.y(),
+                            base_color.z(),
+                            base_color.w(),
+                        ]);
+                    }
+                    if let Some(emissive) = &material_info.emissive {
+                        material_override.emissive_color =
+                            Some([emissive.x, emissive.y, emissive.z]);
+                    }
+                    if material_info.roughness.is_some() {
+                        material_override.roughness = material_info.roughness;
+                    }
+                    if material_info.metallic.is_some() {
+                        material_override.metallic = material_info.metallic;
+                    }
+                    for (slot, texture) in textures.iter() {
+                        if let Some(texture) = texture {
+                            material_override.textures.insert(*slot, *texture);
+                            material_override.textures_dirty = true;
+                        }
+                    }
+                    material_override.uniform_dirty = true;
+                    return Ok(());
+                }
+            };
+            let material = horizon_model.materials.get_mut(&index).unwrap();
+            if let Some(base_color) = &material_info.base_color {
+                material.base_color = [
+                    base_color.x(),
+                    base_color.y(),
+                    base_color.z(),
+                    base_color.w(),
+                ];
+            }
+            if let Some(emissive) = &material_info.emissive {
+                material.emissive_color = [emissive.x, emissive.y, emissive.z];
+            }
+            if let Some(roughness) = material_info.roughness {
+                material.pbr_roughness = roughness;
+            }
+            if let Some(metallic) = material_info.metallic {
+                material.metallic_factor = metallic;
+            }
+            let mut textures_changed = false;
+            for (slot, texture) in textures.iter() {
+                if texture.is_some() {
+                    *material.texture_mut(*slot) = *texture;
+                    textures_changed = true;
+                }
+            }
+            if let Some(raw_material) = raw_model.materials.get_mut(&index) {
+                raw_material.uniform_dirty = true;
+                raw_material.textures_dirty |= textures_changed;
+            }
+            Ok(())
+        } else if let Some(ref next) = self.next {
+            next.parse(component_data, entity, world)
+        } else {
+            Err(ComponentParserError::NotFound(
+                component_data.component_type,
+            ))
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use crate::components::physicshandle::PhysicsValues;
 use crate::scripting::util::componentconversions::{
-    CollisionShapeComponent, PointLightComponent, TransformComponent,
+    CollisionShapeComponent, MaterialComponent, PointLightComponent, SpotLightComponent,
+    TransformComponent,
 };
 use serde::{Deserialize, Serialize};
 #[cfg(target_arch = "wasm32")]
@@ -14,6 +15,8 @@ pub enum ComponentTypes {
     Transform,
     PointLight,
     CollisionShape,
+    SpotLight,
+    Material,
     None,
 }
 impl From<u32> for ComponentTypes {
@@ -24,6 +27,8 @@ impl From<u32> for ComponentTypes {
             2 => ComponentTypes::Transform,
             3 => ComponentTypes::PointLight,
             4 => ComponentTypes::CollisionShape,
+            5 => ComponentTypes::SpotLight,
+            6 => ComponentTypes::Material,
             _ => ComponentTypes::None,
         }
     }
@@ -36,4 +41,7 @@ pub enum ComponentData {
     AssetIdentifier(String),
     PointLight(PointLightComponent),
     CollisionShape(CollisionShapeComponent),
+    SpotLight(SpotLightComponent),
+    /// The glTF materials of the entity's model, ordered by their index.
+    Material(Vec<MaterialComponent>),
 }
@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+
+use specs::*;
+
+use crate::components::modelnode::ModelNode;
+
+/// Draws the opaque primitives with a material of the `MaterialLibrary` instead of their glTF materials.
+/// On a model node it applies to the node, on a model instance to all of its nodes
+/// and on the model entity to every instance without a material of its own.
+#[derive(Component)]
+#[storage(DenseVecStorage)]
+pub struct CustomMaterial {
+    pub name: String,
+    /// Parameters set for this entity, the others use the material's defaults.
+    pub parameters: HashMap<String, Vec<f32>>,
+    /// Created by `UpdateMaterials`, `None` while the material isn't built.
+    pub bind_group: Option<wgpu::BindGroup>,
+    pub buffer: Option<wgpu::Buffer>,
+    /// Revision of the material pipeline the bind group was created for.
+    pub pipeline_revision: Option<u32>,
+    /// Whether the parameters changed since they were written to the buffer.
+    pub dirty: bool,
+}
+
+impl CustomMaterial {
+    pub fn new(name: &str, parameters: HashMap<String, Vec<f32>>) -> Self {
+        Self {
+            name: name.to_string(),
+            parameters,
+            bind_group: None,
+            buffer: None,
+            pipeline_revision: None,
+            dirty: true,
+        }
+    }
+    pub fn set_parameter(&mut self, name: &str, value: Vec<f32>) {
+        self.parameters.insert(name.to_string(), value);
+        self.dirty = true;
+    }
+    /// The entity whose material the model node is drawn with: the node, its instance or the model, `None` for the glTF materials.
+    pub fn material_entity(
+        node_ent: Entity,
+        model_ent: Entity,
+        model_nodes: &ReadStorage<ModelNode>,
+        custom_materials: &ReadStorage<CustomMaterial>,
+    ) -> Option<Entity> {
+        let instance = model_nodes.get(node_ent).map(|node| node.instance);
+        [Some(node_ent), instance, Some(model_ent)]
+            .into_iter()
+            .flatten()
+            .find(|entity| custom_materials.contains(*entity))
+    }
+}
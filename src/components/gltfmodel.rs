@@ -1,38 +1,381 @@
 use std::collections::HashMap;
-use std::ops::Range;
-use crate::renderer::primitives::material::GltfMaterial;
-use crate::renderer::primitives::mesh::GltfMesh;
+use std::ops::{Add, Mul, Range};
+use crate::renderer::primitives::material::{GltfMaterial, TextureKey};
+use crate::renderer::primitives::mesh::{BoundingSphere, GltfMesh};
+use crate::renderer::primitives::texture::Texture;
 use specs::*;
 use wgpu::BindGroup;
 use crate::BindGroupContainer;
+use crate::components::materialoverride::MaterialOverride;
+use crate::components::modelnode::ModelNode;
+use crate::components::transform::{Transform, TransformRaw};
+use rapier3d::na::{Matrix4, Quaternion, UnitQuaternion, Vector3};
+use gltf::animation::Interpolation;
+use crate::components::skin::Skin;
+use crate::components::morphweights::MorphWeights;
+use crate::renderer::primitives::uniforms::InstanceDeformation;
+use crate::resources::frustumculling::DrawIndexedIndirectRaw;
 
 #[derive(Debug)]
 pub struct GltfModel {
     pub primitives: Vec<GltfPrimitive>,
+    /// Default morph target weights of the mesh.
+    pub weights: Vec<f32>,
+}
+impl GltfModel {
+    /// Number of morph targets, every primitive of a mesh has the same amount.
+    pub fn morph_target_count(&self) -> usize {
+        self.primitives
+            .iter()
+            .map(|primitive| primitive.mesh.morph_targets.len())
+            .max()
+            .unwrap_or(0)
+    }
 }
 #[derive(Debug)]
 pub struct GltfPrimitive {
     pub material: Option<usize>,
     pub mesh: GltfMesh,
 }
+/// A node of the glTF scene graph with its local transform, `children` are indices into `HorizonModel::nodes`.
+#[derive(Debug, Clone)]
+pub struct GltfNode {
+    pub name: String,
+    pub translation: Vector3<f32>,
+    pub rotation: UnitQuaternion<f32>,
+    pub scale: Vector3<f32>,
+    pub mesh: Option<usize>,
+    /// Index into `HorizonModel::skins` for skinned meshes.
+    pub skin: Option<usize>,
+    /// Morph target weights overriding the mesh's defaults.
+    pub weights: Option<Vec<f32>>,
+    pub children: Vec<usize>,
+}
+/// Joints of a skin, `joints` are node indices and each has a matching inverse bind matrix.
+#[derive(Debug, Clone)]
+pub struct GltfSkin {
+    pub name: String,
+    pub joints: Vec<usize>,
+    pub inverse_bind_matrices: Vec<Matrix4<f32>>,
+}
+#[derive(Debug, Clone)]
+pub struct GltfAnimation {
+    pub name: String,
+    pub channels: Vec<GltfChannel>,
+    /// Time of the last keyframe in seconds.
+    pub duration: f32,
+}
+/// Keyframes targeting a single property of a node.
+/// Cubic spline channels store an in-tangent, the value and an out-tangent for every keyframe.
+#[derive(Debug, Clone)]
+pub struct GltfChannel {
+    pub node: usize,
+    pub interpolation: Interpolation,
+    pub times: Vec<f32>,
+    pub values: GltfChannelValues,
+}
+#[derive(Debug, Clone)]
+pub enum GltfChannelValues {
+    Translations(Vec<Vector3<f32>>),
+    Rotations(Vec<Quaternion<f32>>),
+    Scales(Vec<Vector3<f32>>),
+    /// Morph target weights, every keyframe holds a weight for each target of the mesh.
+    Weights(Vec<f32>),
+}
+#[derive(Debug, Clone)]
+pub enum ChannelSample {
+    Translation(Vector3<f32>),
+    Rotation(UnitQuaternion<f32>),
+    Scale(Vector3<f32>),
+    Weights(Vec<f32>),
+}
+impl ChannelSample {
+    /// Interpolates towards a sample of the same property, `to` is returned for a different property.
+    pub fn lerp(&self, to: &ChannelSample, factor: f32) -> ChannelSample {
+        match (self, to) {
+            (ChannelSample::Translation(from), ChannelSample::Translation(to)) => {
+                ChannelSample::Translation(from.lerp(to, factor))
+            }
+            (ChannelSample::Rotation(from), ChannelSample::Rotation(to)) => {
+                ChannelSample::Rotation(
+                    from.try_slerp(to, factor, 1.0e-6)
+                        .unwrap_or_else(|| from.nlerp(to, factor)),
+                )
+            }
+            (ChannelSample::Scale(from), ChannelSample::Scale(to)) => {
+                ChannelSample::Scale(from.lerp(to, factor))
+            }
+            (ChannelSample::Weights(from), ChannelSample::Weights(to)) => ChannelSample::Weights(
+                from.iter()
+                    .zip(to)
+                    .map(|(from, to)| from + (to - from) * factor)
+                    .collect(),
+            ),
+            _ => to.clone(),
+        }
+    }
+}
+impl GltfChannel {
+    /// Samples the channel at the given time, times outside of the keyframes are clamped.
+    pub fn sample(&self, time: f32) -> Option<ChannelSample> {
+        let last = self.times.len().checked_sub(1)?;
+        let (from, to, factor) = if time <= self.times[0] {
+            (0, 0, 0.0)
+        } else if time >= self.times[last] {
+            (last, last, 0.0)
+        } else {
+            let to = self.times.partition_point(|keyframe| *keyframe <= time);
+            let from = to - 1;
+            let factor = (time - self.times[from]) / (self.times[to] - self.times[from]);
+            (from, to, factor)
+        };
+        let sample = match &self.values {
+            GltfChannelValues::Translations(values) => {
+                ChannelSample::Translation(self.interpolate(values, from, to, factor)?)
+            }
+            GltfChannelValues::Rotations(values) => {
+                let rotation = match self.interpolation {
+                    Interpolation::Linear => {
+                        let from = UnitQuaternion::from_quaternion(*values.get(from)?);
+                        let to = UnitQuaternion::from_quaternion(*values.get(to)?);
+                        from.try_slerp(&to, factor, 1.0e-6)
+                            .unwrap_or_else(|| from.nlerp(&to, factor))
+                    }
+                    _ => UnitQuaternion::from_quaternion(
+                        self.interpolate(values, from, to, factor)?,
+                    ),
+                };
+                ChannelSample::Rotation(rotation)
+            }
+            GltfChannelValues::Scales(values) => {
+                ChannelSample::Scale(self.interpolate(values, from, to, factor)?)
+            }
+            GltfChannelValues::Weights(values) => {
+                let values_per_target = match self.interpolation {
+                    Interpolation::CubicSpline => self.times.len() * 3,
+                    _ => self.times.len(),
+                };
+                let targets = values.len() / values_per_target;
+                let weights = (0..targets)
+                    .map(|target| {
+                        let target_values = values
+                            .iter()
+                            .skip(target)
+                            .step_by(targets)
+                            .copied()
+                            .collect::<Vec<_>>();
+                        self.interpolate(&target_values, from, to, factor)
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                ChannelSample::Weights(weights)
+            }
+        };
+        Some(sample)
+    }
+    /// Interpolates component-wise between two keyframes.
+    fn interpolate<T>(&self, values: &[T], from: usize, to: usize, factor: f32) -> Option<T>
+    where
+        T: Copy + Add<Output = T> + Mul<f32, Output = T>,
+    {
+        match self.interpolation {
+            Interpolation::Step => values.get(from).copied(),
+            Interpolation::Linear => {
+                Some(*values.get(from)? * (1.0 - factor) + *values.get(to)? * factor)
+            }
+            Interpolation::CubicSpline => {
+                // keyframes are stored as in-tangent, value and out-tangent.
+                let value_from = *values.get(from * 3 + 1)?;
+                if from == to {
+                    return Some(value_from);
+                }
+                let out_tangent = *values.get(from * 3 + 2)?;
+                let in_tangent = *values.get(to * 3)?;
+                let value_to = *values.get(to * 3 + 1)?;
+                let delta = self.times[to] - self.times[from];
+                let t = factor;
+                let t2 = t * t;
+                let t3 = t2 * t;
+                Some(
+                    value_from * (2.0 * t3 - 3.0 * t2 + 1.0)
+                        + out_tangent * (delta * (t3 - 2.0 * t2 + t))
+                        + value_to * (-2.0 * t3 + 3.0 * t2)
+                        + in_tangent * (delta * (t3 - t2)),
+                )
+            }
+        }
+    }
+}
 
 pub struct RawMesh {
     pub vertex_buffer: wgpu::Buffer,
     pub index_buffer: wgpu::Buffer,
     pub material_index: usize,
+    /// Index of the glTF mesh this primitive belongs to.
+    pub mesh_index: usize,
     pub name:String,
-    pub index_buffer_len: u32,
+    /// The base indices followed by the lower levels of detail, all of them are in the index buffer.
+    pub lods: Vec<RawMeshLod>,
+    /// Bounds of the primitive in the mesh's local space.
+    pub bounds: Option<BoundingSphere>,
+}
+impl RawMesh {
+    /// The given level of detail, primitives with fewer levels use their last one.
+    pub fn lod(&self, level: usize) -> &RawMeshLod {
+        &self.lods[level.min(self.lods.len() - 1)]
+    }
+}
+/// Range of a level of detail in the primitive's index buffer.
+#[derive(Debug, Clone, Copy)]
+pub struct RawMeshLod {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// Screen size below which the level replaces the previous one, unused by the base level.
+    pub screen_size: f32,
 }
 
 pub struct RawMaterial
 {
     pub bind_group_container:BindGroupContainer,
+    pub uniform_buffer: wgpu::Buffer,
+    /// Blended and transmissive materials are skipped by the opaque passes and sorted by the transparent pass.
+    pub transparent: bool,
+    /// Set when scripts change the values of the material, `UpdateMaterials` writes the uniform buffer then.
+    pub uniform_dirty: bool,
+    /// Set when scripts change the textures of the material, `UpdateMaterials` creates the bind group again.
+    pub textures_dirty: bool,
+}
+impl RawMaterial {
+    pub fn new(
+        bind_group_container: BindGroupContainer,
+        uniform_buffer: wgpu::Buffer,
+        transparent: bool,
+    ) -> Self {
+        Self {
+            bind_group_container,
+            uniform_buffer,
+            transparent,
+            uniform_dirty: false,
+            textures_dirty: false,
+        }
+    }
 }
 #[derive(Component)]
 #[storage(VecStorage)]
 pub struct RawModel {
     pub meshes: Vec<RawMesh>,
     pub materials: HashMap<usize,RawMaterial>,
+    /// The textures of the materials by the index of their image in the `HorizonModel` and their color space.
+    pub textures: HashMap<TextureKey, Texture>,
+}
+impl RawModel {
+    /// The entity holding the model drawn for the entity, the entity itself or the model of an instance.
+    pub fn model_entity(world: &World, entity: Entity) -> Option<Entity> {
+        if world.read_storage::<RawModel>().contains(entity) {
+            return Some(entity);
+        }
+        let transforms = world.read_storage::<Transform>();
+        if let Some(model) = transforms.get(entity).and_then(|transform| transform.model) {
+            return Some(model);
+        }
+        let model_nodes = world.read_storage::<ModelNode>();
+        (&model_nodes, &transforms)
+            .join()
+            .find_map(|(node, transform)| transform.model.filter(|_| node.instance == entity))
+    }
+    pub fn mesh_count(&self) -> usize {
+        self.meshes.iter().map(|mesh| mesh.mesh_index + 1).max().unwrap_or(0)
+    }
+    /// Bounds of every primitive of the given mesh, `None` if one of them has no bounds.
+    pub fn mesh_bounds(&self, mesh_index: usize) -> Option<BoundingSphere> {
+        self.mesh_primitives(mesh_index)
+            .map(|mesh| mesh.bounds)
+            .reduce(|bounds, other| Some(bounds?.merge(&other?)))
+            .flatten()
+    }
+    /// Levels of detail of the given mesh, the most any of its primitives has.
+    pub fn mesh_lod_count(&self, mesh_index: usize) -> usize {
+        self.mesh_primitives(mesh_index)
+            .map(|mesh| mesh.lods.len())
+            .max()
+            .unwrap_or(1)
+    }
+    /// Screen sizes below which each lower level of detail of the mesh is used, unused levels are never reached.
+    pub fn mesh_lod_screen_sizes(&self, mesh_index: usize) -> [f32; GltfMesh::MAX_LOD_COUNT] {
+        let mut screen_sizes = [-1.0; GltfMesh::MAX_LOD_COUNT];
+        if let Some(mesh) = self
+            .mesh_primitives(mesh_index)
+            .max_by_key(|mesh| mesh.lods.len())
+        {
+            for (screen_size, lod) in screen_sizes.iter_mut().zip(mesh.lods.iter().skip(1)) {
+                *screen_size = lod.screen_size;
+            }
+        }
+        screen_sizes
+    }
+    /// Whether one of the mesh's primitives is drawn by the transparent pass.
+    pub fn mesh_is_transparent(&self, mesh_index: usize) -> bool {
+        self.mesh_primitives(mesh_index)
+            .any(|mesh| self.materials[&mesh.material_index].transparent)
+    }
+    /// The material a primitive is drawn with, the entity's override replaces the shared one.
+    pub fn material<'m>(
+        &'m self,
+        material_index: usize,
+        material_override: Option<&'m MaterialOverride>,
+    ) -> &'m RawMaterial {
+        material_override
+            .and_then(|material_override| material_override.materials.get(&material_index))
+            .unwrap_or(&self.materials[&material_index])
+    }
+    /// Primitives of the given mesh, each of them is a separate draw.
+    pub fn mesh_primitives(&self, mesh_index: usize) -> impl Iterator<Item = &RawMesh> {
+        self.meshes.iter().filter(move |mesh| mesh.mesh_index == mesh_index)
+    }
+    /// Collects the model nodes which draw the given mesh of the model entity with their transforms,
+    /// along with where each instance's joint matrices and morph weights begin.
+    pub fn mesh_instances(
+        model_ent: Entity,
+        mesh_index: usize,
+        entities: &EntitiesRes,
+        transforms: &ReadStorage<Transform>,
+        nodes: &ReadStorage<ModelNode>,
+        skins: &ReadStorage<Skin>,
+        morph_weights: &ReadStorage<MorphWeights>,
+    ) -> (Vec<Entity>, Vec<TransformRaw>, Vec<InstanceDeformation>) {
+        let mut node_entities = Vec::new();
+        let mut instances = Vec::new();
+        let mut deformations = Vec::new();
+        for (node_ent, transform, node, skin, weights) in (
+            entities,
+            transforms,
+            nodes,
+            skins.maybe(),
+            morph_weights.maybe(),
+        )
+            .join()
+        {
+            if transform.model != Some(model_ent) || node.mesh != Some(mesh_index) {
+                continue;
+            }
+            // weights which didn't fit into the buffer leave the mesh in its base shape.
+            let (weight_offset, target_count) = weights
+                .and_then(|weights| {
+                    weights
+                        .weight_offset
+                        .map(|offset| (offset, weights.weights.len() as u32))
+                })
+                .unwrap_or((0, 0));
+            node_entities.push(node_ent);
+            instances.push(transform.to_raw());
+            deformations.push(InstanceDeformation::new(
+                skin.and_then(|skin| skin.joint_offset)
+                    .unwrap_or(Skin::NOT_SKINNED),
+                weight_offset,
+                target_count,
+            ));
+        }
+        (node_entities, instances, deformations)
+    }
 }
 pub trait DrawModel<'a, 'b>
     where
@@ -58,6 +401,39 @@ pub trait DrawModel<'a, 'b>
         model: &'b RawModel,
         instances: Range<u32>,
     );
+    fn draw_model_mesh_instanced(
+        &mut self,
+        model: &'b RawModel,
+        mesh_index: usize,
+        instances: Range<u32>,
+    );
+    fn draw_mesh_indirect(
+        &mut self,
+        mesh:&'a RawMesh,
+        material:&'a RawMaterial,
+        indirect_buffer: &'a wgpu::Buffer,
+        indirect_offset: wgpu::BufferAddress,
+    );
+    /// Draws the primitives of the mesh with the arguments written by the frustum culling,
+    /// the primitives' arguments of every level of detail follow each other starting at `indirect_offset`.
+    /// The materials of the override are used instead of the model's when there's one.
+    fn draw_model_mesh_indirect(
+        &mut self,
+        model: &'b RawModel,
+        mesh_index: usize,
+        material_override: Option<&'b MaterialOverride>,
+        indirect_buffer: &'a wgpu::Buffer,
+        indirect_offset: wgpu::BufferAddress,
+    );
+    /// Same as `draw_model_mesh_indirect` with the bind group of a custom material instead of the glTF materials.
+    fn draw_model_mesh_indirect_with_material(
+        &mut self,
+        model: &'b RawModel,
+        mesh_index: usize,
+        material_bind_group: &'a wgpu::BindGroup,
+        indirect_buffer: &'a wgpu::Buffer,
+        indirect_offset: wgpu::BufferAddress,
+    );
 }
 
 impl<'a, 'b> DrawModel<'a, 'b> for wgpu::RenderPass<'a>
@@ -82,7 +458,8 @@ impl<'a, 'b> DrawModel<'a, 'b> for wgpu::RenderPass<'a>
         self.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
 
         self.set_bind_group(1, &material.bind_group_container.bind_group, &[]);
-        self.draw_indexed(0..mesh.index_buffer_len, 0, instances);
+        let base = mesh.lod(0);
+        self.draw_indexed(base.first_index..base.first_index + base.index_count, 0, instances);
     }
 
     fn draw_model(
@@ -106,4 +483,86 @@ impl<'a, 'b> DrawModel<'a, 'b> for wgpu::RenderPass<'a>
             );
         }
     }
+    fn draw_model_mesh_instanced(
+        &mut self,
+        model: &'b RawModel,
+        mesh_index: usize,
+        instances: Range<u32>,
+    ) {
+        for mesh in model.mesh_primitives(mesh_index) {
+            self.draw_mesh_instanced(
+                mesh,
+                &model.materials[&mesh.material_index],
+                instances.clone(),
+            );
+        }
+    }
+    fn draw_mesh_indirect(
+        &mut self,
+        mesh:&'a RawMesh,
+        material:&'a RawMaterial,
+        indirect_buffer: &'a wgpu::Buffer,
+        indirect_offset: wgpu::BufferAddress,
+    ) {
+        self.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+        self.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+
+        self.set_bind_group(1, &material.bind_group_container.bind_group, &[]);
+        self.draw_indexed_indirect(indirect_buffer, indirect_offset);
+    }
+    fn draw_model_mesh_indirect(
+        &mut self,
+        model: &'b RawModel,
+        mesh_index: usize,
+        material_override: Option<&'b MaterialOverride>,
+        indirect_buffer: &'a wgpu::Buffer,
+        indirect_offset: wgpu::BufferAddress,
+    ) {
+        let primitive_count = model.mesh_primitives(mesh_index).count();
+        for lod in 0..model.mesh_lod_count(mesh_index) {
+            for (index, mesh) in model.mesh_primitives(mesh_index).enumerate() {
+                let material = model.material(mesh.material_index, material_override);
+                // transparent primitives are drawn sorted by the transparent pass.
+                if material.transparent {
+                    continue;
+                }
+                self.draw_mesh_indirect(
+                    mesh,
+                    material,
+                    indirect_buffer,
+                    indirect_offset
+                        + ((lod * primitive_count + index)
+                            * std::mem::size_of::<DrawIndexedIndirectRaw>())
+                            as wgpu::BufferAddress,
+                );
+            }
+        }
+    }
+    fn draw_model_mesh_indirect_with_material(
+        &mut self,
+        model: &'b RawModel,
+        mesh_index: usize,
+        material_bind_group: &'a wgpu::BindGroup,
+        indirect_buffer: &'a wgpu::Buffer,
+        indirect_offset: wgpu::BufferAddress,
+    ) {
+        let primitive_count = model.mesh_primitives(mesh_index).count();
+        self.set_bind_group(1, material_bind_group, &[]);
+        for lod in 0..model.mesh_lod_count(mesh_index) {
+            for (index, mesh) in model.mesh_primitives(mesh_index).enumerate() {
+                if model.materials[&mesh.material_index].transparent {
+                    continue;
+                }
+                self.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                self.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                self.draw_indexed_indirect(
+                    indirect_buffer,
+                    indirect_offset
+                        + ((lod * primitive_count + index)
+                            * std::mem::size_of::<DrawIndexedIndirectRaw>())
+                            as wgpu::BufferAddress,
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,13 @@
+use specs::*;
+
+/// The entity this entity's transform is relative to.
+/// Lights attached to an entity are its children, so a light's position is an offset from the entity
+/// instead of being replaced by the entity's position.
+#[derive(Component, Copy, Clone, Debug)]
+#[storage(DenseVecStorage)]
+pub struct Parent(pub Entity);
+
+/// Entities which have this entity as their `Parent`, rebuilt every frame by `PropagateTransforms`.
+#[derive(Component, Clone, Debug, Default)]
+#[storage(DenseVecStorage)]
+pub struct Children(pub Vec<Entity>);
@@ -0,0 +1,78 @@
+use std::collections::HashMap;
+
+use specs::*;
+
+use crate::components::gltfmodel::RawMaterial;
+use crate::components::modelnode::ModelNode;
+use crate::renderer::primitives::material::{GltfMaterial, MaterialTextureSlot};
+
+/// glTF material values scripts set for one entity, the other entities drawing the model keep its materials.
+/// On a model node it applies to the node, on a model instance to all of its nodes
+/// and on the model entity to every instance without an override of its own.
+#[derive(Component)]
+#[storage(DenseVecStorage)]
+pub struct MaterialOverride {
+    /// The entity holding the model whose materials are overridden.
+    pub model: Entity,
+    pub base_color: Option<[f32; 4]>,
+    pub emissive_color: Option<[f32; 3]>,
+    pub roughness: Option<f32>,
+    pub metallic: Option<f32>,
+    /// Images of the model replacing the textures of every material.
+    pub textures: HashMap<MaterialTextureSlot, usize>,
+    /// The overridden materials by the index of the model's material, created by `UpdateMaterials`.
+    pub materials: HashMap<usize, RawMaterial>,
+    /// Set when the values change, `UpdateMaterials` writes the uniform buffers then.
+    pub uniform_dirty: bool,
+    /// Set when the textures change, `UpdateMaterials` creates the bind groups again.
+    pub textures_dirty: bool,
+}
+
+impl MaterialOverride {
+    pub fn new(model: Entity) -> Self {
+        Self {
+            model,
+            base_color: None,
+            emissive_color: None,
+            roughness: None,
+            metallic: None,
+            textures: HashMap::new(),
+            materials: HashMap::new(),
+            uniform_dirty: true,
+            textures_dirty: true,
+        }
+    }
+    /// The model's material with the overridden values.
+    pub fn apply(&self, material: &GltfMaterial) -> GltfMaterial {
+        let mut material = material.clone();
+        if let Some(base_color) = self.base_color {
+            material.base_color = base_color;
+        }
+        if let Some(emissive_color) = self.emissive_color {
+            material.emissive_color = emissive_color;
+        }
+        if let Some(roughness) = self.roughness {
+            material.pbr_roughness = roughness;
+        }
+        if let Some(metallic) = self.metallic {
+            material.metallic_factor = metallic;
+        }
+        for (slot, image) in self.textures.iter() {
+            *material.texture_mut(*slot) = Some(*image);
+        }
+        material
+    }
+    /// The entity whose override the model node is drawn with: the node, its instance or the model.
+    pub fn override_entity(
+        node_ent: Entity,
+        model_ent: Entity,
+        model_nodes: &ReadStorage<ModelNode>,
+        material_overrides: &ReadStorage<MaterialOverride>,
+    ) -> Option<Entity> {
+        let instance = model_nodes.get(node_ent).map(|node| node.instance);
+        [Some(node_ent), instance, Some(model_ent)]
+            .into_iter()
+            .flatten()
+            .find(|entity| material_overrides.contains(*entity))
+    }
+}
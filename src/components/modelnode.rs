@@ -0,0 +1,132 @@
+use std::collections::HashMap;
+
+use specs::*;
+
+use crate::components::animator::Animator;
+use crate::components::hierarchy::Parent;
+use crate::components::morphweights::MorphWeights;
+use crate::components::skin::Skin;
+use crate::components::transform::Transform;
+use crate::renderer::model::HorizonModel;
+
+/// A glTF node spawned for a model instance, only nodes with a mesh are drawn.
+#[derive(Component, Clone, Debug)]
+#[storage(VecStorage)]
+pub struct ModelNode {
+    pub name: String,
+    pub node_index: usize,
+    pub mesh: Option<usize>,
+    /// The entity whose transform refers to the model, the root of the node hierarchy.
+    pub instance: Entity,
+}
+
+impl ModelNode {
+    /// Spawns the model's node hierarchy as children of the instance entity.
+    /// Skinned nodes get a `Skin` referring to the spawned joints, nodes with morph targets `MorphWeights`
+    /// and animated models an idle `Animator` on the instance.
+    pub fn spawn_nodes(world: &World, instance: Entity, model_ent: Entity) {
+        let models = world.read_storage::<HorizonModel>();
+        let model = if let Some(model) = models.get(model_ent) {
+            model
+        } else {
+            return;
+        };
+        let entities = world.entities();
+        let mut transforms = world.write_storage::<Transform>();
+        let mut parents = world.write_storage::<Parent>();
+        let mut model_nodes = world.write_storage::<ModelNode>();
+        let mut node_entities = HashMap::new();
+        let mut stack = model
+            .root_nodes
+            .iter()
+            .map(|node_index| (*node_index, instance))
+            .collect::<Vec<_>>();
+        while let Some((node_index, parent)) = stack.pop() {
+            let node = &model.nodes[node_index];
+            let node_ent = entities.create();
+            transforms
+                .insert(
+                    node_ent,
+                    Transform::new(
+                        node.translation,
+                        node.rotation,
+                        node.scale,
+                        node.mesh.map(|_| model_ent),
+                    ),
+                )
+                .unwrap();
+            parents.insert(node_ent, Parent(parent)).unwrap();
+            model_nodes
+                .insert(
+                    node_ent,
+                    ModelNode {
+                        name: node.name.clone(),
+                        node_index,
+                        mesh: node.mesh,
+                        instance,
+                    },
+                )
+                .unwrap();
+            node_entities.insert(node_index, node_ent);
+            stack.extend(node.children.iter().map(|child| (*child, node_ent)));
+        }
+
+        let mut skins = world.write_storage::<Skin>();
+        for (node_index, node_ent) in &node_entities {
+            let skin = match model.nodes[*node_index].skin {
+                Some(skin) if model.nodes[*node_index].mesh.is_some() => &model.skins[skin],
+                _ => continue,
+            };
+            let joints = skin
+                .joints
+                .iter()
+                .map(|joint| node_entities.get(joint).copied())
+                .collect();
+            skins
+                .insert(
+                    *node_ent,
+                    Skin {
+                        joints,
+                        inverse_bind_matrices: skin.inverse_bind_matrices.clone(),
+                        joint_offset: None,
+                    },
+                )
+                .unwrap();
+        }
+        let mut morph_weights = world.write_storage::<MorphWeights>();
+        for (node_index, node_ent) in &node_entities {
+            let node = &model.nodes[*node_index];
+            let mesh = match node.mesh.map(|mesh| &model.meshes[mesh]) {
+                Some(mesh) if mesh.morph_target_count() > 0 => mesh,
+                _ => continue,
+            };
+            // node weights take precedence over the mesh's defaults.
+            let mut weights = vec![0.0; mesh.morph_target_count()];
+            let defaults = node.weights.as_ref().unwrap_or(&mesh.weights);
+            for (weight, default) in weights.iter_mut().zip(defaults) {
+                *weight = *default;
+            }
+            morph_weights
+                .insert(*node_ent, MorphWeights::new(weights))
+                .unwrap();
+        }
+        // the animator starts idle, clips are played by scripts.
+        if !model.animations.is_empty() {
+            world
+                .write_storage::<Animator>()
+                .insert(instance, Animator::new(model_ent, node_entities))
+                .unwrap();
+        }
+    }
+    /// Deletes the nodes spawned for the instance entity.
+    pub fn despawn_nodes(world: &World, instance: Entity) {
+        world.write_storage::<Animator>().remove(instance);
+        let entities = world.entities();
+        let model_nodes = world.read_storage::<ModelNode>();
+        for (node_ent, node) in (&entities, &model_nodes).join() {
+            if node.instance == instance {
+                entities.delete(node_ent).unwrap();
+            }
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use specs::*;
+
+/// Morph target weights of a model node, written to the morph weight buffer by `UpdateMorphWeights`.
+#[derive(Component, Clone, Debug)]
+#[storage(DenseVecStorage)]
+pub struct MorphWeights {
+    /// One weight for each morph target of the node's mesh.
+    pub weights: Vec<f32>,
+    /// Index of the first weight in the buffer, `None` if it didn't fit this frame.
+    pub weight_offset: Option<u32>,
+}
+
+impl MorphWeights {
+    pub fn new(weights: Vec<f32>) -> Self {
+        Self {
+            weights,
+            weight_offset: None,
+        }
+    }
+    /// Overwrites the weights, extra values are ignored and missing ones are left unchanged.
+    pub fn set_weights(&mut self, weights: &[f32]) {
+        for (current, weight) in self.weights.iter_mut().zip(weights) {
+            *current = *weight;
+        }
+    }
+}
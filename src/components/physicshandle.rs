@@ -1,4 +1,6 @@
+use crate::scripting::util::entityinfo::Damping;
 use crate::scripting::util::glmconversion::Vec3;
+use crate::scripting::util::RigidBodyType;
 use rapier3d::{dynamics::RigidBodyHandle, geometry::ColliderHandle};
 use serde::{Deserialize, Serialize};
 use specs::{Component, VecStorage};
@@ -8,6 +10,15 @@ use specs::{Component, VecStorage};
 pub struct PhysicsHandle {
     pub rigid_body_handle: RigidBodyHandle,
     pub collider_handles: Vec<ColliderHandle>,
+    pub body_settings: PhysicsBodySettings,
+}
+/// The values the rigid body was built from, kept so the body can be recreated when a scene is loaded.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicsBodySettings {
+    pub body_type: RigidBodyType,
+    pub mass: f64,
+    pub damping: Vec<Damping>,
 }
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(rename_all = "camelCase")]
@@ -69,6 +69,15 @@ impl<'a> ExecuteFunction<'a> for ScriptingCallback {
                     )
                     .unwrap();
             }
+            CallbackArgs::AnimationFinished(entity, animation) => {
+                self.get_callback()
+                    .call2(
+                        &JsValue::NULL,
+                        &JsValue::from_serde(&entity).unwrap(),
+                        &JsValue::from(animation),
+                    )
+                    .unwrap();
+            }
         }
     }
 }
@@ -120,6 +129,13 @@ impl<'s> ExecuteFunction<'s> for ScriptingCallback {
                     .open(scope)
                     .call(scope, recv, &[entity_one, entity_two]);
             }
+            CallbackArgs::AnimationFinished(entity, animation) => {
+                let entity = deno_core::serde_v8::to_v8(scope, entity).unwrap();
+                let animation = v8::String::new(scope, animation.as_str()).unwrap().into();
+                self.callback
+                    .open(scope)
+                    .call(scope, recv, &[entity, animation]);
+            }
             CallbackArgs::None => {
                 self.callback.open(scope).call(scope, recv, &[]);
             }
@@ -149,6 +165,8 @@ pub enum CallbackArgs {
     MouseClickEvent(u16),
     MouseMoveEvent((f64, f64)),
     EntityCollision(HorizonEntity, HorizonEntity),
+    /// The animated entity and the name of the finished clip.
+    AnimationFinished(HorizonEntity, String),
 }
 impl CallbackArgs {
     pub fn from_winit_keycode_to_js(keycode: VirtualKeyCode) -> CallbackArgs {
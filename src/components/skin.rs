@@ -0,0 +1,19 @@
+use rapier3d::na::Matrix4;
+use specs::*;
+
+/// Joints of a skinned model node, their matrices are written to the joint matrix buffer by `UpdateJointMatrices`.
+#[derive(Component, Clone, Debug)]
+#[storage(DenseVecStorage)]
+pub struct Skin {
+    /// Spawned node entities of the joints in the order the vertices refer to them.
+    /// Joints outside of the default scene aren't spawned, their vertices stay in bind pose.
+    pub joints: Vec<Option<Entity>>,
+    pub inverse_bind_matrices: Vec<Matrix4<f32>>,
+    /// Index of the skin's first joint matrix in the buffer, `None` if it didn't fit this frame.
+    pub joint_offset: Option<u32>,
+}
+
+impl Skin {
+    /// Joint offset of instances drawn without skinning.
+    pub const NOT_SKINNED: u32 = u32::MAX;
+}
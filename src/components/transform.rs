@@ -10,6 +10,8 @@ pub struct Transform {
     pub rotation: UnitQuaternion<f32>,
     pub scale: Vector3<f32>,
     pub model: Option<Entity>,
+    /// Local matrix combined with the parents' ones, updated by `PropagateTransforms`.
+    pub world_matrix: Matrix4<f32>,
 }
 
 impl Transform {
@@ -19,12 +21,15 @@ impl Transform {
         scale: Vector3<f32>,
         model: Option<Entity>,
     ) -> Self {
-        Self {
+        let mut transform = Self {
             position,
             rotation,
             scale,
             model,
-        }
+            world_matrix: Matrix4::identity(),
+        };
+        transform.world_matrix = transform.local_matrix();
+        transform
     }
     pub fn set_position(&mut self, position: Vector3<f32>) {
         self.position = position;
@@ -50,13 +55,18 @@ impl Transform {
     pub fn get_scale(&self) -> Vector3<f32> {
         self.scale
     }
+    pub fn get_world_position(&self) -> Vector3<f32> {
+        self.world_matrix.column(3).xyz()
+    }
+    pub fn local_matrix(&self) -> Matrix4<f32> {
+        (Matrix4::new_translation(&self.position)
+            * self.rotation.to_rotation_matrix().to_homogeneous())
+        .append_nonuniform_scaling(&self.scale)
+    }
 
     pub fn to_raw(&self) -> TransformRaw {
         TransformRaw {
-            data: (Matrix4::new_translation(&self.position)
-                * self.rotation.to_rotation_matrix().to_homogeneous())
-            .append_nonuniform_scaling(&self.scale)
-            .into(),
+            data: self.world_matrix.into(),
         }
     }
 }
@@ -66,6 +76,9 @@ pub struct TransformRaw {
     data: [[f32; 4]; 4],
 }
 impl TransformRaw {
+    pub fn matrix(&self) -> Matrix4<f32> {
+        Matrix4::from(self.data)
+    }
     pub fn get_normal_matrix(&self) -> [[f32; 4]; 4] {
         let mat4 = Matrix4::from(self.data);
         let inverted = mat4.try_inverse().unwrap();
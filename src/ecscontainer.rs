@@ -9,12 +9,39 @@ use wgpu::{PipelineStatisticsTypes, QueryType};
 
 use crate::components::assetidentifier::AssetIdentifier;
 use crate::components::collisionshape::CollisionShape;
+use crate::components::custommaterial::CustomMaterial;
+use crate::components::materialoverride::MaterialOverride;
+use crate::components::hierarchy::{Children, Parent};
 use crate::components::modelcollider::ModelCollider;
+use crate::components::modelnode::ModelNode;
+use crate::components::morphweights::MorphWeights;
+use crate::renderer::bindgroups::frustumculling::FrustumCullingBindGroup;
+use crate::renderer::bindgroups::ssao::SsaoBindGroup;
+use crate::renderer::postprocessing::PostProcessStage;
+use crate::renderer::rendergraph::{
+    RenderGraph, RenderGraphError, RenderGraphPass, RenderGraphPosition, RenderGraphResource,
+    SystemPass,
+};
+use crate::resources::framecapture::FrameCapture;
+use crate::resources::frustumculling::FrustumCulling;
 use crate::resources::gpuquerysets::{
     GpuQuerySet, GpuQuerySetContainer, PipelineStatisticsQueries,
 };
+use crate::resources::lodsettings::LodSettings;
+use crate::resources::materiallibrary::MaterialLibrary;
+use crate::resources::morphtargetstorage::MorphTargetStorage;
+use crate::resources::postprocesssettings::{
+    BloomSettings, ChromaticAberrationSettings, ColorGradingSettings, FxaaSettings,
+    VignetteSettings,
+};
 use crate::resources::scriptingstate::ScriptingState;
+use crate::resources::shadererrors::ShaderErrors;
+use crate::resources::shadowatlas::ShadowAtlas;
+use crate::resources::shadowsettings::ShadowSettings;
+use crate::resources::skyboxtexture::SkyboxTexture;
+use crate::resources::ssaosettings::SsaoSettings;
 use crate::resources::surfacetexture::SurfaceTexture;
+use crate::resources::tonemappingsettings::ToneMappingSettings;
 use crate::scripting::scriptevent::ScriptEvent;
 use crate::scripting::scriptingengine::HorizonScriptingEngine;
 use crate::{
@@ -23,14 +50,16 @@ use crate::{
     renderer::{
         bindgroupcontainer::BindGroupContainer,
         bindgroups::{
-            deferred::DeferredBindGroup, lighting::LightBindGroup, shadow::ShadowBindGroup,
-            tiling::TilingBindGroup, uniforms::UniformBindGroup,
+            deferred::DeferredBindGroup, environment::EnvironmentBindGroup,
+            lighting::LightBindGroup, shadow::ShadowBindGroup, tiling::TilingBindGroup,
+            tonemapping::ToneMappingBindGroup, transparent::TransparentBindGroup,
+            uniforms::UniformBindGroup,
         },
         modelbuilder::ModelBuilder,
         state::State,
     },
     resources::{
-        bindingresourcecontainer::BindingResourceContainer,
+        bindingresourcecontainer::{BindingResourceContainer, BufferTypes},
         commandencoder::HorizonCommandEncoder,
         windowevents::{KeyboardEvent, MouseInputEvent, MouseMoveEvent, ResizeEvent},
     },
@@ -42,17 +71,33 @@ use crate::{
 
 use crate::systems::events::handlewindowevents::HandleInternalWindowEvents;
 use crate::systems::events::resize::Resize;
+use crate::systems::propagatetransforms::PropagateTransforms;
 use crate::systems::rendering::acquiretexture::AcquireTexture;
+use crate::systems::rendering::builddepthpyramid::BuildDepthPyramid;
+use crate::systems::rendering::captureframe::CaptureFrame;
+use crate::systems::rendering::computefrustumculling::ComputeFrustumCulling;
 use crate::systems::rendering::computelightculling::ComputeLightCulling;
+use crate::systems::rendering::executerendergraph::ExecuteRenderGraph;
+use crate::systems::rendering::presentframe::PresentFrame;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::systems::rendering::reloadshaders::ReloadShaders;
 use crate::systems::rendering::rendercollision::RenderCollision;
 use crate::systems::rendering::renderforwardpass::RenderForwardPass;
+use crate::systems::rendering::renderpostprocessing::RenderPostProcessing;
 use crate::systems::rendering::rendershadowpass::RenderShadowPass;
 use crate::systems::rendering::renderskybox::RenderSkyBox;
+use crate::systems::rendering::renderssao::RenderSsao;
+use crate::systems::rendering::rendertonemapping::RenderToneMapping;
+use crate::systems::rendering::rendertransparentpass::RenderTransparentPass;
 use crate::systems::rendering::renderuipass::RenderUIPass;
 use crate::systems::rendering::resolvequerysets::ResolveQuerySets;
 use crate::systems::rendering::updatebuffers::UpdateBuffers;
 use crate::systems::rendering::updatecamera::UpdateCamera;
+use crate::systems::rendering::updatejointmatrices::UpdateJointMatrices;
+use crate::systems::rendering::updatematerials::UpdateMaterials;
+use crate::systems::rendering::updatemorphweights::UpdateMorphWeights;
 use crate::systems::rendering::writegbuffer::WriteGBuffer;
+use crate::systems::updateanimations::UpdateAnimations;
 use crate::systems::util::calculatedeltatime::UpdateDeltaTime;
 use crate::ui::debugstats::DebugStats;
 
@@ -63,35 +108,208 @@ pub struct ECSContainer {
 impl Default for ECSContainer {
     fn default() -> Self {
         let mut world = World::new();
-        let mut dispatcher = DispatcherBuilder::new()
+        let dispatcher_builder = DispatcherBuilder::new()
             .with(UpdateDeltaTime, stringify!(UpdateDeltaTime), &[])
             .with(
                 HandleInternalWindowEvents,
                 stringify!(HandleWindowEvents),
                 &[],
             )
-            .with(UpdateCamera, stringify!(UpdateCamera), &[])
             .with(Physics, stringify!(Physics), &[])
+            .with(
+                UpdateAnimations,
+                stringify!(UpdateAnimations),
+                &[stringify!(UpdateDeltaTime)],
+            )
+            .with(
+                PropagateTransforms,
+                stringify!(PropagateTransforms),
+                &[stringify!(Physics), stringify!(UpdateAnimations)],
+            )
+            .with(
+                UpdateCamera,
+                stringify!(UpdateCamera),
+                &[stringify!(PropagateTransforms)],
+            );
+        #[cfg(not(target_arch = "wasm32"))]
+        let dispatcher_builder = dispatcher_builder.with_thread_local(ReloadShaders);
+        let mut dispatcher = dispatcher_builder
             .with_thread_local(Resize)
             .with_thread_local(UpdateBuffers)
-            .with_thread_local(RenderShadowPass)
-            .with_thread_local(WriteGBuffer)
-            .with_thread_local(ComputeLightCulling)
-            .with_thread_local(AcquireTexture)
-            .with_thread_local(RenderForwardPass)
-            .with_thread_local(RenderCollision)
-            .with_thread_local(RenderSkyBox)
-            .with_thread_local(RenderUIPass)
+            .with_thread_local(UpdateMaterials)
+            .with_thread_local(UpdateJointMatrices)
+            .with_thread_local(UpdateMorphWeights)
+            .with_thread_local(ExecuteRenderGraph)
+            .with_thread_local(CaptureFrame)
+            .with_thread_local(PresentFrame)
             .with_thread_local(ResolveQuerySets)
             .build();
         dispatcher.setup(&mut world);
+        let mut render_graph = ECSContainer::create_render_graph();
+        render_graph.setup(&mut world);
+        world.insert(render_graph);
         ECSContainer::register_components(&mut world);
         Self { dispatcher, world }
     }
 }
 impl ECSContainer {
+    /// Sets the pass up with the world and adds it to the render graph, the graph orders it on the next frame.
+    pub fn insert_render_pass(
+        &mut self,
+        position: RenderGraphPosition,
+        mut pass: Box<dyn RenderGraphPass>,
+    ) -> Result<(), RenderGraphError> {
+        pass.setup(&mut self.world);
+        self.world
+            .write_resource::<RenderGraph>()
+            .insert_pass(position, pass)
+    }
+    /// The built-in passes in submission order, with the resources they read and write.
+    /// The frustum culling tests against the depth pyramid of the previous frame, so it isn't declared as a read.
+    fn create_render_graph() -> RenderGraph {
+        use RenderGraphResource::{Buffer, Surface, Texture};
+        let passes: Vec<Box<dyn RenderGraphPass>> = vec![
+            Box::new(
+                SystemPass::new("frustum_culling", ComputeFrustumCulling)
+                    .reading(&[
+                        Buffer(BufferTypes::Instances),
+                        Buffer(BufferTypes::InstanceDeformations),
+                        Buffer(BufferTypes::CullingInstances),
+                        Buffer(BufferTypes::CullingGroups),
+                    ])
+                    .writing(&[
+                        Buffer(BufferTypes::IndirectDraws),
+                        Buffer(BufferTypes::VisibleInstances),
+                        Buffer(BufferTypes::CullingStats),
+                    ]),
+            ),
+            Box::new(
+                SystemPass::new("shadow", RenderShadowPass)
+                    .reading(&[
+                        Buffer(BufferTypes::IndirectDraws),
+                        Buffer(BufferTypes::ShadowCascade),
+                        Buffer(BufferTypes::ShadowAtlasTiles),
+                    ])
+                    .writing(&[
+                        Texture(TextureViewTypes::Shadow),
+                        Texture(TextureViewTypes::ShadowAtlas),
+                    ]),
+            ),
+            Box::new(
+                SystemPass::new("gbuffer", WriteGBuffer)
+                    .reading(&[
+                        Buffer(BufferTypes::IndirectDraws),
+                        Buffer(BufferTypes::VisibleInstances),
+                    ])
+                    .writing(&[
+                        Texture(TextureViewTypes::DeferredPosition),
+                        Texture(TextureViewTypes::DeferredNormals),
+                        Texture(TextureViewTypes::DeferredAlbedo),
+                        Texture(TextureViewTypes::DeferredSpecular),
+                        Texture(TextureViewTypes::Depth),
+                    ]),
+            ),
+            Box::new(
+                SystemPass::new("depth_pyramid", BuildDepthPyramid)
+                    .reading(&[Texture(TextureViewTypes::Depth)])
+                    .writing(&[Texture(TextureViewTypes::DepthPyramid)]),
+            ),
+            Box::new(
+                SystemPass::new("light_culling", ComputeLightCulling)
+                    .reading(&[
+                        Buffer(BufferTypes::PointLight),
+                        Buffer(BufferTypes::SpotLight),
+                        Buffer(BufferTypes::LightCulling),
+                    ])
+                    .writing(&[Buffer(BufferTypes::LightId)]),
+            ),
+            Box::new(SystemPass::new("acquire_surface", AcquireTexture).writing(&[Surface])),
+            Box::new(RenderSsao::default()),
+            Box::new(
+                SystemPass::new("forward", RenderForwardPass)
+                    .reading(&[
+                        Texture(TextureViewTypes::DeferredPosition),
+                        Texture(TextureViewTypes::DeferredNormals),
+                        Texture(TextureViewTypes::DeferredAlbedo),
+                        Texture(TextureViewTypes::DeferredSpecular),
+                        Texture(TextureViewTypes::AmbientOcclusionBlurred),
+                        Texture(TextureViewTypes::Shadow),
+                        Texture(TextureViewTypes::ShadowAtlas),
+                        Texture(TextureViewTypes::Irradiance),
+                        Texture(TextureViewTypes::PrefilteredEnvironment),
+                        Texture(TextureViewTypes::BrdfLut),
+                        Buffer(BufferTypes::LightId),
+                    ])
+                    .writing(&[Texture(TextureViewTypes::HdrScene)]),
+            ),
+            Box::new(
+                SystemPass::new("collision", RenderCollision)
+                    .reading(&[Texture(TextureViewTypes::Depth)])
+                    .writing(&[Texture(TextureViewTypes::HdrScene)]),
+            ),
+            Box::new(
+                SystemPass::new("skybox", RenderSkyBox)
+                    .reading(&[
+                        Texture(TextureViewTypes::Depth),
+                        Texture(TextureViewTypes::Skybox),
+                    ])
+                    .writing(&[Texture(TextureViewTypes::HdrScene)]),
+            ),
+            Box::new(
+                SystemPass::new("transparent", RenderTransparentPass)
+                    .reading(&[
+                        Texture(TextureViewTypes::Depth),
+                        Texture(TextureViewTypes::Shadow),
+                        Texture(TextureViewTypes::ShadowAtlas),
+                        Buffer(BufferTypes::LightId),
+                    ])
+                    .writing(&[
+                        Texture(TextureViewTypes::SceneColor),
+                        Texture(TextureViewTypes::HdrScene),
+                    ]),
+            ),
+            Box::new(
+                SystemPass::new(
+                    "post_processing_hdr",
+                    RenderPostProcessing(PostProcessStage::Hdr),
+                )
+                .reading(&[Texture(TextureViewTypes::HdrScene)])
+                .writing(&[Texture(TextureViewTypes::HdrScene)]),
+            ),
+            Box::new(
+                SystemPass::new("tone_mapping", RenderToneMapping)
+                    .reading(&[Texture(TextureViewTypes::HdrScene)])
+                    .writing(&[Texture(TextureViewTypes::PostProcessPing), Surface]),
+            ),
+            Box::new(
+                SystemPass::new(
+                    "post_processing_ldr",
+                    RenderPostProcessing(PostProcessStage::Ldr),
+                )
+                .reading(&[Texture(TextureViewTypes::PostProcessPing)])
+                .writing(&[
+                    Texture(TextureViewTypes::PostProcessPing),
+                    Texture(TextureViewTypes::PostProcessPong),
+                    Surface,
+                ]),
+            ),
+            Box::new(
+                SystemPass::new("ui", RenderUIPass)
+                    .reading(&[Surface])
+                    .writing(&[Surface]),
+            ),
+        ];
+        let mut render_graph = RenderGraph::default();
+        for pass in passes {
+            render_graph
+                .add_pass(pass)
+                .expect("the built-in passes have unique names and no cycles");
+        }
+        render_graph
+    }
     pub fn dispatch(&mut self) {
         self.dispatcher.dispatch(&self.world);
+        self.world.maintain();
     }
     pub fn setup(&mut self, state: State) {
         self.world.insert(state);
@@ -195,11 +413,34 @@ impl ECSContainer {
         world.insert(ScriptingState {
             run_physics_simulation: true,
         });
-        world.insert(SurfaceTexture { texture: None });
+        world.insert(SurfaceTexture::default());
+        world.insert(FrameCapture::default());
+        world.insert(SkyboxTexture::default());
+        world.insert(MorphTargetStorage::default());
+        world.insert(ShadowSettings::default());
+        world.insert(LodSettings::default());
+        world.insert(BloomSettings::default());
+        world.insert(FxaaSettings::default());
+        world.insert(VignetteSettings::default());
+        world.insert(ChromaticAberrationSettings::default());
+        world.insert(ColorGradingSettings::default());
+        world.insert(ShaderErrors::default());
+        world.insert(MaterialLibrary::default());
+        world.insert(ShadowAtlas::default());
+        world.insert(FrustumCulling::default());
         world.insert(DebugStats {
             fps: 0,
             unique_model_count: 1,
             show_collision_wireframes: false,
+            show_shadow_cascades: false,
+            shadow_cascade_splits: Vec::new(),
+            occlusion_culling: true,
+            frustum_culled_instances: 0,
+            occlusion_culled_instances: 0,
+            visible_instances: 0,
+            show_lod_levels: false,
+            tone_mapping: ToneMappingSettings::default(),
+            ssao: SsaoSettings::default(),
             debug_texture: None,
             debug_texture_view: None,
             cam_pos: rapier3d::na::Point3::new(0.0, 0.0, 0.0),
@@ -233,14 +474,25 @@ impl ECSContainer {
         world.register::<DeferredBindGroup>();
         world.register::<TilingBindGroup>();
         world.register::<SkyboxBindGroup>();
+        world.register::<EnvironmentBindGroup>();
+        world.register::<TransparentBindGroup>();
+        world.register::<ToneMappingBindGroup>();
+        world.register::<SsaoBindGroup>();
         world.register::<DebugTextureBindGroup>();
         world.register::<DebugCollisionBindGroup>();
+        world.register::<FrustumCullingBindGroup>();
         world.register::<ScriptingCallback>();
         world.register::<ScriptEvent>();
         world.register::<AssetIdentifier>();
         world.register::<RawModel>();
         world.register::<HorizonModel>();
         world.register::<CollisionShape>();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<ModelNode>();
+        world.register::<MorphWeights>();
+        world.register::<CustomMaterial>();
+        world.register::<MaterialOverride>();
     }
     pub fn global<'a>() -> Ref<'a, ECSContainer> {
         ref_thread_local::RefThreadLocal::borrow(&ECS_CONTAINER)
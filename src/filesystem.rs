@@ -1,4 +1,5 @@
 pub mod fileloader;
 pub mod modelimporter;
 pub mod nativefileloader;
+pub mod scene;
 pub mod webfileloader;
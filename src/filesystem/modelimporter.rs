@@ -6,6 +6,8 @@ use fileloader::FileLoader;
 
 use gltf::{Buffer, Gltf, Image};
 
+use crate::renderer::modelbuilder::{GltfNodeLods, ModelBuilder};
+
 pub struct Importer {
     file_loader: Box<dyn FileLoader>,
 }
@@ -25,17 +27,23 @@ impl Importer {
             gltf::Document,
             Vec<gltf::buffer::Data>,
             Vec<gltf::image::Data>,
+            Vec<GltfNodeLods>,
         ),
         ImporterError,
     > {
-        gltf::import_slice(
-            self.file_loader
-                .load_file(file_path)
-                .await
-                .map_err(|e| ImporterError::LoadError)?
-                .as_slice(),
-        )
-        .map_err(|e| ImporterError::LoadError)
+        let file = self
+            .file_loader
+            .load_file(file_path)
+            .await
+            .map_err(|e| ImporterError::LoadError)?;
+        let (document, buffers, images) =
+            gltf::import_slice(file.as_slice()).map_err(|e| ImporterError::LoadError)?;
+        Ok((
+            document,
+            buffers,
+            images,
+            ModelBuilder::read_node_lods(file.as_slice()),
+        ))
     }
 }
 #[cfg(not(target_arch = "wasm32"))]
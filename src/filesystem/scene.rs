@@ -0,0 +1,595 @@
+use std::collections::HashMap;
+
+use rapier3d::na::{Point3, Vector3};
+use serde::{Deserialize, Serialize};
+use specs::{Builder, Entity, Join, RunNow, World, WorldExt};
+
+use crate::components::assetidentifier::AssetIdentifier;
+use crate::components::collisionshape::CollisionShape;
+use crate::components::componentparser::{ComponentParser, ComponentParserError};
+use crate::components::hierarchy::Parent;
+use crate::components::modelnode::ModelNode;
+use crate::components::physicshandle::PhysicsHandle;
+use crate::components::transform::Transform;
+use crate::renderer::primitives::lights::directionallight::DirectionalLight;
+use crate::renderer::primitives::lights::pointlight::PointLight;
+use crate::renderer::primitives::lights::spotlight::SpotLight;
+use crate::resources::camera::Camera;
+use crate::resources::skyboxtexture::SkyboxTexture;
+use crate::scripting::util::entityinfo::{Component, EntityInfo};
+use crate::scripting::util::glmconversion::Vec3;
+use crate::systems::physics::PhysicsWorld;
+use crate::systems::propagatetransforms::PropagateTransforms;
+
+/// Written into every saved scene, scenes with a newer version are rejected.
+pub const SCENE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Debug, Clone)]
+pub enum SceneError {
+    UnsupportedVersion(u32),
+    InvalidFormat(String),
+    ComponentParseFailed(ComponentParserError),
+}
+
+/// Serializable snapshot of the world, entities are stored the same way scripts create them
+/// so loading goes through the `ComponentParser`.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Scene {
+    pub version: u32,
+    pub camera: SceneCamera,
+    pub directional_light: SceneDirectionalLight,
+    pub skybox_texture: Option<String>,
+    pub models: Vec<SceneModel>,
+    pub entities: Vec<SceneEntity>,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneCamera {
+    pub position: Vec3,
+    /// in radians
+    pub yaw: f32,
+    /// in radians
+    pub pitch: f32,
+    pub follow_target: Option<u32>,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneDirectionalLight {
+    /// in radians
+    pub yaw: f32,
+    /// in radians
+    pub pitch: f32,
+    pub color: Vec3,
+}
+/// A loaded model, `id` is the model's entity id at the time of saving which the entities refer to.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneModel {
+    pub id: u32,
+    pub path: String,
+}
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SceneEntity {
+    pub id: u32,
+    #[serde(flatten)]
+    pub info: EntityInfo,
+}
+#[derive(Deserialize)]
+struct SceneHeader {
+    version: u32,
+}
+
+impl Scene {
+    /// Collects every entity with a transform along with the loaded models, camera, lighting and skybox.
+    pub fn capture(world: &World) -> Self {
+        let entities = world.entities();
+        let transforms = world.read_storage::<Transform>();
+        let point_lights = world.read_storage::<PointLight>();
+        let spot_lights = world.read_storage::<SpotLight>();
+        let physics_handles = world.read_storage::<PhysicsHandle>();
+        let collision_shapes = world.read_storage::<CollisionShape>();
+        let identifiers = world.read_storage::<AssetIdentifier>();
+        let parents = world.read_storage::<Parent>();
+        let model_nodes = world.read_storage::<ModelNode>();
+
+        let models = (&entities, &identifiers)
+            .join()
+            .map(|(entity, identifier)| SceneModel {
+                id: entity.id(),
+                path: identifier.0.clone(),
+            })
+            .collect();
+
+        // model nodes are spawned again when the instance's transform is parsed.
+        let scene_entities = (&entities, &transforms, !&model_nodes)
+            .join()
+            .map(|(entity, transform, _)| {
+                let (roll, pitch, yaw) = transform.rotation.euler_angles();
+                let model = transform.model.map(|model| model.id());
+                let mut components = Vec::new();
+                if let Some(point_light) = point_lights.get(entity) {
+                    components.push(Component {
+                        component_type: "pointLight".to_string(),
+                        radius: Some(point_light.radius),
+                        color: Some(point_light.color.into()),
+                        casts_shadow: Some(point_light.casts_shadow),
+                        ..Default::default()
+                    });
+                }
+                if let Some(spot_light) = spot_lights.get(entity) {
+                    components.push(Component {
+                        component_type: "spotLight".to_string(),
+                        radius: Some(spot_light.radius),
+                        color: Some(spot_light.color.into()),
+                        casts_shadow: Some(spot_light.casts_shadow),
+                        inner_cone_angle: Some(spot_light.inner_cone_angle()),
+                        outer_cone_angle: Some(spot_light.outer_cone_angle()),
+                        ..Default::default()
+                    });
+                }
+                if let Some(physics_handle) = physics_handles.get(entity) {
+                    let settings = &physics_handle.body_settings;
+                    components.push(Component {
+                        component_type: "physics".to_string(),
+                        model,
+                        body_type: Some(settings.body_type.clone()),
+                        mass: Some(settings.mass),
+                        damping: Some(settings.damping.clone()),
+                        ..Default::default()
+                    });
+                }
+                if let Some(collision_shape) = collision_shapes.get(entity) {
+                    components.push(Component {
+                        component_type: "CollisionShape".to_string(),
+                        collision_shape: Some(collision_shape.shape_info.clone()),
+                        ..Default::default()
+                    });
+                }
+                SceneEntity {
+                    id: entity.id(),
+                    info: EntityInfo {
+                        transform: Component {
+                            component_type: "transform".to_string(),
+                            position: Some(transform.position.into()),
+                            rotation: Some(Vec3::new(roll, pitch, yaw)),
+                            scale: Some(transform.scale.into()),
+                            model,
+                            parent: parents.get(entity).map(|parent| parent.0.id()),
+                            ..Default::default()
+                        },
+                        components,
+                    },
+                }
+            })
+            .collect();
+
+        let camera = world.read_resource::<Camera>();
+        let dir_light = world.read_resource::<DirectionalLight>();
+        Self {
+            version: SCENE_FORMAT_VERSION,
+            camera: SceneCamera {
+                position: camera.position.into(),
+                yaw: camera.yaw,
+                pitch: camera.pitch,
+                follow_target: camera.follow_target.map(|ent| ent.id()),
+            },
+            directional_light: SceneDirectionalLight {
+                yaw: dir_light.yaw,
+                pitch: dir_light.pitch,
+                color: Vec3::new(
+                    dir_light.color.r as f32,
+                    dir_light.color.g as f32,
+                    dir_light.color.b as f32,
+                ),
+            },
+            skybox_texture: world.read_resource::<SkyboxTexture>().path.clone(),
+            models,
+            entities: scene_entities,
+        }
+    }
+    pub fn to_json(&self) -> Result<String, SceneError> {
+        serde_json::to_string_pretty(self).map_err(|e| SceneError::InvalidFormat(e.to_string()))
+    }
+    pub fn from_json(data: &[u8]) -> Result<Self, SceneError> {
+        // check the version first so newer scenes aren't reported as malformed.
+        let header = serde_json::from_slice::<SceneHeader>(data)
+            .map_err(|e| SceneError::InvalidFormat(e.to_string()))?;
+        if header.version > SCENE_FORMAT_VERSION {
+            return Err(SceneError::UnsupportedVersion(header.version));
+        }
+        serde_json::from_slice::<Self>(data).map_err(|e| SceneError::InvalidFormat(e.to_string()))
+    }
+    /// Deletes every entity with a transform, removing their bodies and colliders from the physics world.
+    pub fn clear(world: &World) {
+        let entities = world.entities();
+        let transforms = world.read_storage::<Transform>();
+        let mut physics_handles = world.write_storage::<PhysicsHandle>();
+        let mut collision_shapes = world.write_storage::<CollisionShape>();
+        let mut physics_world = world.write_resource::<PhysicsWorld>();
+        for (entity, _) in (&entities, &transforms).join() {
+            // physics components are removed right away, the rest of the entity is cleaned up after the frame.
+            if let Some(physics_handle) = physics_handles.remove(entity) {
+                physics_world.delete_rigid_body(physics_handle.rigid_body_handle);
+            }
+            if let Some(collision_shape) = collision_shapes.remove(entity) {
+                physics_world.delete_collider(collision_shape.collider);
+            }
+            entities.delete(entity).unwrap();
+        }
+        world.write_resource::<Camera>().set_follow_target_ent(None);
+    }
+    /// The id of the model entity loaded from the path, scenes refer to their models by path.
+    pub fn loaded_model(world: &World, path: &str) -> Option<u32> {
+        (&world.entities(), &world.read_storage::<AssetIdentifier>())
+            .join()
+            .find(|(_, identifier)| identifier.0 == path)
+            .map(|(ent, _)| ent.id())
+    }
+    /// Creates the scene's entities and applies the camera and lighting.
+    /// The models have to be loaded beforehand, `models` maps the saved model ids to the loaded ones.
+    pub fn instantiate(&self, world: &World, models: &HashMap<u32, u32>) -> Result<(), SceneError> {
+        // entities are created up front as parents and the camera can refer to any of them.
+        let created_entities = self
+            .entities
+            .iter()
+            .map(|scene_entity| (scene_entity.id, world.create_entity_unchecked().build()))
+            .collect::<HashMap<u32, Entity>>();
+        let parser = ComponentParser::default();
+        for scene_entity in &self.entities {
+            let entity = created_entities[&scene_entity.id];
+            parser
+                .parse(
+                    Self::remap_ids(&scene_entity.info.transform, models, &created_entities),
+                    entity,
+                    world,
+                )
+                .map_err(SceneError::ComponentParseFailed)?;
+        }
+        // bodies and colliders are placed in world space, the parents' matrices have to be up to date first.
+        PropagateTransforms.run_now(world);
+        for scene_entity in &self.entities {
+            let entity = created_entities[&scene_entity.id];
+            for component in &scene_entity.info.components {
+                parser
+                    .parse(
+                        Self::remap_ids(component, models, &created_entities),
+                        entity,
+                        world,
+                    )
+                    .map_err(SceneError::ComponentParseFailed)?;
+            }
+        }
+
+        let mut camera = world.write_resource::<Camera>();
+        camera.position = Point3::from(Vector3::from(self.camera.position.clone()));
+        camera.yaw = self.camera.yaw;
+        camera.pitch = self.camera.pitch;
+        camera.set_follow_target_ent(
+            self.camera
+                .follow_target
+                .and_then(|id| created_entities.get(&id).copied()),
+        );
+        let mut dir_light = world.write_resource::<DirectionalLight>();
+        dir_light.yaw = self.directional_light.yaw;
+        dir_light.pitch = self.directional_light.pitch;
+        dir_light.color = wgpu::Color {
+            r: self.directional_light.color.x as f64,
+            g: self.directional_light.color.y as f64,
+            b: self.directional_light.color.z as f64,
+            a: 1.0,
+        };
+        Ok(())
+    }
+    fn remap_ids(
+        component: &Component,
+        models: &HashMap<u32, u32>,
+        entities: &HashMap<u32, Entity>,
+    ) -> Component {
+        let mut component = component.clone();
+        component.model = component.model.and_then(|id| models.get(&id).copied());
+        component.attached_to = component
+            .attached_to
+            .and_then(|id| entities.get(&id).map(|ent| ent.id()));
+        component.parent = component
+            .parent
+            .and_then(|id| entities.get(&id).map(|ent| ent.id()));
+        component
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::animator::Animator;
+    use crate::components::gltfmodel::{GltfModel, GltfNode, GltfPrimitive};
+    use crate::components::hierarchy::Children;
+    use crate::components::morphweights::MorphWeights;
+    use crate::components::skin::Skin;
+    use crate::renderer::model::HorizonModel;
+    use crate::renderer::primitives::mesh::{GltfMesh, VertexAttribValues, VertexAttributeType};
+    use crate::scripting::util::entityinfo::{ColliderInfo, Damping};
+    use crate::scripting::util::RigidBodyType;
+    use rapier3d::na::UnitQuaternion;
+    use serde_json::Value;
+
+    fn world() -> World {
+        let mut world = World::new();
+        world.register::<Transform>();
+        world.register::<Parent>();
+        world.register::<Children>();
+        world.register::<ModelNode>();
+        world.register::<Skin>();
+        world.register::<MorphWeights>();
+        world.register::<Animator>();
+        world.register::<HorizonModel>();
+        world.register::<AssetIdentifier>();
+        world.register::<PointLight>();
+        world.register::<SpotLight>();
+        world.register::<PhysicsHandle>();
+        world.register::<CollisionShape>();
+        world.insert(Camera::new(Point3::new(0.0, 2.0, 5.0), -1.2, 0.3));
+        world.insert(DirectionalLight::new(0.4, 0.8, wgpu::Color::WHITE));
+        world.insert(SkyboxTexture::default());
+        world.insert(PhysicsWorld::new(Vector3::y() * -9.81));
+        world
+    }
+
+    /// A model with a single tetrahedron, enough for the physics parser to build a collider.
+    fn tetrahedron() -> HorizonModel {
+        let mut mesh = GltfMesh::new(gltf::mesh::Mode::Triangles, "tetrahedron".to_string());
+        mesh.add_vertex_attribute(
+            VertexAttributeType::Position,
+            VertexAttribValues::Float32x3(vec![
+                [0.0, 0.0, 0.0],
+                [1.0, 0.0, 0.0],
+                [0.0, 1.0, 0.0],
+                [0.0, 0.0, 1.0],
+            ]),
+        );
+        mesh.add_indices(vec![0, 2, 1, 0, 1, 3, 0, 3, 2, 1, 2, 3]);
+        HorizonModel {
+            meshes: vec![GltfModel {
+                primitives: vec![GltfPrimitive {
+                    material: None,
+                    mesh,
+                }],
+                weights: Vec::new(),
+            }],
+            materials: HashMap::new(),
+            textures: HashMap::new(),
+            name: None,
+            nodes: vec![GltfNode {
+                name: "tetrahedron".to_string(),
+                translation: Vector3::zeros(),
+                rotation: UnitQuaternion::identity(),
+                scale: Vector3::repeat(1.0),
+                mesh: Some(0),
+                skin: None,
+                weights: None,
+                children: Vec::new(),
+            }],
+            root_nodes: vec![0],
+            skins: Vec::new(),
+            animations: Vec::new(),
+        }
+    }
+
+    fn transform(position: Vec3, parent: Option<Entity>, model: Option<Entity>) -> Component {
+        Component {
+            component_type: "transform".to_string(),
+            position: Some(position),
+            rotation: Some(Vec3::new(0.1, 0.2, 0.3)),
+            scale: Some(Vec3::new(1.0, 2.0, 1.0)),
+            model: model.map(|model| model.id()),
+            parent: parent.map(|parent| parent.id()),
+            ..Default::default()
+        }
+    }
+
+    /// Creates an entity the way scripts do.
+    fn spawn(world: &World, components: Vec<Component>) -> Entity {
+        let entity = world.create_entity_unchecked().build();
+        let parser = ComponentParser::default();
+        for component in components {
+            parser.parse(component, entity, world).unwrap();
+        }
+        entity
+    }
+
+    /// Ids differ after loading, every entity has a unique position to find the saved one.
+    fn remap_loaded_ids(loaded: &mut Scene, saved: &Scene) {
+        let ids = loaded
+            .entities
+            .iter()
+            .map(|loaded_entity| {
+                let saved_entity = saved
+                    .entities
+                    .iter()
+                    .find(|saved_entity| {
+                        saved_entity.info.transform.position
+                            == loaded_entity.info.transform.position
+                    })
+                    .expect("loaded an entity which wasn't saved");
+                (loaded_entity.id, saved_entity.id)
+            })
+            .collect::<HashMap<u32, u32>>();
+        for entity in loaded.entities.iter_mut() {
+            entity.id = ids[&entity.id];
+            entity.info.transform.parent = entity.info.transform.parent.map(|id| ids[&id]);
+        }
+        loaded.entities.sort_by_key(|entity| entity.id);
+        loaded.camera.follow_target = loaded.camera.follow_target.map(|id| ids[&id]);
+    }
+
+    /// Rotations and cone angles are converted on the way, numbers only have to be close.
+    fn assert_close(saved: &Value, loaded: &Value, path: &str) {
+        match (saved, loaded) {
+            (Value::Number(saved), Value::Number(loaded)) => {
+                let (saved, loaded) = (saved.as_f64().unwrap(), loaded.as_f64().unwrap());
+                assert!(
+                    (saved - loaded).abs() < 1e-4,
+                    "{}: saved {} but loaded {}",
+                    path,
+                    saved,
+                    loaded
+                );
+            }
+            (Value::Array(saved), Value::Array(loaded)) => {
+                assert_eq!(saved.len(), loaded.len(), "{}", path);
+                for (index, (saved, loaded)) in saved.iter().zip(loaded).enumerate() {
+                    assert_close(saved, loaded, format!("{}[{}]", path, index).as_str());
+                }
+            }
+            (Value::Object(saved), Value::Object(loaded)) => {
+                assert_eq!(
+                    saved.keys().collect::<Vec<_>>(),
+                    loaded.keys().collect::<Vec<_>>(),
+                    "{}",
+                    path
+                );
+                for (key, saved) in saved {
+                    assert_close(saved, &loaded[key], format!("{}.{}", path, key).as_str());
+                }
+            }
+            _ => assert_eq!(saved, loaded, "{}", path),
+        }
+    }
+
+    #[test]
+    fn loaded_scene_has_the_saved_components() {
+        let mut world = world();
+        let model = world
+            .create_entity()
+            .with(AssetIdentifier("models/tetrahedron.gltf".to_string()))
+            .with(tetrahedron())
+            .build();
+        let instance = spawn(
+            &world,
+            vec![
+                transform(Vec3::new(1.0, 0.0, 0.0), None, Some(model)),
+                Component {
+                    component_type: "physics".to_string(),
+                    model: Some(model.id()),
+                    body_type: Some(RigidBodyType::Dynamic),
+                    mass: Some(2.0),
+                    damping: Some(vec![Damping {
+                        damping_type: "linear".to_string(),
+                        amount: 0.5,
+                    }]),
+                    ..Default::default()
+                },
+            ],
+        );
+        let child = spawn(
+            &world,
+            vec![
+                transform(Vec3::new(0.0, 1.0, 0.0), Some(instance), None),
+                Component {
+                    component_type: "pointLight".to_string(),
+                    radius: Some(5.0),
+                    color: Some(Vec3::new(1.0, 0.5, 0.25)),
+                    casts_shadow: Some(true),
+                    ..Default::default()
+                },
+            ],
+        );
+        // the child's world matrix is propagated by the next frame before scripts add anything below it.
+        PropagateTransforms.run_now(&world);
+        spawn(
+            &world,
+            vec![
+                transform(Vec3::new(0.0, 0.0, 1.0), Some(child), Some(model)),
+                Component {
+                    component_type: "physics".to_string(),
+                    model: Some(model.id()),
+                    body_type: Some(RigidBodyType::Dynamic),
+                    mass: Some(1.0),
+                    ..Default::default()
+                },
+                Component {
+                    component_type: "CollisionShape".to_string(),
+                    collision_shape: Some(ColliderInfo {
+                        collision_shape_type: Some("Ball".to_string()),
+                        radius: Some(0.5),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                },
+            ],
+        );
+        spawn(
+            &world,
+            vec![
+                transform(Vec3::new(0.0, 0.0, 2.0), None, None),
+                Component {
+                    component_type: "spotLight".to_string(),
+                    radius: Some(8.0),
+                    color: Some(Vec3::new(0.2, 0.4, 1.0)),
+                    inner_cone_angle: Some(20.0),
+                    outer_cone_angle: Some(30.0),
+                    attached_to: Some(instance.id()),
+                    ..Default::default()
+                },
+            ],
+        );
+        world
+            .write_resource::<Camera>()
+            .set_follow_target_ent(Some(instance));
+
+        let saved = Scene::capture(&world).to_json().unwrap();
+        Scene::clear(&world);
+        world.maintain();
+        // the loaded camera and lighting must not be the ones left over from saving.
+        world.insert(Camera::new(Point3::origin(), 0.0, 0.0));
+        world.insert(DirectionalLight::new(0.0, 0.0, wgpu::Color::BLACK));
+
+        let saved = Scene::from_json(saved.as_bytes()).unwrap();
+        let models = saved
+            .models
+            .iter()
+            .map(|model| (model.id, Scene::loaded_model(&world, &model.path).unwrap()))
+            .collect::<HashMap<u32, u32>>();
+        saved.instantiate(&world, &models).unwrap();
+        world.maintain();
+
+        let mut loaded = Scene::capture(&world);
+        remap_loaded_ids(&mut loaded, &saved);
+        assert_eq!(saved.entities.len(), 4);
+        assert_close(
+            &serde_json::to_value(&saved).unwrap(),
+            &serde_json::to_value(&loaded).unwrap(),
+            "scene",
+        );
+        // the bodies and colliders of the cleared entities are gone.
+        let physics_world = world.read_resource::<PhysicsWorld>();
+        assert_eq!(physics_world.body_set.len(), 2);
+        assert_eq!(physics_world.collider_set.len(), 3);
+        // the grandchild's body and collider are placed below both of its ancestors.
+        let local_matrix = |position: Vector3<f32>| {
+            Transform::new(
+                position,
+                UnitQuaternion::from_euler_angles(0.1, 0.2, 0.3),
+                Vector3::new(1.0, 2.0, 1.0),
+                None,
+            )
+            .local_matrix()
+        };
+        let expected = (local_matrix(Vector3::new(1.0, 0.0, 0.0))
+            * local_matrix(Vector3::new(0.0, 1.0, 0.0)))
+        .transform_point(&Point3::new(0.0, 0.0, 1.0));
+        let transforms = world.read_storage::<Transform>();
+        let physics_handles = world.read_storage::<PhysicsHandle>();
+        let collision_shapes = world.read_storage::<CollisionShape>();
+        let (_, physics_handle, collision_shape) =
+            (&transforms, &physics_handles, &collision_shapes)
+                .join()
+                .next()
+                .unwrap();
+        let body_position = physics_world.body_set[physics_handle.rigid_body_handle].translation();
+        let collider_position = physics_world.collider_set[collision_shape.collider].translation();
+        assert!((body_position - expected.coords).norm() < 1e-4);
+        assert!((collider_position - expected.coords).norm() < 1e-4);
+    }
+}
@@ -72,21 +72,39 @@ use wgpu::{BlendFactor, ColorWrites, Maintain};
 use wasm_bindgen::prelude::*;
 
 use crate::components::assetidentifier::AssetIdentifier;
-use crate::components::gltfmodel::{RawMaterial, RawMesh, RawModel};
+use crate::components::gltfmodel::{RawMaterial, RawMesh, RawMeshLod, RawModel};
 use crate::filesystem::modelimporter::Importer;
 use crate::renderer::bindgroupcontainer::BindGroupContainer;
 use crate::renderer::bindgroups::debugcollision::DebugCollisionBindGroup;
 use crate::renderer::bindgroups::debugtexture::DebugTextureBindGroup;
+use crate::renderer::bindgroups::environment::EnvironmentBindGroup;
+use crate::renderer::bindgroups::frustumculling::FrustumCullingBindGroup;
 use crate::renderer::bindgroups::material::MaterialBindGroup;
 use crate::renderer::bindgroups::skybox::SkyboxBindGroup;
+use crate::renderer::bindgroups::ssao::{SsaoBindGroup, SsaoBlurBindGroup};
+use crate::renderer::bindgroups::tonemapping::ToneMappingBindGroup;
+use crate::renderer::bindgroups::transparent::TransparentBindGroup;
 use crate::renderer::model::HorizonModel;
 use crate::renderer::pipelines::debugcollision::DebugCollisionPipeline;
 use crate::renderer::pipelines::debugtexturepipeline::DebugTexturePipeline;
+use crate::renderer::pipelines::frustumcullingpipeline::FrustumCullingPipeline;
+use crate::renderer::pipelines::luminancepipeline::{
+    AverageLuminancePipeline, LuminanceHistogramPipeline,
+};
 use crate::renderer::pipelines::skyboxpipeline::SkyboxPipeline;
-use crate::renderer::primitives::material::{GltfMaterial, MaterialUniform};
+use crate::renderer::pipelines::ssaopipeline::{SsaoBlurPipeline, SsaoPipeline};
+use crate::renderer::pipelines::tonemappingpipeline::ToneMappingPipeline;
+use crate::renderer::pipelines::transparentpipeline::TransparentPipeline;
+use crate::renderer::postprocessing::PostProcessStack;
+use crate::renderer::primitives::material::{GltfMaterial, MaterialUniform, TextureKey};
+pub use crate::renderer::primitives::materialdefinition::{
+    MaterialDefinition, MaterialError, MaterialParameterType,
+};
 use crate::renderer::primitives::mesh::{VertexAttribValues, VertexAttributeType};
 use crate::renderer::primitives::texture::Texture;
-use crate::renderer::primitives::vertex::MeshVertexData;
+use crate::renderer::primitives::vertex::{MeshVertexData, MorphTargetDelta};
+use crate::renderer::utils::depthpyramidgenerator::DepthPyramidGenerator;
+use crate::renderer::utils::environmentmapgenerator::EnvironmentMapGenerator;
 use crate::resources::bindingresourcecontainer::BufferTypes::{
     CanvasSize, Instances, Normals, PointLight, ShadowUniform, Skybox, SpotLight, Tiling, Uniform,
 };
@@ -101,13 +119,19 @@ use crate::resources::camera::CameraController;
 use crate::resources::defaulttexturecontainer::{DefaultTextureContainer, DefaultTextureTypes};
 use crate::resources::deltatime::DeltaTime;
 use crate::resources::eguicontainer::EguiContainer;
+use crate::resources::framecapture::FrameCapture;
+pub use crate::resources::framecapture::{CaptureTarget, FrameCaptureError};
 use crate::resources::gpuquerysets::GpuQuerySetContainer;
+use crate::resources::materiallibrary::MaterialLibrary;
+use crate::resources::morphtargetstorage::MorphTargetStorage;
 use crate::resources::projection::Projection;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::resources::shaderhotreload::ShaderHotReload;
 use crate::resources::windowstate::WindowState;
 use crate::scripting::scriptingengine::HorizonScriptingEngine;
 use crate::scripting::ScriptingError;
 use crate::systems::events::handlelifecycleevents::{
-    HandleInitCallbacks, InvokeEntityCollisionHandlers,
+    HandleInitCallbacks, InvokeAnimationFinishedHandlers, InvokeEntityCollisionHandlers,
 };
 use crate::systems::events::handlewindowevents::{
     HandleKeyboardEvent, HandleMouseInputEvent, HandleMouseMoveEvent,
@@ -283,6 +307,139 @@ pub async fn setup() -> (EventLoop<CustomEvent>, Window) {
     winit_resources
 }
 
+/// Sets up the engine without a window, every frame is rendered into an offscreen texture.
+/// Scripts are not loaded, the world can be populated directly through the [`ECSContainer`].
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn setup_headless(width: u32, height: u32) {
+    let _ = env_logger::try_init();
+    let mut ecs = ECSContainer::global_mut();
+    let state = State::new_headless(width, height).await;
+    ecs.world.insert(EguiContainer {
+        render_pass: RenderPass::new(&state.device, state.sc_descriptor.format, 1),
+        state: egui_winit::State::from_pixels_per_point(4096, state.scale_factor as f32),
+        context: egui::Context::default(),
+    });
+    ecs.setup(state);
+    setup_pipelines(&mut ecs.world);
+}
+
+/// Runs the dispatcher once for a headless state and waits for the GPU to finish the frame.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn render_headless_frame() {
+    {
+        let ecs = ECSContainer::global();
+        let mut render_callbacks =
+            crate::systems::events::handlelifecycleevents::HandleOnRenderCallbacks {};
+        render_callbacks.run_now(&ecs.world);
+        let state = ecs.world.read_resource::<State>();
+        let mut egui_container = ecs.world.write_resource::<EguiContainer>();
+        let inputs = egui::RawInput {
+            screen_rect: Some(egui::Rect::from_min_size(
+                Default::default(),
+                egui::vec2(
+                    state.sc_descriptor.width as f32,
+                    state.sc_descriptor.height as f32,
+                ),
+            )),
+            ..Default::default()
+        };
+        egui_container.context.begin_frame(inputs);
+    }
+    {
+        let mut container = ECSContainer::global_mut();
+        container.dispatch();
+    }
+    let container = ECSContainer::global();
+    container
+        .world
+        .read_resource::<State>()
+        .device
+        .poll(Maintain::Wait);
+}
+
+/// Loads a gltf model from the given path without going through the event loop.
+/// Returns the id of the model entity which can be referenced by transforms.
+#[cfg(not(target_arch = "wasm32"))]
+pub async fn load_model_headless(path: &str) -> Result<u32, String> {
+    let path = std::path::Path::new(path);
+    let importer = Importer::new(Box::new(
+        crate::filesystem::nativefileloader::NativeFileLoader::new(
+            path.parent().unwrap_or_else(|| std::path::Path::new("")).to_path_buf(),
+        ),
+    ));
+    let file_name = path.file_name().unwrap().to_str().unwrap();
+    let gltf_contents = importer
+        .import_gltf_model(file_name)
+        .await
+        .map_err(|e| format!("{:?}", e))?;
+    let mut model = ModelBuilder::create_gltf_model(gltf_contents).map_err(|e| format!("{:?}", e))?;
+    model.name = Some(file_name.to_string());
+    let (sender, mut receiver) =
+        futures::channel::oneshot::channel::<Result<Entity, ScriptingError>>();
+    handle_model_load(CustomEvent::RequestModelLoad(model, sender));
+    match receiver.try_recv() {
+        Ok(Some(Ok(entity))) => Ok(entity.id()),
+        Ok(Some(Err(e))) => Err(format!("{:?}", e)),
+        _ => Err("model load was cancelled".to_string()),
+    }
+}
+
+/// Creates an entity from the same json description that scripts pass to `op_create_entity`.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn create_entity_headless(entity_info: &str) -> Result<u32, String> {
+    let entity_info =
+        deno_core::serde_json::from_str::<crate::scripting::util::entityinfo::EntityInfo>(entity_info)
+            .map_err(|e| e.to_string())?;
+    crate::scripting::scriptingfunctions::ScriptingFunctions::create_entity(entity_info)
+        .map(|entity| entity.get_id())
+        .map_err(|e| format!("{:?}", e))
+}
+
+/// Registers a custom material, a material with the same name is replaced. It's built before the next frame.
+pub fn register_material(definition: MaterialDefinition) -> Result<(), MaterialError> {
+    ECSContainer::global()
+        .world
+        .write_resource::<MaterialLibrary>()
+        .register(definition)
+}
+
+/// Draws the entity with a registered material, the same as `entity.setMaterial` in scripts.
+pub fn set_entity_material(
+    entity_id: u32,
+    material_name: &str,
+    parameters: HashMap<String, Vec<f32>>,
+) -> Result<(), String> {
+    crate::scripting::scriptingfunctions::ScriptingFunctions::set_material(
+        entity_id,
+        material_name.to_string(),
+        parameters,
+    )
+    .map_err(|e| format!("{:?}", e))
+}
+
+/// Reads back the final frame or one of the intermediate pass targets of the last rendered frame.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn capture_headless_target(target: CaptureTarget) -> Result<image::RgbaImage, FrameCaptureError> {
+    let ecs = ECSContainer::global();
+    let state = ecs.world.read_resource::<State>();
+    let binding_resource_container = ecs.world.read_resource::<BindingResourceContainer>();
+    ecs.world
+        .read_resource::<FrameCapture>()
+        .capture_target(&state, &binding_resource_container, target)
+}
+
+/// Makes the passes which share the hdr scene target keep a copy of their output,
+/// so [`CaptureTarget::Forward`] and [`CaptureTarget::Skybox`] can be captured after the next frame.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn keep_headless_pass_outputs(keep: bool) {
+    let ecs = ECSContainer::global();
+    let mut frame_capture = ecs.world.write_resource::<FrameCapture>();
+    frame_capture.keep_pass_outputs = keep;
+    if !keep {
+        frame_capture.pass_outputs.clear();
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 pub fn run(event_loop: EventLoop<CustomEvent>, window: winit::window::Window, runtime: Runtime) {
     run_init();
@@ -336,8 +493,11 @@ fn handle_model_load(custom_event: CustomEvent) {
         let container = ECSContainer::global();
         let state = container.world.read_resource::<State>();
         let default_texture_container = container.world.read_resource::<DefaultTextureContainer>();
+        let binding_resource_container =
+            container.world.read_resource::<BindingResourceContainer>();
+        let mut morph_target_storage = container.world.write_resource::<MorphTargetStorage>();
         let mut gpu_mats = HashMap::new();
-        let mut loaded_gpu_textures: HashMap<usize, Texture> = HashMap::new();
+        let mut loaded_gpu_textures: HashMap<TextureKey, Texture> = HashMap::new();
         for (index, material_data) in &data.materials {
             material_data.upload_material_textures_to_gpu(
                 &state.device,
@@ -345,20 +505,20 @@ fn handle_model_load(custom_event: CustomEvent) {
                 &data.textures,
                 &mut loaded_gpu_textures,
             );
+            let uniform_buffer = material_data.create_uniform_buffer(&state.device);
             let bind_group = material_data.register_bind_group(
                 &state.device,
                 &loaded_gpu_textures,
                 &default_texture_container.elements,
+                &uniform_buffer,
             );
             gpu_mats.insert(
                 *index,
-                RawMaterial {
-                    bind_group_container: bind_group,
-                },
+                RawMaterial::new(bind_group, uniform_buffer, material_data.is_transparent()),
             );
         }
         let mut meshes = Vec::new();
-        for mesh in &data.meshes {
+        for (mesh_index, mesh) in data.meshes.iter().enumerate() {
             for primitive in &mesh.primitives {
                 if let Some(VertexAttribValues::Float32x3(pos)) = primitive
                     .mesh
@@ -418,6 +578,49 @@ fn handle_model_load(custom_event: CustomEvent) {
                     {
                         joint_ids.copy_from_slice(joint_id_values.as_slice());
                     }
+                    let mut morph_indices = vec![u32::MAX; vertex_count];
+                    let morph_targets = &primitive.mesh.morph_targets;
+                    if !morph_targets.is_empty() {
+                        // deltas are stored per vertex so a vertex's targets are next to each other.
+                        match morph_target_storage.allocate(vertex_count * morph_targets.len()) {
+                            Some(base_index) => {
+                                let mut deltas = Vec::with_capacity(
+                                    vertex_count * morph_targets.len(),
+                                );
+                                for (i, morph_index) in morph_indices.iter_mut().enumerate() {
+                                    *morph_index =
+                                        base_index + (i * morph_targets.len()) as u32;
+                                    for target in morph_targets {
+                                        let position =
+                                            target.positions.get(i).copied().unwrap_or_default();
+                                        let normal = target
+                                            .normals
+                                            .as_ref()
+                                            .and_then(|normals| normals.get(i).copied())
+                                            .unwrap_or_default();
+                                        deltas.push(MorphTargetDelta {
+                                            position: [position[0], position[1], position[2], 0.0],
+                                            normal: [normal[0], normal[1], normal[2], 0.0],
+                                        });
+                                    }
+                                }
+                                state.queue.write_buffer(
+                                    binding_resource_container.buffers[BufferTypes::MorphTargets]
+                                        .as_ref()
+                                        .unwrap(),
+                                    (std::mem::size_of::<MorphTargetDelta>()
+                                        * base_index as usize)
+                                        as wgpu::BufferAddress,
+                                    bytemuck::cast_slice(&deltas),
+                                );
+                            }
+                            None => log::warn!(
+                                target: "model_load",
+                                "morph target buffer is full, {} is drawn without morph targets",
+                                primitive.mesh.name
+                            ),
+                        }
+                    }
                     let mut vertex_data = Vec::new();
                     for i in 0..vertex_count {
                         vertex_data.push(MeshVertexData {
@@ -428,6 +631,7 @@ fn handle_model_load(custom_event: CustomEvent) {
                             vertex_color: vertex_colors[i],
                             tangent: tangents[i],
                             joint_weight: weights[i],
+                            morph_index: morph_indices[i],
                         });
                     }
                     let vertex_buffer =
@@ -440,7 +644,22 @@ fn handle_model_load(custom_event: CustomEvent) {
                                 usage: wgpu::BufferUsages::VERTEX | wgpu::BufferUsages::COPY_DST,
                                 contents: bytemuck::cast_slice(vertex_data.as_slice()),
                             });
-                    let indices = primitive.mesh.indices.as_ref().unwrap();
+                    // the lower levels of detail follow the base indices in the same buffer.
+                    let base_indices = primitive.mesh.indices.as_ref().unwrap();
+                    let mut indices = base_indices.clone();
+                    let mut lods = vec![RawMeshLod {
+                        first_index: 0,
+                        index_count: base_indices.len() as u32,
+                        screen_size: f32::MAX,
+                    }];
+                    for lod in &primitive.mesh.lods {
+                        lods.push(RawMeshLod {
+                            first_index: indices.len() as u32,
+                            index_count: lod.indices.len() as u32,
+                            screen_size: lod.screen_size,
+                        });
+                        indices.extend_from_slice(&lod.indices);
+                    }
                     let index_buffer =
                         state
                             .device
@@ -457,7 +676,9 @@ fn handle_model_load(custom_event: CustomEvent) {
                         index_buffer,
                         vertex_buffer,
                         material_index: primitive.material.unwrap_or(0),
-                        index_buffer_len: indices.len() as u32,
+                        mesh_index,
+                        lods,
+                        bounds: primitive.mesh.bounds,
                     });
                 } else {
                     sender
@@ -472,6 +693,7 @@ fn handle_model_load(custom_event: CustomEvent) {
         let raw_model = RawModel {
             meshes,
             materials: gpu_mats,
+            textures: loaded_gpu_textures,
         };
         let identifier = data.name.as_ref().unwrap().clone();
         let model_entity = container
@@ -515,6 +737,11 @@ fn handle_skybox_texture_override(skybox_event: CustomEvent) {
                     .unwrap(),
             ),
         );
+        // the lighting maps are derived from the skybox.
+        container
+            .world
+            .read_resource::<EnvironmentMapGenerator>()
+            .generate(&state.device, &state.queue, &binding_resource_container);
         sender.send(()).unwrap();
     }
 }
@@ -556,6 +783,8 @@ fn handle_redraw_request(window: &Window, control_flow: &mut winit::event_loop::
 
     let mut collision_callbacks = InvokeEntityCollisionHandlers;
     collision_callbacks.run_now(&container.world);
+    let mut animation_callbacks = InvokeAnimationFinishedHandlers;
+    animation_callbacks.run_now(&container.world);
 }
 fn handle_user_events(event: CustomEvent) {
     match event {
@@ -649,13 +878,43 @@ fn setup_pipelines(world: &mut World) {
     DeferredBindGroup::get_resources(&state.device, &mut binding_resource_container);
     TilingBindGroup::get_resources(&state.device, &mut binding_resource_container);
     SkyboxBindGroup::get_resources(&state.device, &mut binding_resource_container);
+    EnvironmentBindGroup::get_resources(&state.device, &mut binding_resource_container);
     DebugTextureBindGroup::get_resources(&state.device, &mut binding_resource_container);
     DebugCollisionBindGroup::get_resources(&state.device, &mut binding_resource_container);
+    FrustumCullingBindGroup::get_resources(&state.device, &mut binding_resource_container);
+    ToneMappingBindGroup::get_resources(&state.device, &mut binding_resource_container);
+    SsaoBindGroup::get_resources(&state.device, &mut binding_resource_container);
     GBuffer::generate_g_buffers(
         &state.device,
         &state.sc_descriptor,
         &mut binding_resource_container,
     );
+    DepthPyramidGenerator::create_pyramid(
+        &state.device,
+        state.sc_descriptor.width,
+        state.sc_descriptor.height,
+        &mut binding_resource_container,
+    );
+    ToneMappingBindGroup::create_hdr_target(
+        &state.device,
+        &state.sc_descriptor,
+        &mut binding_resource_container,
+    );
+    TransparentBindGroup::create_scene_color(
+        &state.device,
+        &state.sc_descriptor,
+        &mut binding_resource_container,
+    );
+    PostProcessStack::create_targets(
+        &state.device,
+        &state.sc_descriptor,
+        &mut binding_resource_container,
+    );
+    SsaoBindGroup::create_targets(
+        &state.device,
+        &state.sc_descriptor,
+        &mut binding_resource_container,
+    );
 
     let uniform_container = UniformBindGroup::create_container(
         &state.device,
@@ -681,6 +940,24 @@ fn setup_pipelines(world: &mut World) {
             binding_resource_container.buffers[BufferTypes::ShadowCascadeLengths]
                 .as_ref()
                 .unwrap(),
+            binding_resource_container.buffers[BufferTypes::JointMatrices]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::InstanceDeformations]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::MorphTargets]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::MorphWeights]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::ShadowSettingsUniform]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::VisibleInstances]
+                .as_ref()
+                .unwrap(),
         ),
     );
 
@@ -693,6 +970,50 @@ fn setup_pipelines(world: &mut World) {
             binding_resource_container.buffers[Instances]
                 .as_ref()
                 .unwrap(),
+            binding_resource_container.buffers[BufferTypes::JointMatrices]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::InstanceDeformations]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::MorphTargets]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::MorphWeights]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::VisibleInstances]
+                .as_ref()
+                .unwrap(),
+        ),
+    );
+    let frustum_culling_container = FrustumCullingBindGroup::create_container(
+        &state.device,
+        (
+            binding_resource_container.buffers[BufferTypes::FrustumCullingUniform]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[Instances]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::CullingInstances]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::CullingGroups]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::IndirectDraws]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::VisibleInstances]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::DepthPyramid]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::CullingStats]
+                .as_ref()
+                .unwrap(),
         ),
     );
 
@@ -708,6 +1029,12 @@ fn setup_pipelines(world: &mut World) {
             binding_resource_container.buffers[SpotLight]
                 .as_ref()
                 .unwrap(),
+            binding_resource_container.buffers[BufferTypes::ShadowAtlasTiles]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::ShadowAtlas]
+                .as_ref()
+                .unwrap(),
         ),
     );
     let tiling_container = TilingBindGroup::create_container(
@@ -751,6 +1078,9 @@ fn setup_pipelines(world: &mut World) {
                 .as_ref()
                 .unwrap(),
             binding_resource_container.buffers[Tiling].as_ref().unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::AmbientOcclusionBlurred]
+                .as_ref()
+                .unwrap(),
         ),
     );
     let skybox_container = SkyboxBindGroup::create_container(
@@ -765,6 +1095,81 @@ fn setup_pipelines(world: &mut World) {
                 .unwrap(),
         ),
     );
+    let environment_container = EnvironmentBindGroup::create_container(
+        &state.device,
+        (
+            binding_resource_container.texture_views[TextureViewTypes::Irradiance]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::PrefilteredEnvironment]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::BrdfLut]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.samplers[SamplerTypes::Environment]
+                .as_ref()
+                .unwrap(),
+        ),
+    );
+    let transparent_container = TransparentBindGroup::create_container(
+        &state.device,
+        (
+            binding_resource_container.texture_views[TextureViewTypes::Irradiance]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::PrefilteredEnvironment]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::BrdfLut]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.samplers[SamplerTypes::Environment]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[TextureViewTypes::SceneColor]
+                .as_ref()
+                .unwrap(),
+        ),
+    );
+    let tone_mapping_container = ToneMappingBindGroup::create_container(
+        &state.device,
+        (
+            binding_resource_container.texture_views[TextureViewTypes::HdrScene]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::ToneMappingUniform]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::LuminanceHistogram]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::AdaptedLuminance]
+                .as_ref()
+                .unwrap(),
+        ),
+    );
+    let ssao_container = SsaoBindGroup::create_container(
+        &state.device,
+        (
+            binding_resource_container.texture_views[DeferredPosition]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.texture_views[DeferredNormals]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[BufferTypes::SsaoUniform]
+                .as_ref()
+                .unwrap(),
+            binding_resource_container.buffers[Uniform]
+                .as_ref()
+                .unwrap(),
+        ),
+    );
+    let environment_map_generator = EnvironmentMapGenerator::new(&state.device);
+    environment_map_generator.generate(&state.device, &state.queue, &binding_resource_container);
+    let depth_pyramid_generator = DepthPyramidGenerator::new(&state.device);
+    let post_process_stack = PostProcessStack::new(&state.device, &state.sc_descriptor);
 
     let debug_texture_container = DebugTextureBindGroup::create_container(
         &state.device,
@@ -789,16 +1194,7 @@ fn setup_pipelines(world: &mut World) {
             &crate::renderer::bindgroups::material::MaterialBindGroup::get_layout(&state.device),
             &uniform_container.layout,
         ),
-        &[
-            wgpu::TextureFormat::Rgba32Float.into(),
-            wgpu::TextureFormat::Rgba32Float.into(),
-            wgpu::TextureFormat::Rgba32Float.into(),
-            wgpu::ColorTargetState {
-                format: wgpu::TextureFormat::Bgra8Unorm,
-                blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
-                write_mask: ColorWrites::all(),
-            },
-        ],
+        &GBufferPipeline::targets(),
     );
     let forward_pipeline = ForwardPipeline::create_pipeline(
         &state.device,
@@ -806,13 +1202,17 @@ fn setup_pipelines(world: &mut World) {
             &deferred_container.layout,
             &uniform_container.layout,
             &light_container.layout,
+            &environment_container.layout,
         ),
-        &[state.sc_descriptor.format.into()],
+        &[ToneMappingBindGroup::HDR_FORMAT.into()],
     );
 
     let shadow_pipeline = ShadowPipeline::create_pipeline(
         &state.device,
-        &shadow_container.layout,
+        (
+            &shadow_container.layout,
+            &MaterialBindGroup::get_layout(&state.device),
+        ),
         &[wgpu::TextureFormat::Depth32Float.into()],
     );
 
@@ -829,11 +1229,54 @@ fn setup_pipelines(world: &mut World) {
             &tiling_container.layout,
         ),
     );
+    let frustum_culling_pipeline = FrustumCullingPipeline::create_compute_pipeline(
+        &state.device,
+        &frustum_culling_container.layout,
+    );
     let skybox_pipeline = SkyboxPipeline::create_pipeline(
         &state.device,
         &skybox_container.layout,
+        &[ToneMappingBindGroup::HDR_FORMAT.into()],
+    );
+
+    let transparent_pipeline = TransparentPipeline::create_pipeline(
+        &state.device,
+        (
+            &uniform_container.layout,
+            &MaterialBindGroup::get_layout(&state.device),
+            &light_container.layout,
+            &transparent_container.layout,
+        ),
+        &[wgpu::ColorTargetState {
+            format: ToneMappingBindGroup::HDR_FORMAT,
+            blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+            write_mask: ColorWrites::all(),
+        }],
+    );
+
+    let luminance_histogram_pipeline = LuminanceHistogramPipeline::create_compute_pipeline(
+        &state.device,
+        &tone_mapping_container.layout,
+    );
+    let average_luminance_pipeline = AverageLuminancePipeline::create_compute_pipeline(
+        &state.device,
+        &tone_mapping_container.layout,
+    );
+    let tone_mapping_pipeline = ToneMappingPipeline::create_pipeline(
+        &state.device,
+        &tone_mapping_container.layout,
         &[state.sc_descriptor.format.into()],
     );
+    let ssao_pipeline = SsaoPipeline::create_pipeline(
+        &state.device,
+        &ssao_container.layout,
+        &[SsaoBindGroup::AMBIENT_OCCLUSION_FORMAT.into()],
+    );
+    let ssao_blur_pipeline = SsaoBlurPipeline::create_pipeline(
+        &state.device,
+        &SsaoBlurBindGroup::get_layout(&state.device),
+        &[SsaoBindGroup::AMBIENT_OCCLUSION_FORMAT.into()],
+    );
 
     let debug_texture_pipeline = DebugTexturePipeline::create_pipeline(
         &state.device,
@@ -843,7 +1286,7 @@ fn setup_pipelines(world: &mut World) {
     let debug_collision_pipeline = DebugCollisionPipeline::create_pipeline(
         &state.device,
         (&uniform_container.layout, &debug_collision_container.layout),
-        &[state.sc_descriptor.format.into()],
+        &[ToneMappingBindGroup::HDR_FORMAT.into()],
     );
 
     drop(state);
@@ -853,9 +1296,19 @@ fn setup_pipelines(world: &mut World) {
     world.insert(ShadowPipeline(shadow_pipeline));
     world.insert(GBufferPipeline(gbuffer_pipeline));
     world.insert(LightCullingPipeline(lightculling_pipeline));
+    world.insert(FrustumCullingPipeline(frustum_culling_pipeline));
     world.insert(SkyboxPipeline(skybox_pipeline));
+    world.insert(TransparentPipeline(transparent_pipeline));
+    world.insert(LuminanceHistogramPipeline(luminance_histogram_pipeline));
+    world.insert(AverageLuminancePipeline(average_luminance_pipeline));
+    world.insert(ToneMappingPipeline(tone_mapping_pipeline));
+    world.insert(SsaoPipeline(ssao_pipeline));
+    world.insert(SsaoBlurPipeline(ssao_blur_pipeline));
     world.insert(DebugTexturePipeline(debug_texture_pipeline));
     world.insert(DebugCollisionPipeline(debug_collision_pipeline));
+    world.insert(environment_map_generator);
+    world.insert(depth_pyramid_generator);
+    world.insert(post_process_stack);
     world
         .create_entity()
         .with(UniformBindGroup)
@@ -887,6 +1340,26 @@ fn setup_pipelines(world: &mut World) {
         .with(SkyboxBindGroup)
         .with(skybox_container)
         .build();
+    world
+        .create_entity()
+        .with(EnvironmentBindGroup)
+        .with(environment_container)
+        .build();
+    world
+        .create_entity()
+        .with(TransparentBindGroup)
+        .with(transparent_container)
+        .build();
+    world
+        .create_entity()
+        .with(ToneMappingBindGroup)
+        .with(tone_mapping_container)
+        .build();
+    world
+        .create_entity()
+        .with(SsaoBindGroup)
+        .with(ssao_container)
+        .build();
     world
         .create_entity()
         .with(DebugTextureBindGroup)
@@ -897,4 +1370,257 @@ fn setup_pipelines(world: &mut World) {
         .with(DebugCollisionBindGroup)
         .with(debug_collision_container)
         .build();
+    world
+        .create_entity()
+        .with(FrustumCullingBindGroup)
+        .with(frustum_culling_container)
+        .build();
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let surface_format = world.read_resource::<State>().sc_descriptor.format;
+        let mut shader_hot_reload = ShaderHotReload::default();
+        register_shader_reloaders(&mut shader_hot_reload, surface_format);
+        world.insert(shader_hot_reload);
+    }
+}
+
+/// Rebuilds the pipelines of `setup_pipelines` from their current bind group layouts when their shader changes,
+/// they replace the current ones once every pipeline using the shader was built.
+#[cfg(not(target_arch = "wasm32"))]
+fn register_shader_reloaders(
+    shader_hot_reload: &mut ShaderHotReload,
+    surface_format: wgpu::TextureFormat,
+) {
+    shader_hot_reload.register("gbuffer.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<GBufferPipeline>();
+        let layouts: Vec<_> = (0..2)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            GBufferPipeline::create_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1]),
+                &GBufferPipeline::targets(),
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<GBufferPipeline>().0 = rebuilt
+        }))
+    });
+    // the materials are generated from the template, they're built again by `UpdateMaterials`.
+    shader_hot_reload.register("material.wgsl", |_| {
+        Ok(ShaderHotReload::swap(|world| {
+            world.write_resource::<MaterialLibrary>().rebuild_all()
+        }))
+    });
+    shader_hot_reload.register("forward.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<ForwardPipeline>();
+        let layouts: Vec<_> = (0..4)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            ForwardPipeline::create_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1], &layouts[2], &layouts[3]),
+                &[ToneMappingBindGroup::HDR_FORMAT.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<ForwardPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("shadow.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<ShadowPipeline>();
+        let layouts: Vec<_> = (0..2)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            ShadowPipeline::create_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1]),
+                &[wgpu::TextureFormat::Depth32Float.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<ShadowPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("light.wgsl", move |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<LightPipeline>();
+        let layouts: Vec<_> = (0..2)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            LightPipeline::create_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1]),
+                &[surface_format.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<LightPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("lightculling.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<LightCullingPipeline>();
+        let layouts: Vec<_> = (0..3)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            LightCullingPipeline::create_compute_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1], &layouts[2]),
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<LightCullingPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("frustumculling.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<FrustumCullingPipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            FrustumCullingPipeline::create_compute_pipeline(&state.device, &layout)
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<FrustumCullingPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("skybox.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<SkyboxPipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            SkyboxPipeline::create_pipeline(
+                &state.device,
+                &layout,
+                &[ToneMappingBindGroup::HDR_FORMAT.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<SkyboxPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("transparent.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<TransparentPipeline>();
+        let layouts: Vec<_> = (0..4)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            TransparentPipeline::create_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1], &layouts[2], &layouts[3]),
+                &[wgpu::ColorTargetState {
+                    format: ToneMappingBindGroup::HDR_FORMAT,
+                    blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::all(),
+                }],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<TransparentPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("luminance.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<LuminanceHistogramPipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            LuminanceHistogramPipeline::create_compute_pipeline(&state.device, &layout)
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<LuminanceHistogramPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("luminance.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<AverageLuminancePipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            AverageLuminancePipeline::create_compute_pipeline(&state.device, &layout)
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<AverageLuminancePipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("tonemapping.wgsl", move |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<ToneMappingPipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            ToneMappingPipeline::create_pipeline(&state.device, &layout, &[surface_format.into()])
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<ToneMappingPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("ssao.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<SsaoPipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            SsaoPipeline::create_pipeline(
+                &state.device,
+                &layout,
+                &[SsaoBindGroup::AMBIENT_OCCLUSION_FORMAT.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<SsaoPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("ssaoblur.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<SsaoBlurPipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            SsaoBlurPipeline::create_pipeline(
+                &state.device,
+                &layout,
+                &[SsaoBindGroup::AMBIENT_OCCLUSION_FORMAT.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<SsaoBlurPipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("textureRenderer.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<DebugTexturePipeline>();
+        let layout = pipeline.0.get_bind_group_layout(0);
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            DebugTexturePipeline::create_pipeline(
+                &state.device,
+                &layout,
+                &[wgpu::TextureFormat::Bgra8Unorm.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<DebugTexturePipeline>().0 = rebuilt
+        }))
+    });
+    shader_hot_reload.register("colliderRenderer.wgsl", |world| {
+        let state = world.read_resource::<State>();
+        let pipeline = world.read_resource::<DebugCollisionPipeline>();
+        let layouts: Vec<_> = (0..2)
+            .map(|i| pipeline.0.get_bind_group_layout(i))
+            .collect();
+        let rebuilt = ShaderHotReload::try_create(&state.device, || {
+            DebugCollisionPipeline::create_pipeline(
+                &state.device,
+                (&layouts[0], &layouts[1]),
+                &[ToneMappingBindGroup::HDR_FORMAT.into()],
+            )
+        })?;
+        Ok(ShaderHotReload::swap(move |world| {
+            world.write_resource::<DebugCollisionPipeline>().0 = rebuilt
+        }))
+    });
 }
@@ -1,9 +1,15 @@
 pub mod bindgroupcontainer;
 pub mod bindgroups;
+pub mod meshsimplifier;
 pub mod model;
 pub mod modelbuilder;
 pub mod pass;
 pub mod pipelines;
+pub mod postprocessing;
 pub mod primitives;
+pub mod rendergraph;
+pub mod shaderconstants;
+pub mod shaderlibrary;
+pub mod shaderpreprocessor;
 pub mod state;
 pub mod utils;
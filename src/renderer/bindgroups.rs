@@ -7,12 +7,17 @@ use super::bindgroupcontainer::BindGroupContainer;
 pub mod debugcollision;
 pub mod debugtexture;
 pub mod deferred;
+pub mod environment;
+pub mod frustumculling;
 pub mod gbuffer;
 pub mod lighting;
 pub mod material;
 pub mod shadow;
 pub mod skybox;
+pub mod ssao;
 pub mod tiling;
+pub mod tonemapping;
+pub mod transparent;
 pub mod uniforms;
 
 pub trait HorizonBindGroup<'a> {
@@ -31,6 +31,7 @@ impl<'a> HorizonBindGroup<'a> for DeferredBindGroup {
         &'a wgpu::Buffer,
         &'a wgpu::Buffer,
         &'a wgpu::Buffer,
+        &'a wgpu::TextureView,
     );
 
     fn get_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
@@ -113,6 +114,17 @@ impl<'a> HorizonBindGroup<'a> for DeferredBindGroup {
                     },
                     count: None,
                 },
+                // the blurred screen-space ambient occlusion.
+                wgpu::BindGroupLayoutEntry {
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    binding: 8,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                },
             ],
         })
     }
@@ -130,6 +142,7 @@ impl<'a> HorizonBindGroup<'a> for DeferredBindGroup {
             canvas_size_buffer,
             light_id_buffer,
             tile_info_buffer,
+            ambient_occlusion,
         ) = resources;
         let deferred_bind_group_layout = Self::get_layout(device);
         let deferred_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
@@ -167,6 +180,10 @@ impl<'a> HorizonBindGroup<'a> for DeferredBindGroup {
                     binding: 7,
                     resource: tile_info_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: wgpu::BindingResource::TextureView(ambient_occlusion),
+                },
             ],
             label: Some("deferred_bind_group"),
         });
@@ -0,0 +1,164 @@
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::{BindingResourceContainer, HorizonBindGroup};
+use specs::*;
+use wgpu::{BindGroupLayout, Device, ShaderStages};
+
+use crate::resources::bindingresourcecontainer::{SamplerTypes, TextureTypes, TextureViewTypes};
+
+/// Image based lighting maps generated from the skybox by the `EnvironmentMapGenerator`.
+#[derive(Default, Component)]
+#[storage(NullStorage)]
+pub struct EnvironmentBindGroup;
+
+impl EnvironmentBindGroup {
+    pub const IRRADIANCE_SIZE: u32 = 32;
+    pub const PREFILTERED_SIZE: u32 = 128;
+    /// Each mip level of the prefiltered map is filtered for a higher roughness, the last one for fully rough surfaces.
+    pub const PREFILTERED_MIPS: u32 = 5;
+    pub const BRDF_LUT_SIZE: u32 = 256;
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+}
+
+impl<'a> HorizonBindGroup<'a> for EnvironmentBindGroup {
+    type BindingResources = (
+        &'a wgpu::TextureView,
+        &'a wgpu::TextureView,
+        &'a wgpu::TextureView,
+        &'a wgpu::Sampler,
+    );
+
+    fn get_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("environment_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                },
+            ],
+        })
+    }
+
+    fn create_container(device: &Device, resources: Self::BindingResources) -> BindGroupContainer {
+        let (irradiance, prefiltered, brdf_lut, sampler) = resources;
+        let bind_group_layout = Self::get_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("environment_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(irradiance),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(prefiltered),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(brdf_lut),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+            ],
+        });
+        BindGroupContainer::new(bind_group_layout, bind_group)
+    }
+
+    fn get_resources(device: &Device, resource_container: &mut BindingResourceContainer) {
+        let environment_sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("environment_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let usage = wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::STORAGE_BINDING;
+        let cube_texture = |label, size, mip_level_count| {
+            device.create_texture(&wgpu::TextureDescriptor {
+                label: Some(label),
+                size: wgpu::Extent3d {
+                    width: size,
+                    height: size,
+                    depth_or_array_layers: 6,
+                },
+                mip_level_count,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: Self::FORMAT,
+                usage,
+            })
+        };
+        let irradiance_texture = cube_texture("irradiance_texture", Self::IRRADIANCE_SIZE, 1);
+        let prefiltered_texture = cube_texture(
+            "prefiltered_environment_texture",
+            Self::PREFILTERED_SIZE,
+            Self::PREFILTERED_MIPS,
+        );
+        let brdf_lut_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("brdf_lut_texture"),
+            size: wgpu::Extent3d {
+                width: Self::BRDF_LUT_SIZE,
+                height: Self::BRDF_LUT_SIZE,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage,
+        });
+        let cube_view_descriptor = wgpu::TextureViewDescriptor {
+            dimension: Some(wgpu::TextureViewDimension::Cube),
+            ..Default::default()
+        };
+        resource_container.texture_views[TextureViewTypes::Irradiance] =
+            Some(irradiance_texture.create_view(&cube_view_descriptor));
+        resource_container.texture_views[TextureViewTypes::PrefilteredEnvironment] =
+            Some(prefiltered_texture.create_view(&cube_view_descriptor));
+        resource_container.texture_views[TextureViewTypes::BrdfLut] =
+            Some(brdf_lut_texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        resource_container.textures[TextureTypes::Irradiance] = Some(irradiance_texture);
+        resource_container.textures[TextureTypes::PrefilteredEnvironment] =
+            Some(prefiltered_texture);
+        resource_container.textures[TextureTypes::BrdfLut] = Some(brdf_lut_texture);
+        resource_container.samplers[SamplerTypes::Environment] = Some(environment_sampler);
+    }
+}
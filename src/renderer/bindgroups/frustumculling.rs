@@ -0,0 +1,205 @@
+use specs::Component;
+use specs::NullStorage;
+
+use wgpu::BufferUsages;
+
+use crate::components::transform::TransformRaw;
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::primitives::mesh::GltfMesh;
+use crate::resources::bindingresourcecontainer::BufferTypes::{
+    CullingGroups, CullingInstances, CullingStats, CullingStatsReadback, FrustumCullingUniform,
+    IndirectDraws, VisibleInstances,
+};
+use crate::resources::frustumculling::{
+    CullingGroupRaw, CullingInstanceRaw, CullingStatsRaw, DrawIndexedIndirectRaw, FrustumCulling,
+    FrustumCullingUniforms,
+};
+use crate::State;
+
+use super::HorizonBindGroup;
+
+#[derive(Component, Default)]
+#[storage(NullStorage)]
+pub struct FrustumCullingBindGroup;
+
+impl FrustumCullingBindGroup {
+    fn storage_entry(binding: u32, read_only: bool) -> wgpu::BindGroupLayoutEntry {
+        wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    }
+}
+
+impl<'a> HorizonBindGroup<'a> for FrustumCullingBindGroup {
+    type BindingResources = (
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::TextureView,
+        &'a wgpu::Buffer,
+    );
+
+    fn get_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Buffer {
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                        ty: wgpu::BufferBindingType::Uniform,
+                    },
+                    count: None,
+                },
+                Self::storage_entry(1, true),
+                Self::storage_entry(2, true),
+                Self::storage_entry(3, true),
+                Self::storage_entry(4, false),
+                Self::storage_entry(5, false),
+                wgpu::BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    },
+                    count: None,
+                },
+                Self::storage_entry(7, false),
+            ],
+            label: Some("Frustum culling bind group layout"),
+        })
+    }
+
+    fn create_container(
+        device: &wgpu::Device,
+        resources: Self::BindingResources,
+    ) -> BindGroupContainer {
+        let (
+            culling_uniforms,
+            instance_buffer,
+            culling_instances,
+            culling_groups,
+            indirect_draws,
+            visible_instances,
+            depth_pyramid,
+            culling_stats,
+        ) = resources;
+        let layout = Self::get_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: culling_uniforms.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: instance_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: culling_instances.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: culling_groups.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: indirect_draws.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: visible_instances.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: wgpu::BindingResource::TextureView(depth_pyramid),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: culling_stats.as_entire_binding(),
+                },
+            ],
+            label: Some("Frustum culling bind group"),
+            layout: &layout,
+        });
+        BindGroupContainer::new(layout, bind_group)
+    }
+
+    fn get_resources(
+        device: &wgpu::Device,
+        resource_container: &mut crate::resources::bindingresourcecontainer::BindingResourceContainer,
+    ) {
+        let max_instances = State::MAX_ENTITY_COUNT as usize / std::mem::size_of::<TransformRaw>();
+        let culling_uniforms = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frustum culling uniform buffer"),
+            mapped_at_creation: false,
+            size: std::mem::size_of::<FrustumCullingUniforms>() as wgpu::BufferAddress,
+            usage: BufferUsages::UNIFORM | BufferUsages::COPY_DST,
+        });
+        let culling_instances = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("culling instance buffer"),
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<CullingInstanceRaw>() * max_instances)
+                as wgpu::BufferAddress,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+        let culling_groups = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("culling group buffer"),
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<CullingGroupRaw>() * State::MAX_INDIRECT_DRAWS)
+                as wgpu::BufferAddress,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+        let indirect_draws = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("indirect draw buffer"),
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<DrawIndexedIndirectRaw>()
+                * State::MAX_INDIRECT_DRAWS
+                * FrustumCulling::VIEW_COUNT) as wgpu::BufferAddress,
+            usage: BufferUsages::INDIRECT | BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+        // every level of detail of a mesh has a slot for each of its instances.
+        let visible_instances = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("visible instance buffer"),
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<u32>()
+                * max_instances
+                * GltfMesh::MAX_LOD_COUNT
+                * FrustumCulling::VIEW_COUNT) as wgpu::BufferAddress,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+        });
+        let culling_stats = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("culling stats buffer"),
+            mapped_at_creation: false,
+            size: std::mem::size_of::<CullingStatsRaw>() as wgpu::BufferAddress,
+            usage: BufferUsages::STORAGE | BufferUsages::COPY_SRC | BufferUsages::COPY_DST,
+        });
+        let culling_stats_readback = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("culling stats readback buffer"),
+            mapped_at_creation: false,
+            size: std::mem::size_of::<CullingStatsRaw>() as wgpu::BufferAddress,
+            usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+        });
+        resource_container.buffers[FrustumCullingUniform] = Some(culling_uniforms);
+        resource_container.buffers[CullingInstances] = Some(culling_instances);
+        resource_container.buffers[CullingGroups] = Some(culling_groups);
+        resource_container.buffers[IndirectDraws] = Some(indirect_draws);
+        resource_container.buffers[VisibleInstances] = Some(visible_instances);
+        resource_container.buffers[CullingStats] = Some(culling_stats);
+        resource_container.buffers[CullingStatsReadback] = Some(culling_stats_readback);
+    }
+}
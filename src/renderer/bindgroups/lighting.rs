@@ -14,7 +14,13 @@ use crate::{BufferTypes};
 pub struct LightBindGroup;
 
 impl<'a> HorizonBindGroup<'a> for LightBindGroup {
-    type BindingResources = (&'a wgpu::Buffer, &'a wgpu::Buffer, &'a wgpu::Buffer);
+    type BindingResources = (
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::TextureView,
+    );
     fn get_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             entries: &[
@@ -53,6 +59,26 @@ impl<'a> HorizonBindGroup<'a> for LightBindGroup {
                     },
                     count: None,
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        sample_type: wgpu::TextureSampleType::Depth,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
             ],
             label: Some("light_bind_group_layout"),
         })
@@ -62,7 +88,13 @@ impl<'a> HorizonBindGroup<'a> for LightBindGroup {
         device: &wgpu::Device,
         binding_resources: Self::BindingResources,
     ) -> crate::renderer::bindgroupcontainer::BindGroupContainer {
-        let (directional_light_buffer, point_light_buffer, spot_light_buffer) = binding_resources;
+        let (
+            directional_light_buffer,
+            point_light_buffer,
+            spot_light_buffer,
+            shadow_atlas_tiles,
+            shadow_atlas,
+        ) = binding_resources;
 
         let light_bind_group_layout = Self::get_layout(device);
 
@@ -82,6 +114,14 @@ impl<'a> HorizonBindGroup<'a> for LightBindGroup {
                     resource: spot_light_buffer.as_entire_binding(),
                     binding: 2,
                 },
+                wgpu::BindGroupEntry {
+                    resource: shadow_atlas_tiles.as_entire_binding(),
+                    binding: 3,
+                },
+                wgpu::BindGroupEntry {
+                    resource: wgpu::BindingResource::TextureView(shadow_atlas),
+                    binding: 4,
+                },
             ],
         });
 
@@ -1,8 +1,12 @@
 use super::HorizonBindGroup;
-use crate::renderer::{bindgroups::BindGroupContainer, primitives::uniforms::ShadowUniforms};
+use crate::renderer::{
+    bindgroups::BindGroupContainer,
+    primitives::uniforms::{ShadowSettingsUniforms, ShadowUniforms},
+};
 use std::num::NonZeroU32;
 
 use crate::resources::bindingresourcecontainer::TextureArrayViewTypes;
+use crate::resources::shadowatlas::ShadowAtlasTileRaw;
 use crate::{SamplerTypes, ShadowUniform, State, TextureTypes, TextureViewTypes};
 use specs::*;
 use std::default::Default;
@@ -13,7 +17,15 @@ use wgpu::util::DeviceExt;
 pub struct ShadowBindGroup;
 
 impl<'a> HorizonBindGroup<'a> for ShadowBindGroup {
-    type BindingResources = (&'a wgpu::Buffer, &'a wgpu::Buffer);
+    type BindingResources = (
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+    );
     fn get_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
             label: Some("shadow_bind_group_layout"),
@@ -38,6 +50,56 @@ impl<'a> HorizonBindGroup<'a> for ShadowBindGroup {
                         min_binding_size: None,
                     },
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 5,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
             ],
         })
     }
@@ -46,7 +108,15 @@ impl<'a> HorizonBindGroup<'a> for ShadowBindGroup {
         binding_resources: Self::BindingResources,
     ) -> crate::renderer::bindgroupcontainer::BindGroupContainer {
         let shadow_bind_group_layout = Self::get_layout(device);
-        let (shadow_uniform_buffer, instance_buffer) = binding_resources;
+        let (
+            shadow_uniform_buffer,
+            instance_buffer,
+            joint_matrices,
+            instance_deformations,
+            morph_targets,
+            morph_weights,
+            visible_instances,
+        ) = binding_resources;
 
         let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
             layout: &shadow_bind_group_layout,
@@ -59,6 +129,26 @@ impl<'a> HorizonBindGroup<'a> for ShadowBindGroup {
                     binding: 1,
                     resource: instance_buffer.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: joint_matrices.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: instance_deformations.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: morph_targets.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 5,
+                    resource: morph_weights.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 6,
+                    resource: visible_instances.as_entire_binding(),
+                },
             ],
             label: Some("shadow_bind_group"),
         });
@@ -102,7 +192,9 @@ impl<'a> HorizonBindGroup<'a> for ShadowBindGroup {
             size: State::SHADOW_SIZE,
             dimension: wgpu::TextureDimension::D2,
             format: wgpu::TextureFormat::Depth32Float,
-            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::RENDER_ATTACHMENT,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
             label: Some("shadow texture"),
             mip_level_count: 1,
             sample_count: 1,
@@ -127,12 +219,48 @@ impl<'a> HorizonBindGroup<'a> for ShadowBindGroup {
                 as wgpu::BufferAddress,
             mapped_at_creation: false,
         });
+        let shadow_settings_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow settings buffer"),
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::UNIFORM,
+            size: std::mem::size_of::<ShadowSettingsUniforms>() as wgpu::BufferAddress,
+            mapped_at_creation: false,
+        });
+        let shadow_atlas_texture = device.create_texture(&wgpu::TextureDescriptor {
+            size: wgpu::Extent3d {
+                width: State::SHADOW_ATLAS_SIZE,
+                height: State::SHADOW_ATLAS_SIZE,
+                depth_or_array_layers: 1,
+            },
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Depth32Float,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::COPY_SRC,
+            label: Some("shadow atlas texture"),
+            mip_level_count: 1,
+            sample_count: 1,
+        });
+        let shadow_atlas_tiles = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("Shadow atlas tiles"),
+            usage: wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC
+                | wgpu::BufferUsages::STORAGE,
+            size: (std::mem::size_of::<ShadowAtlasTileRaw>() * State::MAX_SHADOW_ATLAS_TILES)
+                as wgpu::BufferAddress,
+            mapped_at_creation: false,
+        });
         let shadow_view = shadow_texture.create_view(&wgpu::TextureViewDescriptor::default());
         resource_container.buffers[crate::BufferTypes::ShadowCascadeLengths] =
             Some(shadow_cascade_lengths);
+        resource_container.buffers[crate::BufferTypes::ShadowSettingsUniform] =
+            Some(shadow_settings_buffer);
         resource_container.buffers[ShadowUniform] = Some(uniform_buffer);
         resource_container.buffers[crate::BufferTypes::ShadowCascade] = Some(shadow_cascade_buffer);
         resource_container.samplers[SamplerTypes::Shadow] = Some(shadow_sampler);
+        resource_container.buffers[crate::BufferTypes::ShadowAtlasTiles] = Some(shadow_atlas_tiles);
+        resource_container.texture_views[TextureViewTypes::ShadowAtlas] =
+            Some(shadow_atlas_texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        resource_container.textures[TextureTypes::ShadowAtlas] = Some(shadow_atlas_texture);
         resource_container.textures[TextureTypes::Shadow] = Some(shadow_texture);
         resource_container.texture_views[TextureViewTypes::Shadow] = Some(shadow_view);
     }
@@ -0,0 +1,159 @@
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::primitives::uniforms::SsaoUniforms;
+use crate::{BindingResourceContainer, HorizonBindGroup};
+use specs::*;
+use wgpu::{BindGroupLayout, Device, ShaderStages};
+
+use crate::resources::bindingresourcecontainer::{BufferTypes, TextureTypes, TextureViewTypes};
+
+/// The gbuffer's positions and normals along with the globals the ambient occlusion is computed from.
+#[derive(Default, Component)]
+#[storage(NullStorage)]
+pub struct SsaoBindGroup;
+
+impl SsaoBindGroup {
+    pub const AMBIENT_OCCLUSION_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R8Unorm;
+
+    /// The transient render graph target the noisy ambient occlusion is written to, it's only read by the blur.
+    pub const RAW_TARGET: &'static str = "ssao_raw";
+
+    /// The blurred ambient occlusion has the size of the surface, it's recreated on every resize.
+    pub fn create_targets(
+        device: &Device,
+        sc_descriptor: &wgpu::SurfaceConfiguration,
+        resource_container: &mut BindingResourceContainer,
+    ) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("ambient_occlusion_blurred_texture"),
+            size: wgpu::Extent3d {
+                width: sc_descriptor.width,
+                height: sc_descriptor.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::AMBIENT_OCCLUSION_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+        resource_container.texture_views[TextureViewTypes::AmbientOcclusionBlurred] =
+            Some(texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        resource_container.textures[TextureTypes::AmbientOcclusionBlurred] = Some(texture);
+    }
+}
+
+impl<'a> HorizonBindGroup<'a> for SsaoBindGroup {
+    type BindingResources = (
+        &'a wgpu::TextureView,
+        &'a wgpu::TextureView,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+    );
+
+    fn get_layout(device: &Device) -> BindGroupLayout {
+        let texture = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Texture {
+                multisampled: false,
+                view_dimension: wgpu::TextureViewDimension::D2,
+                sample_type: wgpu::TextureSampleType::Float { filterable: false },
+            },
+        };
+        let uniform = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT,
+            count: None,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+        };
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ssao_bind_group_layout"),
+            entries: &[texture(0), texture(1), uniform(2), uniform(3)],
+        })
+    }
+
+    fn create_container(device: &Device, resources: Self::BindingResources) -> BindGroupContainer {
+        let (positions, normals, ssao_uniform_buffer, globals_buffer) = resources;
+        let bind_group_layout = Self::get_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ssao_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(positions),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(normals),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: ssao_uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: globals_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        BindGroupContainer::new(bind_group_layout, bind_group)
+    }
+
+    fn get_resources(device: &Device, resource_container: &mut BindingResourceContainer) {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("ssao_uniform_buffer"),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+            size: std::mem::size_of::<SsaoUniforms>() as wgpu::BufferAddress,
+        });
+        resource_container.buffers[BufferTypes::SsaoUniform] = Some(uniform_buffer);
+    }
+}
+
+/// The noisy ambient occlusion read by the blur, which writes the target the lighting reads.
+/// It's owned by the ssao pass as the noisy target is reallocated with the render graph's transients.
+pub struct SsaoBlurBindGroup;
+
+impl<'a> HorizonBindGroup<'a> for SsaoBlurBindGroup {
+    type BindingResources = &'a wgpu::TextureView;
+
+    fn get_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("ssao_blur_bind_group_layout"),
+            entries: &[wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                count: None,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                },
+            }],
+        })
+    }
+
+    fn create_container(
+        device: &Device,
+        ambient_occlusion: Self::BindingResources,
+    ) -> BindGroupContainer {
+        let bind_group_layout = Self::get_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("ssao_blur_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(ambient_occlusion),
+            }],
+        });
+        BindGroupContainer::new(bind_group_layout, bind_group)
+    }
+
+    fn get_resources(_device: &Device, _resource_container: &mut BindingResourceContainer) {}
+}
@@ -120,7 +120,9 @@ impl<'a> HorizonBindGroup<'a> for TilingBindGroup {
         let mut tile_info = TileInfo::default();
         let light_id_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             label: Some("light_id_buffer"),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             mapped_at_creation: false,
             size: tile_info.calculate_light_id_buffer_size(600.0, 800.0),
         });
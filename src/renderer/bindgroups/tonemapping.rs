@@ -0,0 +1,147 @@
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::primitives::uniforms::ToneMappingUniforms;
+use crate::renderer::shaderconstants;
+use crate::{BindingResourceContainer, HorizonBindGroup};
+use specs::*;
+use wgpu::{BindGroupLayout, Device, ShaderStages};
+
+use crate::resources::bindingresourcecontainer::{BufferTypes, TextureTypes, TextureViewTypes};
+
+/// The HDR scene target along with the luminance histogram and the adapted luminance of the automatic exposure.
+#[derive(Default, Component)]
+#[storage(NullStorage)]
+pub struct ToneMappingBindGroup;
+
+impl ToneMappingBindGroup {
+    pub const HDR_FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba16Float;
+    pub const HISTOGRAM_BIN_COUNT: u64 = shaderconstants::HISTOGRAM_BIN_COUNT;
+
+    /// The lit scene is rendered into this target instead of the surface, it's recreated on every resize.
+    pub fn create_hdr_target(
+        device: &Device,
+        sc_descriptor: &wgpu::SurfaceConfiguration,
+        resource_container: &mut BindingResourceContainer,
+    ) {
+        let hdr_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("hdr_scene_texture"),
+            size: wgpu::Extent3d {
+                width: sc_descriptor.width,
+                height: sc_descriptor.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                | wgpu::TextureUsages::TEXTURE_BINDING
+                | wgpu::TextureUsages::COPY_SRC,
+        });
+        resource_container.texture_views[TextureViewTypes::HdrScene] =
+            Some(hdr_texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        resource_container.textures[TextureTypes::HdrScene] = Some(hdr_texture);
+    }
+}
+
+impl<'a> HorizonBindGroup<'a> for ToneMappingBindGroup {
+    type BindingResources = (
+        &'a wgpu::TextureView,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+    );
+
+    fn get_layout(device: &Device) -> BindGroupLayout {
+        let storage_buffer = |binding| wgpu::BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::FRAGMENT | ShaderStages::COMPUTE,
+            count: None,
+            ty: wgpu::BindingType::Buffer {
+                ty: wgpu::BufferBindingType::Storage { read_only: false },
+                has_dynamic_offset: false,
+                min_binding_size: None,
+            },
+        };
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("tone_mapping_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT | ShaderStages::COMPUTE,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT | ShaderStages::COMPUTE,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                storage_buffer(2),
+                storage_buffer(3),
+            ],
+        })
+    }
+
+    fn create_container(device: &Device, resources: Self::BindingResources) -> BindGroupContainer {
+        let (hdr_scene, uniform_buffer, histogram_buffer, adapted_luminance_buffer) = resources;
+        let bind_group_layout = Self::get_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("tone_mapping_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(hdr_scene),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: histogram_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: adapted_luminance_buffer.as_entire_binding(),
+                },
+            ],
+        });
+        BindGroupContainer::new(bind_group_layout, bind_group)
+    }
+
+    fn get_resources(device: &Device, resource_container: &mut BindingResourceContainer) {
+        let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("tone_mapping_uniform_buffer"),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+            size: std::mem::size_of::<ToneMappingUniforms>() as wgpu::BufferAddress,
+        });
+        // the averaging pass clears the bins for the next frame.
+        let histogram_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("luminance_histogram_buffer"),
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+            size: Self::HISTOGRAM_BIN_COUNT * std::mem::size_of::<u32>() as wgpu::BufferAddress,
+        });
+        // starts at zero, the first frames adapt from a dark scene.
+        let adapted_luminance_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("adapted_luminance_buffer"),
+            usage: wgpu::BufferUsages::STORAGE,
+            mapped_at_creation: false,
+            size: std::mem::size_of::<f32>() as wgpu::BufferAddress,
+        });
+        resource_container.buffers[BufferTypes::ToneMappingUniform] = Some(uniform_buffer);
+        resource_container.buffers[BufferTypes::LuminanceHistogram] = Some(histogram_buffer);
+        resource_container.buffers[BufferTypes::AdaptedLuminance] = Some(adapted_luminance_buffer);
+    }
+}
@@ -0,0 +1,137 @@
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::bindgroups::tonemapping::ToneMappingBindGroup;
+use crate::{BindingResourceContainer, HorizonBindGroup};
+use specs::*;
+use wgpu::{BindGroupLayout, Device, ShaderStages};
+
+use crate::resources::bindingresourcecontainer::{TextureTypes, TextureViewTypes};
+
+/// Image based lighting maps and a copy of the lit opaque scene, transmissive materials refract the copy.
+#[derive(Default, Component)]
+#[storage(NullStorage)]
+pub struct TransparentBindGroup;
+
+impl TransparentBindGroup {
+    /// The scene color has the size and format of the HDR scene target, it's recreated on every resize.
+    pub fn create_scene_color(
+        device: &Device,
+        sc_descriptor: &wgpu::SurfaceConfiguration,
+        resource_container: &mut BindingResourceContainer,
+    ) {
+        let scene_color_texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("scene_color_texture"),
+            size: wgpu::Extent3d {
+                width: sc_descriptor.width,
+                height: sc_descriptor.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: ToneMappingBindGroup::HDR_FORMAT,
+            usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+        resource_container.texture_views[TextureViewTypes::SceneColor] =
+            Some(scene_color_texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        resource_container.textures[TextureTypes::SceneColor] = Some(scene_color_texture);
+    }
+}
+
+impl<'a> HorizonBindGroup<'a> for TransparentBindGroup {
+    type BindingResources = (
+        &'a wgpu::TextureView,
+        &'a wgpu::TextureView,
+        &'a wgpu::TextureView,
+        &'a wgpu::Sampler,
+        &'a wgpu::TextureView,
+    );
+
+    fn get_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("transparent_bind_group_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::D2,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+            ],
+        })
+    }
+
+    fn create_container(device: &Device, resources: Self::BindingResources) -> BindGroupContainer {
+        let (irradiance, prefiltered, brdf_lut, sampler, scene_color) = resources;
+        let bind_group_layout = Self::get_layout(device);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("transparent_bind_group"),
+            layout: &bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(irradiance),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::TextureView(prefiltered),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: wgpu::BindingResource::TextureView(brdf_lut),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::Sampler(sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 4,
+                    resource: wgpu::BindingResource::TextureView(scene_color),
+                },
+            ],
+        });
+        BindGroupContainer::new(bind_group_layout, bind_group)
+    }
+
+    /// The environment maps and their sampler are created by the `EnvironmentBindGroup`.
+    fn get_resources(_device: &Device, _resource_container: &mut BindingResourceContainer) {}
+}
@@ -1,9 +1,15 @@
 use super::HorizonBindGroup;
 use crate::renderer::{
-    bindgroups::BindGroupContainer, primitives::uniforms::Globals, state::State,
+    bindgroups::BindGroupContainer,
+    primitives::{
+        uniforms::{Globals, InstanceDeformation},
+        vertex::MorphTargetDelta,
+    },
+    state::State,
 };
 
-use crate::resources::bindingresourcecontainer::{TextureTypes, TextureViewTypes};
+use crate::components::transform::TransformRaw;
+use crate::resources::bindingresourcecontainer::{BufferTypes, TextureTypes, TextureViewTypes};
 use crate::{Instances, Normals, Shadow, Uniform};
 use specs::*;
 
@@ -20,6 +26,12 @@ impl<'a> HorizonBindGroup<'a> for UniformBindGroup {
         &'a wgpu::Buffer,
         &'a wgpu::Buffer,
         &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
+        &'a wgpu::Buffer,
     );
     fn get_layout(device: &wgpu::Device) -> wgpu::BindGroupLayout {
         device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
@@ -107,6 +119,66 @@ impl<'a> HorizonBindGroup<'a> for UniformBindGroup {
                         min_binding_size: None,
                     },
                 },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 7,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 8,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 9,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 10,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 11,
+                    visibility: wgpu::ShaderStages::FRAGMENT,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 12,
+                    visibility: wgpu::ShaderStages::VERTEX,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Storage { read_only: true },
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
             ],
         })
     }
@@ -123,6 +195,12 @@ impl<'a> HorizonBindGroup<'a> for UniformBindGroup {
             instance_buffer,
             shadow_cascade_buffer,
             cascade_lengths,
+            joint_matrices,
+            instance_deformations,
+            morph_targets,
+            morph_weights,
+            shadow_settings,
+            visible_instances,
         ) = binding_resources;
 
         let uniform_bind_group_layout = UniformBindGroup::get_layout(device);
@@ -157,6 +235,30 @@ impl<'a> HorizonBindGroup<'a> for UniformBindGroup {
                     binding: 6,
                     resource: cascade_lengths.as_entire_binding(),
                 },
+                wgpu::BindGroupEntry {
+                    binding: 7,
+                    resource: joint_matrices.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 8,
+                    resource: instance_deformations.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 9,
+                    resource: morph_targets.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 10,
+                    resource: morph_weights.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 11,
+                    resource: shadow_settings.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 12,
+                    resource: visible_instances.as_entire_binding(),
+                },
             ],
             layout: &uniform_bind_group_layout,
         });
@@ -182,6 +284,36 @@ impl<'a> HorizonBindGroup<'a> for UniformBindGroup {
             size: State::MAX_ENTITY_COUNT,
         });
 
+        let joint_matrix_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("joint_matrix_buffer"),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<[[f32; 4]; 4]>() * State::MAX_JOINT_MATRICES)
+                as wgpu::BufferAddress,
+        });
+        // one deformation entry for every instance.
+        let instance_deformation_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("instance_deformation_buffer"),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+            size: (State::MAX_ENTITY_COUNT as usize / std::mem::size_of::<TransformRaw>()
+                * std::mem::size_of::<InstanceDeformation>())
+                as wgpu::BufferAddress,
+        });
+        let morph_target_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("morph_target_buffer"),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<MorphTargetDelta>() * State::MAX_MORPH_TARGET_DELTAS)
+                as wgpu::BufferAddress,
+        });
+        let morph_weight_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("morph_weight_buffer"),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+            size: (std::mem::size_of::<f32>() * State::MAX_MORPH_WEIGHTS) as wgpu::BufferAddress,
+        });
+
         let uniform_size = std::mem::size_of::<Globals>() as wgpu::BufferAddress;
         let uniform_buffer = device.create_buffer(&wgpu::BufferDescriptor {
             usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
@@ -193,5 +325,10 @@ impl<'a> HorizonBindGroup<'a> for UniformBindGroup {
         resource_container.buffers[Normals] = Some(normal_matrix_buffer);
         resource_container.buffers[Instances] = Some(instance_buffer);
         resource_container.buffers[Uniform] = Some(uniform_buffer);
+        resource_container.buffers[BufferTypes::JointMatrices] = Some(joint_matrix_buffer);
+        resource_container.buffers[BufferTypes::InstanceDeformations] =
+            Some(instance_deformation_buffer);
+        resource_container.buffers[BufferTypes::MorphTargets] = Some(morph_target_buffer);
+        resource_container.buffers[BufferTypes::MorphWeights] = Some(morph_weight_buffer);
     }
 }
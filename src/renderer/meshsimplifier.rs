@@ -0,0 +1,119 @@
+use std::collections::{HashMap, HashSet};
+
+use rapier3d::na::Vector3;
+
+use super::primitives::mesh::{GltfMesh, GltfMeshLod, VertexAttribValues, VertexAttributeType};
+
+/// Generates the levels of detail of imported primitives which don't have any,
+/// vertices are clustered on a grid so the lower levels keep indexing into the primitive's vertices.
+pub struct MeshSimplifier;
+
+impl MeshSimplifier {
+    /// Screen sizes below which the generated levels replace the previous one.
+    pub const LOD_SCREEN_SIZES: [f32; 3] = [0.25, 0.1, 0.04];
+    /// Primitives with fewer triangles are drawn without levels of detail.
+    const MIN_TRIANGLE_COUNT: usize = 256;
+    /// Grid cells along the bounding sphere's diameter the first level starts with.
+    const MAX_GRID_RESOLUTION: u32 = 256;
+    const MIN_GRID_RESOLUTION: u32 = 4;
+
+    /// Each level aims for half the triangles of the previous one,
+    /// the chain ends once a level would barely reduce the triangle count.
+    pub fn generate_lods(mesh: &GltfMesh) -> Vec<GltfMeshLod> {
+        let mut lods = Vec::new();
+        let (positions, indices, bounds) = match (
+            mesh.attribute(VertexAttributeType::Position),
+            mesh.indices.as_ref(),
+            mesh.bounds,
+        ) {
+            (Some(VertexAttribValues::Float32x3(positions)), Some(indices), Some(bounds))
+                if mesh.mode() == wgpu::PrimitiveTopology::TriangleList && bounds.radius > 0.0 =>
+            {
+                (positions, indices, bounds)
+            }
+            _ => return lods,
+        };
+        let mut triangle_count = indices.len() / 3;
+        if triangle_count < Self::MIN_TRIANGLE_COUNT {
+            return lods;
+        }
+        let min = bounds.center - Vector3::repeat(bounds.radius);
+        let mut resolution = Self::MAX_GRID_RESOLUTION;
+        for screen_size in Self::LOD_SCREEN_SIZES
+            .into_iter()
+            .take(GltfMesh::MAX_LOD_COUNT - 1)
+        {
+            let target_count = triangle_count / 2;
+            let cell_size = |resolution: u32| bounds.radius * 2.0 / resolution as f32;
+            let mut lod_indices = Self::cluster(positions, indices, &min, cell_size(resolution));
+            while lod_indices.len() / 3 > target_count && resolution > Self::MIN_GRID_RESOLUTION {
+                resolution /= 2;
+                lod_indices = Self::cluster(positions, indices, &min, cell_size(resolution));
+            }
+            // a level which keeps most of the triangles isn't worth the extra draws.
+            if lod_indices.is_empty() || lod_indices.len() / 3 * 4 > triangle_count * 3 {
+                break;
+            }
+            triangle_count = lod_indices.len() / 3;
+            lods.push(GltfMeshLod {
+                indices: lod_indices,
+                screen_size,
+            });
+        }
+        lods
+    }
+    /// Replaces every vertex by the one closest to the center of its grid cell,
+    /// triangles which collapse or end up duplicated are removed.
+    fn cluster(
+        positions: &[[f32; 3]],
+        indices: &[u32],
+        min: &Vector3<f32>,
+        cell_size: f32,
+    ) -> Vec<u32> {
+        let cell = |position: &[f32; 3]| {
+            let cell = (Vector3::from(*position) - min) / cell_size;
+            (cell.x as i32, cell.y as i32, cell.z as i32)
+        };
+        let mut cell_sums: HashMap<(i32, i32, i32), (Vector3<f32>, u32)> = HashMap::new();
+        for position in positions {
+            let sum = cell_sums
+                .entry(cell(position))
+                .or_insert((Vector3::zeros(), 0));
+            sum.0 += Vector3::from(*position);
+            sum.1 += 1;
+        }
+        let mut representatives: HashMap<(i32, i32, i32), (u32, f32)> = HashMap::new();
+        for (index, position) in positions.iter().enumerate() {
+            let key = cell(position);
+            let (sum, count) = cell_sums[&key];
+            let distance = (Vector3::from(*position) - sum / count as f32).norm_squared();
+            let representative = representatives
+                .entry(key)
+                .or_insert((index as u32, distance));
+            if distance < representative.1 {
+                *representative = (index as u32, distance);
+            }
+        }
+        let mut triangles = HashSet::new();
+        let mut cluster_indices = Vec::new();
+        for triangle in indices.chunks_exact(3) {
+            let [a, b, c] = [triangle[0], triangle[1], triangle[2]]
+                .map(|index| representatives[&cell(&positions[index as usize])].0);
+            if a == b || b == c || a == c {
+                continue;
+            }
+            // rotated so the smallest index is first, the winding stays the same.
+            let key = if a < b && a < c {
+                (a, b, c)
+            } else if b < c {
+                (b, c, a)
+            } else {
+                (c, a, b)
+            };
+            if triangles.insert(key) {
+                cluster_indices.extend([a, b, c]);
+            }
+        }
+        cluster_indices
+    }
+}
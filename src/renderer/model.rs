@@ -4,8 +4,9 @@ use std::ops::Range;
 
 use super::primitives::material::Material;
 use super::primitives::mesh::Mesh;
-use crate::components::gltfmodel::GltfModel;
+use crate::components::gltfmodel::{GltfAnimation, GltfModel, GltfNode, GltfSkin};
 use crate::renderer::primitives::material::GltfMaterial;
+use rapier3d::na::Matrix4;
 use specs::{Component, VecStorage};
 
 #[derive(Component, Debug)]
@@ -15,4 +16,32 @@ pub struct HorizonModel {
     pub materials: HashMap<usize, GltfMaterial>,
     pub textures: HashMap<usize, DynamicImage>,
     pub name: Option<String>,
+    pub nodes: Vec<GltfNode>,
+    /// Nodes of the default scene.
+    pub root_nodes: Vec<usize>,
+    pub skins: Vec<GltfSkin>,
+    pub animations: Vec<GltfAnimation>,
+}
+impl HorizonModel {
+    /// Returns every mesh referenced by the scene graph with its transform relative to the model's origin.
+    pub fn mesh_transforms(&self) -> Vec<(usize, Matrix4<f32>)> {
+        let mut mesh_transforms = Vec::new();
+        let mut stack = self
+            .root_nodes
+            .iter()
+            .map(|node_index| (*node_index, Matrix4::identity()))
+            .collect::<Vec<_>>();
+        while let Some((node_index, parent_matrix)) = stack.pop() {
+            let node = &self.nodes[node_index];
+            let matrix = parent_matrix
+                * (Matrix4::new_translation(&node.translation)
+                    * node.rotation.to_rotation_matrix().to_homogeneous())
+                .append_nonuniform_scaling(&node.scale);
+            if let Some(mesh) = node.mesh {
+                mesh_transforms.push((mesh, matrix));
+            }
+            stack.extend(node.children.iter().map(|child| (*child, matrix)));
+        }
+        mesh_transforms
+    }
 }
@@ -1,11 +1,17 @@
-use crate::components::gltfmodel::{GltfModel, GltfPrimitive};
+use crate::components::gltfmodel::{
+    GltfAnimation, GltfChannel, GltfChannelValues, GltfModel, GltfNode, GltfPrimitive, GltfSkin,
+};
+use gltf::animation::util::ReadOutputs;
 use gltf::Document;
 use image::{load, DynamicImage};
-use rapier3d::na::{Vector2, Vector3};
+use rapier3d::na::{Matrix4, Quaternion, UnitQuaternion, Vector2, Vector3};
 use std::collections::{HashMap, HashSet};
 
+use crate::renderer::meshsimplifier::MeshSimplifier;
 use crate::renderer::primitives::material::GltfMaterial;
-use crate::renderer::primitives::mesh::{GltfMesh, VertexAttribValues, VertexAttributeType};
+use crate::renderer::primitives::mesh::{
+    BoundingSphere, GltfMesh, MorphTarget, VertexAttribValues, VertexAttributeType,
+};
 use crate::renderer::primitives::texture::ImageLoadError;
 use crate::Texture;
 
@@ -13,12 +19,26 @@ use super::{
     model::HorizonModel,
     primitives::{mesh, texture},
 };
+/// The `MSFT_lod` extension of a node, the glTF crate drops the extensions it doesn't know.
+#[derive(Debug, Clone)]
+pub struct GltfNodeLods {
+    pub node: usize,
+    /// Nodes holding the lower levels of detail, ordered by decreasing detail.
+    pub lod_nodes: Vec<usize>,
+    /// `MSFT_screencoverage` of the node's extras, the screen size down to which each level is used.
+    pub screen_coverages: Vec<f32>,
+}
 /// Model importer
 pub struct ModelBuilder;
 
 impl ModelBuilder {
     pub fn create_gltf_model(
-        data: (Document, Vec<gltf::buffer::Data>, Vec<gltf::image::Data>),
+        data: (
+            Document,
+            Vec<gltf::buffer::Data>,
+            Vec<gltf::image::Data>,
+            Vec<GltfNodeLods>,
+        ),
     ) -> Result<HorizonModel, GltfLoadError> {
         let mut materials = HashMap::new();
         let mut loaded_textures: HashMap<usize, DynamicImage> = HashMap::new();
@@ -44,13 +64,11 @@ impl ModelBuilder {
                         .unwrap_or(format!("Object {}", index).as_str())
                         .to_string(),
                 );
-                if let Some(position_vertex_attribs) = reader
-                    .read_positions()
-                    .map(|v| VertexAttribValues::Float32x3(v.collect()))
-                {
+                if let Some(positions) = reader.read_positions().map(|v| v.collect::<Vec<_>>()) {
+                    own_mesh.bounds = BoundingSphere::from_points(&positions);
                     own_mesh.add_vertex_attribute(
                         VertexAttributeType::Position,
-                        position_vertex_attribs,
+                        VertexAttribValues::Float32x3(positions),
                     );
                 }
                 if let Some(normal_vertex_attribs) = reader
@@ -77,6 +95,35 @@ impl ModelBuilder {
                         tex_coords_attribs,
                     );
                 }
+                // joint indices are packed into a single u32, so a skin can have at most 256 joints.
+                if let Some(joint_attribs) = reader.read_joints(0).map(|v| {
+                    VertexAttribValues::Uint32(
+                        v.into_u16()
+                            .map(|joints| {
+                                joints.iter().enumerate().fold(0, |packed, (i, joint)| {
+                                    packed | ((*joint as u32).min(255) << (8 * i))
+                                })
+                            })
+                            .collect(),
+                    )
+                }) {
+                    own_mesh.add_vertex_attribute(VertexAttributeType::JointIndex, joint_attribs);
+                }
+                if let Some(weight_attribs) = reader
+                    .read_weights(0)
+                    .map(|v| VertexAttribValues::Float32x4(v.into_f32().collect()))
+                {
+                    own_mesh.add_vertex_attribute(VertexAttributeType::JointWeight, weight_attribs);
+                }
+                let vertex_count = reader.read_positions().map(|v| v.len()).unwrap_or(0);
+                for (positions, normals, _) in reader.read_morph_targets() {
+                    own_mesh.add_morph_target(MorphTarget {
+                        positions: positions
+                            .map(|v| v.collect())
+                            .unwrap_or_else(|| vec![[0.0; 3]; vertex_count]),
+                        normals: normals.map(|v| v.collect()),
+                    });
+                }
                 if let Some(indices) = reader.read_indices().map(|v| v.into_u32().collect()) {
                     own_mesh.add_indices(indices);
                 }
@@ -85,15 +132,280 @@ impl ModelBuilder {
                     material: primitive.material().index(),
                 });
             }
-            meshes.push(GltfModel { primitives });
+            meshes.push(GltfModel {
+                primitives,
+                weights: mesh.weights().map(|w| w.to_vec()).unwrap_or_default(),
+            });
         }
+        let (mut nodes, mut root_nodes) = Self::load_gltf_nodes(&data.0);
+        Self::load_node_lods(&data.3, &mut meshes, &mut nodes, &mut root_nodes);
+        for primitive in meshes
+            .iter_mut()
+            .flat_map(|mesh| mesh.primitives.iter_mut())
+        {
+            if primitive.mesh.lods.is_empty() {
+                primitive.mesh.lods = MeshSimplifier::generate_lods(&primitive.mesh);
+            }
+        }
+        let skins = Self::load_gltf_skins(&data.0, &data.1);
+        let animations = Self::load_gltf_animations(&data.0, &data.1);
         Ok(HorizonModel {
             meshes,
             materials,
             textures: loaded_textures,
             name: None,
+            nodes,
+            root_nodes,
+            skins,
+            animations,
         })
     }
+    /// Reads the `MSFT_lod` extension of the nodes from the raw glTF or GLB file.
+    pub fn read_node_lods(file: &[u8]) -> Vec<GltfNodeLods> {
+        let json = if file.starts_with(b"glTF") {
+            // the JSON chunk follows the 12 byte header and its own 8 byte chunk header.
+            file.get(12..16)
+                .map(|length| u32::from_le_bytes([length[0], length[1], length[2], length[3]]))
+                .and_then(|length| file.get(20..20 + length as usize))
+        } else {
+            Some(file)
+        };
+        let root =
+            match json.and_then(|json| serde_json::from_slice::<serde_json::Value>(json).ok()) {
+                Some(root) => root,
+                None => return Vec::new(),
+            };
+        root["nodes"]
+            .as_array()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(node, value)| {
+                        let lod_nodes = value["extensions"]["MSFT_lod"]["ids"]
+                            .as_array()?
+                            .iter()
+                            .filter_map(|id| id.as_u64().map(|id| id as usize))
+                            .collect();
+                        let screen_coverages = value["extras"]["MSFT_screencoverage"]
+                            .as_array()
+                            .map(|coverages| {
+                                coverages
+                                    .iter()
+                                    .filter_map(|coverage| coverage.as_f64().map(|c| c as f32))
+                                    .collect()
+                            })
+                            .unwrap_or_default();
+                        Some(GltfNodeLods {
+                            node,
+                            lod_nodes,
+                            screen_coverages,
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+    /// Appends the primitives of the LOD nodes' meshes as levels of detail of the matching primitives of the node's mesh,
+    /// the LOD nodes themselves are removed from the scene graph.
+    fn load_node_lods(
+        node_lods: &[GltfNodeLods],
+        meshes: &mut [GltfModel],
+        nodes: &mut [GltfNode],
+        root_nodes: &mut Vec<usize>,
+    ) {
+        let mesh_of = |node: usize| nodes.get(node).and_then(|node| node.mesh);
+        for lods in node_lods {
+            let base_mesh = match mesh_of(lods.node) {
+                Some(mesh) if mesh < meshes.len() => mesh,
+                _ => continue,
+            };
+            for primitive_index in 0..meshes[base_mesh].primitives.len() {
+                for (level, lod_node) in lods.lod_nodes.iter().enumerate() {
+                    let lod_primitive = mesh_of(*lod_node)
+                        .filter(|lod_mesh| *lod_mesh != base_mesh)
+                        .and_then(|lod_mesh| meshes.get(lod_mesh)?.primitives.get(primitive_index))
+                        .map(|primitive| primitive.mesh.clone());
+                    // without coverages the levels switch at the same sizes as the generated ones.
+                    let screen_size = lods.screen_coverages.get(level).copied().unwrap_or(
+                        MeshSimplifier::LOD_SCREEN_SIZES
+                            [level.min(MeshSimplifier::LOD_SCREEN_SIZES.len() - 1)],
+                    );
+                    let appended = lod_primitive.map_or(false, |lod_primitive| {
+                        meshes[base_mesh].primitives[primitive_index]
+                            .mesh
+                            .append_lod(&lod_primitive, screen_size)
+                    });
+                    if !appended {
+                        log::warn!(
+                            target: "model_import",
+                            "level {} of node {} doesn't match its primitive #{}, the remaining levels are skipped",
+                            level + 1,
+                            lods.node,
+                            primitive_index
+                        );
+                        break;
+                    }
+                }
+            }
+        }
+        let lod_nodes = node_lods
+            .iter()
+            .flat_map(|lods| lods.lod_nodes.iter().copied())
+            .collect::<HashSet<_>>();
+        root_nodes.retain(|node| !lod_nodes.contains(node));
+        for node in nodes.iter_mut() {
+            node.children.retain(|child| !lod_nodes.contains(child));
+        }
+    }
+    fn load_gltf_skins(document: &Document, buffer_data: &[gltf::buffer::Data]) -> Vec<GltfSkin> {
+        document
+            .skins()
+            .map(|skin| {
+                let joints = skin.joints().map(|joint| joint.index()).collect::<Vec<_>>();
+                if joints.len() > 256 {
+                    log::warn!(
+                        target: "model_import",
+                        "skin {} has {} joints, only the first 256 can be referenced",
+                        skin.index(),
+                        joints.len()
+                    );
+                }
+                let reader = skin.reader(|buffer| Some(&buffer_data[buffer.index()]));
+                // the inverse bind matrices are identity matrices when they're not specified.
+                let inverse_bind_matrices = match reader.read_inverse_bind_matrices() {
+                    Some(matrices) => matrices.map(Matrix4::from).collect(),
+                    None => vec![Matrix4::identity(); joints.len()],
+                };
+                GltfSkin {
+                    name: skin
+                        .name()
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| format!("Skin {}", skin.index())),
+                    joints,
+                    inverse_bind_matrices,
+                }
+            })
+            .collect()
+    }
+    fn load_gltf_animations(
+        document: &Document,
+        buffer_data: &[gltf::buffer::Data],
+    ) -> Vec<GltfAnimation> {
+        document
+            .animations()
+            .map(|animation| {
+                let channels = animation
+                    .channels()
+                    .filter_map(|channel| {
+                        let reader = channel.reader(|buffer| Some(&buffer_data[buffer.index()]));
+                        let times = reader.read_inputs()?.collect::<Vec<_>>();
+                        let values = match reader.read_outputs()? {
+                            ReadOutputs::Translations(translations) => {
+                                GltfChannelValues::Translations(
+                                    translations.map(Vector3::from).collect(),
+                                )
+                            }
+                            ReadOutputs::Rotations(rotations) => GltfChannelValues::Rotations(
+                                rotations
+                                    .into_f32()
+                                    .map(|r| Quaternion::new(r[3], r[0], r[1], r[2]))
+                                    .collect(),
+                            ),
+                            ReadOutputs::Scales(scales) => {
+                                GltfChannelValues::Scales(scales.map(Vector3::from).collect())
+                            }
+                            ReadOutputs::MorphTargetWeights(weights) => {
+                                GltfChannelValues::Weights(weights.into_f32().collect())
+                            }
+                        };
+                        Some(GltfChannel {
+                            node: channel.target().node().index(),
+                            interpolation: channel.sampler().interpolation(),
+                            times,
+                            values,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                let duration = channels
+                    .iter()
+                    .filter_map(|channel| channel.times.last().copied())
+                    .fold(0.0, f32::max);
+                GltfAnimation {
+                    name: animation
+                        .name()
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| format!("Animation {}", animation.index())),
+                    channels,
+                    duration,
+                }
+            })
+            .collect()
+    }
+    /// Reads the node hierarchy along with the root nodes of the default scene.
+    /// Files without nodes get a node for each mesh so they're still drawn.
+    fn load_gltf_nodes(document: &Document) -> (Vec<GltfNode>, Vec<usize>) {
+        let nodes = document
+            .nodes()
+            .map(|node| {
+                let (translation, rotation, scale) = node.transform().decomposed();
+                GltfNode {
+                    name: node
+                        .name()
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| format!("Node {}", node.index())),
+                    translation: Vector3::from(translation),
+                    rotation: UnitQuaternion::from_quaternion(Quaternion::new(
+                        rotation[3],
+                        rotation[0],
+                        rotation[1],
+                        rotation[2],
+                    )),
+                    scale: Vector3::from(scale),
+                    mesh: node.mesh().map(|mesh| mesh.index()),
+                    skin: node.skin().map(|skin| skin.index()),
+                    weights: node.weights().map(|weights| weights.to_vec()),
+                    children: node.children().map(|child| child.index()).collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if nodes.is_empty() {
+            let nodes = document
+                .meshes()
+                .map(|mesh| GltfNode {
+                    name: mesh
+                        .name()
+                        .map(|name| name.to_string())
+                        .unwrap_or_else(|| format!("Mesh {}", mesh.index())),
+                    translation: Vector3::zeros(),
+                    rotation: UnitQuaternion::identity(),
+                    scale: Vector3::new(1.0, 1.0, 1.0),
+                    mesh: Some(mesh.index()),
+                    skin: None,
+                    weights: None,
+                    children: Vec::new(),
+                })
+                .collect::<Vec<_>>();
+            let root_nodes = (0..nodes.len()).collect();
+            return (nodes, root_nodes);
+        }
+        let root_nodes = if let Some(scene) = document
+            .default_scene()
+            .or_else(|| document.scenes().next())
+        {
+            scene.nodes().map(|node| node.index()).collect()
+        } else {
+            // no scenes, every node which isn't a child is a root.
+            let child_nodes = nodes
+                .iter()
+                .flat_map(|node| node.children.iter().copied())
+                .collect::<HashSet<_>>();
+            (0..nodes.len())
+                .filter(|index| !child_nodes.contains(index))
+                .collect()
+        };
+        (nodes, root_nodes)
+    }
 
     fn load_gltf_material(
         material: &gltf::material::Material,
@@ -156,6 +468,11 @@ impl ModelBuilder {
             emissive_color: emissive,
             metallic_factor: pbr.metallic_factor(),
             alpha_mode: material.alpha_mode(),
+            alpha_cutoff: material.alpha_cutoff().unwrap_or(0.5),
+            transmission: material
+                .transmission()
+                .map(|transmission| transmission.transmission_factor())
+                .unwrap_or(0.0),
             name,
         })
     }
@@ -1,12 +1,18 @@
 pub mod debugcollision;
 pub mod debugtexturepipeline;
 pub mod forwardpipeline;
+pub mod frustumcullingpipeline;
 pub mod gbufferpipeline;
 pub mod lightcullingpipeline;
 pub mod lightpipeline;
+pub mod luminancepipeline;
+pub mod materialpipeline;
 pub mod shadowpipeline;
 pub mod skyboxpipeline;
+pub mod ssaopipeline;
 pub mod texturepipeline;
+pub mod tonemappingpipeline;
+pub mod transparentpipeline;
 
 use wgpu::ColorTargetState;
 pub trait HorizonPipeline<'a> {
@@ -1,3 +1,4 @@
+use crate::renderer::shaderlibrary::ShaderLibrary;
 use crate::{HorizonPipeline, Texture};
 use wgpu::{ColorTargetState, CompareFunction, Device, RenderPipeline};
 
@@ -17,11 +18,17 @@ impl<'a> HorizonPipeline<'a> for DebugCollisionPipeline {
             push_constant_ranges: &[],
         });
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/colliderRenderer.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/web/colliderRenderer.wgsl"
+            ))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/colliderRenderer.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/colliderRenderer.wgsl"
+            ))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "colliderRenderer.wgsl", wgsl);
         let attribs = wgpu::vertex_attr_array![0=>Float32x3];
         let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             label: Some("debug collision pipeline"),
@@ -1,3 +1,4 @@
+use crate::renderer::shaderlibrary::ShaderLibrary;
 use crate::HorizonPipeline;
 use wgpu::{ColorTargetState, Device, RenderPipeline};
 
@@ -17,11 +18,17 @@ impl<'a> HorizonPipeline<'a> for DebugTexturePipeline {
             push_constant_ranges: &[],
         });
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/textureRenderer.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/web/textureRenderer.wgsl"
+            ))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/textureRenderer.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/textureRenderer.wgsl"
+            ))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "textureRenderer.wgsl", wgsl);
         let attribs = wgpu::vertex_attr_array![0=>Float32x4,1=>Float32x2];
         let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             multiview: None,
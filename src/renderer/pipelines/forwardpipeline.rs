@@ -3,6 +3,7 @@ use super::HorizonPipeline;
 use crate::renderer::pipelines::RenderPipelineBuilder;
 use crate::renderer::primitives::texture::Texture;
 
+use crate::renderer::shaderlibrary::ShaderLibrary;
 use wgpu::{ColorTargetState, DepthStencilState};
 
 pub struct ForwardPipeline(pub wgpu::RenderPipeline);
@@ -12,6 +13,7 @@ impl<'a> HorizonPipeline<'a> for ForwardPipeline {
         &'a wgpu::BindGroupLayout,
         &'a wgpu::BindGroupLayout,
         &'a wgpu::BindGroupLayout,
+        &'a wgpu::BindGroupLayout,
     );
 
     fn create_pipeline(
@@ -19,20 +21,26 @@ impl<'a> HorizonPipeline<'a> for ForwardPipeline {
         bind_group_layouts: Self::RequiredLayouts,
         targets: &[ColorTargetState],
     ) -> wgpu::RenderPipeline {
-        let (deferred_bind_group, uniform_bind_group, light_bind_group) = bind_group_layouts;
+        let (deferred_bind_group, uniform_bind_group, light_bind_group, environment_bind_group) =
+            bind_group_layouts;
 
         let render_pipeline_layout =
             device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                bind_group_layouts: &[deferred_bind_group, uniform_bind_group, light_bind_group],
+                bind_group_layouts: &[
+                    deferred_bind_group,
+                    uniform_bind_group,
+                    light_bind_group,
+                    environment_bind_group,
+                ],
                 label: Some("forward render pipeline layout"),
                 push_constant_ranges: &[],
             });
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/forward.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/forward.wgsl"))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/forward.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/forward.wgsl"))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "forward.wgsl", wgsl);
 
         let vbo_layout = wgpu::vertex_attr_array![0=>Float32x4,1=>Float32x2];
 
@@ -0,0 +1,38 @@
+use wgpu::BindGroupLayout;
+
+use super::HorizonComputePipeline;
+use crate::renderer::shaderlibrary::ShaderLibrary;
+
+pub struct FrustumCullingPipeline(pub wgpu::ComputePipeline);
+
+impl<'a> HorizonComputePipeline<'a> for FrustumCullingPipeline {
+    type RequiredLayouts = &'a BindGroupLayout;
+
+    fn create_compute_pipeline(
+        device: &wgpu::Device,
+        bind_group_layouts: Self::RequiredLayouts,
+    ) -> wgpu::ComputePipeline {
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            bind_group_layouts: &[bind_group_layouts],
+            label: Some("Frustum Culling Pipeline Layout"),
+            push_constant_ranges: &[],
+        });
+
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/frustumculling.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/frustumculling.wgsl"
+            ))
+        };
+        let module = ShaderLibrary::create_module(device, "frustumculling.wgsl", wgsl);
+
+        device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            entry_point: "main",
+            label: Some("Frustum Culling pipeline"),
+            layout: Some(&layout),
+            module: &module,
+        })
+    }
+}
@@ -7,9 +7,50 @@ use crate::renderer::{
 };
 
 use super::HorizonPipeline;
+use crate::renderer::shaderlibrary::ShaderLibrary;
 
 pub struct GBufferPipeline(pub wgpu::RenderPipeline);
 
+impl GBufferPipeline {
+    /// The position, normal, specular and albedo targets, the custom material pipelines write them as well.
+    pub fn targets() -> [wgpu::ColorTargetState; 4] {
+        [
+            wgpu::TextureFormat::Rgba32Float.into(),
+            wgpu::TextureFormat::Rgba32Float.into(),
+            wgpu::TextureFormat::Rgba32Float.into(),
+            wgpu::ColorTargetState {
+                format: wgpu::TextureFormat::Bgra8Unorm,
+                blend: Some(wgpu::BlendState::PREMULTIPLIED_ALPHA_BLENDING),
+                write_mask: wgpu::ColorWrites::all(),
+            },
+        ]
+    }
+    pub fn depth_stencil_state() -> wgpu::DepthStencilState {
+        wgpu::DepthStencilState {
+            bias: wgpu::DepthBiasState {
+                clamp: 0.0,
+                constant: -2, // bilinear filtering
+                slope_scale: -2.0,
+            },
+            depth_compare: wgpu::CompareFunction::GreaterEqual,
+            format: Texture::DEPTH_FORMAT,
+            depth_write_enabled: true,
+            stencil: wgpu::StencilState::default(),
+        }
+    }
+    pub fn primitive_state() -> wgpu::PrimitiveState {
+        wgpu::PrimitiveState {
+            front_face: wgpu::FrontFace::Ccw,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            strip_index_format: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            unclipped_depth: false,
+            ..Default::default()
+        }
+    }
+}
+
 impl<'a> HorizonPipeline<'a> for GBufferPipeline {
     type RequiredLayouts = (&'a wgpu::BindGroupLayout, &'a wgpu::BindGroupLayout);
     fn create_pipeline(
@@ -26,11 +67,11 @@ impl<'a> HorizonPipeline<'a> for GBufferPipeline {
                 push_constant_ranges: &[],
             });
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/gbuffer.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/gbuffer.wgsl"))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/gbuffer.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/gbuffer.wgsl"))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "gbuffer.wgsl", wgsl);
         let vertex_state = wgpu::VertexState {
             buffers: &[MeshVertexData::desc()],
             entry_point: "vs_main",
@@ -42,35 +83,14 @@ impl<'a> HorizonPipeline<'a> for GBufferPipeline {
             entry_point: "fs_main",
         });
 
-        let depth_stencil_state = wgpu::DepthStencilState {
-            bias: wgpu::DepthBiasState {
-                clamp: 0.0,
-                constant: -2, // bilinear filtering
-                slope_scale: -2.0,
-            },
-            depth_compare: wgpu::CompareFunction::GreaterEqual,
-            format: Texture::DEPTH_FORMAT,
-            depth_write_enabled: true,
-            stencil: wgpu::StencilState::default(),
-        };
-        let primitve_state = wgpu::PrimitiveState {
-            front_face: wgpu::FrontFace::Ccw,
-            topology: wgpu::PrimitiveTopology::TriangleList,
-            cull_mode: None,
-            strip_index_format: None,
-            polygon_mode: wgpu::PolygonMode::Fill,
-            unclipped_depth: false,
-            ..Default::default()
-        };
-
         RenderPipelineBuilder::create_pipeline(
             fragment_state,
-            primitve_state,
+            Self::primitive_state(),
             vertex_state,
             device,
             &render_pipeline_layout,
             Some("GBuffer pipeline"),
-            Some(depth_stencil_state),
+            Some(Self::depth_stencil_state()),
         )
     }
 }
@@ -1,6 +1,7 @@
 use wgpu::BindGroupLayout;
 
 use super::HorizonComputePipeline;
+use crate::renderer::shaderlibrary::ShaderLibrary;
 
 pub struct LightCullingPipeline(pub wgpu::ComputePipeline);
 
@@ -23,11 +24,14 @@ impl<'a> HorizonComputePipeline<'a> for LightCullingPipeline {
         });
 
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/lightculling.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/lightculling.wgsl"))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/lightculling.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/lightculling.wgsl"
+            ))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "lightculling.wgsl", wgsl);
 
         device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
             entry_point: "main",
@@ -5,6 +5,7 @@ use wgpu::{BindGroupLayout, ColorTargetState};
 use super::HorizonPipeline;
 
 use crate::renderer::primitives::texture::Texture;
+use crate::renderer::shaderlibrary::ShaderLibrary;
 
 pub struct LightPipeline(pub wgpu::RenderPipeline);
 
@@ -24,11 +25,11 @@ impl<'a> HorizonPipeline<'a> for LightPipeline {
         });
 
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/light.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/light.wgsl"))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/light.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/light.wgsl"))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "light.wgsl", wgsl);
         let vertex_state = wgpu::VertexState {
             buffers: &[],
             entry_point: "vs_main",
@@ -0,0 +1,57 @@
+use wgpu::BindGroupLayout;
+
+use super::HorizonComputePipeline;
+use crate::renderer::shaderlibrary::ShaderLibrary;
+
+/// Counts the pixels of the HDR scene into the luminance histogram.
+pub struct LuminanceHistogramPipeline(pub wgpu::ComputePipeline);
+/// Averages the histogram and moves the adapted luminance towards the average.
+pub struct AverageLuminancePipeline(pub wgpu::ComputePipeline);
+
+impl<'a> HorizonComputePipeline<'a> for LuminanceHistogramPipeline {
+    type RequiredLayouts = &'a BindGroupLayout;
+
+    fn create_compute_pipeline(
+        device: &wgpu::Device,
+        bind_group_layouts: Self::RequiredLayouts,
+    ) -> wgpu::ComputePipeline {
+        create_luminance_pipeline(device, bind_group_layouts, "build_histogram")
+    }
+}
+
+impl<'a> HorizonComputePipeline<'a> for AverageLuminancePipeline {
+    type RequiredLayouts = &'a BindGroupLayout;
+
+    fn create_compute_pipeline(
+        device: &wgpu::Device,
+        bind_group_layouts: Self::RequiredLayouts,
+    ) -> wgpu::ComputePipeline {
+        create_luminance_pipeline(device, bind_group_layouts, "average_luminance")
+    }
+}
+
+fn create_luminance_pipeline(
+    device: &wgpu::Device,
+    tone_mapping_bind_group: &BindGroupLayout,
+    entry_point: &str,
+) -> wgpu::ComputePipeline {
+    let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        bind_group_layouts: &[tone_mapping_bind_group],
+        label: Some("Luminance Pipeline Layout"),
+        push_constant_ranges: &[],
+    });
+
+    let wgsl = if cfg!(target_arch = "wasm32") {
+        wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/luminance.wgsl"))
+    } else {
+        wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/luminance.wgsl"))
+    };
+    let module = ShaderLibrary::create_module(device, "luminance.wgsl", wgsl);
+
+    device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        entry_point,
+        label: Some(entry_point),
+        layout: Some(&layout),
+        module: &module,
+    })
+}
@@ -0,0 +1,174 @@
+use std::borrow::Cow;
+
+use crate::renderer::{
+    pipelines::{gbufferpipeline::GBufferPipeline, RenderPipelineBuilder},
+    primitives::{
+        materialdefinition::MaterialDefinition,
+        texture::Texture,
+        vertex::{MeshVertexData, Vertex},
+    },
+    shaderlibrary::ShaderLibrary,
+};
+
+/// GBuffer pipeline of a custom material, generated from its `MaterialDefinition`.
+/// Uses the vertex stage and the bind group 0 of the `GBufferPipeline`, the material's bindings are in group 1.
+pub struct MaterialPipeline {
+    pub pipeline: wgpu::RenderPipeline,
+    pub bind_group_layout: wgpu::BindGroupLayout,
+    pub sampler: wgpu::Sampler,
+    pub textures: Vec<Texture>,
+    pub uniform_size: wgpu::BufferAddress,
+    /// Changes every time the material is built, the bind groups of the older builds are recreated.
+    pub revision: u32,
+}
+
+impl MaterialPipeline {
+    /// The `material.wgsl` template, without the material's bindings and snippet.
+    pub fn template() -> String {
+        let embedded = if cfg!(target_arch = "wasm32") {
+            include_str!(concat!(env!("OUT_DIR"), "/shaders/web/material.wgsl"))
+        } else {
+            include_str!(concat!(env!("OUT_DIR"), "/shaders/native/material.wgsl"))
+        };
+        ShaderLibrary::source("material.wgsl", embedded)
+    }
+    pub fn new(
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        definition: &MaterialDefinition,
+        uniform_layout: &wgpu::BindGroupLayout,
+        source: &str,
+        revision: u32,
+    ) -> anyhow::Result<Self> {
+        let textures = definition
+            .textures
+            .iter()
+            .map(|texture| {
+                Texture::from_image(
+                    device,
+                    queue,
+                    &texture.image,
+                    Some(texture.name.as_str()),
+                    texture.linear,
+                )
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("material sampler"),
+            address_mode_u: wgpu::AddressMode::Repeat,
+            address_mode_v: wgpu::AddressMode::Repeat,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            mipmap_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let bind_group_layout = Self::bind_group_layout(device, definition);
+        let label = definition.shader_name();
+        let module = device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+            label: Some(label.as_str()),
+            source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source)),
+        });
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                bind_group_layouts: &[uniform_layout, &bind_group_layout],
+                label: Some("material pipeline layout"),
+                push_constant_ranges: &[],
+            });
+        let targets = GBufferPipeline::targets();
+        let pipeline = RenderPipelineBuilder::create_pipeline(
+            Some(wgpu::FragmentState {
+                targets: &targets,
+                module: &module,
+                entry_point: "fs_main",
+            }),
+            GBufferPipeline::primitive_state(),
+            wgpu::VertexState {
+                buffers: &[MeshVertexData::desc()],
+                entry_point: "vs_main",
+                module: &module,
+            },
+            device,
+            &render_pipeline_layout,
+            Some(label.as_str()),
+            Some(GBufferPipeline::depth_stencil_state()),
+        );
+        let (_, uniform_size) = definition.parameter_layout();
+        Ok(Self {
+            pipeline,
+            bind_group_layout,
+            sampler,
+            textures,
+            uniform_size: (uniform_size * std::mem::size_of::<f32>()) as wgpu::BufferAddress,
+            revision,
+        })
+    }
+    /// The parameters' uniform buffer, the sampler and a texture binding per texture of the material.
+    fn bind_group_layout(
+        device: &wgpu::Device,
+        definition: &MaterialDefinition,
+    ) -> wgpu::BindGroupLayout {
+        let mut entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    min_binding_size: None,
+                    has_dynamic_offset: false,
+                },
+                count: None,
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                count: None,
+            },
+        ];
+        for index in 0..definition.textures.len() {
+            entries.push(wgpu::BindGroupLayoutEntry {
+                binding: index as u32 + 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                },
+                count: None,
+            });
+        }
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            entries: &entries,
+            label: Some("material bind group layout"),
+        })
+    }
+    /// Bind group of an entity using the material, `buffer` holds the entity's parameters.
+    pub fn create_bind_group(
+        &self,
+        device: &wgpu::Device,
+        buffer: &wgpu::Buffer,
+    ) -> wgpu::BindGroup {
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: buffer.as_entire_binding(),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&self.sampler),
+            },
+        ];
+        for (index, texture) in self.textures.iter().enumerate() {
+            entries.push(wgpu::BindGroupEntry {
+                binding: index as u32 + 2,
+                resource: wgpu::BindingResource::TextureView(&texture.view),
+            });
+        }
+        device.create_bind_group(&wgpu::BindGroupDescriptor {
+            layout: &self.bind_group_layout,
+            entries: &entries,
+            label: Some("material bind group"),
+        })
+    }
+}
@@ -3,30 +3,32 @@ use super::HorizonPipeline;
 use crate::renderer::pipelines::RenderPipelineBuilder;
 use crate::renderer::primitives::vertex::{MeshVertexData, Vertex};
 
-use wgpu::{vertex_attr_array, BindGroupLayout, ColorTargetState};
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use wgpu::{BindGroupLayout, ColorTargetState};
 
 pub struct ShadowPipeline(pub wgpu::RenderPipeline);
 
 impl<'a> HorizonPipeline<'a> for ShadowPipeline {
-    type RequiredLayouts = &'a BindGroupLayout;
+    type RequiredLayouts = (&'a BindGroupLayout, &'a BindGroupLayout);
 
     fn create_pipeline(
         device: &wgpu::Device,
         bind_group_layouts: Self::RequiredLayouts,
         _targets: &[ColorTargetState],
     ) -> wgpu::RenderPipeline {
+        let (shadow_bind_group, material_bind_group) = bind_group_layouts;
         let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
             label: Some("shadow"),
-            bind_group_layouts: &[bind_group_layouts],
+            bind_group_layouts: &[shadow_bind_group, material_bind_group],
             push_constant_ranges: &[],
         });
 
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/shadow.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/shadow.wgsl"))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/shadow.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/shadow.wgsl"))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "shadow.wgsl", wgsl);
         let depth_stencil_state = wgpu::DepthStencilState {
             format: wgpu::TextureFormat::Depth32Float,
             bias: wgpu::DepthBiasState {
@@ -38,16 +40,18 @@ impl<'a> HorizonPipeline<'a> for ShadowPipeline {
             depth_write_enabled: true,
             stencil: wgpu::StencilState::default(),
         };
-        let attr_array = vertex_attr_array![0=>Float32x3];
+        // the joint weights and indices are needed for skinning besides the position.
         let vertex_state = wgpu::VertexState {
-            buffers: &[wgpu::VertexBufferLayout {
-                array_stride: std::mem::size_of::<MeshVertexData>() as wgpu::BufferAddress,
-                attributes: &attr_array,
-                step_mode: wgpu::VertexStepMode::Vertex,
-            }],
+            buffers: &[MeshVertexData::desc()],
             entry_point: "vs_main",
             module: &module,
         };
+        // only discards masked texels, the depth is written without color targets.
+        let fragment_state = Some(wgpu::FragmentState {
+            targets: &[],
+            module: &module,
+            entry_point: "fs_main",
+        });
         let primitve_state = wgpu::PrimitiveState {
             front_face: wgpu::FrontFace::Ccw,
             topology: wgpu::PrimitiveTopology::TriangleList,
@@ -57,7 +61,7 @@ impl<'a> HorizonPipeline<'a> for ShadowPipeline {
             ..Default::default()
         };
         RenderPipelineBuilder::create_pipeline(
-            None,
+            fragment_state,
             primitve_state,
             vertex_state,
             device,
@@ -1,5 +1,6 @@
 use crate::renderer::pipelines::RenderPipelineBuilder;
 use crate::renderer::primitives::texture::Texture;
+use crate::renderer::shaderlibrary::ShaderLibrary;
 use crate::HorizonPipeline;
 use egui::CursorIcon::Default;
 use std::borrow::Cow;
@@ -21,11 +22,11 @@ impl<'a> HorizonPipeline<'a> for SkyboxPipeline {
             bind_group_layouts: &[bind_group_layouts],
         });
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/skybox.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/skybox.wgsl"))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/skybox.wgsl")
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/skybox.wgsl"))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "skybox.wgsl", wgsl);
         let vertex_state = wgpu::VertexState {
             buffers: &[],
             entry_point: "sky_vs",
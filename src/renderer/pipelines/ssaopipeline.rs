@@ -0,0 +1,89 @@
+use crate::renderer::pipelines::RenderPipelineBuilder;
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::HorizonPipeline;
+use wgpu::{BindGroupLayout, ColorTargetState, Device, RenderPipeline};
+
+/// Draws a triangle covering the screen which computes the noisy ambient occlusion.
+pub struct SsaoPipeline(pub wgpu::RenderPipeline);
+
+/// Draws a triangle covering the screen which blurs the ambient occlusion.
+pub struct SsaoBlurPipeline(pub wgpu::RenderPipeline);
+
+fn create_fullscreen_pipeline(
+    device: &Device,
+    bind_group_layout: &BindGroupLayout,
+    module: &wgpu::ShaderModule,
+    targets: &[ColorTargetState],
+    label: &str,
+) -> RenderPipeline {
+    let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+        label: Some(label),
+        push_constant_ranges: &[],
+        bind_group_layouts: &[bind_group_layout],
+    });
+    RenderPipelineBuilder::create_pipeline(
+        Some(wgpu::FragmentState {
+            module,
+            targets,
+            entry_point: "fs_main",
+        }),
+        wgpu::PrimitiveState::default(),
+        wgpu::VertexState {
+            buffers: &[],
+            entry_point: "vs_main",
+            module,
+        },
+        device,
+        &pipeline_layout,
+        Some(label),
+        None,
+    )
+}
+
+impl<'a> HorizonPipeline<'a> for SsaoPipeline {
+    type RequiredLayouts = &'a BindGroupLayout;
+
+    fn create_pipeline(
+        device: &Device,
+        bind_group_layouts: Self::RequiredLayouts,
+        targets: &[ColorTargetState],
+    ) -> RenderPipeline {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/ssao.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/ssao.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "ssao.wgsl", wgsl);
+        create_fullscreen_pipeline(
+            device,
+            bind_group_layouts,
+            &module,
+            targets,
+            "Ssao render pipeline",
+        )
+    }
+}
+
+impl<'a> HorizonPipeline<'a> for SsaoBlurPipeline {
+    type RequiredLayouts = &'a BindGroupLayout;
+
+    fn create_pipeline(
+        device: &Device,
+        bind_group_layouts: Self::RequiredLayouts,
+        targets: &[ColorTargetState],
+    ) -> RenderPipeline {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/ssaoblur.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/ssaoblur.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "ssaoblur.wgsl", wgsl);
+        create_fullscreen_pipeline(
+            device,
+            bind_group_layouts,
+            &module,
+            targets,
+            "Ssao blur render pipeline",
+        )
+    }
+}
@@ -0,0 +1,49 @@
+use crate::renderer::pipelines::RenderPipelineBuilder;
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::HorizonPipeline;
+use wgpu::{BindGroupLayout, ColorTargetState, Device, RenderPipeline};
+
+/// Draws a triangle covering the surface which maps the HDR scene to it.
+pub struct ToneMappingPipeline(pub wgpu::RenderPipeline);
+
+impl<'a> HorizonPipeline<'a> for ToneMappingPipeline {
+    type RequiredLayouts = &'a BindGroupLayout;
+
+    fn create_pipeline(
+        device: &Device,
+        bind_group_layouts: Self::RequiredLayouts,
+        targets: &[ColorTargetState],
+    ) -> RenderPipeline {
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("tone_mapping_render_pipeline_layout"),
+            push_constant_ranges: &[],
+            bind_group_layouts: &[bind_group_layouts],
+        });
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/tonemapping.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/tonemapping.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "tonemapping.wgsl", wgsl);
+        let vertex_state = wgpu::VertexState {
+            buffers: &[],
+            entry_point: "vs_main",
+            module: &module,
+        };
+        let fragment_state = wgpu::FragmentState {
+            module: &module,
+            targets,
+            entry_point: "fs_main",
+        };
+
+        RenderPipelineBuilder::create_pipeline(
+            Some(fragment_state),
+            wgpu::PrimitiveState::default(),
+            vertex_state,
+            device,
+            &pipeline_layout,
+            Some("Tone mapping render pipeline"),
+            None,
+        )
+    }
+}
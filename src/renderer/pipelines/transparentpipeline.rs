@@ -0,0 +1,87 @@
+use super::HorizonPipeline;
+
+use crate::renderer::pipelines::RenderPipelineBuilder;
+use crate::renderer::primitives::texture::Texture;
+use crate::renderer::primitives::vertex::{MeshVertexData, Vertex};
+
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use wgpu::ColorTargetState;
+
+/// Lights and blends the transparent primitives over the lit opaque scene.
+pub struct TransparentPipeline(pub wgpu::RenderPipeline);
+
+impl<'a> HorizonPipeline<'a> for TransparentPipeline {
+    type RequiredLayouts = (
+        &'a wgpu::BindGroupLayout,
+        &'a wgpu::BindGroupLayout,
+        &'a wgpu::BindGroupLayout,
+        &'a wgpu::BindGroupLayout,
+    );
+
+    fn create_pipeline(
+        device: &wgpu::Device,
+        bind_group_layouts: Self::RequiredLayouts,
+        targets: &[ColorTargetState],
+    ) -> wgpu::RenderPipeline {
+        let (uniform_bind_group, material_bind_group, light_bind_group, transparent_bind_group) =
+            bind_group_layouts;
+
+        let render_pipeline_layout =
+            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                bind_group_layouts: &[
+                    uniform_bind_group,
+                    material_bind_group,
+                    light_bind_group,
+                    transparent_bind_group,
+                ],
+                label: Some("transparent render pipeline layout"),
+                push_constant_ranges: &[],
+            });
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/transparent.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/transparent.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "transparent.wgsl", wgsl);
+        let vertex_state = wgpu::VertexState {
+            buffers: &[MeshVertexData::desc()],
+            entry_point: "vs_main",
+            module: &module,
+        };
+        let fragment_state = Some(wgpu::FragmentState {
+            targets,
+            module: &module,
+            entry_point: if cfg!(target_arch = "wasm32") {
+                "fs_main_web"
+            } else {
+                "fs_main"
+            },
+        });
+        // sorted primitives are blended over each other, they are tested against the opaque depth without writing it.
+        let depth_stencil_state = wgpu::DepthStencilState {
+            bias: wgpu::DepthBiasState::default(),
+            depth_compare: wgpu::CompareFunction::GreaterEqual,
+            format: Texture::DEPTH_FORMAT,
+            depth_write_enabled: false,
+            stencil: wgpu::StencilState::default(),
+        };
+        let primitive_state = wgpu::PrimitiveState {
+            front_face: wgpu::FrontFace::Ccw,
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            cull_mode: None,
+            strip_index_format: None,
+            polygon_mode: wgpu::PolygonMode::Fill,
+            ..Default::default()
+        };
+
+        RenderPipelineBuilder::create_pipeline(
+            fragment_state,
+            primitive_state,
+            vertex_state,
+            device,
+            &render_pipeline_layout,
+            Some("transparent Render pipeline"),
+            Some(depth_stencil_state),
+        )
+    }
+}
@@ -0,0 +1,347 @@
+pub mod bloom;
+pub mod chromaticaberration;
+pub mod colorgrading;
+pub mod fxaa;
+pub mod vignette;
+
+use specs::{World, WorldExt};
+use wgpu::util::DeviceExt;
+
+use crate::renderer::pipelines::RenderPipelineBuilder;
+use crate::renderer::primitives::uniforms::PostProcessUniforms;
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, TextureTypes, TextureViewTypes,
+};
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::surfacetexture::SurfaceTexture;
+
+use self::bloom::BloomEffect;
+use self::chromaticaberration::ChromaticAberrationEffect;
+use self::colorgrading::ColorGradingEffect;
+use self::fxaa::FxaaEffect;
+use self::vignette::VignetteEffect;
+
+/// Where an effect runs in the frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PostProcessStage {
+    /// Before the tone mapping, the effect blends onto the HDR scene.
+    Hdr,
+    /// After the tone mapping, every effect reads the output of the previous one.
+    Ldr,
+}
+
+/// A full-screen effect of the `PostProcessStack`, its settings are a resource of the world.
+pub trait PostProcessEffect: Send + Sync {
+    /// Identifies the effect in scripts.
+    fn name(&self) -> &'static str;
+    fn stage(&self) -> PostProcessStage;
+    fn is_enabled(&self, world: &World) -> bool;
+    fn set_enabled(&self, world: &World, enabled: bool);
+    /// Recreates the resources which depend on the size of the surface.
+    fn resize(&mut self, _device: &wgpu::Device, _sc_descriptor: &wgpu::SurfaceConfiguration) {}
+    /// Records the effect, HDR effects get the HDR scene as both the `source` and the `destination`.
+    fn apply(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+    );
+}
+
+/// The ordered list of post-processing effects.
+/// When any LDR effect is enabled the tone mapping renders into a ping-pong target instead of the surface,
+/// the last enabled effect writes the surface.
+#[derive(Default)]
+pub struct PostProcessStack {
+    effects: Vec<Box<dyn PostProcessEffect>>,
+    /// Which effects run this frame, decided once before the HDR stage.
+    enabled: Vec<bool>,
+}
+
+impl PostProcessStack {
+    /// The built-in effects: bloom, color grading, FXAA, chromatic aberration and vignette.
+    pub fn new(device: &wgpu::Device, sc_descriptor: &wgpu::SurfaceConfiguration) -> Self {
+        let mut stack = Self::default();
+        stack.push(Box::new(BloomEffect::new(device, sc_descriptor)));
+        stack.push(Box::new(ColorGradingEffect::new(
+            device,
+            sc_descriptor.format,
+        )));
+        stack.push(Box::new(FxaaEffect::new(device, sc_descriptor.format)));
+        stack.push(Box::new(ChromaticAberrationEffect::new(
+            device,
+            sc_descriptor.format,
+        )));
+        stack.push(Box::new(VignetteEffect::new(device, sc_descriptor.format)));
+        stack
+    }
+    /// Appends an effect, effects of the same stage run in the order they were pushed.
+    pub fn push(&mut self, effect: Box<dyn PostProcessEffect>) {
+        self.effects.push(effect);
+        self.enabled.push(false);
+    }
+    pub fn effect_names(&self) -> Vec<&'static str> {
+        self.effects.iter().map(|effect| effect.name()).collect()
+    }
+    /// Returns false if there's no effect with the name.
+    pub fn set_enabled(&self, world: &World, name: &str, enabled: bool) -> bool {
+        match self.effects.iter().find(|effect| effect.name() == name) {
+            Some(effect) => {
+                effect.set_enabled(world, enabled);
+                true
+            }
+            None => false,
+        }
+    }
+    pub fn is_enabled(&self, world: &World, name: &str) -> Option<bool> {
+        self.effects
+            .iter()
+            .find(|effect| effect.name() == name)
+            .map(|effect| effect.is_enabled(world))
+    }
+    /// Reads the settings of every effect, the stages of the frame run the effects enabled at this point.
+    pub fn prepare(&mut self, world: &World) {
+        for (enabled, effect) in self.enabled.iter_mut().zip(self.effects.iter()) {
+            *enabled = effect.is_enabled(world);
+        }
+    }
+    pub fn has_enabled(&self, stage: PostProcessStage) -> bool {
+        self.enabled_effects(stage).next().is_some()
+    }
+    fn enabled_effects(&self, stage: PostProcessStage) -> impl Iterator<Item = usize> + '_ {
+        (0..self.effects.len())
+            .filter(move |index| self.enabled[*index] && self.effects[*index].stage() == stage)
+    }
+    /// The ping-pong targets of the LDR stage have the size and format of the surface, they're recreated on every resize.
+    pub fn create_targets(
+        device: &wgpu::Device,
+        sc_descriptor: &wgpu::SurfaceConfiguration,
+        resource_container: &mut BindingResourceContainer,
+    ) {
+        for (texture_type, view_type) in [
+            (
+                TextureTypes::PostProcessPing,
+                TextureViewTypes::PostProcessPing,
+            ),
+            (
+                TextureTypes::PostProcessPong,
+                TextureViewTypes::PostProcessPong,
+            ),
+        ] {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("post_process_target"),
+                size: wgpu::Extent3d {
+                    width: sc_descriptor.width,
+                    height: sc_descriptor.height,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: sc_descriptor.format,
+                usage: wgpu::TextureUsages::RENDER_ATTACHMENT
+                    | wgpu::TextureUsages::TEXTURE_BINDING,
+            });
+            resource_container.texture_views[view_type] =
+                Some(texture.create_view(&wgpu::TextureViewDescriptor::default()));
+            resource_container.textures[texture_type] = Some(texture);
+        }
+    }
+    pub fn resize(&mut self, device: &wgpu::Device, sc_descriptor: &wgpu::SurfaceConfiguration) {
+        for effect in self.effects.iter_mut() {
+            effect.resize(device, sc_descriptor);
+        }
+    }
+    /// Records the enabled effects of the stage.
+    pub fn render(&mut self, world: &World, stage: PostProcessStage) {
+        let enabled_effects = self.enabled_effects(stage).collect::<Vec<_>>();
+        if enabled_effects.is_empty() {
+            return;
+        }
+        let binding_resource_container = world.read_resource::<BindingResourceContainer>();
+        let surface_texture = world.read_resource::<SurfaceTexture>();
+        let mut command_encoder = world.write_resource::<HorizonCommandEncoder>();
+        let encoder = command_encoder.get_encoder();
+        let view = |view_type: TextureViewTypes| {
+            binding_resource_container.texture_views[view_type]
+                .as_ref()
+                .unwrap()
+        };
+        match stage {
+            PostProcessStage::Hdr => {
+                let hdr_scene = view(TextureViewTypes::HdrScene);
+                for index in enabled_effects {
+                    self.effects[index].apply(world, encoder, hdr_scene, hdr_scene);
+                }
+            }
+            PostProcessStage::Ldr => {
+                // the tone mapping wrote the ping target.
+                let mut source = view(TextureViewTypes::PostProcessPing);
+                let mut spare = view(TextureViewTypes::PostProcessPong);
+                let last = enabled_effects.len() - 1;
+                for (position, index) in enabled_effects.into_iter().enumerate() {
+                    let destination = if position == last {
+                        surface_texture.view.as_ref().unwrap()
+                    } else {
+                        spare
+                    };
+                    self.effects[index].apply(world, encoder, source, destination);
+                    spare = source;
+                    source = destination;
+                }
+            }
+        }
+    }
+}
+
+/// A pipeline drawing a triangle covering the destination, which samples the source texture.
+/// The bind group has the source at binding 0, a linear sampler at 1, the `PostProcessUniforms` at 2
+/// and the effect's extra entries after them.
+pub struct FullscreenPass {
+    layout: wgpu::BindGroupLayout,
+    pipeline: wgpu::RenderPipeline,
+    sampler: wgpu::Sampler,
+    uniform_buffer: wgpu::Buffer,
+}
+
+impl FullscreenPass {
+    pub fn new(
+        device: &wgpu::Device,
+        module: &wgpu::ShaderModule,
+        fragment_entry_point: &str,
+        target: wgpu::ColorTargetState,
+        extra_entries: &[wgpu::BindGroupLayoutEntry],
+    ) -> Self {
+        let mut entries = vec![
+            wgpu::BindGroupLayoutEntry {
+                binding: 0,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                count: None,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D2,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                },
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 1,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                count: None,
+                ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            },
+            wgpu::BindGroupLayoutEntry {
+                binding: 2,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                count: None,
+                ty: wgpu::BindingType::Buffer {
+                    ty: wgpu::BufferBindingType::Uniform,
+                    has_dynamic_offset: false,
+                    min_binding_size: None,
+                },
+            },
+        ];
+        entries.extend_from_slice(extra_entries);
+        let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some(fragment_entry_point),
+            entries: &entries,
+        });
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some(fragment_entry_point),
+            bind_group_layouts: &[&layout],
+            push_constant_ranges: &[],
+        });
+        let pipeline = RenderPipelineBuilder::create_pipeline(
+            Some(wgpu::FragmentState {
+                module,
+                entry_point: fragment_entry_point,
+                targets: &[target],
+            }),
+            wgpu::PrimitiveState::default(),
+            wgpu::VertexState {
+                module,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            device,
+            &pipeline_layout,
+            Some(fragment_entry_point),
+            None,
+        );
+        let sampler = device.create_sampler(&wgpu::SamplerDescriptor {
+            label: Some("post_process_sampler"),
+            address_mode_u: wgpu::AddressMode::ClampToEdge,
+            address_mode_v: wgpu::AddressMode::ClampToEdge,
+            address_mode_w: wgpu::AddressMode::ClampToEdge,
+            mag_filter: wgpu::FilterMode::Linear,
+            min_filter: wgpu::FilterMode::Linear,
+            ..Default::default()
+        });
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("post_process_uniforms"),
+            contents: bytemuck::bytes_of(&PostProcessUniforms {
+                parameters: [0.0; 4],
+            }),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+        Self {
+            layout,
+            pipeline,
+            sampler,
+            uniform_buffer,
+        }
+    }
+    pub fn write_uniforms(&self, queue: &wgpu::Queue, uniforms: PostProcessUniforms) {
+        queue.write_buffer(&self.uniform_buffer, 0, bytemuck::bytes_of(&uniforms));
+    }
+    /// Draws into the destination, `load` keeps its contents for blending pipelines.
+    pub fn draw(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+        load: bool,
+        extra_entries: &[wgpu::BindGroupEntry],
+    ) {
+        let mut entries = vec![
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(source),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::Sampler(&self.sampler),
+            },
+            wgpu::BindGroupEntry {
+                binding: 2,
+                resource: self.uniform_buffer.as_entire_binding(),
+            },
+        ];
+        entries.extend_from_slice(extra_entries);
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("post_process_bind_group"),
+            layout: &self.layout,
+            entries: &entries,
+        });
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("post process pass"),
+            color_attachments: &[wgpu::RenderPassColorAttachment {
+                view: destination,
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: if load {
+                        wgpu::LoadOp::Load
+                    } else {
+                        wgpu::LoadOp::Clear(wgpu::Color::BLACK)
+                    },
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
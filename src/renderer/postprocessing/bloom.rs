@@ -0,0 +1,166 @@
+use specs::{World, WorldExt};
+
+use crate::renderer::bindgroups::tonemapping::ToneMappingBindGroup;
+use crate::renderer::postprocessing::{FullscreenPass, PostProcessEffect, PostProcessStage};
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::renderer::state::State;
+use crate::resources::postprocesssettings::BloomSettings;
+
+/// Blurs the bright parts of the HDR scene by downsampling them through a mip chain and
+/// upsampling them back with a tent filter, the result is added onto the scene.
+pub struct BloomEffect {
+    prefilter: FullscreenPass,
+    downsample: FullscreenPass,
+    upsample: FullscreenPass,
+    composite: FullscreenPass,
+    mip_views: Vec<wgpu::TextureView>,
+    _mip_chain: wgpu::Texture,
+}
+
+impl BloomEffect {
+    pub const MAX_MIP_COUNT: u32 = 6;
+
+    pub fn new(device: &wgpu::Device, sc_descriptor: &wgpu::SurfaceConfiguration) -> Self {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/bloom.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/bloom.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "bloom.wgsl", wgsl);
+        let replace = wgpu::ColorTargetState {
+            format: ToneMappingBindGroup::HDR_FORMAT,
+            blend: None,
+            write_mask: wgpu::ColorWrites::ALL,
+        };
+        let additive = wgpu::ColorTargetState {
+            format: ToneMappingBindGroup::HDR_FORMAT,
+            blend: Some(wgpu::BlendState {
+                color: wgpu::BlendComponent {
+                    src_factor: wgpu::BlendFactor::One,
+                    dst_factor: wgpu::BlendFactor::One,
+                    operation: wgpu::BlendOperation::Add,
+                },
+                alpha: wgpu::BlendComponent::REPLACE,
+            }),
+            write_mask: wgpu::ColorWrites::ALL,
+        };
+        let (mip_chain, mip_views) = Self::create_mip_chain(device, sc_descriptor);
+        Self {
+            prefilter: FullscreenPass::new(device, &module, "fs_prefilter", replace.clone(), &[]),
+            downsample: FullscreenPass::new(device, &module, "fs_downsample", replace, &[]),
+            upsample: FullscreenPass::new(device, &module, "fs_upsample", additive.clone(), &[]),
+            composite: FullscreenPass::new(device, &module, "fs_composite", additive, &[]),
+            mip_views,
+            _mip_chain: mip_chain,
+        }
+    }
+    /// The chain starts at half the size of the surface and stops before the mips get smaller than a few pixels.
+    fn create_mip_chain(
+        device: &wgpu::Device,
+        sc_descriptor: &wgpu::SurfaceConfiguration,
+    ) -> (wgpu::Texture, Vec<wgpu::TextureView>) {
+        let width = (sc_descriptor.width / 2).max(1);
+        let height = (sc_descriptor.height / 2).max(1);
+        let mip_count = (32 - width.min(height).leading_zeros())
+            .saturating_sub(2)
+            .clamp(1, Self::MAX_MIP_COUNT);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("bloom_mip_chain"),
+            size: wgpu::Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: mip_count,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: ToneMappingBindGroup::HDR_FORMAT,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+        let views = (0..mip_count)
+            .map(|mip| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("bloom_mip"),
+                    base_mip_level: mip,
+                    mip_level_count: std::num::NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            })
+            .collect();
+        (texture, views)
+    }
+}
+
+impl PostProcessEffect for BloomEffect {
+    fn name(&self) -> &'static str {
+        "bloom"
+    }
+    fn stage(&self) -> PostProcessStage {
+        PostProcessStage::Hdr
+    }
+    fn is_enabled(&self, world: &World) -> bool {
+        world.read_resource::<BloomSettings>().enabled
+    }
+    fn set_enabled(&self, world: &World, enabled: bool) {
+        world.write_resource::<BloomSettings>().enabled = enabled;
+    }
+    fn resize(&mut self, device: &wgpu::Device, sc_descriptor: &wgpu::SurfaceConfiguration) {
+        let (mip_chain, mip_views) = Self::create_mip_chain(device, sc_descriptor);
+        self._mip_chain = mip_chain;
+        self.mip_views = mip_views;
+    }
+    fn apply(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+    ) {
+        let state = world.read_resource::<State>();
+        let uniforms = world.read_resource::<BloomSettings>().to_raw();
+        for pass in [
+            &self.prefilter,
+            &self.downsample,
+            &self.upsample,
+            &self.composite,
+        ] {
+            pass.write_uniforms(&state.queue, uniforms);
+        }
+        self.prefilter.draw(
+            &state.device,
+            encoder,
+            source,
+            &self.mip_views[0],
+            false,
+            &[],
+        );
+        for mip in 1..self.mip_views.len() {
+            self.downsample.draw(
+                &state.device,
+                encoder,
+                &self.mip_views[mip - 1],
+                &self.mip_views[mip],
+                false,
+                &[],
+            );
+        }
+        for mip in (1..self.mip_views.len()).rev() {
+            self.upsample.draw(
+                &state.device,
+                encoder,
+                &self.mip_views[mip],
+                &self.mip_views[mip - 1],
+                true,
+                &[],
+            );
+        }
+        self.composite.draw(
+            &state.device,
+            encoder,
+            &self.mip_views[0],
+            destination,
+            true,
+            &[],
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use specs::{World, WorldExt};
+
+use crate::renderer::postprocessing::{FullscreenPass, PostProcessEffect, PostProcessStage};
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::renderer::state::State;
+use crate::resources::postprocesssettings::ChromaticAberrationSettings;
+
+/// Splits the red and blue channels towards the edges of the frame, imitating a cheap lens.
+pub struct ChromaticAberrationEffect {
+    pass: FullscreenPass,
+}
+
+impl ChromaticAberrationEffect {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/web/chromaticaberration.wgsl"
+            ))
+        } else {
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/chromaticaberration.wgsl"
+            ))
+        };
+        let module = ShaderLibrary::create_module(device, "chromaticaberration.wgsl", wgsl);
+        Self {
+            pass: FullscreenPass::new(device, &module, "fs_main", format.into(), &[]),
+        }
+    }
+}
+
+impl PostProcessEffect for ChromaticAberrationEffect {
+    fn name(&self) -> &'static str {
+        "chromaticAberration"
+    }
+    fn stage(&self) -> PostProcessStage {
+        PostProcessStage::Ldr
+    }
+    fn is_enabled(&self, world: &World) -> bool {
+        world.read_resource::<ChromaticAberrationSettings>().enabled
+    }
+    fn set_enabled(&self, world: &World, enabled: bool) {
+        world
+            .write_resource::<ChromaticAberrationSettings>()
+            .enabled = enabled;
+    }
+    fn apply(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+    ) {
+        let state = world.read_resource::<State>();
+        self.pass.write_uniforms(
+            &state.queue,
+            world
+                .read_resource::<ChromaticAberrationSettings>()
+                .to_raw(),
+        );
+        self.pass
+            .draw(&state.device, encoder, source, destination, false, &[]);
+    }
+}
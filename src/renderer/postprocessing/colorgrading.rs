@@ -0,0 +1,151 @@
+use std::num::NonZeroU32;
+
+use specs::{World, WorldExt};
+
+use crate::renderer::postprocessing::{FullscreenPass, PostProcessEffect, PostProcessStage};
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::renderer::state::State;
+use crate::resources::postprocesssettings::{ColorGradingLut, ColorGradingSettings};
+
+/// Remaps the colors of the tone mapped frame with the lookup table of the `ColorGradingSettings`.
+pub struct ColorGradingEffect {
+    pass: FullscreenPass,
+    lut_texture: wgpu::Texture,
+    lut_view: wgpu::TextureView,
+    lut_size: u32,
+    /// Revision of the lookup table in `lut_texture`, none until the first upload.
+    uploaded_revision: Option<u32>,
+}
+
+impl ColorGradingEffect {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/colorgrading.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/colorgrading.wgsl"
+            ))
+        };
+        let module = ShaderLibrary::create_module(device, "colorgrading.wgsl", wgsl);
+        let pass = FullscreenPass::new(
+            device,
+            &module,
+            "fs_main",
+            format.into(),
+            &[wgpu::BindGroupLayoutEntry {
+                binding: 3,
+                visibility: wgpu::ShaderStages::FRAGMENT,
+                count: None,
+                ty: wgpu::BindingType::Texture {
+                    multisampled: false,
+                    view_dimension: wgpu::TextureViewDimension::D3,
+                    sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                },
+            }],
+        );
+        let (lut_texture, lut_view) =
+            Self::create_lut_texture(device, ColorGradingLut::IDENTITY_SIZE);
+        Self {
+            pass,
+            lut_texture,
+            lut_view,
+            lut_size: ColorGradingLut::IDENTITY_SIZE,
+            uploaded_revision: None,
+        }
+    }
+    fn create_lut_texture(device: &wgpu::Device, size: u32) -> (wgpu::Texture, wgpu::TextureView) {
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("color_grading_lut"),
+            size: wgpu::Extent3d {
+                width: size,
+                height: size,
+                depth_or_array_layers: size,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D3,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        (texture, view)
+    }
+    fn upload_lut(&mut self, device: &wgpu::Device, queue: &wgpu::Queue, lut: &ColorGradingLut) {
+        if self.lut_size != lut.size {
+            let (lut_texture, lut_view) = Self::create_lut_texture(device, lut.size);
+            self.lut_texture = lut_texture;
+            self.lut_view = lut_view;
+            self.lut_size = lut.size;
+        }
+        let data = lut
+            .entries
+            .iter()
+            .flat_map(|entry| {
+                let [r, g, b] =
+                    entry.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+                [r, g, b, 255]
+            })
+            .collect::<Vec<_>>();
+        queue.write_texture(
+            wgpu::ImageCopyTexture {
+                texture: &self.lut_texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d::ZERO,
+                aspect: wgpu::TextureAspect::All,
+            },
+            &data,
+            wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(4 * lut.size),
+                rows_per_image: NonZeroU32::new(lut.size),
+            },
+            wgpu::Extent3d {
+                width: lut.size,
+                height: lut.size,
+                depth_or_array_layers: lut.size,
+            },
+        );
+    }
+}
+
+impl PostProcessEffect for ColorGradingEffect {
+    fn name(&self) -> &'static str {
+        "colorGrading"
+    }
+    fn stage(&self) -> PostProcessStage {
+        PostProcessStage::Ldr
+    }
+    fn is_enabled(&self, world: &World) -> bool {
+        world.read_resource::<ColorGradingSettings>().enabled
+    }
+    fn set_enabled(&self, world: &World, enabled: bool) {
+        world.write_resource::<ColorGradingSettings>().enabled = enabled;
+    }
+    fn apply(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+    ) {
+        let state = world.read_resource::<State>();
+        let settings = world.read_resource::<ColorGradingSettings>();
+        if self.uploaded_revision != Some(settings.lut_revision()) {
+            self.upload_lut(&state.device, &state.queue, settings.lut());
+            self.uploaded_revision = Some(settings.lut_revision());
+        }
+        self.pass.write_uniforms(&state.queue, settings.to_raw());
+        self.pass.draw(
+            &state.device,
+            encoder,
+            source,
+            destination,
+            false,
+            &[wgpu::BindGroupEntry {
+                binding: 3,
+                resource: wgpu::BindingResource::TextureView(&self.lut_view),
+            }],
+        );
+    }
+}
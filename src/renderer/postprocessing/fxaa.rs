@@ -0,0 +1,53 @@
+use specs::{World, WorldExt};
+
+use crate::renderer::postprocessing::{FullscreenPass, PostProcessEffect, PostProcessStage};
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::renderer::state::State;
+use crate::resources::postprocesssettings::FxaaSettings;
+
+/// Smooths the aliased edges of the tone mapped frame, it should run before the effects which distort the frame.
+pub struct FxaaEffect {
+    pass: FullscreenPass,
+}
+
+impl FxaaEffect {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/fxaa.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/fxaa.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "fxaa.wgsl", wgsl);
+        Self {
+            pass: FullscreenPass::new(device, &module, "fs_main", format.into(), &[]),
+        }
+    }
+}
+
+impl PostProcessEffect for FxaaEffect {
+    fn name(&self) -> &'static str {
+        "fxaa"
+    }
+    fn stage(&self) -> PostProcessStage {
+        PostProcessStage::Ldr
+    }
+    fn is_enabled(&self, world: &World) -> bool {
+        world.read_resource::<FxaaSettings>().enabled
+    }
+    fn set_enabled(&self, world: &World, enabled: bool) {
+        world.write_resource::<FxaaSettings>().enabled = enabled;
+    }
+    fn apply(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+    ) {
+        let state = world.read_resource::<State>();
+        self.pass
+            .write_uniforms(&state.queue, world.read_resource::<FxaaSettings>().to_raw());
+        self.pass
+            .draw(&state.device, encoder, source, destination, false, &[]);
+    }
+}
@@ -0,0 +1,55 @@
+use specs::{World, WorldExt};
+
+use crate::renderer::postprocessing::{FullscreenPass, PostProcessEffect, PostProcessStage};
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::renderer::state::State;
+use crate::resources::postprocesssettings::VignetteSettings;
+
+/// Darkens the corners of the frame.
+pub struct VignetteEffect {
+    pass: FullscreenPass,
+}
+
+impl VignetteEffect {
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/vignette.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/vignette.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "vignette.wgsl", wgsl);
+        Self {
+            pass: FullscreenPass::new(device, &module, "fs_main", format.into(), &[]),
+        }
+    }
+}
+
+impl PostProcessEffect for VignetteEffect {
+    fn name(&self) -> &'static str {
+        "vignette"
+    }
+    fn stage(&self) -> PostProcessStage {
+        PostProcessStage::Ldr
+    }
+    fn is_enabled(&self, world: &World) -> bool {
+        world.read_resource::<VignetteSettings>().enabled
+    }
+    fn set_enabled(&self, world: &World, enabled: bool) {
+        world.write_resource::<VignetteSettings>().enabled = enabled;
+    }
+    fn apply(
+        &mut self,
+        world: &World,
+        encoder: &mut wgpu::CommandEncoder,
+        source: &wgpu::TextureView,
+        destination: &wgpu::TextureView,
+    ) {
+        let state = world.read_resource::<State>();
+        self.pass.write_uniforms(
+            &state.queue,
+            world.read_resource::<VignetteSettings>().to_raw(),
+        );
+        self.pass
+            .draw(&state.device, encoder, source, destination, false, &[]);
+    }
+}
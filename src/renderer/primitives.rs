@@ -1,5 +1,6 @@
 pub mod lights;
 pub mod material;
+pub mod materialdefinition;
 pub mod mesh;
 pub mod texture;
 pub mod uniforms;
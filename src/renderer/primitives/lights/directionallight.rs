@@ -1,7 +1,7 @@
 use bytemuck::*;
-use rapier3d::na::{Matrix4, Perspective3, Point3, Vector3, Vector4};
-use std::ops::DivAssign;
+use rapier3d::na::{Matrix4, Point3, Vector3, Vector4};
 
+use crate::resources::shadowsettings::ShadowSettings;
 use crate::{renderer::state::State, resources::camera::Camera, Projection};
 
 pub struct DirectionalLight {
@@ -11,16 +11,17 @@ pub struct DirectionalLight {
 }
 
 impl DirectionalLight {
+    /// Objects this far outside of a cascade towards the light still cast shadows into it.
+    const CASTER_DISTANCE: f32 = 25.0;
+
     pub fn new(yaw: f32, pitch: f32, color: wgpu::Color) -> Self {
         Self { yaw, pitch, color }
     }
 
     pub fn to_raw(&self) -> DirectionalLightRaw {
         DirectionalLightRaw {
-            direction: Vector3::new(self.yaw.cos(), self.pitch.sin(), self.yaw.sin())
-                .normalize()
-                .to_homogeneous()
-                .into(),
+            // has to match the direction the shadow cascades are rendered from.
+            direction: self.get_direction().to_homogeneous().into(),
             color: [
                 self.color.r as f32,
                 self.color.g as f32,
@@ -29,6 +30,7 @@ impl DirectionalLight {
             ],
         }
     }
+    /// Points towards the light.
     fn get_direction(&self) -> Vector3<f32> {
         Vector3::new(
             self.yaw.cos() * self.pitch.cos(),
@@ -37,105 +39,105 @@ impl DirectionalLight {
         )
         .normalize()
     }
+    /// Splits the camera frustum up to the shadow distance into cascades and returns the far
+    /// view space depth of every cascade along with the matrix its shadow map is rendered with.
     pub fn get_view_and_proj_matrices(
         &self,
         cam: &Camera,
-        z_near: f32,
-        mut z_far: f32,
+        projection: &Projection,
+        settings: &ShadowSettings,
     ) -> Vec<(f32, Matrix4<f32>)> {
-        if cfg!(target_arch = "wasm32") {
-            z_far /= 10.0; // if on web reduce shadow map gen range in order to keep detail at close range.
-        }
-        let mut cascade_splits = Vec::new();
-        let mut cascades = Vec::new();
-
+        let cascade_count = settings.cascade_count();
+        let z_near = projection.z_near;
+        let z_far = settings.shadow_distance.max(z_near * 2.0);
         let clip_range = z_far - z_near;
-        let min_z = z_near;
-        let max_z = z_near + clip_range;
-        let range = max_z - min_z;
-        let ratio = max_z / min_z;
-        let cascade_split_lambda = 0.95;
+        let ratio = z_far / z_near;
         // from: https://developer.nvidia.com/gpugems/GPUGems3/gpugems3_ch10.html
-        for i in 0..State::SHADOW_SIZE.depth_or_array_layers {
-            let p = (i as f32 + 1.0) / State::SHADOW_SIZE.depth_or_array_layers as f32;
-            let log = min_z * ratio.powf(p);
-            let uniform = min_z + range * p;
-            let d = cascade_split_lambda * (log - uniform) + uniform;
-            cascade_splits.push((d - z_near) / clip_range);
-        }
-
-        let mut last_split_dist = 0.0;
-        for split in cascade_splits {
-            let proj =
-                nalgebra_glm::perspective_rh(16.0 / 9.0, 45.0_f32.to_radians(), z_near, z_far);
+        let cascade_splits = (0..cascade_count).map(|i| {
+            let p = (i as f32 + 1.0) / cascade_count as f32;
+            let log = z_near * ratio.powf(p);
+            let uniform = z_near + clip_range * p;
+            settings.split_lambda * (log - uniform) + uniform
+        });
 
-            let view_proj_inverse = (Matrix4::from(proj.data.0) * cam.get_view_matrix())
-                .try_inverse()
-                .unwrap();
-            let mut corners = vec![
-                Vector3::new(-1.0, 1.0, -1.0),
-                Vector3::new(1.0, 1.0, -1.0),
-                Vector3::new(1.0, -1.0, -1.0),
-                Vector3::new(-1.0, -1.0, -1.0),
-                Vector3::new(-1.0, 1.0, 1.0),
-                Vector3::new(1.0, 1.0, 1.0),
-                Vector3::new(1.0, -1.0, 1.0),
-                Vector3::new(-1.0, -1.0, 1.0),
-            ];
-
-            for corner in &mut corners {
-                let inv_corner =
-                    view_proj_inverse * Vector4::new(corner.x, corner.y, corner.z, 1.0);
-                *corner = (inv_corner.component_div(&Vector4::new(
-                    inv_corner.w,
-                    inv_corner.w,
-                    inv_corner.w,
-                    inv_corner.w,
-                )))
-                .xyz();
-            }
+        let proj = nalgebra_glm::perspective_rh_zo(
+            projection.aspect_ratio,
+            projection.fov_y,
+            z_near,
+            z_far,
+        );
+        let view_proj_inverse = (Matrix4::from(proj.data.0) * cam.get_view_matrix())
+            .try_inverse()
+            .unwrap();
+        // corners of the near plane followed by the ones of the far plane in world space.
+        let mut frustum_corners = [Vector3::zeros(); 8];
+        for (i, &(x, y, z)) in [
+            (-1.0, 1.0, 0.0),
+            (1.0, 1.0, 0.0),
+            (1.0, -1.0, 0.0),
+            (-1.0, -1.0, 0.0),
+            (-1.0, 1.0, 1.0),
+            (1.0, 1.0, 1.0),
+            (1.0, -1.0, 1.0),
+            (-1.0, -1.0, 1.0),
+        ]
+        .iter()
+        .enumerate()
+        {
+            let corner = view_proj_inverse * Vector4::new(x, y, z, 1.0);
+            frustum_corners[i] = corner.xyz() / corner.w;
+        }
 
+        let light_direction = self.get_direction();
+        let up = if light_direction.y.abs() > 0.99 {
+            Vector3::z()
+        } else {
+            Vector3::y()
+        };
+        let texel_count = settings.resolution() as f32;
+        let mut cascades = Vec::new();
+        let mut last_split = 0.0;
+        for split_depth in cascade_splits {
+            let split = (split_depth - z_near) / clip_range;
+            let mut corners = frustum_corners;
             for i in 0..4 {
-                let dist = corners[i + 4] - corners[i];
-                corners[i + 4] = corners[i] + (dist * split);
-                corners[i] = corners[i] + (dist * last_split_dist);
-            }
-            // Calculate center
-            let mut center = Vector3::zeros();
-            for c in &corners {
-                center += c.xyz();
+                let dist = frustum_corners[i + 4] - frustum_corners[i];
+                corners[i + 4] = frustum_corners[i] + dist * split;
+                corners[i] = frustum_corners[i] + dist * last_split;
             }
-            let len = corners.len() as f32;
-            center.div_assign(len);
+            let center = corners.iter().sum::<Vector3<f32>>() / corners.len() as f32;
+            // the bounding sphere doesn't change with the camera's rotation which keeps the
+            // cascade's size stable, the radius is rounded to avoid flickering from float errors.
+            let radius = corners
+                .iter()
+                .map(|corner| (corner - center).magnitude())
+                .fold(0.0_f32, f32::max);
+            let radius = (radius * 16.0).ceil() / 16.0;
 
-            let mut radius = 0.0_f32;
-            for corner in corners {
-                let dist = (corner.xyz() - center).magnitude();
-                radius = radius.max(dist);
-            }
-            let ceil = (radius * 16.0).ceil();
-            radius = ceil / 16.0;
-            let max_extent = Vector3::new(radius, radius, radius);
-            let min_extent = -max_extent;
-            let light_dir = -self.get_direction();
             let light_view = Matrix4::look_at_rh(
-                &Point3::from(center - light_dir * -min_extent.z),
+                &Point3::from(center + light_direction * (radius + Self::CASTER_DISTANCE)),
                 &Point3::from(center),
-                &Vector3::y_axis(),
+                &up,
             );
-            let ortho: Matrix4<f32> = Matrix4::new_orthographic(
-                min_extent.x,
-                max_extent.x,
-                min_extent.y,
-                max_extent.y,
+            let ortho = Matrix4::new_orthographic(
+                -radius,
+                radius,
+                -radius,
+                radius,
                 0.0,
-                max_extent.z - min_extent.z,
+                2.0 * radius + Self::CASTER_DISTANCE,
             );
-            let split_depth = (z_near + split * clip_range) * -1.; // may not be necessary to multiply by -1.
-            let view_proj = ortho * light_view;
-            cascades.push((split_depth, view_proj));
+            let mut shadow_matrix =
+                Matrix4::from(State::OPENGL_TO_WGPU_MATRIX) * ortho * light_view;
+            // snap the world origin to a texel so the shadow edges don't shimmer as the camera moves.
+            let origin = shadow_matrix * Vector4::new(0.0, 0.0, 0.0, 1.0);
+            let origin_texel = origin.xy() * (texel_count / 2.0);
+            let offset = (origin_texel.map(f32::round) - origin_texel) * (2.0 / texel_count);
+            shadow_matrix[(0, 3)] += offset.x;
+            shadow_matrix[(1, 3)] += offset.y;
+            cascades.push((split_depth, shadow_matrix));
 
-            last_split_dist = split;
+            last_split = split;
         }
         cascades
     }
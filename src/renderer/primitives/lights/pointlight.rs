@@ -1,12 +1,16 @@
 use bytemuck::*;
-use rapier3d::na::{Point3, Vector3};
+use rapier3d::na::{Matrix4, Point3, Vector3};
 use specs::*;
+
+use crate::renderer::state::State;
+
 #[derive(Component, Copy, Clone)]
 #[storage(VecStorage)]
 pub struct PointLight {
     pub color: Vector3<f32>,
     pub radius: f32,
-    pub attached_to: Option<Entity>,
+    /// Shadows are only rendered for the closest shadow casting lights, see `ShadowSettings`.
+    pub casts_shadow: bool,
 }
 #[repr(C)]
 #[derive(Clone, Copy, Pod, Zeroable)]
@@ -14,24 +18,49 @@ pub struct PointLightRaw {
     pos: [f32; 4],
     color: [f32; 3],
     radius: f32,
+    /// Index of the first of the six cube face tiles in the shadow atlas, -1 without shadows.
+    shadow_index: i32,
+    _padding: [u32; 3],
 }
 
 impl PointLight {
-    pub fn new(color: Vector3<f32>, radius: f32, attached_to: Option<Entity>) -> Self {
+    pub fn new(color: Vector3<f32>, radius: f32, casts_shadow: bool) -> Self {
         Self {
             color,
             radius,
-            attached_to,
+            casts_shadow,
         }
     }
-    pub fn attach_to(&mut self, ent: Entity) {
-        self.attached_to = Some(ent);
-    }
-    pub fn to_raw(&self, pos: Vector3<f32>) -> PointLightRaw {
+    pub fn to_raw(&self, pos: Vector3<f32>, shadow_index: i32) -> PointLightRaw {
         PointLightRaw {
             radius: self.radius,
             color: [self.color.x, self.color.y, self.color.z],
             pos: [pos.x, pos.y, pos.z, 1.0],
+            shadow_index,
+            _padding: [0; 3],
         }
     }
+    /// View projection matrices of the cube faces in the +X, -X, +Y, -Y, +Z, -Z order the shaders expect.
+    pub fn get_shadow_matrices(&self, pos: Vector3<f32>) -> [Matrix4<f32>; 6] {
+        let proj = Matrix4::from(
+            nalgebra_glm::perspective_rh_zo(
+                1.0,
+                90.0_f32.to_radians(),
+                State::LIGHT_SHADOW_NEAR_PLANE,
+                self.radius.max(State::LIGHT_SHADOW_NEAR_PLANE * 2.0),
+            )
+            .data
+            .0,
+        );
+        let eye = Point3::from(pos);
+        let faces = [
+            (Vector3::x(), -Vector3::y()),
+            (-Vector3::x(), -Vector3::y()),
+            (Vector3::y(), Vector3::z()),
+            (-Vector3::y(), -Vector3::z()),
+            (Vector3::z(), -Vector3::y()),
+            (-Vector3::z(), -Vector3::y()),
+        ];
+        faces.map(|(direction, up)| proj * Matrix4::look_at_rh(&eye, &(eye + direction), &up))
+    }
 }
@@ -1,52 +1,109 @@
-use rapier3d::math::Point;
 use rapier3d::na::{Matrix4, Point3, Vector3};
 use specs::*;
-#[derive(Component)]
+
+use crate::renderer::state::State;
+
+#[derive(Component, Copy, Clone)]
 #[storage(VecStorage)]
 pub struct SpotLight {
-    position: Point3<f32>,
-    direction: Matrix4<f32>,
-    color: Vector3<f32>,
-    radius: f32,
+    pub color: Vector3<f32>,
+    pub radius: f32,
     /// Requires cos
     inner_cutoff: f32,
     /// Requires cos
     outer_cutoff: f32,
+    /// Shadows are only rendered for the closest shadow casting lights, see `ShadowSettings`.
+    pub casts_shadow: bool,
 }
 #[repr(C)]
 #[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
 pub struct SpotLightRaw {
     pos: [f32; 4],
-    dir: [[f32; 4]; 4],
+    direction: [f32; 4],
     color: [f32; 3],
     radius: f32,
     cutoffs: [f32; 4],
+    /// Index of the light's tile in the shadow atlas, -1 without shadows.
+    shadow_index: i32,
+    _padding: [u32; 3],
 }
 impl SpotLight {
     pub fn new(
-        position: Point3<f32>,
-        direction: Matrix4<f32>,
         color: Vector3<f32>,
         radius: f32,
         inner_cutoff: f32,
         outer_cutoff: f32,
+        casts_shadow: bool,
     ) -> Self {
         Self {
-            position,
             color,
-            direction,
             radius,
             inner_cutoff,
             outer_cutoff,
+            casts_shadow,
         }
     }
-    pub fn to_raw(&self) -> SpotLightRaw {
+    /// Creates the light from the half angles of its cones in degrees measured from the direction,
+    /// the outer cone is never narrower than the inner one and both stay below 90 degrees.
+    pub fn from_cone_angles(
+        color: Vector3<f32>,
+        radius: f32,
+        inner_angle: f32,
+        outer_angle: f32,
+        casts_shadow: bool,
+    ) -> Self {
+        let inner_angle = inner_angle.clamp(0.0, 89.0);
+        let outer_angle = outer_angle.clamp(inner_angle, 89.0);
+        Self::new(
+            color,
+            radius,
+            inner_angle.to_radians().cos(),
+            outer_angle.to_radians().cos(),
+            casts_shadow,
+        )
+    }
+    /// Half angle of the inner cone in degrees.
+    pub fn inner_cone_angle(&self) -> f32 {
+        self.inner_cutoff.clamp(-1.0, 1.0).acos().to_degrees()
+    }
+    /// Half angle of the outer cone in degrees.
+    pub fn outer_cone_angle(&self) -> f32 {
+        self.outer_cutoff.clamp(-1.0, 1.0).acos().to_degrees()
+    }
+    /// The light shines along the negative z axis of its transform.
+    pub fn get_direction(world_matrix: &Matrix4<f32>) -> Vector3<f32> {
+        world_matrix.transform_vector(&-Vector3::z()).normalize()
+    }
+    pub fn to_raw(
+        &self,
+        pos: Vector3<f32>,
+        direction: Vector3<f32>,
+        shadow_index: i32,
+    ) -> SpotLightRaw {
         SpotLightRaw {
             radius: self.radius,
             color: [self.color.x, self.color.y, self.color.z],
-            pos: [self.position.x, self.position.y, self.position.z, 1.0],
-            dir: self.direction.into(),
+            pos: [pos.x, pos.y, pos.z, 1.0],
+            direction: direction.to_homogeneous().into(),
             cutoffs: [self.inner_cutoff, self.outer_cutoff, 1.0, 1.0],
+            shadow_index,
+            _padding: [0; 3],
         }
     }
+    pub fn get_shadow_matrix(&self, pos: Vector3<f32>, direction: Vector3<f32>) -> Matrix4<f32> {
+        let fov = 2.0 * self.outer_cutoff.clamp(-1.0, 1.0).acos();
+        let proj = nalgebra_glm::perspective_rh_zo(
+            1.0,
+            fov.clamp(1.0_f32.to_radians(), 179.0_f32.to_radians()),
+            State::LIGHT_SHADOW_NEAR_PLANE,
+            self.radius.max(State::LIGHT_SHADOW_NEAR_PLANE * 2.0),
+        );
+        let up = if direction.y.abs() > 0.99 {
+            Vector3::z()
+        } else {
+            Vector3::y()
+        };
+        let eye = Point3::from(pos);
+        Matrix4::from(proj.data.0) * Matrix4::look_at_rh(&eye, &(eye + direction), &up)
+    }
 }
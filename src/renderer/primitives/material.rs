@@ -4,6 +4,7 @@ use bytemuck::*;
 use enum_map::EnumMap;
 use gltf::material::AlphaMode;
 use image::DynamicImage;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use wgpu::util::DeviceExt;
 
@@ -13,6 +14,50 @@ pub struct Material {
     pub bind_group: wgpu::BindGroup,
     pub normal_texture: Texture,
 }
+/// The textures of a glTF material scripts can replace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MaterialTextureSlot {
+    BaseColor,
+    MetallicRoughness,
+    Normal,
+    Occlusion,
+    Emissive,
+}
+impl MaterialTextureSlot {
+    pub const ALL: [MaterialTextureSlot; 5] = [
+        MaterialTextureSlot::BaseColor,
+        MaterialTextureSlot::MetallicRoughness,
+        MaterialTextureSlot::Normal,
+        MaterialTextureSlot::Occlusion,
+        MaterialTextureSlot::Emissive,
+    ];
+    /// Whether the slot holds data rather than colors, those textures aren't sRGB decoded.
+    pub fn is_linear(self) -> bool {
+        matches!(
+            self,
+            MaterialTextureSlot::MetallicRoughness
+                | MaterialTextureSlot::Normal
+                | MaterialTextureSlot::Occlusion
+        )
+    }
+    /// The key of the slot's texture made from the given image.
+    pub fn texture_key(self, image: usize) -> TextureKey {
+        (image, self.is_linear())
+    }
+    fn label(self) -> &'static str {
+        match self {
+            MaterialTextureSlot::BaseColor => "diffuse",
+            MaterialTextureSlot::MetallicRoughness => "metallic-roughness",
+            MaterialTextureSlot::Normal => "normal",
+            MaterialTextureSlot::Occlusion => "occlusion",
+            MaterialTextureSlot::Emissive => "emissive",
+        }
+    }
+}
+/// A model's GPU texture, the index of its image and whether it's linear instead of sRGB.
+pub type TextureKey = (usize, bool);
+
 #[derive(Clone, Debug)]
 pub struct GltfMaterial {
     pub base_color: [f32; 4],
@@ -27,6 +72,10 @@ pub struct GltfMaterial {
     pub emissive_texture: Option<usize>,
     pub unlit: bool,
     pub alpha_mode: AlphaMode,
+    /// Alpha below which masked texels are discarded.
+    pub alpha_cutoff: f32,
+    /// Fraction of the light transmitted through the surface, `KHR_materials_transmission`.
+    pub transmission: f32,
     pub name: String,
 }
 impl GltfMaterial {
@@ -46,131 +95,112 @@ impl GltfMaterial {
                 0.0,
             ],
             base_color_factor: self.base_color,
+            alpha_cutoff_mode_transmission: [
+                self.alpha_cutoff,
+                Self::alpha_mode_index(self.alpha_mode),
+                self.transmission,
+                0.0,
+            ],
+        }
+    }
+    /// Materials which are blended or transmit light are drawn by the transparent pass.
+    pub fn is_transparent(&self) -> bool {
+        self.alpha_mode == AlphaMode::Blend || self.transmission > 0.0
+    }
+    fn alpha_mode_index(alpha_mode: AlphaMode) -> f32 {
+        match alpha_mode {
+            AlphaMode::Opaque => 0.0,
+            AlphaMode::Mask => 1.0,
+            AlphaMode::Blend => 2.0,
+        }
+    }
+    /// The uniform buffer of the material's bind group, rewritten when scripts change the material.
+    pub fn create_uniform_buffer(&self, device: &wgpu::Device) -> wgpu::Buffer {
+        device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(format!("material-uniform-{}", self.name).as_str()),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+            contents: bytemuck::bytes_of(&self.to_raw_material()),
+        })
+    }
+    /// The texture index of a slot, `None` when the slot uses the default texture.
+    pub fn texture_mut(&mut self, slot: MaterialTextureSlot) -> &mut Option<usize> {
+        match slot {
+            MaterialTextureSlot::BaseColor => &mut self.base_color_texture,
+            MaterialTextureSlot::MetallicRoughness => &mut self.roughness_texture,
+            MaterialTextureSlot::Normal => &mut self.normal_map_texture,
+            MaterialTextureSlot::Occlusion => &mut self.occlusion_texture,
+            MaterialTextureSlot::Emissive => &mut self.emissive_texture,
+        }
+    }
+    /// The texture index of a slot, `None` when the slot uses the default texture.
+    pub fn texture(&self, slot: MaterialTextureSlot) -> Option<usize> {
+        match slot {
+            MaterialTextureSlot::BaseColor => self.base_color_texture,
+            MaterialTextureSlot::MetallicRoughness => self.roughness_texture,
+            MaterialTextureSlot::Normal => self.normal_map_texture,
+            MaterialTextureSlot::Occlusion => self.occlusion_texture,
+            MaterialTextureSlot::Emissive => self.emissive_texture,
         }
     }
     pub fn register_bind_group(
         &self,
         device: &wgpu::Device,
-        loaded_textures: &HashMap<usize, Texture>,
+        loaded_textures: &HashMap<TextureKey, Texture>,
         default_textures: &EnumMap<DefaultTextureTypes, Texture>,
+        material_uniform: &wgpu::Buffer,
     ) -> BindGroupContainer {
-        let diffuse_texture = if let Some(base_tex_id) = self.base_color_texture {
-            loaded_textures.get(&base_tex_id).unwrap()
-        } else {
-            &default_textures[DefaultTextureTypes::BaseColor]
-        };
-        let normal_map = if let Some(normal_id) = self.normal_map_texture {
-            loaded_textures.get(&normal_id).unwrap()
-        } else {
-            &default_textures[DefaultTextureTypes::NormalMap]
-        };
-        let occlusion_texture = if let Some(occulison) = self.occlusion_texture {
-            loaded_textures.get(&occulison).unwrap()
-        } else {
-            &default_textures[DefaultTextureTypes::Occlusion]
-        };
-        let roughness_texture = if let Some(roughness) = self.roughness_texture {
-            loaded_textures.get(&roughness).unwrap()
-        } else {
-            &default_textures[DefaultTextureTypes::MetallicRoughness]
-        };
-        let emissive_texture = if let Some(emissive) = self.emissive_texture {
-            loaded_textures.get(&emissive).unwrap()
-        } else {
-            &default_textures[DefaultTextureTypes::Emissive]
+        let texture = |slot: MaterialTextureSlot, default_texture: DefaultTextureTypes| {
+            if let Some(image) = self.texture(slot) {
+                loaded_textures.get(&slot.texture_key(image)).unwrap()
+            } else {
+                &default_textures[default_texture]
+            }
         };
-        let material_uniforms = self.to_raw_material();
-        let material_uniform = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
-            label: Some(format!("material-uniform-{}", self.name).as_str()),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-            contents: bytemuck::bytes_of(&material_uniforms),
-        });
         MaterialBindGroup::create_container(
             &device,
             (
-                &diffuse_texture,
-                &roughness_texture,
-                &normal_map,
-                &occlusion_texture,
-                &emissive_texture,
-                &material_uniform,
+                texture(
+                    MaterialTextureSlot::BaseColor,
+                    DefaultTextureTypes::BaseColor,
+                ),
+                texture(
+                    MaterialTextureSlot::MetallicRoughness,
+                    DefaultTextureTypes::MetallicRoughness,
+                ),
+                texture(MaterialTextureSlot::Normal, DefaultTextureTypes::NormalMap),
+                texture(
+                    MaterialTextureSlot::Occlusion,
+                    DefaultTextureTypes::Occlusion,
+                ),
+                texture(MaterialTextureSlot::Emissive, DefaultTextureTypes::Emissive),
+                material_uniform,
             ),
         )
     }
+    /// Uploads the images of the material's textures which aren't on the GPU yet,
+    /// an image used by slots with different color spaces is uploaded once for each.
     pub fn upload_material_textures_to_gpu(
         &self,
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         image_list: &HashMap<usize, DynamicImage>,
-        gpu_texture_container: &mut HashMap<usize, Texture>,
+        gpu_texture_container: &mut HashMap<TextureKey, Texture>,
     ) {
-        if let Some(base_color_tex) = self.base_color_texture {
-            if !gpu_texture_container.contains_key(&base_color_tex) {
-                gpu_texture_container.insert(
-                    base_color_tex,
-                    Self::load_texture_from_image(
-                        format!("diffuse-{}", self.name).as_str(),
-                        &device,
-                        &queue,
-                        &image_list[&base_color_tex],
-                        false,
-                    ),
-                );
-            }
-        }
-        if let Some(normal_tex) = self.normal_map_texture {
-            if !gpu_texture_container.contains_key(&normal_tex) {
-                gpu_texture_container.insert(
-                    normal_tex,
-                    Self::load_texture_from_image(
-                        format!("normal-{}", self.name).as_str(),
-                        &device,
-                        &queue,
-                        &image_list[&normal_tex],
-                        true,
-                    ),
-                );
-            }
-        }
-        if let Some(metallic_roughness) = self.roughness_texture {
-            if !gpu_texture_container.contains_key(&metallic_roughness) {
-                gpu_texture_container.insert(
-                    metallic_roughness,
-                    Self::load_texture_from_image(
-                        format!("metallic-roughness-{}", self.name).as_str(),
-                        &device,
-                        &queue,
-                        &image_list[&metallic_roughness],
-                        false,
-                    ),
-                );
-            }
-        }
-
-        if let Some(emissive) = self.emissive_texture {
-            if !gpu_texture_container.contains_key(&emissive) {
-                gpu_texture_container.insert(
-                    emissive,
-                    Self::load_texture_from_image(
-                        format!("emissive-{}", self.name).as_str(),
-                        &device,
-                        &queue,
-                        &image_list[&emissive],
-                        false,
-                    ),
-                );
-            }
-        }
-        if let Some(occlusion) = self.occlusion_texture {
-            if !gpu_texture_container.contains_key(&occlusion) {
+        for slot in MaterialTextureSlot::ALL {
+            let image = match self.texture(slot) {
+                Some(image) => image,
+                None => continue,
+            };
+            let key = slot.texture_key(image);
+            if !gpu_texture_container.contains_key(&key) {
                 gpu_texture_container.insert(
-                    occlusion,
+                    key,
                     Self::load_texture_from_image(
-                        format!("occlusion-{}", self.name).as_str(),
+                        format!("{}-{}", slot.label(), self.name).as_str(),
                         &device,
                         &queue,
-                        &image_list[&occlusion],
-                        false,
+                        &image_list[&image],
+                        slot.is_linear(),
                     ),
                 );
             }
@@ -182,14 +212,14 @@ impl GltfMaterial {
         device: &wgpu::Device,
         queue: &wgpu::Queue,
         image: &DynamicImage,
-        is_normal: bool,
+        linear: bool,
     ) -> Texture {
         Texture::from_image(
             device,
             queue,
             image,
             Some(name.to_string().as_str()),
-            is_normal,
+            linear,
         )
         .unwrap()
         // else {
@@ -204,4 +234,6 @@ pub struct MaterialUniform {
     pub base_color_factor: [f32; 4],
     pub roughness_metallic_double_sided: [f32; 4],
     pub emissive_color: [f32; 4],
+    /// alpha cutoff, alpha mode (0 opaque, 1 mask, 2 blend) and transmission factor.
+    pub alpha_cutoff_mode_transmission: [f32; 4],
 }
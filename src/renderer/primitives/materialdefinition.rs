@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use image::DynamicImage;
+use serde::Deserialize;
+
+use crate::renderer::shaderpreprocessor::{PreprocessedShader, ShaderPreprocessor};
+
+/// Type of a parameter of a custom material, the parameters are the members of its uniform struct.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MaterialParameterType {
+    Float,
+    Vec2,
+    Vec3,
+    Vec4,
+}
+
+impl MaterialParameterType {
+    pub fn component_count(&self) -> usize {
+        match self {
+            MaterialParameterType::Float => 1,
+            MaterialParameterType::Vec2 => 2,
+            MaterialParameterType::Vec3 => 3,
+            MaterialParameterType::Vec4 => 4,
+        }
+    }
+    /// Alignment in floats, a `vec3` is aligned like a `vec4` in the uniform address space.
+    fn alignment(&self) -> usize {
+        match self {
+            MaterialParameterType::Float => 1,
+            MaterialParameterType::Vec2 => 2,
+            MaterialParameterType::Vec3 | MaterialParameterType::Vec4 => 4,
+        }
+    }
+    fn wgsl_type(&self) -> &'static str {
+        match self {
+            MaterialParameterType::Float => "f32",
+            MaterialParameterType::Vec2 => "vec2<f32>",
+            MaterialParameterType::Vec3 => "vec3<f32>",
+            MaterialParameterType::Vec4 => "vec4<f32>",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaterialParameter {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub parameter_type: MaterialParameterType,
+    /// Used by the entities which don't set the parameter, missing components are zero.
+    #[serde(default)]
+    pub default: Vec<f32>,
+}
+
+#[derive(Clone)]
+pub struct MaterialTexture {
+    pub name: String,
+    pub image: DynamicImage,
+    /// Data like normal maps isn't stored as sRGB.
+    pub linear: bool,
+}
+
+#[derive(Debug)]
+pub enum MaterialError {
+    InvalidName(String),
+    /// Two parameters or textures of the material have the same name.
+    DuplicateName(String),
+    UnknownParameter {
+        material: String,
+        parameter: String,
+    },
+    InvalidParameterValue {
+        parameter: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// A material defined by a WGSL snippet and the parameters and textures it reads.
+/// The snippet defines `fn material(input: MaterialInput) -> MaterialOutput`, see `material.wgsl` for the structs.
+/// It's written in the native syntax like the engine's shaders, the web build converts it.
+#[derive(Clone)]
+pub struct MaterialDefinition {
+    pub name: String,
+    pub fragment: String,
+    pub parameters: Vec<MaterialParameter>,
+    pub textures: Vec<MaterialTexture>,
+}
+
+impl MaterialDefinition {
+    /// Names of the bindings every material has, the parameters and textures can't use them.
+    const RESERVED_NAMES: [&'static str; 2] = ["material_parameters", "material_sampler"];
+    const BEGIN_MARKER: &'static str = "// MATERIAL_BEGIN";
+    const END_MARKER: &'static str = "// MATERIAL_END";
+
+    pub fn new(name: &str, fragment: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            fragment: fragment.to_string(),
+            parameters: Vec::new(),
+            textures: Vec::new(),
+        }
+    }
+    pub fn with_parameter(
+        mut self,
+        name: &str,
+        parameter_type: MaterialParameterType,
+        default: &[f32],
+    ) -> Self {
+        self.parameters.push(MaterialParameter {
+            name: name.to_string(),
+            parameter_type,
+            default: default.to_vec(),
+        });
+        self
+    }
+    pub fn with_texture(mut self, name: &str, image: DynamicImage, linear: bool) -> Self {
+        self.textures.push(MaterialTexture {
+            name: name.to_string(),
+            image,
+            linear,
+        });
+        self
+    }
+    /// Checks that the parameters and textures have distinct names which are valid in WGSL.
+    pub fn validate(&self) -> Result<(), MaterialError> {
+        if self.name.is_empty() {
+            return Err(MaterialError::InvalidName(self.name.clone()));
+        }
+        let mut names: Vec<&str> = Vec::new();
+        let binding_names = self
+            .parameters
+            .iter()
+            .map(|parameter| parameter.name.as_str())
+            .chain(self.textures.iter().map(|texture| texture.name.as_str()));
+        for name in binding_names {
+            if !Self::is_identifier(name) || Self::RESERVED_NAMES.contains(&name) {
+                return Err(MaterialError::InvalidName(name.to_string()));
+            }
+            if names.contains(&name) {
+                return Err(MaterialError::DuplicateName(name.to_string()));
+            }
+            names.push(name);
+        }
+        for parameter in self.parameters.iter() {
+            self.check_parameter(parameter.name.as_str(), parameter.default.as_slice())?;
+        }
+        Ok(())
+    }
+    fn is_identifier(name: &str) -> bool {
+        let mut chars = name.chars();
+        matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+            && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+    }
+    /// Checks that the material has the parameter and the value fits into it.
+    pub fn check_parameter(&self, name: &str, value: &[f32]) -> Result<(), MaterialError> {
+        let parameter = self
+            .parameters
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .ok_or_else(|| MaterialError::UnknownParameter {
+                material: self.name.clone(),
+                parameter: name.to_string(),
+            })?;
+        let expected = parameter.parameter_type.component_count();
+        if value.len() > expected {
+            return Err(MaterialError::InvalidParameterValue {
+                parameter: name.to_string(),
+                expected,
+                found: value.len(),
+            });
+        }
+        Ok(())
+    }
+    /// Offset of every parameter in floats and the size of the uniform buffer in floats.
+    pub fn parameter_layout(&self) -> (Vec<usize>, usize) {
+        let mut offsets = Vec::with_capacity(self.parameters.len());
+        let mut size = 0;
+        for parameter in self.parameters.iter() {
+            let alignment = parameter.parameter_type.alignment();
+            let offset = (size + alignment - 1) / alignment * alignment;
+            offsets.push(offset);
+            size = offset + parameter.parameter_type.component_count();
+        }
+        // structs are 16 byte aligned, a material without parameters still has a padding member.
+        (offsets, ((size + 3) / 4 * 4).max(4))
+    }
+    /// Contents of the uniform buffer, the values override the defaults of the parameters.
+    pub fn pack_parameters(&self, values: &HashMap<String, Vec<f32>>) -> Vec<f32> {
+        let (offsets, size) = self.parameter_layout();
+        let mut packed = vec![0.0; size];
+        for (parameter, offset) in self.parameters.iter().zip(offsets) {
+            let value = values.get(&parameter.name).unwrap_or(&parameter.default);
+            for (index, component) in value
+                .iter()
+                .take(parameter.parameter_type.component_count())
+                .enumerate()
+            {
+                packed[offset + index] = *component;
+            }
+        }
+        packed
+    }
+    /// Name the errors of the material's shader are reported under.
+    pub fn shader_name(&self) -> String {
+        format!("{}.material", self.name)
+    }
+    /// Puts the material's bindings and snippet between the markers of the `material.wgsl` template.
+    /// The lines of the snippet are mapped to the material, the others to their line in the generated shader.
+    pub fn shader_source(&self, template: &str) -> PreprocessedShader {
+        let mut shader = PreprocessedShader {
+            source: String::new(),
+            line_map: Vec::new(),
+        };
+        let push_line = |shader: &mut PreprocessedShader, line: &str, location: (String, usize)| {
+            shader.source.push_str(line);
+            shader.source.push('\n');
+            shader.line_map.push(location);
+        };
+        let mut in_material = false;
+        for line in template.lines() {
+            if line.trim() == Self::END_MARKER {
+                in_material = false;
+            } else if in_material {
+                continue;
+            }
+            let template_line = shader.line_map.len() + 1;
+            push_line(
+                &mut shader,
+                line,
+                ("material.wgsl".to_string(), template_line),
+            );
+            if line.trim() != Self::BEGIN_MARKER {
+                continue;
+            }
+            in_material = true;
+            let mut material_source = self.bindings();
+            let binding_lines = material_source.lines().count();
+            material_source.push_str(self.fragment.as_str());
+            if cfg!(target_arch = "wasm32") {
+                material_source = ShaderPreprocessor::to_web_syntax(material_source.as_str());
+            }
+            for (index, line) in material_source.lines().enumerate() {
+                let location = if index < binding_lines {
+                    ("material.wgsl".to_string(), shader.line_map.len() + 1)
+                } else {
+                    (self.shader_name(), index - binding_lines + 1)
+                };
+                push_line(&mut shader, line, location);
+            }
+        }
+        shader
+    }
+    /// Declarations of the uniform struct, the sampler and the textures in group 1.
+    fn bindings(&self) -> String {
+        let mut bindings = String::from("struct MaterialParameters {\n");
+        for parameter in self.parameters.iter() {
+            bindings.push_str(
+                format!(
+                    "    {}: {};\n",
+                    parameter.name,
+                    parameter.parameter_type.wgsl_type()
+                )
+                .as_str(),
+            );
+        }
+        if self.parameters.is_empty() {
+            bindings.push_str("    padding: vec4<f32>;\n");
+        }
+        bindings.push_str("};\n");
+        bindings.push_str("[[group(1), binding(0)]]\n");
+        bindings.push_str("var<uniform> material_parameters: MaterialParameters;\n");
+        bindings.push_str("[[group(1), binding(1)]]\n");
+        bindings.push_str("var material_sampler: sampler;\n");
+        for (index, texture) in self.textures.iter().enumerate() {
+            bindings.push_str(format!("[[group(1), binding({})]]\n", index + 2).as_str());
+            bindings.push_str(format!("var {}: texture_2d<f32>;\n", texture.name).as_str());
+        }
+        bindings
+    }
+}
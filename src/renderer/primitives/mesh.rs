@@ -12,6 +12,53 @@ pub struct Mesh {
     pub material: usize,
     pub points: Vec<Point3<f32>>,
 }
+/// Sphere around a mesh in its local space, used to cull instances which are outside of the view.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BoundingSphere {
+    pub center: Vector3<f32>,
+    pub radius: f32,
+}
+impl BoundingSphere {
+    /// Centers the sphere on the points' bounding box, `None` without points.
+    pub fn from_points(points: &[[f32; 3]]) -> Option<Self> {
+        let first = Vector3::from(*points.first()?);
+        let (min, max) = points.iter().fold((first, first), |(min, max), point| {
+            let point = Vector3::from(*point);
+            (min.inf(&point), max.sup(&point))
+        });
+        let center = (min + max) * 0.5;
+        let radius = points
+            .iter()
+            .map(|point| (Vector3::from(*point) - center).norm())
+            .fold(0.0, f32::max);
+        Some(Self { center, radius })
+    }
+    /// The smallest sphere containing both spheres.
+    pub fn merge(&self, other: &Self) -> Self {
+        let offset = other.center - self.center;
+        let distance = offset.norm();
+        if distance + other.radius <= self.radius {
+            return *self;
+        }
+        if distance + self.radius <= other.radius {
+            return *other;
+        }
+        let radius = (distance + self.radius + other.radius) * 0.5;
+        Self {
+            center: self.center + offset * ((radius - self.radius) / distance),
+            radius,
+        }
+    }    /// The sphere moved into the matrix' space, its radius grows with the largest axis scale.
+    pub fn transform(&self, matrix: &Matrix4<f32>) -> Self {
+        let scale = (0..3)
+            .map(|axis| matrix.column(axis).xyz().norm())
+            .fold(0.0, f32::max);
+        Self {
+            center: matrix.transform_point(&Point3::from(self.center)).coords,
+            radius: self.radius * scale,
+        }
+    }
+}
 #[derive(Ord, PartialOrd, Eq, PartialEq,Debug)]
 pub enum VertexAttributeType {
     Position,
@@ -22,15 +69,35 @@ pub enum VertexAttributeType {
     JointWeight,
     JointIndex,
 }
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GltfMesh {
    pub name:String,
     mode: wgpu::PrimitiveTopology,
     pub vertex_attribs: BTreeMap<VertexAttributeType, VertexAttribValues>,
     pub indices: Option<Vec<u32>>,
+    pub morph_targets: Vec<MorphTarget>,
+    pub bounds: Option<BoundingSphere>,
+    /// Lower levels of detail after the base indices, ordered by decreasing detail.
+    pub lods: Vec<GltfMeshLod>,
+}
+/// A lower detail index list of a primitive, it indexes into the primitive's vertices.
+#[derive(Debug, Clone)]
+pub struct GltfMeshLod {
+    pub indices: Vec<u32>,
+    /// The level replaces the previous one once the bounding sphere's height relative to the screen's falls below it.
+    pub screen_size: f32,
+}
+/// Per vertex offsets of a blend shape, missing normals are treated as zero.
+#[derive(Debug, Clone)]
+pub struct MorphTarget {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Option<Vec<[f32; 3]>>,
 }
 
 impl GltfMesh {
+    /// Levels of detail a primitive can have along with its base indices.
+    pub const MAX_LOD_COUNT: usize = 4;
+
     pub fn new(mode: gltf::mesh::Mode,name:String) -> Self {
         let topology = match mode {
             Mode::Triangles => wgpu::PrimitiveTopology::TriangleList,
@@ -42,11 +109,17 @@ impl GltfMesh {
             mode: topology,
             vertex_attribs: Default::default(),
             indices: Default::default(),
+            morph_targets: Vec::new(),
+            bounds: None,
+            lods: Vec::new(),
         }
     }
     pub fn add_indices(&mut self, data:Vec<u32>) {
         self.indices = Some(data);
     }
+    pub fn add_morph_target(&mut self, target: MorphTarget) {
+        self.morph_targets.push(target);
+    }
     pub fn add_vertex_attribute(&mut self, attrib_type: VertexAttributeType, data: VertexAttribValues) {
         self.vertex_attribs.insert(attrib_type,data);
     }
@@ -57,6 +130,39 @@ impl GltfMesh {
     pub fn mode(&self) -> wgpu::PrimitiveTopology  {
         self.mode
     }
+    /// Appends the vertices of another primitive and adds its indices as the next level of detail.
+    /// Fails without changes if the primitives don't have the same vertex attributes.
+    pub fn append_lod(&mut self, other: &GltfMesh, screen_size: f32) -> bool {
+        let indices = match other.indices {
+            Some(ref indices) => indices,
+            None => return false,
+        };
+        if self.mode != other.mode
+            || self.lods.len() + 1 >= Self::MAX_LOD_COUNT
+            || !self.vertex_attribs.keys().eq(other.vertex_attribs.keys())
+        {
+            return false;
+        }
+        let vertex_count = match self.attribute(VertexAttributeType::Position) {
+            Some(VertexAttribValues::Float32x3(positions)) => positions.len() as u32,
+            _ => return false,
+        };
+        let mut vertex_attribs = self.vertex_attribs.clone();
+        for (attrib_type, values) in vertex_attribs.iter_mut() {
+            if !values.append(&other.vertex_attribs[attrib_type]) {
+                return false;
+            }
+        }
+        self.vertex_attribs = vertex_attribs;
+        self.lods.push(GltfMeshLod {
+            indices: indices.iter().map(|index| index + vertex_count).collect(),
+            screen_size,
+        });
+        if let (Some(bounds), Some(other_bounds)) = (self.bounds, other.bounds) {
+            self.bounds = Some(bounds.merge(&other_bounds));
+        }
+        true
+    }
 }
 // https://github.com/bevyengine/bevy/blob/e369a8ad5138af28a7e760fac3f07b278c27ebb4/crates/bevy_render/src/mesh/mesh/mod.rs
 #[derive(Clone,Debug)]
@@ -90,3 +196,24 @@ pub enum VertexAttribValues {
     Uint8x4(Vec<[u8; 4]>),
     Unorm8x4(Vec<[u8; 4]>),
 }
+impl VertexAttribValues {
+    /// Appends the values of the same type, only the types read by the model importer are supported.
+    pub fn append(&mut self, other: &VertexAttribValues) -> bool {
+        match (self, other) {
+            (VertexAttribValues::Uint32(values), VertexAttribValues::Uint32(other)) => {
+                values.extend_from_slice(other)
+            }
+            (VertexAttribValues::Float32x2(values), VertexAttribValues::Float32x2(other)) => {
+                values.extend_from_slice(other)
+            }
+            (VertexAttribValues::Float32x3(values), VertexAttribValues::Float32x3(other)) => {
+                values.extend_from_slice(other)
+            }
+            (VertexAttribValues::Float32x4(values), VertexAttribValues::Float32x4(other)) => {
+                values.extend_from_slice(other)
+            }
+            _ => return false,
+        }
+        true
+    }
+}
@@ -2,6 +2,8 @@ use bytemuck::{Pod, Zeroable};
 use nalgebra_glm::proj;
 use rapier3d::na::{Matrix4, Perspective3};
 
+use crate::components::skin::Skin;
+use crate::renderer::shaderconstants::TILE_LIGHT_SLOTS;
 use crate::resources::camera::Camera;
 use crate::resources::projection::Projection;
 #[repr(C)]
@@ -38,9 +40,15 @@ impl Globals {
     pub fn set_spot_light_count(&mut self, new_count: u32) {
         self.num_lights[1] = new_count;
     }
+    pub fn get_view_proj(&self) -> Matrix4<f32> {
+        Matrix4::from(self.view_proj)
+    }
     pub fn get_point_light_count(&self) -> u32 {
         self.num_lights[0]
     }
+    pub fn get_spot_light_count(&self) -> u32 {
+        self.num_lights[1]
+    }
 }
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
@@ -65,7 +73,7 @@ impl Default for TileInfo {
     fn default() -> Self {
         Self {
             tile_size: 16,
-            num_tile_light_slot: 128,
+            num_tile_light_slot: TILE_LIGHT_SLOTS,
             tile_count_y: 0,
             tile_count_x: 0,
             num_tiles: 0,
@@ -96,6 +104,27 @@ pub struct SkyboxUniform {
     pub projection_inverse: [[f32; 4]; 4],
     pub view: [[f32; 4]; 4],
 }
+/// Parameters of a single image based lighting map generation dispatch.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Pod, Zeroable)]
+pub struct EnvironmentUniforms {
+    /// Roughness the specular map is prefiltered for, unused by the other maps.
+    pub roughness: f32,
+    /// Width and height of the written mip level.
+    pub size: u32,
+    pub sample_count: u32,
+    _padding: u32,
+}
+impl EnvironmentUniforms {
+    pub fn new(roughness: f32, size: u32, sample_count: u32) -> Self {
+        Self {
+            roughness,
+            size,
+            sample_count,
+            _padding: 0,
+        }
+    }
+}
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Pod, Zeroable)]
 pub struct LightCullingUniforms {
@@ -117,3 +146,144 @@ impl LightCullingUniforms {
         }
     }
 }
+/// Where an instance's joint matrices and morph weights begin, indexed by the instance index in the shaders.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct InstanceDeformation {
+    /// `Skin::NOT_SKINNED` for instances without a skin.
+    pub joint_offset: u32,
+    pub morph_weight_offset: u32,
+    /// Number of morph targets, 0 for instances without morph weights.
+    pub morph_target_count: u32,
+    _padding: u32,
+}
+impl InstanceDeformation {
+    pub fn new(joint_offset: u32, morph_weight_offset: u32, morph_target_count: u32) -> Self {
+        Self {
+            joint_offset,
+            morph_weight_offset,
+            morph_target_count,
+            _padding: 0,
+        }
+    }
+    /// Skinned and morphed instances can leave the bounds of their mesh.
+    pub fn is_deformed(&self) -> bool {
+        self.joint_offset != Skin::NOT_SKINNED || self.morph_target_count > 0
+    }
+}
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct ShadowSettingsUniforms {
+    pub cascade_count: u32,
+    pub pcf_kernel: u32,
+    pub bias: f32,
+    /// Part of the shadow texture covered by a cascade, the cascades are rendered into its top left corner.
+    pub resolution_scale: f32,
+    /// Non zero to tint the fragments by their cascade.
+    pub show_cascades: u32,
+    _padding: [u32; 3],
+}
+impl ShadowSettingsUniforms {
+    pub fn new(
+        cascade_count: u32,
+        pcf_kernel: u32,
+        bias: f32,
+        resolution_scale: f32,
+        show_cascades: bool,
+    ) -> Self {
+        Self {
+            cascade_count,
+            pcf_kernel,
+            bias,
+            resolution_scale,
+            show_cascades: show_cascades as u32,
+            _padding: [0; 3],
+        }
+    }
+}
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct ToneMappingUniforms {
+    pub min_log_luminance: f32,
+    pub log_luminance_range: f32,
+    /// Part of the difference to the frame's luminance the adapted luminance moves by every frame.
+    pub adaptation: f32,
+    /// Exposure in stops.
+    pub exposure: f32,
+    pub pixel_count: u32,
+    /// Index of the `ToneMappingOperator`.
+    pub tone_operator: u32,
+    /// Non zero to scale the exposure by the adapted luminance.
+    pub auto_exposure: u32,
+    _padding: u32,
+}
+impl ToneMappingUniforms {
+    pub fn new(
+        min_log_luminance: f32,
+        log_luminance_range: f32,
+        adaptation: f32,
+        exposure: f32,
+        pixel_count: u32,
+        tone_operator: u32,
+        auto_exposure: bool,
+    ) -> Self {
+        Self {
+            min_log_luminance,
+            log_luminance_range,
+            adaptation,
+            exposure,
+            pixel_count,
+            tone_operator,
+            auto_exposure: auto_exposure as u32,
+            _padding: 0,
+        }
+    }
+}
+/// Uniforms of a post-processing effect, the effect's settings decide what the parameters mean.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct PostProcessUniforms {
+    pub parameters: [f32; 4],
+}
+pub use crate::renderer::shaderconstants::SSAO_KERNEL_SIZE;
+/// Uniforms of the screen-space ambient occlusion.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct SsaoUniforms {
+    kernel: [[f32; 4]; SSAO_KERNEL_SIZE],
+    radius: f32,
+    bias: f32,
+    intensity: f32,
+    sample_count: u32,
+}
+impl SsaoUniforms {
+    pub fn new(radius: f32, bias: f32, intensity: f32, sample_count: u32) -> Self {
+        Self {
+            kernel: Self::kernel(),
+            radius,
+            bias,
+            intensity,
+            sample_count,
+        }
+    }
+    /// Cosine weighted directions of a Hammersley set in the hemisphere around +Z,
+    /// scaled so the samples crowd near the center where the occlusion matters most.
+    fn kernel() -> [[f32; 4]; SSAO_KERNEL_SIZE] {
+        let mut kernel = [[0.0; 4]; SSAO_KERNEL_SIZE];
+        for (index, sample) in kernel.iter_mut().enumerate() {
+            let u = (index as f32 + 0.5) / SSAO_KERNEL_SIZE as f32;
+            let v = (index as u32).reverse_bits() as f32 / 4_294_967_296.0;
+            let radius = u.sqrt();
+            let phi = std::f32::consts::TAU * v;
+            let t = index as f32 / SSAO_KERNEL_SIZE as f32;
+            let scale = 0.1 + 0.9 * t * t;
+            *sample = [
+                radius * phi.cos() * scale,
+                radius * phi.sin() * scale,
+                (1.0 - u).sqrt() * scale,
+                0.0,
+            ];
+        }
+        kernel
+    }
+}
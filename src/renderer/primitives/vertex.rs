@@ -15,6 +15,15 @@ pub struct MeshVertexData {
     pub vertex_color:u32,
     pub joint_weight:[f32;4],
     pub joint_index:u32,
+    /// Index of the vertex's first morph target delta, `u32::MAX` without morph targets.
+    pub morph_index:u32,
+}
+/// Offset of a morph target for a single vertex, stored in the morph target buffer.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Pod, Zeroable)]
+pub struct MorphTargetDelta {
+    pub position: [f32; 4],
+    pub normal: [f32; 4],
 }
 
 impl Vertex for MeshVertexData {
@@ -58,6 +67,11 @@ impl Vertex for MeshVertexData {
                     offset: (mem::size_of::<[f32; 16]>() + std::mem::size_of::<u32>()) as wgpu::BufferAddress,
                     shader_location: 6,
                     format: wgpu::VertexFormat::Uint32
+                },
+                wgpu::VertexAttribute {
+                    offset: (mem::size_of::<[f32; 16]>() + std::mem::size_of::<[u32; 2]>()) as wgpu::BufferAddress,
+                    shader_location: 7,
+                    format: wgpu::VertexFormat::Uint32
                 }]
         }
     }
@@ -0,0 +1,582 @@
+use std::collections::{HashMap, VecDeque};
+
+use specs::{RunNow, World, WorldExt};
+
+use crate::resources::bindingresourcecontainer::{BufferTypes, TextureViewTypes};
+use crate::State;
+
+/// Something a pass of the `RenderGraph` reads or writes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderGraphResource {
+    /// A persistent texture of the `BindingResourceContainer`.
+    Texture(TextureViewTypes),
+    /// A persistent buffer of the `BindingResourceContainer`.
+    Buffer(BufferTypes),
+    /// The texture acquired from the surface for the current frame.
+    Surface,
+    /// A target declared by one of the passes, it only lives for the passes which use it.
+    Transient(&'static str),
+}
+
+/// Describes a transient target, its size is a fraction of the surface size.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TransientTarget {
+    pub name: &'static str,
+    pub format: wgpu::TextureFormat,
+    pub scale: f32,
+    pub usage: wgpu::TextureUsages,
+}
+
+impl TransientTarget {
+    pub fn new(name: &'static str, format: wgpu::TextureFormat) -> Self {
+        Self {
+            name,
+            format,
+            scale: 1.0,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+        }
+    }
+    pub fn with_scale(mut self, scale: f32) -> Self {
+        self.scale = scale;
+        self
+    }
+    pub fn with_usage(mut self, usage: wgpu::TextureUsages) -> Self {
+        self.usage = usage;
+        self
+    }
+    /// Targets with the same description can share a texture when their lifetimes don't overlap.
+    fn can_alias(&self, other: &TransientTarget) -> bool {
+        self.format == other.format && self.scale == other.scale && self.usage == other.usage
+    }
+}
+
+/// A pass of the frame, it declares the resources it touches so the graph can order it against the other passes.
+pub trait RenderGraphPass: Send + Sync {
+    /// Identifies the pass, other passes are inserted before or after it by name.
+    fn name(&self) -> &'static str;
+    /// A pass reads the value written by the last writer inserted before it, the next writer waits for it.
+    /// Passes inserted before every writer of a resource read the value of the last one.
+    fn reads(&self) -> Vec<RenderGraphResource>;
+    fn writes(&self) -> Vec<RenderGraphResource>;
+    /// The transient targets owned by this pass, other passes may read or write them too.
+    fn transient_targets(&self) -> Vec<TransientTarget> {
+        Vec::new()
+    }
+    /// Called once with the world before the pass is added to the graph, pipelines and resources are created here.
+    fn setup(&mut self, _world: &mut World) {}
+    fn run(&mut self, world: &World, targets: &TransientTargets);
+}
+
+/// Runs a system as a pass of the graph, the built-in passes of the renderer are systems.
+pub struct SystemPass<S> {
+    name: &'static str,
+    system: S,
+    reads: Vec<RenderGraphResource>,
+    writes: Vec<RenderGraphResource>,
+}
+
+impl<S> SystemPass<S>
+where
+    S: for<'a> RunNow<'a> + Send + Sync,
+{
+    pub fn new(name: &'static str, system: S) -> Self {
+        Self {
+            name,
+            system,
+            reads: Vec::new(),
+            writes: Vec::new(),
+        }
+    }
+    pub fn reading(mut self, resources: &[RenderGraphResource]) -> Self {
+        self.reads.extend_from_slice(resources);
+        self
+    }
+    pub fn writing(mut self, resources: &[RenderGraphResource]) -> Self {
+        self.writes.extend_from_slice(resources);
+        self
+    }
+}
+
+impl<S> RenderGraphPass for SystemPass<S>
+where
+    S: for<'a> RunNow<'a> + Send + Sync,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+    fn reads(&self) -> Vec<RenderGraphResource> {
+        self.reads.clone()
+    }
+    fn writes(&self) -> Vec<RenderGraphResource> {
+        self.writes.clone()
+    }
+    fn setup(&mut self, world: &mut World) {
+        self.system.setup(world);
+    }
+    fn run(&mut self, world: &World, _targets: &TransientTargets) {
+        self.system.run_now(world);
+    }
+}
+
+/// The textures backing the transient targets of the compiled graph.
+#[derive(Default)]
+pub struct TransientTargets {
+    textures: Vec<(wgpu::Texture, wgpu::TextureView)>,
+    slots: HashMap<&'static str, usize>,
+    size: (u32, u32),
+    generation: u64,
+}
+
+impl TransientTargets {
+    pub fn texture(&self, name: &str) -> Option<&wgpu::Texture> {
+        self.slots.get(name).map(|slot| &self.textures[*slot].0)
+    }
+    pub fn view(&self, name: &str) -> Option<&wgpu::TextureView> {
+        self.slots.get(name).map(|slot| &self.textures[*slot].1)
+    }
+    /// Number of textures allocated, lower than the number of targets when some of them are aliased.
+    pub fn texture_count(&self) -> usize {
+        self.textures.len()
+    }
+    /// Changes whenever the textures are reallocated, passes keeping bind groups of transient targets recreate them then.
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+#[derive(Debug)]
+pub enum RenderGraphError {
+    DuplicatePass(&'static str),
+    /// No pass with the name to insert the new pass next to.
+    UnknownAnchor(&'static str),
+    DuplicateTransient(&'static str),
+    /// A pass uses a transient target none of the passes declares.
+    UnknownTransient {
+        pass: &'static str,
+        target: &'static str,
+    },
+    /// The passes depend on each other through their resources, none of them can run first.
+    Cycle(Vec<&'static str>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderGraphPosition {
+    Before(&'static str),
+    After(&'static str),
+    End,
+}
+
+struct PassNode {
+    pass: Box<dyn RenderGraphPass>,
+    reads: Vec<RenderGraphResource>,
+    writes: Vec<RenderGraphResource>,
+    transients: Vec<TransientTarget>,
+}
+
+impl PassNode {
+    fn uses(&self, resource: &RenderGraphResource) -> bool {
+        self.reads.contains(resource) || self.writes.contains(resource)
+    }
+}
+
+#[derive(Default)]
+pub struct RenderGraph {
+    passes: Vec<PassNode>,
+    order: Option<Vec<usize>>,
+    transients: TransientTargets,
+    dependencies: Vec<Vec<usize>>,
+}
+
+impl RenderGraph {
+    /// Adds a pass which has already been set up, use `ECSContainer::insert_render_pass` to set it up with the world.
+    pub fn insert_pass(
+        &mut self,
+        position: RenderGraphPosition,
+        pass: Box<dyn RenderGraphPass>,
+    ) -> Result<(), RenderGraphError> {
+        if self.position(pass.name()).is_some() {
+            return Err(RenderGraphError::DuplicatePass(pass.name()));
+        }
+        let transients = pass.transient_targets();
+        for target in &transients {
+            if self
+                .passes
+                .iter()
+                .any(|node| node.transients.iter().any(|t| t.name == target.name))
+            {
+                return Err(RenderGraphError::DuplicateTransient(target.name));
+            }
+        }
+        let index = match position {
+            RenderGraphPosition::Before(anchor) => self
+                .position(anchor)
+                .ok_or(RenderGraphError::UnknownAnchor(anchor))?,
+            RenderGraphPosition::After(anchor) => {
+                self.position(anchor)
+                    .ok_or(RenderGraphError::UnknownAnchor(anchor))?
+                    + 1
+            }
+            RenderGraphPosition::End => self.passes.len(),
+        };
+        let node = PassNode {
+            reads: pass.reads(),
+            writes: pass.writes(),
+            transients,
+            pass,
+        };
+        self.passes.insert(index, node);
+        self.order = None;
+        // unknown transients may still be declared by a pass inserted later, they're reported when the graph runs.
+        if let Err(RenderGraphError::Cycle(passes)) = self.compile() {
+            self.passes.remove(index);
+            self.order = None;
+            return Err(RenderGraphError::Cycle(passes));
+        }
+        Ok(())
+    }
+    pub fn add_pass(&mut self, pass: Box<dyn RenderGraphPass>) -> Result<(), RenderGraphError> {
+        self.insert_pass(RenderGraphPosition::End, pass)
+    }
+    pub fn remove_pass(&mut self, name: &str) -> Option<Box<dyn RenderGraphPass>> {
+        let index = self.position(name)?;
+        self.order = None;
+        Some(self.passes.remove(index).pass)
+    }
+    /// Names of the passes in the order they run in.
+    pub fn pass_names(&mut self) -> Vec<&'static str> {
+        if self.compile().is_err() {
+            return Vec::new();
+        }
+        self.order
+            .as_ref()
+            .unwrap()
+            .iter()
+            .map(|index| self.passes[*index].pass.name())
+            .collect()
+    }
+    /// Sets up every pass, called once by the `ECSContainer` for the built-in passes.
+    pub fn setup(&mut self, world: &mut World) {
+        for node in self.passes.iter_mut() {
+            node.pass.setup(world);
+        }
+    }
+    fn position(&self, name: &str) -> Option<usize> {
+        self.passes.iter().position(|node| node.pass.name() == name)
+    }
+    /// Builds the dependencies between the passes and sorts them topologically.
+    /// The writers of a resource depend on the previous writer, the passes only reading it on the writer
+    /// before them and the writer after them on the readers, see `RenderGraphPass::reads`.
+    /// A pass runs as soon as everything it depends on has run.
+    fn compile(&mut self) -> Result<(), RenderGraphError> {
+        if self.order.is_none() {
+            for node in self.passes.iter() {
+                for resource in node.reads.iter().chain(node.writes.iter()) {
+                    if let RenderGraphResource::Transient(target) = resource {
+                        if !self
+                            .passes
+                            .iter()
+                            .any(|n| n.transients.iter().any(|t| t.name == *target))
+                        {
+                            return Err(RenderGraphError::UnknownTransient {
+                                pass: node.pass.name(),
+                                target: *target,
+                            });
+                        }
+                    }
+                }
+            }
+            let pass_count = self.passes.len();
+            let mut writers: Vec<(RenderGraphResource, Vec<usize>)> = Vec::new();
+            for (index, node) in self.passes.iter().enumerate() {
+                for resource in node.writes.iter() {
+                    match writers.iter_mut().find(|(written, _)| written == resource) {
+                        Some((_, passes)) if !passes.contains(&index) => passes.push(index),
+                        Some(_) => {}
+                        None => writers.push((*resource, vec![index])),
+                    }
+                }
+            }
+            let mut dependencies = vec![Vec::new(); pass_count];
+            let mut add_dependency = |pass: usize, dependency: usize| {
+                if pass != dependency && !dependencies[pass].contains(&dependency) {
+                    dependencies[pass].push(dependency);
+                }
+            };
+            for (_, passes) in writers.iter() {
+                for pair in passes.windows(2) {
+                    add_dependency(pair[1], pair[0]);
+                }
+            }
+            for (index, node) in self.passes.iter().enumerate() {
+                for resource in node.reads.iter().filter(|r| !node.writes.contains(*r)) {
+                    if let Some((_, passes)) =
+                        writers.iter().find(|(written, _)| written == resource)
+                    {
+                        match passes.iter().rposition(|writer| *writer < index) {
+                            // the next writer waits until the value has been read.
+                            Some(previous) => {
+                                add_dependency(index, passes[previous]);
+                                if let Some(next) = passes.get(previous + 1) {
+                                    add_dependency(*next, index);
+                                }
+                            }
+                            None => add_dependency(index, *passes.last().unwrap()),
+                        }
+                    }
+                }
+            }
+            let mut dependants = vec![Vec::new(); pass_count];
+            for (index, pass_dependencies) in dependencies.iter().enumerate() {
+                for dependency in pass_dependencies {
+                    dependants[*dependency].push(index);
+                }
+            }
+            let mut remaining: Vec<usize> = dependencies.iter().map(|d| d.len()).collect();
+            let mut order = Vec::with_capacity(pass_count);
+            let mut ready: VecDeque<usize> =
+                (0..pass_count).filter(|i| remaining[*i] == 0).collect();
+            while let Some(index) = ready.pop_front() {
+                order.push(index);
+                for dependant in &dependants[index] {
+                    remaining[*dependant] -= 1;
+                    if remaining[*dependant] == 0 {
+                        ready.push_back(*dependant);
+                    }
+                }
+            }
+            if order.len() != pass_count {
+                return Err(RenderGraphError::Cycle(
+                    (0..pass_count)
+                        .filter(|index| remaining[*index] > 0)
+                        .map(|index| self.passes[index].pass.name())
+                        .collect(),
+                ));
+            }
+            self.dependencies = dependencies;
+            // the transients are reallocated for the new order on the next execution
+            self.transients.size = (0, 0);
+            self.order = Some(order);
+        }
+        Ok(())
+    }
+    /// Names of the passes `name` waits on.
+    pub fn dependencies(&mut self, name: &str) -> Vec<&'static str> {
+        if self.compile().is_err() {
+            return Vec::new();
+        }
+        match self.position(name) {
+            Some(index) => self.dependencies[index]
+                .iter()
+                .map(|dependency| self.passes[*dependency].pass.name())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+    /// Gives every transient target a texture, targets whose lifetimes don't overlap share one when their descriptions match.
+    fn allocate_transients(&mut self, device: &wgpu::Device, width: u32, height: u32) {
+        let order = self.order.as_ref().unwrap();
+        let mut lifetimes: Vec<(TransientTarget, usize, usize)> = Vec::new();
+        for node in self.passes.iter() {
+            for target in node.transients.iter() {
+                let resource = RenderGraphResource::Transient(target.name);
+                let mut steps = order.iter().enumerate().filter(|(_, index)| {
+                    let node = &self.passes[**index];
+                    node.uses(&resource) || node.transients.iter().any(|t| t.name == target.name)
+                });
+                let first = steps.next().map(|(step, _)| step).unwrap_or(0);
+                let last = steps.last().map(|(step, _)| step).unwrap_or(first);
+                lifetimes.push((*target, first, last));
+            }
+        }
+        lifetimes.sort_by_key(|(_, first, _)| *first);
+        // description and last step of every allocated texture
+        let mut allocations: Vec<(TransientTarget, usize)> = Vec::new();
+        let mut slots = HashMap::new();
+        for (target, first, last) in lifetimes {
+            let slot = allocations.iter().position(|(allocated, free_after)| {
+                *free_after < first && allocated.can_alias(&target)
+            });
+            let slot = match slot {
+                Some(slot) => {
+                    allocations[slot].1 = last;
+                    slot
+                }
+                None => {
+                    allocations.push((target, last));
+                    allocations.len() - 1
+                }
+            };
+            slots.insert(target.name, slot);
+        }
+        let textures = allocations
+            .iter()
+            .map(|(target, _)| {
+                let texture = device.create_texture(&wgpu::TextureDescriptor {
+                    label: Some(target.name),
+                    size: wgpu::Extent3d {
+                        width: ((width as f32 * target.scale) as u32).max(1),
+                        height: ((height as f32 * target.scale) as u32).max(1),
+                        depth_or_array_layers: 1,
+                    },
+                    mip_level_count: 1,
+                    sample_count: 1,
+                    dimension: wgpu::TextureDimension::D2,
+                    format: target.format,
+                    usage: target.usage,
+                });
+                let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+                (texture, view)
+            })
+            .collect();
+        self.transients = TransientTargets {
+            textures,
+            slots,
+            size: (width, height),
+            generation: self.transients.generation + 1,
+        };
+    }
+    /// Runs the passes of the frame, the graph is compiled again after passes have been inserted or removed
+    /// and the transient targets follow the size of the surface.
+    pub fn execute(&mut self, world: &World) {
+        if let Err(e) = self.compile() {
+            log::error!(target: "render_graph", "failed to compile the render graph: {:?}", e);
+            return;
+        }
+        {
+            let state = world.read_resource::<State>();
+            let size = (state.sc_descriptor.width, state.sc_descriptor.height);
+            if self.transients.size != size {
+                self.allocate_transients(&state.device, size.0, size.1);
+            }
+        }
+        let order = self.order.as_ref().unwrap();
+        for index in order {
+            self.passes[*index].pass.run(world, &self.transients);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestPass {
+        name: &'static str,
+        reads: Vec<RenderGraphResource>,
+        writes: Vec<RenderGraphResource>,
+    }
+
+    impl RenderGraphPass for TestPass {
+        fn name(&self) -> &'static str {
+            self.name
+        }
+        fn reads(&self) -> Vec<RenderGraphResource> {
+            self.reads.clone()
+        }
+        fn writes(&self) -> Vec<RenderGraphResource> {
+            self.writes.clone()
+        }
+        fn run(&mut self, _world: &World, _targets: &TransientTargets) {}
+    }
+
+    fn pass(
+        name: &'static str,
+        reads: &[RenderGraphResource],
+        writes: &[RenderGraphResource],
+    ) -> Box<dyn RenderGraphPass> {
+        Box::new(TestPass {
+            name,
+            reads: reads.to_vec(),
+            writes: writes.to_vec(),
+        })
+    }
+
+    const DEPTH: RenderGraphResource = RenderGraphResource::Texture(TextureViewTypes::Depth);
+    const HDR_SCENE: RenderGraphResource = RenderGraphResource::Texture(TextureViewTypes::HdrScene);
+    const SURFACE: RenderGraphResource = RenderGraphResource::Surface;
+
+    fn frame_graph() -> RenderGraph {
+        let mut graph = RenderGraph::default();
+        for pass in [
+            pass("gbuffer", &[], &[DEPTH]),
+            pass("forward", &[DEPTH], &[HDR_SCENE]),
+            pass("skybox", &[DEPTH], &[HDR_SCENE]),
+            pass("tone_mapping", &[HDR_SCENE], &[SURFACE]),
+            pass("ui", &[SURFACE], &[SURFACE]),
+        ] {
+            graph.add_pass(pass).unwrap();
+        }
+        graph
+    }
+
+    fn position(names: &[&'static str], name: &str) -> usize {
+        names.iter().position(|n| *n == name).unwrap()
+    }
+
+    #[test]
+    fn reader_inserted_out_of_order_runs_after_the_last_writer() {
+        let mut graph = frame_graph();
+        graph
+            .insert_pass(
+                RenderGraphPosition::End,
+                pass("hdr_debug", &[HDR_SCENE], &[]),
+            )
+            .unwrap();
+        graph
+            .insert_pass(
+                RenderGraphPosition::Before("gbuffer"),
+                pass("depth_debug", &[DEPTH], &[]),
+            )
+            .unwrap();
+        let names = graph.pass_names();
+        assert_eq!(names.len(), 7);
+        assert!(position(&names, "hdr_debug") > position(&names, "skybox"));
+        assert!(position(&names, "hdr_debug") < position(&names, "ui"));
+        assert!(position(&names, "depth_debug") > position(&names, "gbuffer"));
+        assert_eq!(graph.dependencies("hdr_debug"), vec!["skybox"]);
+    }
+
+    #[test]
+    fn reader_inserted_between_writers_reads_the_intermediate_value() {
+        let mut graph = frame_graph();
+        graph
+            .insert_pass(
+                RenderGraphPosition::After("forward"),
+                pass("opaque_debug", &[HDR_SCENE], &[]),
+            )
+            .unwrap();
+        let names = graph.pass_names();
+        assert!(position(&names, "opaque_debug") > position(&names, "forward"));
+        assert!(position(&names, "opaque_debug") < position(&names, "skybox"));
+        assert_eq!(graph.dependencies("opaque_debug"), vec!["forward"]);
+        assert!(graph.dependencies("skybox").contains(&"opaque_debug"));
+    }
+
+    #[test]
+    fn writers_keep_their_submission_order() {
+        let mut graph = frame_graph();
+        graph
+            .insert_pass(
+                RenderGraphPosition::After("forward"),
+                pass("decals", &[DEPTH], &[HDR_SCENE]),
+            )
+            .unwrap();
+        let names = graph.pass_names();
+        assert!(position(&names, "decals") > position(&names, "forward"));
+        assert!(position(&names, "decals") < position(&names, "skybox"));
+    }
+
+    #[test]
+    fn cycles_are_rejected() {
+        let mut graph = frame_graph();
+        let result = graph.insert_pass(
+            RenderGraphPosition::Before("forward"),
+            pass("feedback", &[HDR_SCENE], &[DEPTH]),
+        );
+        assert!(matches!(result, Err(RenderGraphError::Cycle(_))));
+        assert_eq!(
+            graph.pass_names(),
+            vec!["gbuffer", "forward", "skybox", "tone_mapping", "ui"]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+//! Limits shared by the renderer and the shaders, they're injected into the shaders by the `ShaderPreprocessor`.
+//! The build script includes this file as well, it only depends on the standard library.
+
+pub const MAX_POINT_LIGHTS: usize = 1024;
+pub const MAX_SPOT_LIGHTS: usize = 1024;
+/// Number of lights a tile of the light culling can be influenced by.
+pub const TILE_LIGHT_SLOTS: u32 = 128;
+/// Hemisphere samples of the screen-space ambient occlusion.
+pub const SSAO_KERNEL_SIZE: usize = 32;
+pub const HISTOGRAM_BIN_COUNT: u64 = 256;
+
+/// The web build renders a single shadow cascade.
+pub const fn shadow_cascade_count(web: bool) -> u32 {
+    if web {
+        1
+    } else {
+        4
+    }
+}
+
+/// The camera, the shadow cascades and the unculled view.
+pub const fn frustum_view_count(web: bool) -> usize {
+    2 + shadow_cascade_count(web) as usize
+}
+
+/// Names and WGSL literals of the constants, in the shaders the names are replaced by the literals.
+pub fn shader_constants(web: bool) -> Vec<(&'static str, String)> {
+    vec![
+        ("MAX_POINT_LIGHTS", format!("{}u", MAX_POINT_LIGHTS)),
+        ("MAX_SPOT_LIGHTS", format!("{}u", MAX_SPOT_LIGHTS)),
+        ("TILE_LIGHT_SLOTS", format!("{}u", TILE_LIGHT_SLOTS)),
+        ("SSAO_KERNEL_SIZE", format!("{}u", SSAO_KERNEL_SIZE)),
+        ("HISTOGRAM_BIN_COUNT", format!("{}u", HISTOGRAM_BIN_COUNT)),
+        (
+            "SHADOW_CASCADE_COUNT",
+            format!("{}u", shadow_cascade_count(web)),
+        ),
+        (
+            "FRUSTUM_VIEW_COUNT",
+            format!("{}u", frustum_view_count(web)),
+        ),
+    ]
+}
@@ -0,0 +1,66 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use once_cell::sync::Lazy;
+
+/// Sources replacing the shaders embedded at compile time, keyed by file name.
+static OVERRIDES: Lazy<Mutex<HashMap<String, String>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+// the native variants embedded at compile time, generated by the build script.
+#[cfg(not(target_arch = "wasm32"))]
+include!(concat!(env!("OUT_DIR"), "/shaders/native_sources.rs"));
+
+/// Creates the shader modules of the pipelines, the shader hot reload overrides the embedded sources on native.
+pub struct ShaderLibrary;
+
+impl ShaderLibrary {
+    /// Uses the override of `file_name` if there's one, `embedded` otherwise.
+    pub fn create_module(
+        device: &wgpu::Device,
+        file_name: &str,
+        embedded: wgpu::ShaderModuleDescriptor,
+    ) -> wgpu::ShaderModule {
+        let overrides = OVERRIDES.lock().unwrap();
+        match overrides.get(file_name) {
+            Some(source) => device.create_shader_module(&wgpu::ShaderModuleDescriptor {
+                label: Some(file_name),
+                source: wgpu::ShaderSource::Wgsl(Cow::Borrowed(source.as_str())),
+            }),
+            None => device.create_shader_module(&embedded),
+        }
+    }
+    /// The override of `file_name` if there's one, `embedded` otherwise. For shaders which are completed at runtime.
+    pub fn source(file_name: &str, embedded: &str) -> String {
+        OVERRIDES
+            .lock()
+            .unwrap()
+            .get(file_name)
+            .cloned()
+            .unwrap_or_else(|| embedded.to_string())
+    }
+    /// Returns the previous override so a failed reload can restore it.
+    pub fn set_override(file_name: &str, source: String) -> Option<String> {
+        OVERRIDES
+            .lock()
+            .unwrap()
+            .insert(file_name.to_string(), source)
+    }
+    /// Whether the pipelines of `file_name` are built from `source`, the override or else the embedded native variant.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn is_current(file_name: &str, source: &str) -> bool {
+        if let Some(current) = OVERRIDES.lock().unwrap().get(file_name) {
+            return current == source;
+        }
+        NATIVE_SOURCES
+            .iter()
+            .any(|(name, embedded)| *name == file_name && *embedded == source)
+    }
+    pub fn restore_override(file_name: &str, previous: Option<String>) {
+        let mut overrides = OVERRIDES.lock().unwrap();
+        match previous {
+            Some(source) => overrides.insert(file_name.to_string(), source),
+            None => overrides.remove(file_name),
+        };
+    }
+}
@@ -0,0 +1,457 @@
+//! Generates the native and the web variant of a shader from the sources in `src/shaders`.
+//! The build script includes this file as well, it only depends on the standard library.
+use std::collections::HashMap;
+
+/// The sources are written with the native attribute syntax, the web variant rewrites them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShaderTarget {
+    Native,
+    Web,
+}
+
+impl ShaderTarget {
+    /// Name of the directory the variants of this target are generated into.
+    pub fn directory(&self) -> &'static str {
+        match self {
+            ShaderTarget::Native => "native",
+            ShaderTarget::Web => "web",
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum PreprocessError {
+    MissingFile(String),
+    RecursiveInclude(String),
+    /// File and 1-based line of a directive which couldn't be parsed.
+    InvalidDirective {
+        file_name: String,
+        line: usize,
+        directive: String,
+    },
+    UnterminatedConditional(String),
+}
+
+impl std::fmt::Display for PreprocessError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PreprocessError::MissingFile(file_name) => write!(f, "can't find {}", file_name),
+            PreprocessError::RecursiveInclude(file_name) => {
+                write!(f, "{} includes itself", file_name)
+            }
+            PreprocessError::InvalidDirective {
+                file_name,
+                line,
+                directive,
+            } => write!(f, "{}:{}: invalid directive {}", file_name, line, directive),
+            PreprocessError::UnterminatedConditional(file_name) => {
+                write!(f, "{} is missing an #endif", file_name)
+            }
+        }
+    }
+}
+
+/// The generated source, every line knows the file and line it comes from.
+pub struct PreprocessedShader {
+    pub source: String,
+    pub line_map: Vec<(String, usize)>,
+}
+
+impl PreprocessedShader {
+    /// File and 1-based line of a 1-based line of the generated source.
+    pub fn source_location(&self, line: usize) -> Option<(&str, usize)> {
+        self.line_map
+            .get(line.checked_sub(1)?)
+            .map(|(file_name, line)| (file_name.as_str(), *line))
+    }
+}
+
+/// Supports `#include "file"`, `#define NAME value`, `#ifdef NAME`, `#ifndef NAME`, `#else` and `#endif`.
+/// Identifiers matching a define with a value are replaced by the value, this is how the constants are injected.
+/// A file is only included once per shader.
+pub struct ShaderPreprocessor {
+    target: ShaderTarget,
+    defines: HashMap<String, String>,
+}
+
+impl ShaderPreprocessor {
+    pub fn new(target: ShaderTarget) -> Self {
+        let mut defines = HashMap::new();
+        let target_define = match target {
+            ShaderTarget::Native => "TARGET_NATIVE",
+            ShaderTarget::Web => "TARGET_WEB",
+        };
+        defines.insert(target_define.to_string(), String::new());
+        Self { target, defines }
+    }
+    pub fn define(&mut self, name: &str, value: &str) {
+        self.defines.insert(name.to_string(), value.to_string());
+    }
+    /// `load` returns the contents of a file relative to the shader directory.
+    pub fn process(
+        &self,
+        file_name: &str,
+        load: &dyn Fn(&str) -> Option<String>,
+    ) -> Result<PreprocessedShader, PreprocessError> {
+        let mut output = PreprocessedShader {
+            source: String::new(),
+            line_map: Vec::new(),
+        };
+        let mut defines = self.defines.clone();
+        let mut included = Vec::new();
+        self.process_file(
+            file_name,
+            load,
+            &mut defines,
+            &mut included,
+            &mut Vec::new(),
+            &mut output,
+        )?;
+        if self.target == ShaderTarget::Web {
+            output.source = Self::to_web_syntax(output.source.as_str());
+        }
+        Ok(output)
+    }
+    fn process_file(
+        &self,
+        file_name: &str,
+        load: &dyn Fn(&str) -> Option<String>,
+        defines: &mut HashMap<String, String>,
+        included: &mut Vec<String>,
+        stack: &mut Vec<String>,
+        output: &mut PreprocessedShader,
+    ) -> Result<(), PreprocessError> {
+        if stack.iter().any(|name| name == file_name) {
+            return Err(PreprocessError::RecursiveInclude(file_name.to_string()));
+        }
+        if included.iter().any(|name| name == file_name) {
+            return Ok(());
+        }
+        included.push(file_name.to_string());
+        let contents =
+            load(file_name).ok_or_else(|| PreprocessError::MissingFile(file_name.to_string()))?;
+        stack.push(file_name.to_string());
+        // whether the lines of every nested conditional are kept
+        let mut conditionals: Vec<bool> = Vec::new();
+        for (index, line) in contents.lines().enumerate() {
+            let line_number = index + 1;
+            let active = conditionals.iter().all(|active| *active);
+            let trimmed = line.trim();
+            if let Some(directive) = trimmed.strip_prefix('#') {
+                let invalid = || PreprocessError::InvalidDirective {
+                    file_name: file_name.to_string(),
+                    line: line_number,
+                    directive: trimmed.to_string(),
+                };
+                let mut parts = directive.splitn(2, char::is_whitespace);
+                let keyword = parts.next().unwrap_or("");
+                let argument = parts.next().unwrap_or("").trim();
+                match keyword {
+                    "ifdef" | "ifndef" if !argument.is_empty() => {
+                        conditionals.push(defines.contains_key(argument) == (keyword == "ifdef"));
+                    }
+                    "else" => {
+                        let last = conditionals.last_mut().ok_or_else(invalid)?;
+                        *last = !*last;
+                    }
+                    "endif" => {
+                        conditionals.pop().ok_or_else(invalid)?;
+                    }
+                    "define" if active => {
+                        let mut parts = argument.splitn(2, char::is_whitespace);
+                        let name = parts.next().filter(|name| !name.is_empty());
+                        let value = parts.next().unwrap_or("").trim();
+                        defines.insert(name.ok_or_else(invalid)?.to_string(), value.to_string());
+                    }
+                    "include" if active => {
+                        let included_file = argument
+                            .strip_prefix('"')
+                            .and_then(|argument| argument.strip_suffix('"'))
+                            .ok_or_else(invalid)?;
+                        self.process_file(included_file, load, defines, included, stack, output)?;
+                    }
+                    "define" | "include" => {}
+                    _ => return Err(invalid()),
+                }
+                // the other directives become empty lines, an include is replaced by the included lines
+                if keyword != "include" || !active {
+                    output.source.push('\n');
+                    output.line_map.push((file_name.to_string(), line_number));
+                }
+                continue;
+            }
+            if active {
+                output
+                    .source
+                    .push_str(Self::substitute(line, defines).as_str());
+            }
+            output.source.push('\n');
+            output.line_map.push((file_name.to_string(), line_number));
+        }
+        if !conditionals.is_empty() {
+            return Err(PreprocessError::UnterminatedConditional(
+                file_name.to_string(),
+            ));
+        }
+        stack.pop();
+        Ok(())
+    }
+    /// Replaces the identifiers of the line which are defined with a value, comments are left alone.
+    fn substitute(line: &str, defines: &HashMap<String, String>) -> String {
+        let (code, comment) = match line.find("//") {
+            Some(index) => line.split_at(index),
+            None => (line, ""),
+        };
+        let mut result = String::with_capacity(line.len());
+        let mut identifier = String::new();
+        let flush = |identifier: &mut String, result: &mut String| {
+            match defines.get(identifier.as_str()) {
+                Some(value) if !value.is_empty() => result.push_str(value),
+                _ => result.push_str(identifier),
+            }
+            identifier.clear();
+        };
+        for c in code.chars() {
+            if c.is_ascii_alphanumeric() || c == '_' {
+                // digits starting a literal like `1u` aren't identifiers
+                if identifier.is_empty() && c.is_ascii_digit() {
+                    result.push(c);
+                } else {
+                    identifier.push(c);
+                }
+            } else {
+                flush(&mut identifier, &mut result);
+                result.push(c);
+            }
+        }
+        flush(&mut identifier, &mut result);
+        result.push_str(comment);
+        result
+    }
+    /// Rewrites `[[a(b), c(d)]]` attributes into `@a(b) @c(d)` and separates the struct members with commas.
+    /// The line count doesn't change so the line map stays valid.
+    pub fn to_web_syntax(source: &str) -> String {
+        let mut result = String::with_capacity(source.len());
+        let mut rest = source;
+        while let Some(start) = rest.find("[[") {
+            let end = match rest[start..].find("]]") {
+                Some(end) => start + end,
+                None => break,
+            };
+            result.push_str(&rest[..start]);
+            let mut depth = 0;
+            let mut attribute = String::new();
+            let mut separator = String::new();
+            let mut first = true;
+            let mut emit = |attribute: &mut String, separator: &mut String, result: &mut String| {
+                if !first {
+                    if separator.contains('\n') {
+                        result.push_str(separator.as_str());
+                    } else {
+                        result.push(' ');
+                    }
+                }
+                first = false;
+                result.push('@');
+                result.push_str(attribute.trim());
+                // the whitespace after the attribute belongs to the separator of the next one
+                separator.clear();
+                separator.push_str(&attribute[attribute.trim_end().len()..]);
+                attribute.clear();
+            };
+            for c in rest[start + 2..end].chars() {
+                match c {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => {}
+                }
+                if c == ',' && depth == 0 {
+                    emit(&mut attribute, &mut separator, &mut result);
+                } else if attribute.trim().is_empty() && c.is_whitespace() {
+                    separator.push(c);
+                } else {
+                    attribute.push(c);
+                }
+            }
+            emit(&mut attribute, &mut separator, &mut result);
+            result.push_str(separator.as_str());
+            rest = &rest[end + 2..];
+        }
+        result.push_str(rest);
+
+        let mut in_struct = false;
+        let lines: Vec<String> = result
+            .split('\n')
+            .map(|line| {
+                let trimmed = line.trim_start();
+                if trimmed.starts_with("struct ") && trimmed.trim_end().ends_with('{') {
+                    in_struct = true;
+                } else if in_struct && trimmed.starts_with('}') {
+                    in_struct = false;
+                } else if in_struct {
+                    let (code, comment) = match line.find("//") {
+                        Some(index) => line.split_at(index),
+                        None => (line, ""),
+                    };
+                    let code_end = code.trim_end().len();
+                    if code[..code_end].ends_with(';') {
+                        return format!(
+                            "{},{}{}",
+                            &code[..code_end - 1],
+                            &code[code_end..],
+                            comment
+                        );
+                    }
+                }
+                line.to_string()
+            })
+            .collect();
+        lines.join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Processes the first file, the others can be included by it.
+    fn process(
+        preprocessor: &ShaderPreprocessor,
+        files: &[(&str, &str)],
+    ) -> Result<PreprocessedShader, PreprocessError> {
+        let load = |file_name: &str| {
+            files
+                .iter()
+                .find(|(name, _)| *name == file_name)
+                .map(|(_, contents)| contents.to_string())
+        };
+        preprocessor.process(files[0].0, &load)
+    }
+
+    fn code_lines(shader: &PreprocessedShader) -> Vec<&str> {
+        shader
+            .source
+            .lines()
+            .filter(|line| !line.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn includes_are_inserted_once_and_keep_their_lines() {
+        let shader = process(
+            &ShaderPreprocessor::new(ShaderTarget::Native),
+            &[
+                (
+                    "main.wgsl",
+                    "#include \"include/a.wgsl\"\n#include \"include/a.wgsl\"\nfn main() {}",
+                ),
+                ("include/a.wgsl", "let a: f32 = 1.0;"),
+            ],
+        )
+        .unwrap();
+        assert_eq!(shader.source, "let a: f32 = 1.0;\nfn main() {}\n");
+        assert_eq!(shader.source_location(1), Some(("include/a.wgsl", 1)));
+        assert_eq!(shader.source_location(2), Some(("main.wgsl", 3)));
+        assert_eq!(shader.source_location(3), None);
+    }
+
+    #[test]
+    fn include_cycles_are_rejected() {
+        let result = process(
+            &ShaderPreprocessor::new(ShaderTarget::Native),
+            &[
+                ("a.wgsl", "#include \"b.wgsl\""),
+                ("b.wgsl", "#include \"a.wgsl\""),
+            ],
+        );
+        assert!(
+            matches!(result, Err(PreprocessError::RecursiveInclude(file_name)) if file_name == "a.wgsl")
+        );
+    }
+
+    #[test]
+    fn nested_conditionals_select_the_target_lines() {
+        let files = [(
+            "main.wgsl",
+            "#ifdef TARGET_WEB\nweb\n#ifndef DEBUG\nweb_release\n#else\nweb_debug\n#endif\n#else\nnative\n#endif",
+        )];
+        let web = process(&ShaderPreprocessor::new(ShaderTarget::Web), &files).unwrap();
+        assert_eq!(code_lines(&web), vec!["web", "web_release"]);
+        // the skipped lines and the directives stay as empty lines.
+        assert_eq!(web.line_map.len(), 10);
+
+        let mut debug = ShaderPreprocessor::new(ShaderTarget::Web);
+        debug.define("DEBUG", "");
+        let web_debug = process(&debug, &files).unwrap();
+        assert_eq!(code_lines(&web_debug), vec!["web", "web_debug"]);
+
+        let native = process(&ShaderPreprocessor::new(ShaderTarget::Native), &files).unwrap();
+        assert_eq!(code_lines(&native), vec!["native"]);
+    }
+
+    #[test]
+    fn defines_replace_whole_identifiers_outside_of_comments() {
+        let mut preprocessor = ShaderPreprocessor::new(ShaderTarget::Native);
+        preprocessor.define("MAX_LIGHTS", "64u");
+        let shader = process(
+            &preprocessor,
+            &[(
+                "main.wgsl",
+                "#define GROUP 2\n[[group(GROUP), binding(0)]] var<uniform> lights: array<Light, MAX_LIGHTS>; // MAX_LIGHTS lights\nlet MAX_LIGHTS_SQUARED: u32 = 1u;",
+            )],
+        )
+        .unwrap();
+        assert_eq!(
+            shader.source,
+            "\n[[group(2), binding(0)]] var<uniform> lights: array<Light, 64u>; // MAX_LIGHTS lights\nlet MAX_LIGHTS_SQUARED: u32 = 1u;\n"
+        );
+    }
+
+    #[test]
+    fn web_variant_uses_the_new_attribute_syntax() {
+        let shader = process(
+            &ShaderPreprocessor::new(ShaderTarget::Web),
+            &[(
+                "main.wgsl",
+                "struct VertexOutput {\n    [[builtin(position)]] clip_position: vec4<f32>;\n    [[location(0)]] uv: vec2<f32>; // texture coordinates\n};\n[[group(0), binding(1)]]\nvar t_diffuse: texture_2d<f32>;",
+            )],
+        )
+        .unwrap();
+        assert_eq!(
+            shader.source,
+            "struct VertexOutput {\n    @builtin(position) clip_position: vec4<f32>,\n    @location(0) uv: vec2<f32>, // texture coordinates\n};\n@group(0) @binding(1)\nvar t_diffuse: texture_2d<f32>;\n"
+        );
+        assert_eq!(shader.line_map.len(), 6);
+    }
+
+    #[test]
+    fn missing_includes_are_reported() {
+        let result = process(
+            &ShaderPreprocessor::new(ShaderTarget::Native),
+            &[("main.wgsl", "#include \"include/missing.wgsl\"")],
+        );
+        assert!(
+            matches!(result, Err(PreprocessError::MissingFile(file_name)) if file_name == "include/missing.wgsl")
+        );
+    }
+
+    #[test]
+    fn unbalanced_conditionals_are_reported() {
+        let result = process(
+            &ShaderPreprocessor::new(ShaderTarget::Native),
+            &[("main.wgsl", "fn main() {}\n#endif")],
+        );
+        assert!(matches!(
+            result,
+            Err(PreprocessError::InvalidDirective { file_name, line: 2, directive })
+                if file_name == "main.wgsl" && directive == "#endif"
+        ));
+        let result = process(
+            &ShaderPreprocessor::new(ShaderTarget::Native),
+            &[("main.wgsl", "#ifdef TARGET_NATIVE\nfn main() {}")],
+        );
+        assert!(
+            matches!(result, Err(PreprocessError::UnterminatedConditional(file_name)) if file_name == "main.wgsl")
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::renderer::primitives::texture::Texture;
+use crate::renderer::shaderconstants;
 use std::ops::BitAnd;
 
 use crate::components::transform::*;
@@ -6,13 +7,15 @@ use specs::WorldExt;
 use winit::{event::*, window::Window};
 
 pub struct State {
-    pub surface: wgpu::Surface,
+    /// `None` when running headless, frames are rendered into `offscreen_texture` instead.
+    pub surface: Option<wgpu::Surface>,
     pub device: wgpu::Device,
     pub queue: wgpu::Queue,
     pub sc_descriptor: wgpu::SurfaceConfiguration,
     pub size: winit::dpi::PhysicalSize<u32>,
     pub depth_texture: Texture,
     pub scale_factor: f64,
+    pub offscreen_texture: Option<wgpu::Texture>,
 }
 impl State {
     pub const OPENGL_TO_WGPU_MATRIX: [[f32; 4]; 4] = [
@@ -21,17 +24,38 @@ impl State {
         [0.0, 0.0, 0.5, 0.0],
         [0.0, 0.0, 0.5, 1.0],
     ];
-    pub const CASCADE_DISTS: (f32, f32) = (0.1, 10.0);
-    pub const NUM_PASSES: u32 = 6;
+    pub const NUM_PASSES: u32 = 11;
     pub const MAX_ENTITY_COUNT: wgpu::BufferAddress =
         (std::mem::size_of::<TransformRaw>() * 2048) as wgpu::BufferAddress;
-    pub const MAX_POINT_LIGHTS: usize = 1024;
-    pub const MAX_SPOT_LIGHTS: usize = 1024;
+    pub const MAX_JOINT_MATRICES: usize = 4096;
+    pub const MAX_MORPH_TARGET_DELTAS: usize = 262144;
+    pub const MAX_MORPH_WEIGHTS: usize = 4096;
+    pub const MAX_POINT_LIGHTS: usize = shaderconstants::MAX_POINT_LIGHTS;
+    pub const MAX_SPOT_LIGHTS: usize = shaderconstants::MAX_SPOT_LIGHTS;
+    pub const MAX_SHADOW_ATLAS_TILES: usize = 256;
+    /// Indirect draws per culling view, every primitive of a drawn mesh takes one.
+    pub const MAX_INDIRECT_DRAWS: usize = 4096;
+    /// The maximum cascade count and resolution of the `ShadowSettings`.
     pub const SHADOW_SIZE: wgpu::Extent3d = wgpu::Extent3d {
-        depth_or_array_layers: if !cfg!(target_arch = "wasm32") { 4 } else { 1 },
-        height: 1024,
-        width: 1024,
+        depth_or_array_layers: shaderconstants::shadow_cascade_count(cfg!(target_arch = "wasm32")),
+        height: if !cfg!(target_arch = "wasm32") {
+            2048
+        } else {
+            1024
+        },
+        width: if !cfg!(target_arch = "wasm32") {
+            2048
+        } else {
+            1024
+        },
     };
+    /// Point and spot light shadows are rendered into tiles of this texture.
+    pub const SHADOW_ATLAS_SIZE: u32 = if !cfg!(target_arch = "wasm32") {
+        4096
+    } else {
+        2048
+    };
+    pub const LIGHT_SHADOW_NEAR_PLANE: f32 = 0.05;
     pub async fn new(window: &Window) -> Self {
         let size = window.inner_size();
 
@@ -47,19 +71,7 @@ impl State {
             .await
             .unwrap();
 
-        let (device, queue) = adapter
-            .request_device(
-                &wgpu::DeviceDescriptor {
-                    features: adapter.features().bitand(
-                        wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::PIPELINE_STATISTICS_QUERY,
-                    ),
-                    limits: wgpu::Limits::default(),
-                    label: Some("Device descriptor"),
-                },
-                None,
-            )
-            .await
-            .unwrap();
+        let (device, queue) = Self::request_device(&adapter, wgpu::Limits::default()).await;
         let sc_desc = wgpu::SurfaceConfiguration {
             usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
             format: if cfg!(target_arch = "wasm32") {
@@ -76,11 +88,98 @@ impl State {
         Self {
             depth_texture: Texture::create_depth_texture(&device, &sc_desc, "depth_texture"),
             device,
-            surface,
+            surface: Some(surface),
             queue,
             sc_descriptor: sc_desc,
             size,
             scale_factor: window.scale_factor(),
+            offscreen_texture: None,
         }
     }
+    /// Creates a state without a window or surface, rendering into an offscreen texture.
+    /// Falls back to a software adapter if no hardware adapter is present (e.g. on CI machines).
+    pub async fn new_headless(width: u32, height: u32) -> Self {
+        let size = winit::dpi::PhysicalSize::new(width, height);
+
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let adapter = match instance
+            .request_adapter(&wgpu::RequestAdapterOptions {
+                force_fallback_adapter: false,
+                power_preference: wgpu::PowerPreference::HighPerformance,
+                compatible_surface: None,
+            })
+            .await
+        {
+            Some(adapter) => adapter,
+            None => instance
+                .request_adapter(&wgpu::RequestAdapterOptions {
+                    force_fallback_adapter: true,
+                    power_preference: wgpu::PowerPreference::LowPower,
+                    compatible_surface: None,
+                })
+                .await
+                .expect("no hardware or fallback adapter found"),
+        };
+        log::info!(target:"headless","using adapter {:?}", adapter.get_info());
+        // software adapters may not support the default limits.
+        let (device, queue) = Self::request_device(&adapter, adapter.limits()).await;
+        let sc_desc = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: size.width,
+            height: size.height,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+
+        Self {
+            depth_texture: Texture::create_depth_texture(&device, &sc_desc, "depth_texture"),
+            offscreen_texture: Some(Self::create_offscreen_texture(&device, &sc_desc)),
+            device,
+            surface: None,
+            queue,
+            sc_descriptor: sc_desc,
+            size,
+            scale_factor: 1.0,
+        }
+    }
+    pub fn is_headless(&self) -> bool {
+        self.surface.is_none()
+    }
+    /// Creates the texture used in place of the swapchain image when there's no surface.
+    pub fn create_offscreen_texture(
+        device: &wgpu::Device,
+        sc_desc: &wgpu::SurfaceConfiguration,
+    ) -> wgpu::Texture {
+        device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("offscreen_texture"),
+            size: wgpu::Extent3d {
+                width: sc_desc.width,
+                height: sc_desc.height,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: sc_desc.format,
+            usage: sc_desc.usage | wgpu::TextureUsages::TEXTURE_BINDING,
+        })
+    }
+    async fn request_device(
+        adapter: &wgpu::Adapter,
+        limits: wgpu::Limits,
+    ) -> (wgpu::Device, wgpu::Queue) {
+        adapter
+            .request_device(
+                &wgpu::DeviceDescriptor {
+                    features: adapter.features().bitand(
+                        wgpu::Features::TIMESTAMP_QUERY | wgpu::Features::PIPELINE_STATISTICS_QUERY,
+                    ),
+                    limits,
+                    label: Some("Device descriptor"),
+                },
+                None,
+            )
+            .await
+            .unwrap()
+    }
 }
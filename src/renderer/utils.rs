@@ -1 +1,3 @@
+pub mod depthpyramidgenerator;
+pub mod environmentmapgenerator;
 pub mod texturerenderer;
@@ -0,0 +1,218 @@
+use std::num::NonZeroU32;
+
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, TextureTypes, TextureViewTypes,
+};
+
+/// Reduces the depth texture into a mip chain which keeps the farthest depth of every texel,
+/// the frustum culling tests the bounding spheres of the next frame against it.
+pub struct DepthPyramidGenerator {
+    depth_bind_group_layout: wgpu::BindGroupLayout,
+    mip_bind_group_layout: wgpu::BindGroupLayout,
+    depth_pipeline: wgpu::ComputePipeline,
+    mip_pipeline: wgpu::ComputePipeline,
+}
+
+impl DepthPyramidGenerator {
+    pub const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::R32Float;
+    const WORKGROUP_SIZE: u32 = 8;
+
+    pub fn new(device: &wgpu::Device) -> Self {
+        let target_entry = wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::COMPUTE,
+            count: None,
+            ty: wgpu::BindingType::StorageTexture {
+                access: wgpu::StorageTextureAccess::WriteOnly,
+                format: Self::FORMAT,
+                view_dimension: wgpu::TextureViewDimension::D2,
+            },
+        };
+        let depth_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("depth_pyramid_depth_layout"),
+                entries: &[
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 0,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        count: None,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Depth,
+                        },
+                    },
+                    target_entry,
+                ],
+            });
+        let mip_bind_group_layout =
+            device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+                label: Some("depth_pyramid_mip_layout"),
+                entries: &[
+                    target_entry,
+                    wgpu::BindGroupLayoutEntry {
+                        binding: 2,
+                        visibility: wgpu::ShaderStages::COMPUTE,
+                        count: None,
+                        ty: wgpu::BindingType::Texture {
+                            multisampled: false,
+                            view_dimension: wgpu::TextureViewDimension::D2,
+                            sample_type: wgpu::TextureSampleType::Float { filterable: false },
+                        },
+                    },
+                ],
+            });
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/depthpyramid.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/depthpyramid.wgsl"
+            ))
+        };
+        let module = ShaderLibrary::create_module(device, "depthpyramid.wgsl", wgsl);
+        let create_pipeline = |layout: &wgpu::BindGroupLayout, entry_point: &str, label: &str| {
+            let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+                label: Some(label),
+                bind_group_layouts: &[layout],
+                push_constant_ranges: &[],
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                module: &module,
+                entry_point,
+            })
+        };
+        Self {
+            depth_pipeline: create_pipeline(
+                &depth_bind_group_layout,
+                "reduce_depth",
+                "Depth pyramid depth pipeline",
+            ),
+            mip_pipeline: create_pipeline(
+                &mip_bind_group_layout,
+                "reduce_mip",
+                "Depth pyramid mip pipeline",
+            ),
+            depth_bind_group_layout,
+            mip_bind_group_layout,
+        }
+    }
+    /// The largest power of two size which fits into the depth texture.
+    pub fn pyramid_size(width: u32, height: u32) -> (u32, u32) {
+        let previous_power_of_two = |size: u32| 1 << (31 - size.max(1).leading_zeros());
+        (previous_power_of_two(width), previous_power_of_two(height))
+    }
+    pub fn mip_count((width, height): (u32, u32)) -> u32 {
+        32 - width.max(height).leading_zeros()
+    }
+    /// Creates the pyramid texture for a depth texture of the given size, has to run again after a resize.
+    pub fn create_pyramid(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        resource_container: &mut BindingResourceContainer,
+    ) {
+        let size = Self::pyramid_size(width, height);
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: Some("depth pyramid texture"),
+            size: wgpu::Extent3d {
+                width: size.0,
+                height: size.1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: Self::mip_count(size),
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: Self::FORMAT,
+            usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+        });
+        resource_container.texture_views[TextureViewTypes::DepthPyramid] =
+            Some(texture.create_view(&wgpu::TextureViewDescriptor::default()));
+        resource_container.textures[TextureTypes::DepthPyramid] = Some(texture);
+    }
+    /// Records the reduction of the depth texture into every mip of the pyramid,
+    /// `size` is the `pyramid_size` it was created with.
+    pub fn generate(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        depth_view: &wgpu::TextureView,
+        size: (u32, u32),
+        resource_container: &BindingResourceContainer,
+    ) {
+        let texture = resource_container.textures[TextureTypes::DepthPyramid]
+            .as_ref()
+            .unwrap();
+        let mip_views = (0..Self::mip_count(size))
+            .map(|mip_level| {
+                texture.create_view(&wgpu::TextureViewDescriptor {
+                    label: Some("depth_pyramid_mip_view"),
+                    base_mip_level: mip_level,
+                    mip_level_count: NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            })
+            .collect::<Vec<_>>();
+        let bind_groups = mip_views
+            .iter()
+            .enumerate()
+            .map(|(mip_level, mip_view)| {
+                if mip_level == 0 {
+                    device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some("depth_pyramid_depth_bind_group"),
+                        layout: &self.depth_bind_group_layout,
+                        entries: &[
+                            wgpu::BindGroupEntry {
+                                binding: 0,
+                                resource: wgpu::BindingResource::TextureView(depth_view),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 1,
+                                resource: wgpu::BindingResource::TextureView(mip_view),
+                            },
+                        ],
+                    })
+                } else {
+                    device.create_bind_group(&wgpu::BindGroupDescriptor {
+                        label: Some("depth_pyramid_mip_bind_group"),
+                        layout: &self.mip_bind_group_layout,
+                        entries: &[
+                            wgpu::BindGroupEntry {
+                                binding: 1,
+                                resource: wgpu::BindingResource::TextureView(mip_view),
+                            },
+                            wgpu::BindGroupEntry {
+                                binding: 2,
+                                resource: wgpu::BindingResource::TextureView(
+                                    &mip_views[mip_level - 1],
+                                ),
+                            },
+                        ],
+                    })
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("depth pyramid pass"),
+        });
+        for (mip_level, bind_group) in bind_groups.iter().enumerate() {
+            pass.set_pipeline(if mip_level == 0 {
+                &self.depth_pipeline
+            } else {
+                &self.mip_pipeline
+            });
+            pass.set_bind_group(0, bind_group, &[]);
+            let width = (size.0 >> mip_level).max(1);
+            let height = (size.1 >> mip_level).max(1);
+            pass.dispatch(
+                (width + Self::WORKGROUP_SIZE - 1) / Self::WORKGROUP_SIZE,
+                (height + Self::WORKGROUP_SIZE - 1) / Self::WORKGROUP_SIZE,
+                1,
+            );
+        }
+    }
+}
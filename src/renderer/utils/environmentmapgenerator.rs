@@ -0,0 +1,216 @@
+use std::num::NonZeroU32;
+
+use wgpu::util::DeviceExt;
+
+use crate::renderer::bindgroups::environment::EnvironmentBindGroup;
+use crate::renderer::primitives::uniforms::EnvironmentUniforms;
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, SamplerTypes, TextureTypes, TextureViewTypes,
+};
+
+/// Generates the image based lighting maps of the `EnvironmentBindGroup` from the skybox cubemap,
+/// has to run again whenever the skybox changes.
+pub struct EnvironmentMapGenerator {
+    bind_group_layout: wgpu::BindGroupLayout,
+    irradiance_pipeline: wgpu::ComputePipeline,
+    prefilter_pipeline: wgpu::ComputePipeline,
+    brdf_lut_pipeline: wgpu::ComputePipeline,
+}
+
+impl EnvironmentMapGenerator {
+    const WORKGROUP_SIZE: u32 = 8;
+    const SAMPLE_COUNT: u32 = 256;
+
+    pub fn new(device: &wgpu::Device) -> Self {
+        let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("environment_map_generator_layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    count: None,
+                    ty: wgpu::BindingType::Texture {
+                        multisampled: false,
+                        view_dimension: wgpu::TextureViewDimension::Cube,
+                        sample_type: wgpu::TextureSampleType::Float { filterable: true },
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    count: None,
+                    ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    count: None,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                },
+                wgpu::BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: wgpu::ShaderStages::COMPUTE,
+                    count: None,
+                    ty: wgpu::BindingType::StorageTexture {
+                        access: wgpu::StorageTextureAccess::WriteOnly,
+                        format: EnvironmentBindGroup::FORMAT,
+                        view_dimension: wgpu::TextureViewDimension::D2Array,
+                    },
+                },
+            ],
+        });
+        let layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Environment map generator layout"),
+            bind_group_layouts: &[&bind_group_layout],
+            push_constant_ranges: &[],
+        });
+        let wgsl = if cfg!(target_arch = "wasm32") {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/web/environment.wgsl"))
+        } else {
+            wgpu::include_wgsl!(concat!(env!("OUT_DIR"), "/shaders/native/environment.wgsl"))
+        };
+        let module = ShaderLibrary::create_module(device, "environment.wgsl", wgsl);
+        let create_pipeline = |entry_point: &str, label: &str| {
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(label),
+                layout: Some(&layout),
+                module: &module,
+                entry_point,
+            })
+        };
+        Self {
+            irradiance_pipeline: create_pipeline("irradiance_main", "Irradiance map pipeline"),
+            prefilter_pipeline: create_pipeline("prefilter_main", "Prefiltered map pipeline"),
+            brdf_lut_pipeline: create_pipeline("brdf_lut_main", "BRDF lookup table pipeline"),
+            bind_group_layout,
+        }
+    }
+    /// Convolves the skybox into the irradiance and prefiltered maps and integrates the BRDF lookup table.
+    pub fn generate(
+        &self,
+        device: &wgpu::Device,
+        queue: &wgpu::Queue,
+        resource_container: &BindingResourceContainer,
+    ) {
+        let skybox_view = resource_container.texture_views[TextureViewTypes::Skybox]
+            .as_ref()
+            .unwrap();
+        let skybox_sampler = resource_container.samplers[SamplerTypes::Skybox]
+            .as_ref()
+            .unwrap();
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("environment map encoder"),
+        });
+        let mut dispatch = |pipeline: &wgpu::ComputePipeline,
+                            texture: &wgpu::Texture,
+                            mip_level: u32,
+                            layers: u32,
+                            uniforms: EnvironmentUniforms| {
+            let target_view = texture.create_view(&wgpu::TextureViewDescriptor {
+                label: Some("environment_map_target_view"),
+                dimension: Some(wgpu::TextureViewDimension::D2Array),
+                base_mip_level: mip_level,
+                mip_level_count: NonZeroU32::new(1),
+                ..Default::default()
+            });
+            self.dispatch(
+                device,
+                &mut encoder,
+                pipeline,
+                (skybox_view, skybox_sampler, &target_view),
+                layers,
+                uniforms,
+            );
+        };
+
+        dispatch(
+            &self.irradiance_pipeline,
+            resource_container.textures[TextureTypes::Irradiance]
+                .as_ref()
+                .unwrap(),
+            0,
+            6,
+            EnvironmentUniforms::new(0.0, EnvironmentBindGroup::IRRADIANCE_SIZE, 0),
+        );
+        for mip_level in 0..EnvironmentBindGroup::PREFILTERED_MIPS {
+            let roughness = mip_level as f32 / (EnvironmentBindGroup::PREFILTERED_MIPS - 1) as f32;
+            dispatch(
+                &self.prefilter_pipeline,
+                resource_container.textures[TextureTypes::PrefilteredEnvironment]
+                    .as_ref()
+                    .unwrap(),
+                mip_level,
+                6,
+                EnvironmentUniforms::new(
+                    roughness,
+                    EnvironmentBindGroup::PREFILTERED_SIZE >> mip_level,
+                    Self::SAMPLE_COUNT,
+                ),
+            );
+        }
+        dispatch(
+            &self.brdf_lut_pipeline,
+            resource_container.textures[TextureTypes::BrdfLut]
+                .as_ref()
+                .unwrap(),
+            0,
+            1,
+            EnvironmentUniforms::new(0.0, EnvironmentBindGroup::BRDF_LUT_SIZE, Self::SAMPLE_COUNT),
+        );
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+    fn dispatch(
+        &self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        pipeline: &wgpu::ComputePipeline,
+        (source_view, source_sampler, target_view): (
+            &wgpu::TextureView,
+            &wgpu::Sampler,
+            &wgpu::TextureView,
+        ),
+        layers: u32,
+        uniforms: EnvironmentUniforms,
+    ) {
+        // every dispatch gets its own buffer as they're all submitted at once.
+        let uniform_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("environment_uniform_buffer"),
+            contents: bytemuck::cast_slice(&[uniforms]),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("environment_map_generator_bind_group"),
+            layout: &self.bind_group_layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: wgpu::BindingResource::TextureView(source_view),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 1,
+                    resource: wgpu::BindingResource::Sampler(source_sampler),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 2,
+                    resource: uniform_buffer.as_entire_binding(),
+                },
+                wgpu::BindGroupEntry {
+                    binding: 3,
+                    resource: wgpu::BindingResource::TextureView(target_view),
+                },
+            ],
+        });
+        let workgroups = (uniforms.size + Self::WORKGROUP_SIZE - 1) / Self::WORKGROUP_SIZE;
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("environment map pass"),
+        });
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch(workgroups, workgroups, layers);
+    }
+}
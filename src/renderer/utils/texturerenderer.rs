@@ -1,3 +1,4 @@
+use crate::renderer::shaderlibrary::ShaderLibrary;
 use wgpu::SamplerBindingType;
 
 /// Debug renderer for textures like shadow maps
@@ -42,11 +43,17 @@ impl TextureRenderer {
         });
 
         let wgsl = if cfg!(target_arch = "wasm32") {
-            wgpu::include_wgsl!("../../shaders/web/textureRenderer.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/web/textureRenderer.wgsl"
+            ))
         } else {
-            wgpu::include_wgsl!("../../shaders/native/textureRenderer.wgsl")
+            wgpu::include_wgsl!(concat!(
+                env!("OUT_DIR"),
+                "/shaders/native/textureRenderer.wgsl"
+            ))
         };
-        let module = device.create_shader_module(&wgsl);
+        let module = ShaderLibrary::create_module(device, "textureRenderer.wgsl", wgsl);
 
         let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
             multiview: None,
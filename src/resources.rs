@@ -4,10 +4,25 @@ pub mod commandencoder;
 pub mod defaulttexturecontainer;
 pub mod deltatime;
 pub mod eguicontainer;
+pub mod finishedanimations;
+pub mod framecapture;
+pub mod frustumculling;
 pub mod gpuquerysets;
+pub mod lodsettings;
+pub mod materiallibrary;
+pub mod morphtargetstorage;
+pub mod postprocesssettings;
 pub mod projection;
 pub mod renderresult;
 pub mod scriptingstate;
+pub mod shadererrors;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod shaderhotreload;
+pub mod shadowatlas;
+pub mod shadowsettings;
+pub mod skyboxtexture;
+pub mod ssaosettings;
 pub mod surfacetexture;
+pub mod tonemappingsettings;
 pub mod windowevents;
 pub mod windowstate;
@@ -9,7 +9,7 @@ pub struct BindingResourceContainer {
     pub texture_array_views: EnumMap<TextureArrayViewTypes, Vec<wgpu::TextureView>>,
 }
 
-#[derive(Enum)]
+#[derive(Enum, Debug, PartialEq, Copy, Clone)]
 pub enum BufferTypes {
     CanvasSize,
     DeferredVao,
@@ -27,8 +27,25 @@ pub enum BufferTypes {
     DebugTextureVertex,
     ShadowCascade,
     ShadowCascadeLengths,
+    ShadowSettingsUniform,
+    ShadowAtlasTiles,
     DebugCollisionVertex,
-    DebugCollisionUniform
+    DebugCollisionUniform,
+    JointMatrices,
+    InstanceDeformations,
+    MorphTargets,
+    MorphWeights,
+    FrustumCullingUniform,
+    CullingInstances,
+    CullingGroups,
+    IndirectDraws,
+    VisibleInstances,
+    CullingStats,
+    CullingStatsReadback,
+    ToneMappingUniform,
+    LuminanceHistogram,
+    AdaptedLuminance,
+    SsaoUniform,
 }
 
 #[derive(Enum)]
@@ -37,6 +54,7 @@ pub enum SamplerTypes {
     DeferredTexture,
     Skybox,
     DebugTexture,
+    Environment,
 }
 
 #[derive(Enum)]
@@ -44,7 +62,17 @@ pub enum TextureTypes {
     PositionDiffuseNormals,
     Albedo,
     Shadow,
+    ShadowAtlas,
     Skybox,
+    Irradiance,
+    PrefilteredEnvironment,
+    BrdfLut,
+    DepthPyramid,
+    SceneColor,
+    HdrScene,
+    PostProcessPing,
+    PostProcessPong,
+    AmbientOcclusionBlurred,
 }
 
 #[derive(Enum, Debug, PartialEq, Copy, Clone)]
@@ -55,7 +83,17 @@ pub enum TextureViewTypes {
     DeferredSpecular,
     Skybox,
     Shadow,
+    ShadowAtlas,
     Depth,
+    Irradiance,
+    PrefilteredEnvironment,
+    BrdfLut,
+    DepthPyramid,
+    SceneColor,
+    HdrScene,
+    PostProcessPing,
+    PostProcessPong,
+    AmbientOcclusionBlurred,
 }
 #[derive(Enum, Debug, PartialOrd, PartialEq, Copy, Clone)]
 pub enum TextureArrayViewTypes {
@@ -0,0 +1,8 @@
+use specs::Entity;
+
+/// Non-looping animations which reached their end this frame, passed to the `AnimationFinished` callbacks.
+#[derive(Default)]
+pub struct FinishedAnimations {
+    /// The animated instance entity and the name of the clip.
+    pub events: Vec<(Entity, String)>,
+}
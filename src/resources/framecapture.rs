@@ -0,0 +1,404 @@
+use std::collections::HashMap;
+
+use futures::channel::oneshot;
+use image::{ImageFormat, RgbaImage};
+
+use crate::renderer::bindgroups::tonemapping::ToneMappingBindGroup;
+use crate::renderer::primitives::uniforms::TileInfo;
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, BufferTypes, TextureTypes,
+};
+use crate::State;
+
+#[derive(Debug, Clone)]
+pub enum FrameCaptureError {
+    UnsupportedFormat(wgpu::TextureFormat),
+    BufferMapFailed,
+    MissingTarget(CaptureTarget),
+    ImageEncodeFailed(String),
+}
+
+pub type FrameCaptureResult = Result<RgbaImage, FrameCaptureError>;
+
+/// Render targets that can be read back, besides the final frame these are the intermediate results of the passes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CaptureTarget {
+    Frame,
+    ShadowCascade(u32),
+    DeferredPosition,
+    DeferredNormals,
+    DeferredSpecular,
+    DeferredAlbedo,
+    /// The light culling result, one pixel per tile: red is the light count, green and blue mark which of the first 16 lights touch the tile.
+    LightCullingTiles,
+    /// The hdr scene right after the forward pass, only available while pass outputs are kept.
+    Forward,
+    /// The hdr scene right after the skybox pass, only available while pass outputs are kept.
+    Skybox,
+}
+
+/// Pending requests for reading back the composited frame, served by the `CaptureFrame` system after the ui pass.
+#[derive(Default)]
+pub struct FrameCapture {
+    pub requests: Vec<oneshot::Sender<FrameCaptureResult>>,
+    /// When set the passes which share the hdr scene target copy their output, so they can be captured on their own.
+    pub keep_pass_outputs: bool,
+    pub pass_outputs: HashMap<CaptureTarget, (wgpu::Texture, (u32, u32))>,
+}
+
+impl FrameCapture {
+    /// Requests the next rendered frame, the receiver resolves once it has been copied back from the gpu.
+    pub fn request_capture(&mut self) -> oneshot::Receiver<FrameCaptureResult> {
+        let (sender, receiver) = oneshot::channel();
+        self.requests.push(sender);
+        receiver
+    }
+    /// Copies the hdr scene into the kept output of the pass, does nothing unless `keep_pass_outputs` is set.
+    pub fn store_pass_output(
+        &mut self,
+        device: &wgpu::Device,
+        encoder: &mut wgpu::CommandEncoder,
+        target: CaptureTarget,
+        source: &wgpu::Texture,
+        width: u32,
+        height: u32,
+    ) {
+        if !self.keep_pass_outputs {
+            return;
+        }
+        let size = wgpu::Extent3d {
+            width,
+            height,
+            depth_or_array_layers: 1,
+        };
+        let recreate = match self.pass_outputs.get(&target) {
+            Some((_, output_size)) => *output_size != (width, height),
+            None => true,
+        };
+        // the copy follows the size of the surface.
+        if recreate {
+            let texture = device.create_texture(&wgpu::TextureDescriptor {
+                label: Some("pass_output_texture"),
+                size,
+                mip_level_count: 1,
+                sample_count: 1,
+                dimension: wgpu::TextureDimension::D2,
+                format: ToneMappingBindGroup::HDR_FORMAT,
+                usage: wgpu::TextureUsages::COPY_DST | wgpu::TextureUsages::COPY_SRC,
+            });
+            self.pass_outputs.insert(target, (texture, (width, height)));
+        }
+        let (texture, _) = &self.pass_outputs[&target];
+        encoder.copy_texture_to_texture(source.as_image_copy(), texture.as_image_copy(), size);
+    }
+    /// Saves the image to the given path, the encoder is picked based on the extension (png or exr).
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_image(image: &RgbaImage, path: &str) -> Result<(), FrameCaptureError> {
+        let format = ImageFormat::from_path(path)
+            .map_err(|e| FrameCaptureError::ImageEncodeFailed(e.to_string()))?;
+        match format {
+            ImageFormat::OpenExr => {
+                // exr files are expected to be linear.
+                let linear = image::Rgba32FImage::from_fn(image.width(), image.height(), |x, y| {
+                    let pixel = image.get_pixel(x, y);
+                    image::Rgba([
+                        srgb_to_linear(pixel[0]),
+                        srgb_to_linear(pixel[1]),
+                        srgb_to_linear(pixel[2]),
+                        pixel[3] as f32 / 255.0,
+                    ])
+                });
+                image::DynamicImage::ImageRgba32F(linear).save_with_format(path, format)
+            }
+            _ => image.save_with_format(path, format),
+        }
+        .map_err(|e| FrameCaptureError::ImageEncodeFailed(e.to_string()))
+    }
+    /// Copies the given target back from the gpu and blocks until it's available.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn capture_target(
+        &self,
+        state: &State,
+        resource_container: &BindingResourceContainer,
+        target: CaptureTarget,
+    ) -> FrameCaptureResult {
+        let (texture, layer, format, size) = match target {
+            CaptureTarget::LightCullingTiles => {
+                return Self::capture_light_culling_tiles(state, resource_container)
+            }
+            CaptureTarget::Forward | CaptureTarget::Skybox => (
+                self.pass_outputs.get(&target).map(|(texture, _)| texture),
+                0,
+                ToneMappingBindGroup::HDR_FORMAT,
+                (state.sc_descriptor.width, state.sc_descriptor.height),
+            ),
+            CaptureTarget::Frame => (
+                state.offscreen_texture.as_ref(),
+                0,
+                state.sc_descriptor.format,
+                (state.sc_descriptor.width, state.sc_descriptor.height),
+            ),
+            CaptureTarget::ShadowCascade(cascade) => (
+                resource_container.textures[TextureTypes::Shadow].as_ref(),
+                cascade,
+                wgpu::TextureFormat::Depth32Float,
+                (State::SHADOW_SIZE.width, State::SHADOW_SIZE.height),
+            ),
+            CaptureTarget::DeferredPosition
+            | CaptureTarget::DeferredNormals
+            | CaptureTarget::DeferredSpecular => (
+                resource_container.textures[TextureTypes::PositionDiffuseNormals].as_ref(),
+                match target {
+                    CaptureTarget::DeferredPosition => 0,
+                    CaptureTarget::DeferredNormals => 1,
+                    _ => 2,
+                },
+                wgpu::TextureFormat::Rgba32Float,
+                (state.sc_descriptor.width, state.sc_descriptor.height),
+            ),
+            CaptureTarget::DeferredAlbedo => (
+                resource_container.textures[TextureTypes::Albedo].as_ref(),
+                0,
+                wgpu::TextureFormat::Bgra8Unorm,
+                (state.sc_descriptor.width, state.sc_descriptor.height),
+            ),
+        };
+        // windowed states present the frame, so it can only be captured through `request_capture`.
+        let texture = texture.ok_or(FrameCaptureError::MissingTarget(target))?;
+        let readback = FrameReadback::new(&state.device, size.0, size.1, format);
+        let mut encoder = state
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("capture encoder"),
+            });
+        readback.copy_from_texture(&mut encoder, texture, layer);
+        state.queue.submit(std::iter::once(encoder.finish()));
+        let map_future = readback.buffer.slice(..).map_async(wgpu::MapMode::Read);
+        state.device.poll(wgpu::Maintain::Wait);
+        futures::executor::block_on(map_future).map_err(|_| FrameCaptureError::BufferMapFailed)?;
+        readback.to_image()
+    }
+    /// Copies the light id buffer back and turns every tile into a pixel.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn capture_light_culling_tiles(
+        state: &State,
+        resource_container: &BindingResourceContainer,
+    ) -> FrameCaptureResult {
+        let light_ids = resource_container.buffers[BufferTypes::LightId]
+            .as_ref()
+            .ok_or(FrameCaptureError::MissingTarget(
+                CaptureTarget::LightCullingTiles,
+            ))?;
+        let mut tile_info = TileInfo::default();
+        let size = tile_info.calculate_light_id_buffer_size(
+            state.sc_descriptor.width as f32,
+            state.sc_descriptor.height as f32,
+        );
+        let buffer = state.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("light_culling_readback_buffer"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = state
+            .device
+            .create_command_encoder(&wgpu::CommandEncoderDescriptor {
+                label: Some("capture encoder"),
+            });
+        encoder.copy_buffer_to_buffer(light_ids, 0, &buffer, 0, size);
+        state.queue.submit(std::iter::once(encoder.finish()));
+        let map_future = buffer.slice(..).map_async(wgpu::MapMode::Read);
+        state.device.poll(wgpu::Maintain::Wait);
+        futures::executor::block_on(map_future).map_err(|_| FrameCaptureError::BufferMapFailed)?;
+        let image = {
+            let data = buffer.slice(..).get_mapped_range();
+            let words: &[u32] = bytemuck::cast_slice(&data);
+            let tile_words = (tile_info.num_tile_light_slot + 1) as usize;
+            RgbaImage::from_fn(
+                tile_info.tile_count_x as u32,
+                tile_info.tile_count_y as u32,
+                |x, y| {
+                    let tile = &words
+                        [(y * tile_info.tile_count_x as u32 + x) as usize * tile_words..]
+                        [..tile_words];
+                    let light_count = (tile[0] as usize).min(tile_words - 1);
+                    // the ids are appended atomically so their order isn't stable, only the set of lights is compared.
+                    let mask = tile[1..=light_count]
+                        .iter()
+                        .filter(|id| **id < 16)
+                        .fold(0_u16, |mask, id| mask | 1 << *id);
+                    image::Rgba([
+                        light_count.min(255) as u8,
+                        (mask & 0xff) as u8,
+                        (mask >> 8) as u8,
+                        255,
+                    ])
+                },
+            )
+        };
+        buffer.unmap();
+        Ok(image)
+    }
+    pub fn encode_png(image: &RgbaImage) -> Result<Vec<u8>, FrameCaptureError> {
+        let mut bytes = Vec::new();
+        image::DynamicImage::ImageRgba8(image.clone())
+            .write_to(
+                &mut std::io::Cursor::new(&mut bytes),
+                image::ImageOutputFormat::Png,
+            )
+            .map_err(|e| FrameCaptureError::ImageEncodeFailed(e.to_string()))?;
+        Ok(bytes)
+    }
+}
+
+/// A frame copied into a mappable buffer, rows are padded to `wgpu::COPY_BYTES_PER_ROW_ALIGNMENT`.
+pub struct FrameReadback {
+    pub buffer: wgpu::Buffer,
+    pub width: u32,
+    pub height: u32,
+    pub padded_bytes_per_row: u32,
+    pub format: wgpu::TextureFormat,
+}
+
+impl FrameReadback {
+    pub fn new(
+        device: &wgpu::Device,
+        width: u32,
+        height: u32,
+        format: wgpu::TextureFormat,
+    ) -> Self {
+        let unpadded_bytes_per_row = width * Self::bytes_per_pixel(format);
+        let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+        let padded_bytes_per_row = (unpadded_bytes_per_row + align - 1) / align * align;
+        let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("frame_readback_buffer"),
+            size: (padded_bytes_per_row * height) as wgpu::BufferAddress,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false,
+        });
+        Self {
+            buffer,
+            width,
+            height,
+            padded_bytes_per_row,
+            format,
+        }
+    }
+    fn bytes_per_pixel(format: wgpu::TextureFormat) -> u32 {
+        match format {
+            wgpu::TextureFormat::Rgba32Float => 16,
+            wgpu::TextureFormat::Rgba16Float => 8,
+            _ => 4,
+        }
+    }
+    /// Records a copy of a single array layer of the texture into the readback buffer.
+    pub fn copy_from_texture(
+        &self,
+        encoder: &mut wgpu::CommandEncoder,
+        texture: &wgpu::Texture,
+        layer: u32,
+    ) {
+        encoder.copy_texture_to_buffer(
+            wgpu::ImageCopyTexture {
+                texture,
+                mip_level: 0,
+                origin: wgpu::Origin3d {
+                    x: 0,
+                    y: 0,
+                    z: layer,
+                },
+                aspect: if self.format == wgpu::TextureFormat::Depth32Float {
+                    wgpu::TextureAspect::DepthOnly
+                } else {
+                    wgpu::TextureAspect::All
+                },
+            },
+            wgpu::ImageCopyBuffer {
+                buffer: &self.buffer,
+                layout: wgpu::ImageDataLayout {
+                    offset: 0,
+                    bytes_per_row: std::num::NonZeroU32::new(self.padded_bytes_per_row),
+                    rows_per_image: std::num::NonZeroU32::new(self.height),
+                },
+            },
+            wgpu::Extent3d {
+                width: self.width,
+                height: self.height,
+                depth_or_array_layers: 1,
+            },
+        );
+    }
+    /// Converts the mapped buffer into an rgba image, the buffer has to be mapped beforehand.
+    /// Float targets are clamped to [0,1], depth is written as grayscale.
+    pub fn to_image(&self) -> FrameCaptureResult {
+        let unpadded_bytes_per_row = (self.width * Self::bytes_per_pixel(self.format)) as usize;
+        let mut pixels = Vec::with_capacity(unpadded_bytes_per_row * self.height as usize);
+        {
+            let data = self.buffer.slice(..).get_mapped_range();
+            for row in data
+                .chunks(self.padded_bytes_per_row as usize)
+                .take(self.height as usize)
+            {
+                pixels.extend_from_slice(&row[..unpadded_bytes_per_row]);
+            }
+        }
+        self.buffer.unmap();
+        match self.format {
+            wgpu::TextureFormat::Rgba8Unorm | wgpu::TextureFormat::Rgba8UnormSrgb => {}
+            wgpu::TextureFormat::Bgra8Unorm | wgpu::TextureFormat::Bgra8UnormSrgb => {
+                for pixel in pixels.chunks_exact_mut(4) {
+                    pixel.swap(0, 2);
+                }
+            }
+            wgpu::TextureFormat::Rgba32Float => {
+                pixels = pixels
+                    .chunks_exact(4)
+                    .map(|v| unorm_to_u8(f32::from_le_bytes([v[0], v[1], v[2], v[3]])))
+                    .collect();
+            }
+            wgpu::TextureFormat::Rgba16Float => {
+                pixels = pixels
+                    .chunks_exact(2)
+                    .map(|v| unorm_to_u8(f16_to_f32(u16::from_le_bytes([v[0], v[1]]))))
+                    .collect();
+            }
+            wgpu::TextureFormat::Depth32Float => {
+                pixels = pixels
+                    .chunks_exact(4)
+                    .flat_map(|d| {
+                        let value = unorm_to_u8(f32::from_le_bytes([d[0], d[1], d[2], d[3]]));
+                        [value, value, value, 255]
+                    })
+                    .collect();
+            }
+            format => return Err(FrameCaptureError::UnsupportedFormat(format)),
+        }
+        RgbaImage::from_raw(self.width, self.height, pixels).ok_or(
+            FrameCaptureError::ImageEncodeFailed("pixel data doesn't match frame size".to_string()),
+        )
+    }
+}
+
+fn unorm_to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 == 0 { 1.0 } else { -1.0 };
+    let exponent = ((bits >> 10) & 0x1f) as i32;
+    let mantissa = (bits & 0x3ff) as f32;
+    match exponent {
+        0 => sign * mantissa * 2.0_f32.powi(-24),
+        0x1f if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2.0_f32.powi(exponent - 15),
+    }
+}
+#[cfg(not(target_arch = "wasm32"))]
+fn srgb_to_linear(value: u8) -> f32 {
+    let value = value as f32 / 255.0;
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
@@ -0,0 +1,265 @@
+use rapier3d::na::{Matrix4, Vector3};
+use specs::Entity;
+use wgpu::BufferAddress;
+
+use crate::renderer::primitives::mesh::BoundingSphere;
+use crate::renderer::shaderconstants::frustum_view_count;
+
+/// The indirect draws of a model mesh's primitives, the same range is used in every view.
+/// Every level of detail has a draw for each primitive.
+/// The instances of a mesh are split into a draw for each custom material and material override they use.
+pub struct IndirectDraw {
+    pub model: Entity,
+    pub mesh_index: usize,
+    pub first_draw: u32,
+    /// The entity holding the `CustomMaterial` of the instances, `None` when they use their glTF materials.
+    pub material_entity: Option<Entity>,
+    /// The entity holding the `MaterialOverride` of the instances, `None` when they use the model's materials.
+    pub material_override: Option<Entity>,
+}
+
+/// An instance of a mesh with transparent primitives inside the camera's frustum,
+/// the transparent pass draws them one by one from back to front.
+pub struct TransparentInstance {
+    pub model: Entity,
+    pub mesh_index: usize,
+    /// Index of the instance's transform in the instance buffer.
+    pub instance: u32,
+    /// Level of detail of the instance, selected the way the culling shader selects it for the other instances.
+    pub lod: usize,
+    /// The entity holding the `MaterialOverride` of the instance.
+    pub material_override: Option<Entity>,
+    /// Squared distance from the camera to the instance's bounds.
+    pub distance: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct DrawIndexedIndirectRaw {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+}
+impl DrawIndexedIndirectRaw {
+    /// The instance count is filled in by the culling shader.
+    pub fn new(index_count: u32, first_index: u32, first_instance: u32) -> Self {
+        Self {
+            index_count,
+            instance_count: 0,
+            first_index,
+            base_vertex: 0,
+            first_instance,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CullingInstanceRaw {
+    bounds: [f32; 4],
+    group: u32,
+    _padding: [u32; 3],
+}
+impl CullingInstanceRaw {
+    /// Instances without bounds get a negative radius and are never culled.
+    pub fn new(bounds: Option<BoundingSphere>, group: u32) -> Self {
+        let bounds = bounds.map_or([0.0, 0.0, 0.0, -1.0], |bounds| {
+            [
+                bounds.center.x,
+                bounds.center.y,
+                bounds.center.z,
+                bounds.radius,
+            ]
+        });
+        Self {
+            bounds,
+            group,
+            _padding: [0; 3],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CullingGroupRaw {
+    lod_screen_sizes: [f32; 4],
+    first_draw: u32,
+    primitive_count: u32,
+    lod_count: u32,
+    first_slot: u32,
+    instance_count: u32,
+    _padding: [u32; 3],
+}
+impl CullingGroupRaw {
+    /// Every level of detail has `instance_count` slots for the visible instances, starting at `first_slot`.
+    pub fn new(
+        lod_screen_sizes: [f32; 4],
+        first_draw: u32,
+        primitive_count: u32,
+        lod_count: u32,
+        first_slot: u32,
+        instance_count: u32,
+    ) -> Self {
+        Self {
+            lod_screen_sizes,
+            first_draw,
+            primitive_count,
+            lod_count,
+            first_slot,
+            instance_count,
+            _padding: [0; 3],
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FrustumCullingUniforms {
+    /// Six planes per view, a view without planes culls nothing.
+    views: [[[f32; 4]; 6]; FrustumCulling::VIEW_COUNT],
+    occlusion_view: [[f32; 4]; 4],
+    occlusion_projection: [f32; 4],
+    /// Position of the camera selecting the levels of detail and its vertical projection scale.
+    lod_camera: [f32; 4],
+    instance_count: u32,
+    draw_count: u32,
+    /// Zero disables the occlusion culling.
+    pyramid_mip_count: u32,
+    slot_count: u32,
+    show_lod_levels: u32,
+    _padding: [u32; 3],
+}
+impl FrustumCullingUniforms {
+    pub fn new(
+        views: [[[f32; 4]; 6]; FrustumCulling::VIEW_COUNT],
+        occlusion: Option<&OcclusionView>,
+        lod_camera: [f32; 4],
+        show_lod_levels: bool,
+        instance_count: u32,
+        draw_count: u32,
+        slot_count: u32,
+    ) -> Self {
+        let (occlusion_view, occlusion_projection, pyramid_mip_count) =
+            occlusion.map_or(([[0.0; 4]; 4], [0.0; 4], 0), |occlusion| {
+                (
+                    occlusion.view.into(),
+                    occlusion.projection,
+                    occlusion.pyramid_mip_count,
+                )
+            });
+        Self {
+            views,
+            occlusion_view,
+            occlusion_projection,
+            lod_camera,
+            instance_count,
+            draw_count,
+            pyramid_mip_count,
+            slot_count,
+            show_lod_levels: show_lod_levels as u32,
+            _padding: [0; 3],
+        }
+    }
+}
+
+/// Instances of the camera's view counted by the culling shader.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct CullingStatsRaw {
+    pub frustum_culled: u32,
+    pub occlusion_culled: u32,
+    pub visible: u32,
+    _padding: u32,
+}
+
+/// The camera which rendered the depth pyramid, the instances of the next frame are tested against it.
+#[derive(Debug, Clone, Copy)]
+pub struct OcclusionView {
+    pub view: Matrix4<f32>,
+    /// Horizontal and vertical scale of the projection and the near plane.
+    pub projection: [f32; 4],
+    pub pyramid_size: (u32, u32),
+    pub pyramid_mip_count: u32,
+}
+
+/// The indirect draws written by the frustum culling pass of the current frame.
+#[derive(Default)]
+pub struct FrustumCulling {
+    pub draws: Vec<IndirectDraw>,
+    /// Sorted from the furthest to the closest instance.
+    pub transparent_instances: Vec<TransparentInstance>,
+    pub draw_count: u32,
+    pub instance_count: u32,
+    /// Size of every view's range of the visible instances,
+    /// each level of detail of a mesh has a slot for every instance of the mesh.
+    pub slot_count: u32,
+    /// `None` until a depth pyramid has been built with the current size.
+    pub occlusion: Option<OcclusionView>,
+    /// The culling stats of the last frame were copied into the readback buffer.
+    pub stats_pending: bool,
+}
+
+impl FrustumCulling {
+    pub const CAMERA_VIEW: usize = 0;
+    /// The camera, every shadow cascade and an unculled view for the shadow atlas.
+    pub const VIEW_COUNT: usize = frustum_view_count(cfg!(target_arch = "wasm32"));
+    pub const UNCULLED_VIEW: usize = Self::VIEW_COUNT - 1;
+
+    pub fn cascade_view(cascade: usize) -> usize {
+        1 + cascade
+    }
+    /// Byte offset of the draw's first primitive in the indirect buffer.
+    pub fn indirect_offset(&self, view: usize, draw: &IndirectDraw) -> BufferAddress {
+        ((view as u32 * self.draw_count + draw.first_draw) as usize
+            * std::mem::size_of::<DrawIndexedIndirectRaw>()) as BufferAddress
+    }
+    /// Whether the sphere is at least partially on the inner side of every plane.
+    pub fn sphere_visible(planes: &[[f32; 4]; 6], bounds: &BoundingSphere) -> bool {
+        planes.iter().all(|plane| {
+            plane[0] * bounds.center.x
+                + plane[1] * bounds.center.y
+                + plane[2] * bounds.center.z
+                + plane[3]
+                >= -bounds.radius
+        })
+    }
+    /// The level of detail of a mesh for its bounds in world space, the CPU side of the culling shader's `select_lod`.
+    pub fn select_lod(
+        lod_screen_sizes: &[f32; 4],
+        lod_count: usize,
+        bounds: &BoundingSphere,
+        lod_camera: &[f32; 4],
+    ) -> usize {
+        let camera_position = Vector3::new(lod_camera[0], lod_camera[1], lod_camera[2]);
+        let distance = (bounds.center - camera_position).norm().max(0.0001);
+        let screen_size = bounds.radius * lod_camera[3] / distance;
+        (1..lod_count)
+            .filter(|lod| screen_size < lod_screen_sizes[lod - 1])
+            .last()
+            .unwrap_or(0)
+    }
+    /// Extracts the normalized planes of a zero to one depth view projection matrix, pointing inwards.
+    pub fn frustum_planes(view_proj: &Matrix4<f32>) -> [[f32; 4]; 6] {
+        let rows = [
+            view_proj.row(0).transpose(),
+            view_proj.row(1).transpose(),
+            view_proj.row(2).transpose(),
+            view_proj.row(3).transpose(),
+        ];
+        let planes = [
+            rows[3] + rows[0],
+            rows[3] - rows[0],
+            rows[3] + rows[1],
+            rows[3] - rows[1],
+            rows[2],
+            rows[3] - rows[2],
+        ];
+        planes.map(|plane| {
+            let length = plane.xyz().norm();
+            let plane = if length > 0.0 { plane / length } else { plane };
+            [plane.x, plane.y, plane.z, plane.w]
+        })
+    }
+}
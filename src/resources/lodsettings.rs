@@ -0,0 +1,15 @@
+use crate::resources::projection::Projection;
+
+/// Settings of the level of detail selection, read every frame by the frustum culling.
+#[derive(Default)]
+pub struct LodSettings {
+    /// Positive values switch to lower levels of detail sooner, every step halves the instances' screen size.
+    pub bias: f32,
+}
+
+impl LodSettings {
+    /// Scales a bounding sphere's radius over its distance to its height relative to the screen's, including the bias.
+    pub fn projection_scale(&self, projection: &Projection) -> f32 {
+        (0.5 * projection.fov_y).tan().recip() * (-self.bias).exp2()
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashMap;
+
+use crate::renderer::pipelines::materialpipeline::MaterialPipeline;
+use crate::renderer::primitives::materialdefinition::{MaterialDefinition, MaterialError};
+
+/// The registered custom materials and their pipelines, which are built by `UpdateMaterials`.
+#[derive(Default)]
+pub struct MaterialLibrary {
+    definitions: HashMap<String, MaterialDefinition>,
+    pipelines: HashMap<String, MaterialPipeline>,
+    /// Materials to build before the next frame.
+    pending: Vec<String>,
+    revision: u32,
+}
+
+impl MaterialLibrary {
+    /// Adds the material or replaces the one with the same name, the entities using it switch over once it's built.
+    pub fn register(&mut self, definition: MaterialDefinition) -> Result<(), MaterialError> {
+        definition.validate()?;
+        if !self.pending.contains(&definition.name) {
+            self.pending.push(definition.name.clone());
+        }
+        self.definitions.insert(definition.name.clone(), definition);
+        Ok(())
+    }
+    pub fn definition(&self, name: &str) -> Option<&MaterialDefinition> {
+        self.definitions.get(name)
+    }
+    /// `None` until the material is built or when its shader failed to build.
+    pub fn pipeline(&self, name: &str) -> Option<&MaterialPipeline> {
+        self.pipelines.get(name)
+    }
+    /// Builds every material again, used when the `material.wgsl` template changes.
+    pub fn rebuild_all(&mut self) {
+        self.pending = self.definitions.keys().cloned().collect();
+    }
+    pub fn take_pending(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.pending)
+    }
+    pub fn next_revision(&mut self) -> u32 {
+        self.revision += 1;
+        self.revision
+    }
+    /// A failed build removes the previous pipeline, its bind group layout may not match the new definition.
+    pub fn set_pipeline(&mut self, name: &str, pipeline: Option<MaterialPipeline>) {
+        match pipeline {
+            Some(pipeline) => self.pipelines.insert(name.to_string(), pipeline),
+            None => self.pipelines.remove(name),
+        };
+    }
+}
@@ -0,0 +1,21 @@
+use crate::renderer::state::State;
+
+/// Tracks the used part of the morph target buffer, the deltas of loaded models are appended to it.
+#[derive(Default)]
+pub struct MorphTargetStorage {
+    /// Number of deltas written so far.
+    pub used: usize,
+}
+
+impl MorphTargetStorage {
+    /// Reserves room for the given number of deltas and returns the index of the first one,
+    /// `None` if they don't fit into the buffer.
+    pub fn allocate(&mut self, count: usize) -> Option<u32> {
+        if self.used + count > State::MAX_MORPH_TARGET_DELTAS {
+            return None;
+        }
+        let index = self.used as u32;
+        self.used += count;
+        Some(index)
+    }
+}
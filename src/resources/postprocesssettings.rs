@@ -0,0 +1,241 @@
+use crate::renderer::primitives::uniforms::PostProcessUniforms;
+
+/// Settings of the bloom, the bright parts of the HDR scene are blurred and added back onto it.
+pub struct BloomSettings {
+    pub enabled: bool,
+    /// Luminance above which pixels start to bloom.
+    pub threshold: f32,
+    /// Width of the soft transition around the threshold.
+    pub knee: f32,
+    /// Scale of the blurred light added to the scene.
+    pub intensity: f32,
+}
+
+impl BloomSettings {
+    pub fn to_raw(&self) -> PostProcessUniforms {
+        PostProcessUniforms {
+            parameters: [self.threshold, self.knee.max(0.0001), self.intensity, 0.0],
+        }
+    }
+}
+
+impl Default for BloomSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            threshold: 1.0,
+            knee: 0.5,
+            intensity: 0.1,
+        }
+    }
+}
+
+/// Settings of the fast approximate anti-aliasing, which smooths the edges of the tone mapped frame.
+pub struct FxaaSettings {
+    pub enabled: bool,
+    /// Longest distance in pixels searched along an edge.
+    pub span_max: f32,
+    /// Lower values blur edges of dark areas more.
+    pub reduce_min: f32,
+    /// Lower values blur more along the edges.
+    pub reduce_mul: f32,
+}
+
+impl FxaaSettings {
+    pub fn to_raw(&self) -> PostProcessUniforms {
+        PostProcessUniforms {
+            parameters: [self.span_max, self.reduce_min, self.reduce_mul, 0.0],
+        }
+    }
+}
+
+impl Default for FxaaSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            span_max: 8.0,
+            reduce_min: 1.0 / 128.0,
+            reduce_mul: 1.0 / 8.0,
+        }
+    }
+}
+
+/// Settings of the vignette darkening the corners of the frame.
+pub struct VignetteSettings {
+    pub enabled: bool,
+    /// How dark the corners get, 1.0 is black.
+    pub intensity: f32,
+    /// Distance from the center, relative to the corners, where the darkening ends.
+    pub radius: f32,
+    /// Width of the transition towards the center.
+    pub smoothness: f32,
+}
+
+impl VignetteSettings {
+    pub fn to_raw(&self) -> PostProcessUniforms {
+        PostProcessUniforms {
+            parameters: [
+                self.intensity,
+                self.radius,
+                self.smoothness.max(0.0001),
+                0.0,
+            ],
+        }
+    }
+}
+
+impl Default for VignetteSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.5,
+            radius: 0.9,
+            smoothness: 0.5,
+        }
+    }
+}
+
+/// Settings of the chromatic aberration which splits the color channels towards the edges of the frame.
+pub struct ChromaticAberrationSettings {
+    pub enabled: bool,
+    /// Offset of the red and blue channels at the corners, relative to the frame's size.
+    pub intensity: f32,
+}
+
+impl ChromaticAberrationSettings {
+    pub fn to_raw(&self) -> PostProcessUniforms {
+        PostProcessUniforms {
+            parameters: [self.intensity, 0.0, 0.0, 0.0],
+        }
+    }
+}
+
+impl Default for ChromaticAberrationSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            intensity: 0.005,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LutParseError {
+    MissingSize,
+    InvalidSize(String),
+    /// Line number of the entry which couldn't be parsed.
+    InvalidEntry(usize),
+    EntryCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// A 3D color lookup table, red changes the fastest then green and blue.
+#[derive(Clone)]
+pub struct ColorGradingLut {
+    pub size: u32,
+    pub entries: Vec<[f32; 3]>,
+}
+
+impl ColorGradingLut {
+    pub const IDENTITY_SIZE: u32 = 16;
+
+    /// Maps every color to itself.
+    pub fn identity(size: u32) -> Self {
+        let max = (size - 1) as f32;
+        let entries = (0..size * size * size)
+            .map(|index| {
+                [
+                    (index % size) as f32 / max,
+                    (index / size % size) as f32 / max,
+                    (index / (size * size)) as f32 / max,
+                ]
+            })
+            .collect();
+        Self { size, entries }
+    }
+    /// Parses the contents of a `.cube` file, the domain is assumed to be 0 to 1.
+    pub fn from_cube(contents: &str) -> Result<Self, LutParseError> {
+        let mut size = None;
+        let mut entries = Vec::new();
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if let Some(value) = line.strip_prefix("LUT_3D_SIZE") {
+                let value = value.trim();
+                size = Some(
+                    value
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|size| *size >= 2)
+                        .ok_or_else(|| LutParseError::InvalidSize(value.to_string()))?,
+                );
+                continue;
+            }
+            if !line.starts_with(|c: char| c.is_ascii_digit() || c == '-' || c == '.') {
+                // TITLE, DOMAIN_MIN and DOMAIN_MAX.
+                continue;
+            }
+            let values = line
+                .split_whitespace()
+                .map(|value| value.parse::<f32>())
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| LutParseError::InvalidEntry(line_number + 1))?;
+            if values.len() != 3 {
+                return Err(LutParseError::InvalidEntry(line_number + 1));
+            }
+            entries.push([values[0], values[1], values[2]]);
+        }
+        let size = size.ok_or(LutParseError::MissingSize)?;
+        let expected = (size * size * size) as usize;
+        if entries.len() != expected {
+            return Err(LutParseError::EntryCount {
+                expected,
+                found: entries.len(),
+            });
+        }
+        Ok(Self { size, entries })
+    }
+}
+
+/// Settings of the color grading which remaps the tone mapped colors with a 3D lookup table.
+pub struct ColorGradingSettings {
+    pub enabled: bool,
+    /// Blends between the original (0.0) and the graded colors (1.0).
+    pub strength: f32,
+    lut: ColorGradingLut,
+    /// Incremented whenever the lookup table is replaced so the effect uploads it again.
+    lut_revision: u32,
+}
+
+impl ColorGradingSettings {
+    pub fn lut(&self) -> &ColorGradingLut {
+        &self.lut
+    }
+    pub fn lut_revision(&self) -> u32 {
+        self.lut_revision
+    }
+    pub fn set_lut(&mut self, lut: ColorGradingLut) {
+        self.lut = lut;
+        self.lut_revision += 1;
+    }
+    pub fn to_raw(&self) -> PostProcessUniforms {
+        PostProcessUniforms {
+            parameters: [self.strength, self.lut.size as f32, 0.0, 0.0],
+        }
+    }
+}
+
+impl Default for ColorGradingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            strength: 1.0,
+            lut: ColorGradingLut::identity(ColorGradingLut::IDENTITY_SIZE),
+            lut_revision: 0,
+        }
+    }
+}
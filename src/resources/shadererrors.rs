@@ -0,0 +1,26 @@
+/// An error of a reloaded shader.
+#[derive(Debug, Clone)]
+pub struct ShaderError {
+    pub file_name: String,
+    /// File, line and column starting at 1, the file is an included one when the error is in there.
+    /// Errors reported by the device when creating the pipelines have none.
+    pub location: Option<(String, usize, usize)>,
+    pub message: String,
+}
+
+/// Errors of the last reload of every shader, shown in the shader errors window.
+#[derive(Default)]
+pub struct ShaderErrors {
+    pub errors: Vec<ShaderError>,
+}
+
+impl ShaderErrors {
+    /// Replaces the errors of the file with the ones of its last reload.
+    pub fn set_errors(&mut self, file_name: &str, errors: Vec<ShaderError>) {
+        self.errors.retain(|error| error.file_name != file_name);
+        self.errors.extend(errors);
+    }
+    pub fn has_errors(&self, file_name: &str) -> bool {
+        self.errors.iter().any(|error| error.file_name == file_name)
+    }
+}
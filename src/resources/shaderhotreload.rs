@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use specs::World;
+
+use crate::renderer::shaderconstants::shader_constants;
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::renderer::shaderpreprocessor::{PreprocessedShader, ShaderPreprocessor, ShaderTarget};
+use crate::resources::shadererrors::{ShaderError, ShaderErrors};
+
+/// Replaces the current pipelines with the ones a reloader built.
+pub type PipelineSwap = Box<dyn FnOnce(&World)>;
+/// Builds the pipelines using a shader again without touching the current ones, returns the error when that fails.
+pub type ShaderReloader = Box<dyn Fn(&World) -> Result<PipelineSwap, String> + Send + Sync>;
+
+/// Watches the shader sources and knows which pipelines to rebuild when the native variant of a shader changes.
+pub struct ShaderHotReload {
+    pub directory: PathBuf,
+    modified: HashMap<PathBuf, SystemTime>,
+    last_poll: Option<Instant>,
+    reloaders: Vec<(&'static str, ShaderReloader)>,
+}
+
+impl Default for ShaderHotReload {
+    fn default() -> Self {
+        Self {
+            directory: PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src/shaders")),
+            modified: HashMap::new(),
+            last_poll: None,
+            reloaders: Vec::new(),
+        }
+    }
+}
+
+impl ShaderHotReload {
+    const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+    pub fn register(
+        &mut self,
+        file_name: &'static str,
+        reloader: impl Fn(&World) -> Result<PipelineSwap, String> + Send + Sync + 'static,
+    ) {
+        self.reloaders.push((file_name, Box::new(reloader)));
+    }
+    /// Installs the rebuilt pipelines later, once every reloader of the shader succeeded.
+    pub fn swap(install: impl FnOnce(&World) + 'static) -> PipelineSwap {
+        Box::new(install)
+    }
+    pub fn reloaders<'a>(
+        &'a self,
+        file_name: &'a str,
+    ) -> impl Iterator<Item = &'a ShaderReloader> + 'a {
+        self.reloaders
+            .iter()
+            .filter(move |(name, _)| *name == file_name)
+            .map(|(_, reloader)| reloader)
+    }
+    /// Forgets the modification times, the next poll checks every shader again.
+    pub fn stop(&mut self) {
+        self.modified.clear();
+        self.last_poll = None;
+    }
+    /// Generates the native variant of every shader again when a source or an include was modified since the last poll.
+    /// Returns the file names and the results of the shaders whose variant differs from the one their pipelines use,
+    /// so the first poll also picks up the files edited while the hot reload was off.
+    /// Shaders with errors are returned too, reverting a broken edit clears them.
+    pub fn poll(
+        &mut self,
+        shader_errors: &ShaderErrors,
+    ) -> Vec<(String, Result<PreprocessedShader, ShaderError>)> {
+        if matches!(self.last_poll, Some(last_poll) if last_poll.elapsed() < Self::POLL_INTERVAL) {
+            return Vec::new();
+        }
+        self.last_poll = Some(Instant::now());
+        let mut files = Vec::new();
+        Self::collect_files(self.directory.as_path(), &mut files);
+        let mut modified = false;
+        for (path, time) in files {
+            modified |= self.modified.insert(path, time) != Some(time);
+        }
+        if !modified {
+            return Vec::new();
+        }
+        let mut preprocessor = ShaderPreprocessor::new(ShaderTarget::Native);
+        for (name, value) in shader_constants(false) {
+            preprocessor.define(name, value.as_str());
+        }
+        let directory = self.directory.clone();
+        let load = |file_name: &str| std::fs::read_to_string(directory.join(file_name)).ok();
+        let mut changed = Vec::new();
+        for file_name in Self::shader_names(directory.as_path()) {
+            let result = preprocessor
+                .process(file_name.as_str(), &load)
+                .map_err(|e| ShaderError {
+                    file_name: file_name.clone(),
+                    location: None,
+                    message: e.to_string(),
+                });
+            let unchanged = match &result {
+                Ok(shader) => ShaderLibrary::is_current(file_name.as_str(), shader.source.as_str()),
+                Err(_) => false,
+            };
+            if !unchanged || shader_errors.has_errors(file_name.as_str()) {
+                changed.push((file_name, result));
+            }
+        }
+        changed
+    }
+    /// The shaders of the directory, the files of its subdirectories are only included by them.
+    fn shader_names(directory: &Path) -> Vec<String> {
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!(target: "shader_hot_reload", "can't read {:?}: {}", directory, e);
+                return Vec::new();
+            }
+        };
+        entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().and_then(|extension| extension.to_str()) == Some("wgsl")
+            })
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect()
+    }
+    /// Modification times of every shader source, including the subdirectories.
+    fn collect_files(directory: &Path, files: &mut Vec<(PathBuf, SystemTime)>) {
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::collect_files(path.as_path(), files);
+                continue;
+            }
+            if path.extension().and_then(|extension| extension.to_str()) != Some("wgsl") {
+                continue;
+            }
+            if let Ok(modified) = entry.metadata().and_then(|metadata| metadata.modified()) {
+                files.push((path, modified));
+            }
+        }
+    }
+    /// Parses and validates the generated source with naga, the pipelines aren't touched when this fails.
+    /// The locations point into the file the failing line comes from.
+    pub fn validate(file_name: &str, shader: &PreprocessedShader) -> Vec<ShaderError> {
+        let source = shader.source.as_str();
+        let module = match naga::front::wgsl::parse_str(source) {
+            Ok(module) => module,
+            Err(e) => {
+                return vec![ShaderError {
+                    file_name: file_name.to_string(),
+                    location: Self::source_location(shader, e.location(source)),
+                    message: e.emit_to_string(source),
+                }];
+            }
+        };
+        let mut validator = naga::valid::Validator::new(
+            naga::valid::ValidationFlags::all(),
+            naga::valid::Capabilities::all(),
+        );
+        match validator.validate(&module) {
+            Ok(_) => Vec::new(),
+            Err(e) => {
+                // the last span is the innermost one, usually the expression
+                let offset = e
+                    .spans()
+                    .filter_map(|(span, _)| span.to_range())
+                    .last()
+                    .map(|range| range.start)
+                    .unwrap_or(0);
+                let mut message = e.to_string();
+                let mut cause = std::error::Error::source(&e);
+                while let Some(error) = cause {
+                    message.push_str(": ");
+                    message.push_str(error.to_string().as_str());
+                    cause = error.source();
+                }
+                vec![ShaderError {
+                    file_name: file_name.to_string(),
+                    location: Self::source_location(shader, Self::location(source, offset)),
+                    message,
+                }]
+            }
+        }
+    }
+    fn location(source: &str, offset: usize) -> (usize, usize) {
+        let before = &source[..offset.min(source.len())];
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+    fn source_location(
+        shader: &PreprocessedShader,
+        (line, column): (usize, usize),
+    ) -> Option<(String, usize, usize)> {
+        shader
+            .source_location(line)
+            .map(|(source_file, line)| (source_file.to_string(), line, column))
+    }
+    /// Creates a pipeline in an error scope so an invalid shader doesn't replace the last good pipeline.
+    pub fn try_create<T>(device: &wgpu::Device, create: impl FnOnce() -> T) -> Result<T, String> {
+        device.push_error_scope(wgpu::ErrorFilter::Validation);
+        let created = create();
+        match futures::executor::block_on(device.pop_error_scope()) {
+            Some(error) => Err(error.to_string()),
+            None => Ok(created),
+        }
+    }
+}
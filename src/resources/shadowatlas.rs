@@ -0,0 +1,83 @@
+use rapier3d::na::{Matrix4, Vector3};
+
+use crate::renderer::state::State;
+
+/// A tile of the shadow atlas, in texels.
+pub struct ShadowAtlasTile {
+    pub x: u32,
+    pub y: u32,
+    pub size: u32,
+    pub view_proj: Matrix4<f32>,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ShadowAtlasTileRaw {
+    view_proj: [[f32; 4]; 4],
+    /// Offset and size of the tile in texture coordinates.
+    atlas_rect: [f32; 4],
+}
+
+/// Tiles of the point and spot light shadow atlas, they are handed out again every frame.
+#[derive(Default)]
+pub struct ShadowAtlas {
+    pub tiles: Vec<ShadowAtlasTile>,
+}
+
+impl ShadowAtlas {
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+    }
+    /// Reserves a tile for every matrix and returns the index of the first one,
+    /// `None` if they don't fit into the atlas anymore.
+    pub fn allocate(&mut self, tile_size: u32, view_projs: &[Matrix4<f32>]) -> Option<i32> {
+        let tiles_per_row = State::SHADOW_ATLAS_SIZE / tile_size;
+        let capacity =
+            ((tiles_per_row * tiles_per_row) as usize).min(State::MAX_SHADOW_ATLAS_TILES);
+        if self.tiles.len() + view_projs.len() > capacity {
+            return None;
+        }
+        let first_index = self.tiles.len() as i32;
+        for view_proj in view_projs {
+            let index = self.tiles.len() as u32;
+            self.tiles.push(ShadowAtlasTile {
+                x: index % tiles_per_row * tile_size,
+                y: index / tiles_per_row * tile_size,
+                size: tile_size,
+                view_proj: *view_proj,
+            });
+        }
+        Some(first_index)
+    }
+    /// Indices of the shadow casters closest to the camera, at most `budget` of them.
+    pub fn closest_casters(
+        camera_position: &Vector3<f32>,
+        casters: impl Iterator<Item = (usize, Vector3<f32>)>,
+        budget: u32,
+    ) -> Vec<usize> {
+        let mut casters = casters
+            .map(|(index, position)| (index, (position - camera_position).norm_squared()))
+            .collect::<Vec<_>>();
+        casters.sort_by(|a, b| a.1.total_cmp(&b.1));
+        casters
+            .into_iter()
+            .take(budget as usize)
+            .map(|(index, _)| index)
+            .collect()
+    }
+    pub fn to_raw(&self) -> Vec<ShadowAtlasTileRaw> {
+        let atlas_size = State::SHADOW_ATLAS_SIZE as f32;
+        self.tiles
+            .iter()
+            .map(|tile| ShadowAtlasTileRaw {
+                view_proj: tile.view_proj.into(),
+                atlas_rect: [
+                    tile.x as f32 / atlas_size,
+                    tile.y as f32 / atlas_size,
+                    tile.size as f32 / atlas_size,
+                    tile.size as f32 / atlas_size,
+                ],
+            })
+            .collect()
+    }
+}
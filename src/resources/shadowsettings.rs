@@ -0,0 +1,68 @@
+use crate::renderer::primitives::uniforms::ShadowSettingsUniforms;
+use crate::renderer::state::State;
+
+/// Settings of the directional light's cascaded shadow maps, read every frame by the shadow pass.
+pub struct ShadowSettings {
+    /// Number of cascades rendered, at most the layer count of `State::SHADOW_SIZE`.
+    pub cascade_count: u32,
+    /// Blends between uniform (0.0) and logarithmic (1.0) cascade splits.
+    pub split_lambda: f32,
+    /// Distance from the camera up to which shadows are rendered.
+    pub shadow_distance: f32,
+    /// Size of the rendered area of every cascade, at most the width of `State::SHADOW_SIZE`.
+    pub resolution: u32,
+    /// Depth bias applied when comparing against the shadow map, scaled by the surface's slope.
+    pub bias: f32,
+    /// Radius of the PCF kernel in texels, 0 takes a single sample.
+    pub pcf_kernel: u32,
+    /// Number of shadow casting point lights closest to the camera which get shadows,
+    /// every one of them takes six tiles of the shadow atlas.
+    pub max_point_light_shadows: u32,
+    /// Number of shadow casting spot lights closest to the camera which get shadows.
+    pub max_spot_light_shadows: u32,
+    /// Size of a shadow atlas tile, at most `State::SHADOW_ATLAS_SIZE`.
+    pub atlas_tile_size: u32,
+}
+
+impl ShadowSettings {
+    pub fn cascade_count(&self) -> u32 {
+        self.cascade_count
+            .clamp(1, State::SHADOW_SIZE.depth_or_array_layers)
+    }
+    pub fn resolution(&self) -> u32 {
+        self.resolution.clamp(1, State::SHADOW_SIZE.width)
+    }
+    pub fn atlas_tile_size(&self) -> u32 {
+        self.atlas_tile_size.clamp(1, State::SHADOW_ATLAS_SIZE)
+    }
+    pub fn to_raw(&self, show_cascades: bool) -> ShadowSettingsUniforms {
+        ShadowSettingsUniforms::new(
+            self.cascade_count(),
+            self.pcf_kernel,
+            self.bias,
+            self.resolution() as f32 / State::SHADOW_SIZE.width as f32,
+            show_cascades,
+        )
+    }
+}
+
+impl Default for ShadowSettings {
+    fn default() -> Self {
+        Self {
+            cascade_count: State::SHADOW_SIZE.depth_or_array_layers,
+            split_lambda: 0.95,
+            // if on web reduce shadow map gen range in order to keep detail at close range.
+            shadow_distance: if cfg!(target_arch = "wasm32") {
+                10.0
+            } else {
+                50.0
+            },
+            resolution: State::SHADOW_SIZE.width,
+            bias: 0.002,
+            pcf_kernel: 1,
+            max_point_light_shadows: 4,
+            max_spot_light_shadows: 8,
+            atlas_tile_size: 512,
+        }
+    }
+}
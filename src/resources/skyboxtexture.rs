@@ -0,0 +1,5 @@
+/// The path of the texture currently used by the skybox, `None` while the default one is shown.
+#[derive(Default)]
+pub struct SkyboxTexture {
+    pub path: Option<String>,
+}
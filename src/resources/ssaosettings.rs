@@ -0,0 +1,39 @@
+use crate::renderer::primitives::uniforms::{SsaoUniforms, SSAO_KERNEL_SIZE};
+
+/// Settings of the screen-space ambient occlusion, editable from the debug window.
+#[derive(Debug, Clone, Copy)]
+pub struct SsaoSettings {
+    /// The lighting only uses the materials' occlusion textures when disabled.
+    pub enabled: bool,
+    /// Radius of the sampled hemisphere in world units.
+    pub radius: f32,
+    /// Distance a sample has to be behind the scene to count as occluded, hides self-occlusion.
+    pub bias: f32,
+    /// Exponent applied to the occlusion, higher darkens it.
+    pub intensity: f32,
+    /// Samples taken per pixel, at most `SSAO_KERNEL_SIZE`.
+    pub sample_count: u32,
+}
+
+impl SsaoSettings {
+    pub fn to_raw(&self) -> SsaoUniforms {
+        SsaoUniforms::new(
+            self.radius,
+            self.bias,
+            self.intensity,
+            self.sample_count.clamp(1, SSAO_KERNEL_SIZE as u32),
+        )
+    }
+}
+
+impl Default for SsaoSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            radius: 0.5,
+            bias: 0.025,
+            intensity: 1.5,
+            sample_count: 16,
+        }
+    }
+}
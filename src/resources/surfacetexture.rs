@@ -1,4 +1,7 @@
 #[derive(Default)]
 pub struct SurfaceTexture {
+    /// The acquired swapchain texture, always `None` when running headless.
     pub texture: Option<wgpu::SurfaceTexture>,
-}
\ No newline at end of file
+    /// View of the texture the current frame is rendered into.
+    pub view: Option<wgpu::TextureView>,
+}
@@ -0,0 +1,71 @@
+use crate::renderer::primitives::uniforms::ToneMappingUniforms;
+
+/// Curve mapping the HDR scene's colors to the displayable range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToneMappingOperator {
+    Aces,
+    Reinhard,
+    AgX,
+}
+
+impl ToneMappingOperator {
+    pub const ALL: [ToneMappingOperator; 3] = [Self::Aces, Self::Reinhard, Self::AgX];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Aces => "aces",
+            Self::Reinhard => "reinhard",
+            Self::AgX => "agx",
+        }
+    }
+    /// Case insensitive inverse of `name`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|operator| operator.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Settings of the tone mapping pass, editable from the debug window and scripts.
+#[derive(Debug, Clone, Copy)]
+pub struct ToneMappingSettings {
+    pub operator: ToneMappingOperator,
+    /// Adapts the exposure to the average luminance of the frame, `exposure` compensates on top of it.
+    pub auto_exposure: bool,
+    /// Exposure in stops, the manual exposure or the compensation of the automatic one.
+    pub exposure: f32,
+    /// Luminances of the histogram's range in stops, darker and brighter pixels end up in the first and last bins.
+    pub min_log_luminance: f32,
+    pub max_log_luminance: f32,
+    /// How fast the automatic exposure follows the frame's luminance, higher is faster.
+    pub adaptation_speed: f32,
+}
+
+impl ToneMappingSettings {
+    /// The adaptation is frame rate independent, `delta` is the frame time in seconds.
+    pub fn to_raw(&self, pixel_count: u32, delta: f32) -> ToneMappingUniforms {
+        let log_luminance_range = (self.max_log_luminance - self.min_log_luminance).max(0.001);
+        ToneMappingUniforms::new(
+            self.min_log_luminance,
+            log_luminance_range,
+            (1.0 - (-delta * self.adaptation_speed).exp()).clamp(0.0, 1.0),
+            self.exposure,
+            pixel_count,
+            self.operator as u32,
+            self.auto_exposure,
+        )
+    }
+}
+
+impl Default for ToneMappingSettings {
+    fn default() -> Self {
+        Self {
+            operator: ToneMappingOperator::Aces,
+            auto_exposure: true,
+            exposure: 0.0,
+            min_log_luminance: -8.0,
+            max_log_luminance: 4.0,
+            adaptation_speed: 1.5,
+        }
+    }
+}
@@ -10,6 +10,13 @@ pub enum ScriptingError {
     MissingComponent(&'static str),
     ModelLoadFailed(String),
     TextureOverrideFailed(String),
+    FrameCaptureFailed(String),
+    SceneLoadFailed(String),
+    SceneSaveFailed(String),
+    AnimationNotFound(String),
+    LutLoadFailed(String),
+    MaterialLoadFailed(String),
+    MaterialNotFound(String),
 }
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
@@ -18,6 +18,7 @@ pub enum ScriptEvent {
     OnMouseMove = 8,
     OnKeyDown = 9,
     OnMouseClick = 10,
+    AnimationFinished = 11,
 }
 impl ScriptEvent {
     pub fn from_number(val: i32) -> Self {
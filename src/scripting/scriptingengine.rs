@@ -112,6 +112,13 @@ impl HorizonScriptingEngine {
             op_dir_light_get_color::decl(),
             op_dir_light_set_dir::decl(),
             op_dir_light_set_color::decl(),
+            op_tone_mapping_get_operator::decl(),
+            op_tone_mapping_set_operator::decl(),
+            op_tone_mapping_set_exposure::decl(),
+            op_tone_mapping_set_auto_exposure::decl(),
+            op_post_processing_set_enabled::decl(),
+            op_post_processing_is_enabled::decl(),
+            op_set_color_grading_lut::decl(),
             op_create_entity::decl(),
             op_get_component::decl(),
             op_set_component::decl(),
@@ -125,19 +132,35 @@ impl HorizonScriptingEngine {
             op_set_entity_rotation::decl(),
             op_set_entity_world_pos::decl(),
             op_get_forward_vector::decl(),
+            op_capture_frame::decl(),
+            op_save_scene::decl(),
+            op_load_scene::decl(),
+            op_play_animation::decl(),
+            op_set_morph_weights::decl(),
+            op_register_material::decl(),
+            op_set_material::decl(),
+            op_clear_material::decl(),
+            op_set_material_parameter::decl(),
+            op_set_material_texture::decl(),
         ])
     }
 }
 use crate::components::assetidentifier::AssetIdentifier;
 use crate::components::componenttypes::{ComponentData, ComponentTypes};
+use crate::renderer::primitives::material::MaterialTextureSlot;
 use crate::scripting::scriptingfunctions::ScriptingFunctions;
+use crate::scripting::util::animationoptions::AnimationOptions;
 use crate::scripting::util::entityinfo::{Component, EntityInfo};
 use crate::scripting::util::glmconversion::Vec3;
-use crate::scripting::util::horizonresource::{ScriptingCamera, ScriptingDirLight};
+use crate::scripting::util::horizonresource::{
+    ScriptingCamera, ScriptingDirLight, ScriptingPostProcessing, ScriptingToneMapping,
+};
+use crate::scripting::util::materialdescriptor::MaterialDescriptor;
 #[cfg(not(target_arch = "wasm32"))]
 use deno_core::op;
 #[cfg(not(target_arch = "wasm32"))]
 use deno_web::BlobStore;
+use std::collections::HashMap;
 
 #[cfg_attr(not(target_arch = "wasm32"), op)]
 #[cfg(not(target_arch = "wasm32"))]
@@ -230,6 +253,48 @@ fn op_dir_light_set_color(color: Vec3) -> Result<(), deno_core::anyhow::Error> {
 }
 #[cfg_attr(not(target_arch = "wasm32"), op)]
 #[cfg(not(target_arch = "wasm32"))]
+fn op_tone_mapping_get_operator() -> Result<String, deno_core::anyhow::Error> {
+    Ok(ScriptingToneMapping::get_operator())
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_tone_mapping_set_operator(name: String) -> Result<bool, deno_core::anyhow::Error> {
+    Ok(ScriptingToneMapping::set_operator(name))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_tone_mapping_set_exposure(exposure: f32) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingToneMapping::set_exposure(exposure);
+    Ok(())
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_tone_mapping_set_auto_exposure(enabled: bool) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingToneMapping::set_auto_exposure(enabled);
+    Ok(())
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_post_processing_set_enabled(
+    name: String,
+    enabled: bool,
+) -> Result<bool, deno_core::anyhow::Error> {
+    Ok(ScriptingPostProcessing::set_enabled(name, enabled))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_post_processing_is_enabled(name: String) -> Result<bool, deno_core::anyhow::Error> {
+    Ok(ScriptingPostProcessing::is_enabled(name))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn op_set_color_grading_lut(lut_path: String) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::set_color_grading_lut(lut_path)
+        .await
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
 fn op_create_entity(entity_info: String) -> Result<u32, deno_core::anyhow::Error> {
     let entity_data = deno_core::serde_json::from_str::<EntityInfo>(entity_info.as_str())?;
     ScriptingFunctions::create_entity(entity_data)
@@ -328,3 +393,89 @@ fn op_get_forward_vector(entity_id: u32) -> Result<Vec3, deno_core::anyhow::Erro
         .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
         .map(|v| v.into())
 }
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn op_capture_frame(path: String) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::capture_frame_to_file(path)
+        .await
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_save_scene(path: String) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::save_scene_to_file(path)
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn op_load_scene(path: String) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::load_scene(path)
+        .await
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_play_animation(
+    entity_id: u32,
+    animation: String,
+    options: Option<AnimationOptions>,
+) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::play_animation(entity_id, animation, options.unwrap_or_default())
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_set_morph_weights(
+    entity_id: u32,
+    weights: Vec<f32>,
+) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::set_morph_weights(entity_id, weights)
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn op_register_material(
+    descriptor: MaterialDescriptor,
+) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::register_material(descriptor)
+        .await
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_set_material(
+    entity_id: u32,
+    material_name: String,
+    parameters: Option<HashMap<String, Vec<f32>>>,
+) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::set_material(entity_id, material_name, parameters.unwrap_or_default())
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_clear_material(entity_id: u32) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::clear_material(entity_id)
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+fn op_set_material_parameter(
+    entity_id: u32,
+    parameter: String,
+    value: Vec<f32>,
+) -> Result<(), deno_core::anyhow::Error> {
+    ScriptingFunctions::set_material_parameter(entity_id, parameter, value)
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
+#[cfg_attr(not(target_arch = "wasm32"), op)]
+#[cfg(not(target_arch = "wasm32"))]
+async fn op_set_material_texture(
+    entity_id: u32,
+    material_index: Option<usize>,
+    slot: MaterialTextureSlot,
+    texture_path: String,
+) -> Result<usize, deno_core::anyhow::Error> {
+    ScriptingFunctions::set_material_texture(entity_id, material_index, slot, texture_path)
+        .await
+        .map_err(|e| deno_core::anyhow::Error::msg(format!("{:?}", e)))
+}
@@ -1,11 +1,20 @@
+use crate::components::hierarchy::Parent;
 use crate::components::modelcollider::ModelCollider;
 use crate::components::physicshandle::{PhysicsHandle, PhysicsValues};
 use crate::components::scriptingcallback::ScriptingCallback;
 use crate::components::transform::Transform;
+use crate::filesystem::scene::Scene;
+use crate::resources::framecapture::FrameCapture;
+use crate::resources::materiallibrary::MaterialLibrary;
+use crate::resources::postprocesssettings::{ColorGradingLut, ColorGradingSettings};
+use crate::resources::skyboxtexture::SkyboxTexture;
 use crate::ecscontainer::{ECSContainer, ECSError};
 use crate::renderer::primitives::lights::pointlight::PointLight;
+use crate::renderer::primitives::material::MaterialTextureSlot;
+use crate::renderer::primitives::materialdefinition::MaterialDefinition;
+use crate::renderer::primitives::lights::spotlight::SpotLight;
 use crate::systems::physics::PhysicsWorld;
-use crate::{CustomEvent, HorizonModel, ModelBuilder, EVENT_LOOP_PROXY};
+use crate::{CustomEvent, HorizonModel, ModelBuilder, RawModel, EVENT_LOOP_PROXY};
 
 // #[cfg(not(target_arch = "wasm32"))]
 // use super::scriptingengine::V8ScriptingEngine;
@@ -19,15 +28,26 @@ use specs::prelude::*;
 use rapier3d::na::{Isometry3, Point3, UnitQuaternion, Vector3};
 use rapier3d::prelude::{AngVector, Isometry, RigidBody, Rotation};
 use specs::world::Index;
+use std::collections::HashMap;
+use image::RgbaImage;
 
 // #[cfg(not(target_arch = "wasm32"))]
 // use v8::{Function, Global};
 
+use crate::components::animator::Animator;
+use crate::components::custommaterial::CustomMaterial;
+use crate::components::materialoverride::MaterialOverride;
+use crate::components::modelnode::ModelNode;
+use crate::components::morphweights::MorphWeights;
 use crate::components::assetidentifier::AssetIdentifier;
 use crate::components::componentparser::{ComponentParser, ComponentParserError, ParseComponent};
 use crate::components::componenttypes::{ComponentData, ComponentTypes};
-use crate::scripting::util::componentconversions::{PointLightComponent, TransformComponent};
+use crate::scripting::util::componentconversions::{
+    MaterialComponent, PointLightComponent, SpotLightComponent, TransformComponent,
+};
+use crate::scripting::util::animationoptions::AnimationOptions;
 use crate::scripting::util::horizonentity::HorizonEntity;
+use crate::scripting::util::materialdescriptor::MaterialDescriptor;
 use crate::scripting::ScriptingError;
 #[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
@@ -71,10 +91,16 @@ impl ScriptingFunctions {
                 let mut point_light_store = container.world.write_component::<PointLight>();
                 point_light_store.remove(ent);
             }
+            ComponentTypes::SpotLight => {
+                let mut spot_light_store = container.world.write_component::<SpotLight>();
+                spot_light_store.remove(ent);
+            }
             ComponentTypes::AssetIdentifier => {
                 // Not being used currently, might not be the best idea anyways to just remove identifiers,
             }
             ComponentTypes::CollisionShape => {}
+            // models always keep their materials, scripts can only change their values.
+            ComponentTypes::Material => {}
             ComponentTypes::None => {}
         }
     }
@@ -98,7 +124,13 @@ impl ScriptingFunctions {
                     .read_component::<Transform>()
                     .get(container.world.entities().entity(entity_id))
                 {
-                    ComponentData::Transform(TransformComponent::from(*transform))
+                    let mut transform_component = TransformComponent::from(*transform);
+                    transform_component.parent = container
+                        .world
+                        .read_component::<Parent>()
+                        .get(container.world.entities().entity(entity_id))
+                        .map(|parent| HorizonEntity::from_entity_id(parent.0.id()));
+                    ComponentData::Transform(transform_component)
                 } else {
                     ComponentData::Empty
                 }
@@ -143,7 +175,55 @@ impl ScriptingFunctions {
                     .read_component::<PointLight>()
                     .get(container.world.entities().entity(entity_id))
                 {
-                    ComponentData::PointLight(PointLightComponent::from(*point_light))
+                    let mut point_light_component = PointLightComponent::from(*point_light);
+                    point_light_component.attached_to = container
+                        .world
+                        .read_component::<Parent>()
+                        .get(container.world.entities().entity(entity_id))
+                        .map(|parent| HorizonEntity::from_entity_id(parent.0.id()));
+                    ComponentData::PointLight(point_light_component)
+                } else {
+                    ComponentData::Empty
+                }
+            }
+            ComponentTypes::SpotLight => {
+                if let Some(spot_light) = container
+                    .world
+                    .read_component::<SpotLight>()
+                    .get(container.world.entities().entity(entity_id))
+                {
+                    let mut spot_light_component = SpotLightComponent::from(*spot_light);
+                    spot_light_component.attached_to = container
+                        .world
+                        .read_component::<Parent>()
+                        .get(container.world.entities().entity(entity_id))
+                        .map(|parent| HorizonEntity::from_entity_id(parent.0.id()));
+                    ComponentData::SpotLight(spot_light_component)
+                } else {
+                    ComponentData::Empty
+                }
+            }
+            ComponentTypes::Material => {
+                let entity = container.world.entities().entity(entity_id);
+                let horizon_models = container.world.read_component::<HorizonModel>();
+                let material_overrides = container.world.read_component::<MaterialOverride>();
+                if let Some(model) = RawModel::model_entity(&container.world, entity)
+                    .and_then(|model_entity| horizon_models.get(model_entity))
+                {
+                    // the materials as they're drawn for the entity, with its override applied.
+                    let material_override = material_overrides.get(entity);
+                    let mut materials = model
+                        .materials
+                        .iter()
+                        .map(|(index, material)| match material_override {
+                            Some(material_override) => {
+                                MaterialComponent::new(*index, &material_override.apply(material))
+                            }
+                            None => MaterialComponent::new(*index, material),
+                        })
+                        .collect::<Vec<_>>();
+                    materials.sort_by_key(|material| material.material_index);
+                    ComponentData::Material(materials)
                 } else {
                     ComponentData::Empty
                 }
@@ -327,6 +407,222 @@ impl ScriptingFunctions {
         Ok(direction)
     }
 
+    /// Plays the named clip of the model attached to the entity.
+    pub fn play_animation(
+        entity_id: Index,
+        animation_name: String,
+        options: AnimationOptions,
+    ) -> Result<(), ScriptingError> {
+        let ecs = ECSContainer::global();
+        let mut animators = ecs.world.write_storage::<Animator>();
+        let animator = animators
+            .get_mut(ecs.world.entities().entity(entity_id))
+            .ok_or(ScriptingError::MissingComponent("Animator"))?;
+        let models = ecs.world.read_storage::<HorizonModel>();
+        let (animation, duration) = models
+            .get(animator.model)
+            .and_then(|model| {
+                model
+                    .animations
+                    .iter()
+                    .enumerate()
+                    .find(|(_, animation)| animation.name == animation_name)
+                    .map(|(index, animation)| (index, animation.duration))
+            })
+            .ok_or(ScriptingError::AnimationNotFound(animation_name))?;
+        animator.play(
+            animation,
+            duration,
+            options.looping.unwrap_or(false),
+            options.speed.unwrap_or(1.0),
+            options.blend_time.unwrap_or(0.0),
+        );
+        Ok(())
+    }
+    /// Sets the morph target weights of a model node, on model instances every node with morph targets is set.
+    pub fn set_morph_weights(entity_id: Index, weights: Vec<f32>) -> Result<(), ScriptingError> {
+        let ecs = ECSContainer::global();
+        let entity = ecs.world.entities().entity(entity_id);
+        let mut morph_weights = ecs.world.write_storage::<MorphWeights>();
+        if let Some(node_weights) = morph_weights.get_mut(entity) {
+            node_weights.set_weights(&weights);
+            return Ok(());
+        }
+        let model_nodes = ecs.world.read_storage::<ModelNode>();
+        let mut found = false;
+        for (node, node_weights) in (&model_nodes, &mut morph_weights).join() {
+            if node.instance == entity {
+                node_weights.set_weights(&weights);
+                found = true;
+            }
+        }
+        if !found {
+            return Err(ScriptingError::MissingComponent("MorphWeights"));
+        }
+        Ok(())
+    }
+
+    fn model_entity(entity_id: Index) -> Result<Entity, ScriptingError> {
+        let ecs = ECSContainer::global();
+        let entity = ecs.world.entities().entity(entity_id);
+        RawModel::model_entity(&ecs.world, entity)
+            .ok_or(ScriptingError::MissingComponent("RawModel"))
+    }
+    /// The entity itself, after checking that it's a model, one of its instances or one of their nodes.
+    fn material_entity(entity_id: Index) -> Result<Entity, ScriptingError> {
+        Self::model_entity(entity_id)?;
+        Ok(ECSContainer::global().world.entities().entity(entity_id))
+    }
+    /// Registers a custom material after loading its textures, a material with the same name is replaced.
+    pub async fn register_material(descriptor: MaterialDescriptor) -> Result<(), ScriptingError> {
+        let mut definition =
+            MaterialDefinition::new(descriptor.name.as_str(), descriptor.fragment.as_str());
+        definition.parameters = descriptor.parameters;
+        for texture in descriptor.textures {
+            let file_contents = crate::Importer::default()
+                .import_file(texture.path.as_str())
+                .await
+                .map_err(|e| {
+                    ScriptingError::MaterialLoadFailed(format!(
+                        "could not load texture {}: Inner error: {}",
+                        texture.path, e
+                    ))
+                })?;
+            let image = image::load_from_memory(&file_contents)
+                .map_err(|e| ScriptingError::MaterialLoadFailed(format!("{:?}", e)))?;
+            definition = definition.with_texture(texture.name.as_str(), image, texture.linear);
+        }
+        ECSContainer::global()
+            .world
+            .write_resource::<MaterialLibrary>()
+            .register(definition)
+            .map_err(|e| ScriptingError::MaterialLoadFailed(format!("{:?}", e)))
+    }
+    /// Draws the entity with a registered material, the parameters override the material's defaults.
+    /// A material set on the model entity is used by its instances which don't have one of their own.
+    pub fn set_material(
+        entity_id: Index,
+        material_name: String,
+        parameters: HashMap<String, Vec<f32>>,
+    ) -> Result<(), ScriptingError> {
+        let entity = Self::material_entity(entity_id)?;
+        let ecs = ECSContainer::global();
+        let material_library = ecs.world.read_resource::<MaterialLibrary>();
+        let definition = material_library
+            .definition(material_name.as_str())
+            .ok_or_else(|| ScriptingError::MaterialNotFound(material_name.clone()))?;
+        for (name, value) in parameters.iter() {
+            definition
+                .check_parameter(name.as_str(), value.as_slice())
+                .map_err(|e| ScriptingError::MaterialLoadFailed(format!("{:?}", e)))?;
+        }
+        ecs.world
+            .write_storage::<CustomMaterial>()
+            .insert(
+                entity,
+                CustomMaterial::new(material_name.as_str(), parameters),
+            )
+            .map_err(|_| ScriptingError::MissingComponent("RawModel"))?;
+        Ok(())
+    }
+    /// Removes the custom material of the entity, it falls back to its model's custom material or its glTF materials.
+    pub fn clear_material(entity_id: Index) -> Result<(), ScriptingError> {
+        let entity = Self::material_entity(entity_id)?;
+        ECSContainer::global()
+            .world
+            .write_storage::<CustomMaterial>()
+            .remove(entity);
+        Ok(())
+    }
+    /// Sets a parameter of the entity's custom material, the value is uploaded before the next frame.
+    pub fn set_material_parameter(
+        entity_id: Index,
+        parameter: String,
+        value: Vec<f32>,
+    ) -> Result<(), ScriptingError> {
+        let entity = Self::material_entity(entity_id)?;
+        let ecs = ECSContainer::global();
+        let mut custom_materials = ecs.world.write_storage::<CustomMaterial>();
+        let custom_material = custom_materials
+            .get_mut(entity)
+            .ok_or(ScriptingError::MissingComponent("CustomMaterial"))?;
+        ecs.world
+            .read_resource::<MaterialLibrary>()
+            .definition(custom_material.name.as_str())
+            .ok_or_else(|| ScriptingError::MaterialNotFound(custom_material.name.clone()))?
+            .check_parameter(parameter.as_str(), value.as_slice())
+            .map_err(|e| ScriptingError::MaterialLoadFailed(format!("{:?}", e)))?;
+        custom_material.set_parameter(parameter.as_str(), value);
+        Ok(())
+    }
+    /// Loads an image into the model of the entity and uses it as a texture of its glTF materials, returns the index of the image.
+    /// Changes that material of the model for every entity, without a material index the texture overrides
+    /// every material for this entity only.
+    pub async fn set_material_texture(
+        entity_id: Index,
+        material_index: Option<usize>,
+        slot: MaterialTextureSlot,
+        texture_path: String,
+    ) -> Result<usize, ScriptingError> {
+        let file_contents = crate::Importer::default()
+            .import_file(texture_path.as_str())
+            .await
+            .map_err(|e| {
+                ScriptingError::TextureOverrideFailed(format!(
+                    "could not load texture {}: Inner error: {}",
+                    texture_path, e
+                ))
+            })?;
+        let image = image::load_from_memory(&file_contents)
+            .map_err(|e| ScriptingError::TextureOverrideFailed(format!("{:?}", e)))?;
+        // the entity could have been deleted while the file was loading.
+        let model = Self::model_entity(entity_id)?;
+        let ecs = ECSContainer::global();
+        let mut horizon_models = ecs.world.write_storage::<HorizonModel>();
+        let mut raw_models = ecs.world.write_storage::<RawModel>();
+        let (horizon_model, raw_model) =
+            match (horizon_models.get_mut(model), raw_models.get_mut(model)) {
+                (Some(horizon_model), Some(raw_model)) => (horizon_model, raw_model),
+                _ => return Err(ScriptingError::MissingComponent("RawModel")),
+            };
+        if let Some(index) = material_index {
+            if !horizon_model.materials.contains_key(&index) {
+                return Err(ScriptingError::TextureOverrideFailed(format!(
+                    "the model has no material {}",
+                    index
+                )));
+            }
+        }
+        let texture_index = horizon_model
+            .textures
+            .keys()
+            .max()
+            .map_or(0, |index| index + 1);
+        horizon_model.textures.insert(texture_index, image);
+        if let Some(index) = material_index {
+            *horizon_model
+                .materials
+                .get_mut(&index)
+                .unwrap()
+                .texture_mut(slot) = Some(texture_index);
+            if let Some(raw_material) = raw_model.materials.get_mut(&index) {
+                raw_material.textures_dirty = true;
+            }
+        } else {
+            let entity = ecs.world.entities().entity(entity_id);
+            let mut material_overrides = ecs.world.write_storage::<MaterialOverride>();
+            if !material_overrides.contains(entity) {
+                material_overrides
+                    .insert(entity, MaterialOverride::new(model))
+                    .map_err(|_| ScriptingError::MissingComponent("RawModel"))?;
+            }
+            let material_override = material_overrides.get_mut(entity).unwrap();
+            material_override.textures.insert(slot, texture_index);
+            material_override.textures_dirty = true;
+        }
+        Ok(texture_index)
+    }
+
     pub async fn load_model(model_name: String) -> Result<HorizonEntity, ScriptingError> {
         log::info!(target: "model_load","loading model {}",model_name);
         let importer = crate::Importer::default();
@@ -334,12 +630,12 @@ impl ScriptingFunctions {
         let gltf_contents = importer
             .import_gltf_model(model_name.as_str())
             .await
-            .unwrap();
-        let mut model = ModelBuilder::create_gltf_model(gltf_contents)
             .map_err(|e| {
-                ScriptingError::ModelLoadFailed(format!("error during model load: {:?}", e))
-            })
-            .unwrap();
+                ScriptingError::ModelLoadFailed(format!("could not import model: {:?}", e))
+            })?;
+        let mut model = ModelBuilder::create_gltf_model(gltf_contents).map_err(|e| {
+            ScriptingError::ModelLoadFailed(format!("error during model load: {:?}", e))
+        })?;
         model.name = Some(model_name);
         let val = ref_thread_local::RefThreadLocal::borrow(&EVENT_LOOP_PROXY);
         let (sender, receiver) =
@@ -382,6 +678,96 @@ impl ScriptingFunctions {
             .send_event(CustomEvent::SkyboxTextureLoad(file_contents, sender))
             .unwrap();
         let res = receiver.await;
+        ECSContainer::global()
+            .world
+            .write_resource::<SkyboxTexture>()
+            .path = Some(texture_path);
+        Ok(())
+    }
+    /// Serializes the current scene, see `Scene::capture` for what's included.
+    pub fn save_scene() -> Result<String, ScriptingError> {
+        Scene::capture(&ECSContainer::global().world)
+            .to_json()
+            .map_err(|e| ScriptingError::SceneSaveFailed(format!("{:?}", e)))
+    }
+    /// Saves the scene next to the executable, the same place `load_scene` reads from.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_scene_to_file(path: String) -> Result<(), ScriptingError> {
+        let contents = Self::save_scene()?;
+        let file_path = std::env::current_exe()
+            .unwrap()
+            .parent()
+            .unwrap()
+            .join(path.as_str());
+        log::info!(target: "scene","saving scene to {:?}",file_path);
+        std::fs::write(file_path, contents)
+            .map_err(|e| ScriptingError::SceneSaveFailed(e.to_string()))
+    }
+    /// Replaces every entity with a transform with the ones in the scene file.
+    /// Models which are already loaded are reused, the rest are loaded before the entities are created.
+    pub async fn load_scene(path: String) -> Result<(), ScriptingError> {
+        log::info!(target: "scene","loading scene {}",path);
+        let contents = crate::Importer::default()
+            .import_file(path.as_str())
+            .await
+            .map_err(|e| {
+                ScriptingError::SceneLoadFailed(format!("could not read scene file: {}", e))
+            })?;
+        let scene = Scene::from_json(contents.as_slice())
+            .map_err(|e| ScriptingError::SceneLoadFailed(format!("{:?}", e)))?;
+
+        // the models and the skybox are loaded first, the current scene is kept if one of them fails.
+        let mut model_ids = HashMap::new();
+        for model in &scene.models {
+            let loaded_model = Scene::loaded_model(&ECSContainer::global().world, &model.path);
+            let model_id = if let Some(id) = loaded_model {
+                id
+            } else {
+                Self::load_model(model.path.clone()).await?.get_id()
+            };
+            model_ids.insert(model.id, model_id);
+        }
+        if let Some(skybox_texture) = scene.skybox_texture.clone() {
+            Self::set_skybox_texture(skybox_texture).await?;
+        }
+        Scene::clear(&ECSContainer::global().world);
+        scene
+            .instantiate(&ECSContainer::global().world, &model_ids)
+            .map_err(|e| ScriptingError::SceneLoadFailed(format!("{:?}", e)))
+    }
+    /// Waits for the next frame to be rendered and returns it as it looks after the ui pass.
+    pub async fn capture_frame() -> Result<RgbaImage, ScriptingError> {
+        let receiver = ECSContainer::global()
+            .world
+            .write_resource::<FrameCapture>()
+            .request_capture();
+        receiver
+            .await
+            .map_err(|_| ScriptingError::FrameCaptureFailed("capture was cancelled".to_string()))?
+            .map_err(|e| ScriptingError::FrameCaptureFailed(format!("{:?}", e)))
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn capture_frame_to_file(path: String) -> Result<(), ScriptingError> {
+        let image = Self::capture_frame().await?;
+        FrameCapture::save_image(&image, path.as_str())
+            .map_err(|e| ScriptingError::FrameCaptureFailed(format!("{:?}", e)))
+    }
+    /// Replaces the lookup table of the color grading with the contents of a `.cube` file.
+    pub async fn set_color_grading_lut(lut_path: String) -> Result<(), ScriptingError> {
+        let file_contents = crate::Importer::default()
+            .import_file(lut_path.as_str())
+            .await
+            .map_err(|e| {
+                ScriptingError::LutLoadFailed(format!("could not load lut: Inner error: {}", e))
+            })?;
+        let contents = String::from_utf8(file_contents)
+            .map_err(|e| ScriptingError::LutLoadFailed(format!("{:?}", e)))?;
+        let lut = ColorGradingLut::from_cube(contents.as_str())
+            .map_err(|e| ScriptingError::LutLoadFailed(format!("{:?}", e)))?;
+        ECSContainer::global()
+            .world
+            .write_resource::<ColorGradingSettings>()
+            .set_lut(lut);
         Ok(())
     }
 }
@@ -431,3 +817,90 @@ pub async fn set_skybox_texture(texture_path: JsValue) -> Result<JsValue, JsValu
         Err(JsValue::from_str("Invalid argument!"))
     }
 }
+#[cfg(target_arch = "wasm32")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "captureFrame"))]
+pub async fn capture_frame() -> Result<JsValue, JsValue> {
+    ScriptingFunctions::capture_frame()
+        .await
+        .and_then(|image| {
+            FrameCapture::encode_png(&image)
+                .map_err(|e| ScriptingError::FrameCaptureFailed(format!("{:?}", e)))
+        })
+        .map(|bytes| JsValue::from(js_sys::Uint8Array::from(bytes.as_slice())))
+        .map_err(|e| {
+            JsValue::from_str(format!("failed to capture frame inner error: {:?}", e).as_str())
+        })
+}
+#[cfg(target_arch = "wasm32")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setColorGradingLut"))]
+pub async fn set_color_grading_lut(lut_path: JsValue) -> Result<JsValue, JsValue> {
+    if let Some(path) = lut_path.as_string() {
+        ScriptingFunctions::set_color_grading_lut(path)
+            .await
+            .map_err(|e| {
+                JsValue::from_str(format!("failed to set lut inner error: {:?}", e).as_str())
+            })
+            .map(|_| JsValue::NULL)
+    } else {
+        Err(JsValue::from_str("Invalid argument!"))
+    }
+}
+#[cfg(target_arch = "wasm32")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "registerMaterial"))]
+pub async fn register_material(descriptor: JsValue) -> Result<JsValue, JsValue> {
+    let descriptor: MaterialDescriptor = descriptor
+        .into_serde()
+        .map_err(|e| JsValue::from_str(format!("Invalid material: {:?}", e).as_str()))?;
+    ScriptingFunctions::register_material(descriptor)
+        .await
+        .map_err(|e| {
+            JsValue::from_str(format!("failed to register material inner error: {:?}", e).as_str())
+        })
+        .map(|_| JsValue::NULL)
+}
+#[cfg(target_arch = "wasm32")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setMaterialTexture"))]
+pub async fn set_material_texture(
+    entity_id: u32,
+    material_index: Option<u32>,
+    slot: JsValue,
+    texture_path: String,
+) -> Result<JsValue, JsValue> {
+    let slot: MaterialTextureSlot = slot
+        .into_serde()
+        .map_err(|e| JsValue::from_str(format!("Invalid texture slot: {:?}", e).as_str()))?;
+    ScriptingFunctions::set_material_texture(
+        entity_id,
+        material_index.map(|index| index as usize),
+        slot,
+        texture_path,
+    )
+    .await
+    .map_err(|e| {
+        JsValue::from_str(format!("failed to override texture inner error: {:?}", e).as_str())
+    })
+    .map(|texture_index| JsValue::from(texture_index as u32))
+}
+#[cfg(target_arch = "wasm32")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "saveScene"))]
+pub fn save_scene() -> Result<JsValue, JsValue> {
+    ScriptingFunctions::save_scene()
+        .map(|scene| JsValue::from_str(scene.as_str()))
+        .map_err(|e| {
+            JsValue::from_str(format!("failed to save scene inner error: {:?}", e).as_str())
+        })
+}
+#[cfg(target_arch = "wasm32")]
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "loadScene"))]
+pub async fn load_scene(scene_path: JsValue) -> Result<JsValue, JsValue> {
+    if let Some(path) = scene_path.as_string() {
+        ScriptingFunctions::load_scene(path)
+            .await
+            .map_err(|e| {
+                JsValue::from_str(format!("failed to load scene inner error: {:?}", e).as_str())
+            })
+            .map(|_| JsValue::NULL)
+    } else {
+        Err(JsValue::from_str("Invalid scene path!"))
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// Options scripts can pass to `playAnimation`, missing values fall back to a single playback at normal speed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationOptions {
+    #[serde(rename = "loop")]
+    pub looping: Option<bool>,
+    pub speed: Option<f32>,
+    /// Seconds to blend from the currently playing clip.
+    pub blend_time: Option<f32>,
+}
@@ -3,6 +3,8 @@ use crate::scripting::util::horizonentity::HorizonEntity;
 
 use crate::components::transform::Transform;
 use crate::renderer::primitives::lights::pointlight::PointLight;
+use crate::renderer::primitives::lights::spotlight::SpotLight;
+use crate::renderer::primitives::material::GltfMaterial;
 use serde::*;
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -11,6 +13,7 @@ pub struct TransformComponent {
     pub rotation: Vec3,
     pub scale: Vec3,
     pub model: Option<HorizonEntity>,
+    pub parent: Option<HorizonEntity>,
 }
 impl TransformComponent {
     pub fn new(position: Vec3, rotation: Vec3, scale: Vec3, model: Option<HorizonEntity>) -> Self {
@@ -19,6 +22,7 @@ impl TransformComponent {
             rotation,
             scale,
             model,
+            parent: None,
         }
     }
 }
@@ -40,14 +44,22 @@ impl From<Transform> for TransformComponent {
 pub struct PointLightComponent {
     color: Vec3,
     radius: f32,
-    attached_to: Option<HorizonEntity>,
+    casts_shadow: bool,
+    /// The light's parent, its position is an offset from the parent's.
+    pub attached_to: Option<HorizonEntity>,
 }
 
 impl PointLightComponent {
-    pub fn new(color: Vec3, radius: f32, attached_to: Option<HorizonEntity>) -> Self {
+    pub fn new(
+        color: Vec3,
+        radius: f32,
+        casts_shadow: bool,
+        attached_to: Option<HorizonEntity>,
+    ) -> Self {
         PointLightComponent {
             color,
             radius,
+            casts_shadow,
             attached_to,
         }
     }
@@ -55,13 +67,72 @@ impl PointLightComponent {
 
 impl From<PointLight> for PointLightComponent {
     fn from(val: PointLight) -> Self {
-        let attached_ent = val
-            .attached_to
-            .map(|model| HorizonEntity::from_entity_id(model.id()));
-        PointLightComponent::new(val.color.into(), val.radius, attached_ent)
+        PointLightComponent::new(val.color.into(), val.radius, val.casts_shadow, None)
+    }
+}
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotLightComponent {
+    color: Vec3,
+    radius: f32,
+    /// in degrees
+    inner_cone_angle: f32,
+    /// in degrees
+    outer_cone_angle: f32,
+    casts_shadow: bool,
+    /// The light's parent, its position is an offset from the parent's.
+    pub attached_to: Option<HorizonEntity>,
+}
+
+impl From<SpotLight> for SpotLightComponent {
+    fn from(val: SpotLight) -> Self {
+        SpotLightComponent {
+            color: val.color.into(),
+            radius: val.radius,
+            inner_cone_angle: val.inner_cone_angle(),
+            outer_cone_angle: val.outer_cone_angle(),
+            casts_shadow: val.casts_shadow,
+            attached_to: None,
+        }
     }
 }
 #[derive(Serialize, Deserialize, Debug)]
 pub struct CollisionShapeComponent {
     pub pos: Vec3,
 }
+/// A glTF material of a model, the textures are indices into the images of the model.
+#[derive(Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialComponent {
+    pub material_index: usize,
+    name: String,
+    base_color: Vec4<f32>,
+    emissive: Vec3,
+    roughness: f32,
+    metallic: f32,
+    base_color_texture: Option<usize>,
+    metallic_roughness_texture: Option<usize>,
+    normal_texture: Option<usize>,
+    occlusion_texture: Option<usize>,
+    emissive_texture: Option<usize>,
+}
+
+impl MaterialComponent {
+    pub fn new(material_index: usize, material: &GltfMaterial) -> Self {
+        let [r, g, b, a] = material.base_color;
+        let [emissive_r, emissive_g, emissive_b] = material.emissive_color;
+        MaterialComponent {
+            material_index,
+            name: material.name.clone(),
+            base_color: Vec4::new(r, g, b, a),
+            emissive: Vec3::new(emissive_r, emissive_g, emissive_b),
+            roughness: material.pbr_roughness,
+            metallic: material.metallic_factor,
+            base_color_texture: material.base_color_texture,
+            metallic_roughness_texture: material.roughness_texture,
+            normal_texture: material.normal_map_texture,
+            occlusion_texture: material.occlusion_texture,
+            emissive_texture: material.emissive_texture,
+        }
+    }
+}
@@ -26,7 +26,15 @@ pub struct Component {
     pub collision_shape: Option<ColliderInfo>,
     pub lock_rotation: Option<LockRotation>,
     pub damping: Option<Vec<Damping>>,
+    /// Makes a light a child of the entity, the light's position becomes an offset from the entity's.
     pub attached_to: Option<u32>,
+    pub casts_shadow: Option<bool>,
+    /// Half angle of a spot light's inner cone in degrees.
+    pub inner_cone_angle: Option<f32>,
+    /// Half angle of a spot light's outer cone in degrees.
+    pub outer_cone_angle: Option<f32>,
+    pub parent: Option<u32>,
+    pub material: Option<MaterialInfo>,
 }
 #[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -38,6 +46,24 @@ pub struct ColliderInfo {
     pub radius: Option<f32>,
 }
 
+/// Values of a glTF material of a model, the missing ones are left unchanged.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialInfo {
+    /// Changes that material of the model for every entity, without an index the values override
+    /// every material for this entity only.
+    pub material_index: Option<usize>,
+    pub base_color: Option<Vec4<f32>>,
+    pub emissive: Option<Vec3>,
+    pub roughness: Option<f32>,
+    pub metallic: Option<f32>,
+    /// Textures are indices into the images of the model.
+    pub base_color_texture: Option<usize>,
+    pub metallic_roughness_texture: Option<usize>,
+    pub normal_texture: Option<usize>,
+    pub occlusion_texture: Option<usize>,
+    pub emissive_texture: Option<usize>,
+}
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LockRotation {
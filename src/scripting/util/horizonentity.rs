@@ -1,10 +1,14 @@
 use crate::components::componenttypes::{ComponentData, ComponentTypes};
 use crate::scripting::scriptingfunctions::ScriptingFunctions;
+#[cfg(target_arch = "wasm32")]
+use crate::scripting::util::animationoptions::AnimationOptions;
 use crate::scripting::util::entityinfo::EntityInfo;
 use crate::scripting::util::glmconversion::Vec3;
 use serde::Deserialize;
 use serde::Serialize;
 #[cfg(target_arch = "wasm32")]
+use std::collections::HashMap;
+#[cfg(target_arch = "wasm32")]
 use wasm_bindgen::prelude::*;
 
 #[cfg_attr(target_arch = "wasm32", wasm_bindgen)]
@@ -36,6 +40,8 @@ impl HorizonEntity {
         match component_type {
             ComponentData::Transform(d) => JsValue::from_serde(&d).unwrap(),
             ComponentData::PointLight(d) => JsValue::from_serde(&d).unwrap(),
+            ComponentData::SpotLight(d) => JsValue::from_serde(&d).unwrap(),
+            ComponentData::Material(materials) => JsValue::from_serde(&materials).unwrap(),
             ComponentData::AssetIdentifier(name) => JsValue::from_serde(&name).unwrap(),
             ComponentData::Physics(physics) => JsValue::from_serde(&physics).unwrap(),
             ComponentData::CollisionShape(collision) => JsValue::NULL,
@@ -142,4 +148,55 @@ impl HorizonEntity {
             })
             .map(|v| v.into())
     }
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "playAnimation"))]
+    pub fn play_animation(&self, animation: String, options: &JsValue) -> Result<(), JsValue> {
+        let options: AnimationOptions = options.into_serde().unwrap_or_default();
+        ScriptingFunctions::play_animation(self.entity_id, animation, options).map_err(|e| {
+            JsValue::from_str(
+                format!("failure during script execution, inner error: {:?}", e).as_str(),
+            )
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setMorphWeights"))]
+    pub fn set_morph_weights(&self, weights: Vec<f32>) -> Result<(), JsValue> {
+        ScriptingFunctions::set_morph_weights(self.entity_id, weights).map_err(|e| {
+            JsValue::from_str(
+                format!("failure during script execution, inner error: {:?}", e).as_str(),
+            )
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setMaterial"))]
+    pub fn set_material(&self, material_name: String, parameters: &JsValue) -> Result<(), JsValue> {
+        let parameters: HashMap<String, Vec<f32>> = parameters.into_serde().unwrap_or_default();
+        ScriptingFunctions::set_material(self.entity_id, material_name, parameters).map_err(|e| {
+            JsValue::from_str(
+                format!("failure during script execution, inner error: {:?}", e).as_str(),
+            )
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "clearMaterial"))]
+    pub fn clear_material(&self) -> Result<(), JsValue> {
+        ScriptingFunctions::clear_material(self.entity_id).map_err(|e| {
+            JsValue::from_str(
+                format!("failure during script execution, inner error: {:?}", e).as_str(),
+            )
+        })
+    }
+    #[cfg(target_arch = "wasm32")]
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setMaterialParameter"))]
+    pub fn set_material_parameter(
+        &self,
+        parameter: String,
+        value: Vec<f32>,
+    ) -> Result<(), JsValue> {
+        ScriptingFunctions::set_material_parameter(self.entity_id, parameter, value).map_err(|e| {
+            JsValue::from_str(
+                format!("failure during script execution, inner error: {:?}", e).as_str(),
+            )
+        })
+    }
 }
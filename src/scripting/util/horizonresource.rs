@@ -1,4 +1,7 @@
+use crate::renderer::postprocessing::PostProcessStack;
+use crate::resources::tonemappingsettings::ToneMappingOperator;
 use crate::scripting::util::glmconversion::Vec3;
+use crate::ui::debugstats::DebugStats;
 use crate::{DirectionalLight, ECSContainer};
 #[cfg(target_arch = "wasm32")]
 use js_sys::Number;
@@ -110,3 +113,64 @@ impl ScriptingDirLight {
         };
     }
 }
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "ToneMapping"))]
+pub struct ScriptingToneMapping;
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_class = "ToneMapping"))]
+impl ScriptingToneMapping {
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "getOperator"))]
+    pub fn get_operator() -> String {
+        let ecs = ECSContainer::global();
+        let debug_stats = ecs.world.read_resource::<DebugStats>();
+        debug_stats.tone_mapping.operator.name().to_string()
+    }
+    /// Returns false if the name isn't one of "aces", "reinhard" or "agx".
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setOperator"))]
+    pub fn set_operator(name: String) -> bool {
+        let operator = match ToneMappingOperator::from_name(name.as_str()) {
+            Some(operator) => operator,
+            None => return false,
+        };
+        ECSContainer::global()
+            .world
+            .write_resource::<DebugStats>()
+            .tone_mapping
+            .operator = operator;
+        true
+    }
+    /// Exposure in stops, compensates the automatic exposure when it's enabled.
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setExposure"))]
+    pub fn set_exposure(exposure: f32) {
+        ECSContainer::global()
+            .world
+            .write_resource::<DebugStats>()
+            .tone_mapping
+            .exposure = exposure;
+    }
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setAutoExposure"))]
+    pub fn set_auto_exposure(enabled: bool) {
+        ECSContainer::global()
+            .world
+            .write_resource::<DebugStats>()
+            .tone_mapping
+            .auto_exposure = enabled;
+    }
+}
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "PostProcessing"))]
+pub struct ScriptingPostProcessing;
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_class = "PostProcessing"))]
+impl ScriptingPostProcessing {
+    /// Returns false if there's no effect with the name, the built-in ones are "bloom", "colorGrading",
+    /// "fxaa", "chromaticAberration" and "vignette".
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "setEnabled"))]
+    pub fn set_enabled(name: String, enabled: bool) -> bool {
+        let ecs = ECSContainer::global();
+        let stack = ecs.world.read_resource::<PostProcessStack>();
+        stack.set_enabled(&ecs.world, name.as_str(), enabled)
+    }
+    #[cfg_attr(target_arch = "wasm32", wasm_bindgen(js_name = "isEnabled"))]
+    pub fn is_enabled(name: String) -> bool {
+        let ecs = ECSContainer::global();
+        let stack = ecs.world.read_resource::<PostProcessStack>();
+        stack.is_enabled(&ecs.world, name.as_str()).unwrap_or(false)
+    }
+}
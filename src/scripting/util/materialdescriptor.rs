@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+use crate::renderer::primitives::materialdefinition::MaterialParameter;
+
+/// A custom material scripts pass to `registerMaterial`, see `MaterialDefinition` for the fragment snippet.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialDescriptor {
+    pub name: String,
+    pub fragment: String,
+    #[serde(default)]
+    pub parameters: Vec<MaterialParameter>,
+    #[serde(default)]
+    pub textures: Vec<MaterialTextureDescriptor>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterialTextureDescriptor {
+    pub name: String,
+    /// Loaded with the importer like the other assets.
+    pub path: String,
+    /// Data like normal maps isn't stored as sRGB.
+    #[serde(default)]
+    pub linear: bool,
+}
@@ -1,6 +1,8 @@
+pub mod animationoptions;
 pub mod entityinfo;
 pub mod glmconversion;
 pub mod horizonentity;
+pub mod materialdescriptor;
 pub mod componentconversions;
 pub mod horizonresource;
 
@@ -0,0 +1,59 @@
+// bloom of the HDR scene: the bright pixels are downsampled into a mip chain with a dual filter blur,
+// then every mip is upsampled and added onto the next larger one and finally onto the scene.
+// the parameters are the threshold, the knee and the intensity.
+
+#include "include/fullscreen.wgsl"
+
+// keeps the part of the color above the threshold, with a quadratic curve around it.
+fn threshold(color: vec3<f32>) -> vec3<f32> {
+    let brightness = max(color.r, max(color.g, color.b));
+    let knee = uniforms.parameters.y;
+    let soft = clamp(brightness - uniforms.parameters.x + knee, 0.0, 2.0 * knee);
+    let contribution = max(soft * soft / (4.0 * knee), brightness - uniforms.parameters.x) / max(brightness, 0.0001);
+    return color * contribution;
+}
+
+fn downsample(uv: vec2<f32>) -> vec3<f32> {
+    let half_texel = 0.5 / vec2<f32>(textureDimensions(source_texture));
+    var color = textureSample(source_texture, source_sampler, uv).rgb * 4.0;
+    color = color + textureSample(source_texture, source_sampler, uv - half_texel).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv + half_texel).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(half_texel.x, -half_texel.y)).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv - vec2<f32>(half_texel.x, -half_texel.y)).rgb;
+    return color / 8.0;
+}
+
+fn upsample(uv: vec2<f32>) -> vec3<f32> {
+    let half_texel = 0.5 / vec2<f32>(textureDimensions(source_texture));
+    var color = textureSample(source_texture, source_sampler, uv + vec2<f32>(-half_texel.x * 2.0, 0.0)).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(-half_texel.x, half_texel.y)).rgb * 2.0;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(0.0, half_texel.y * 2.0)).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(half_texel.x, half_texel.y)).rgb * 2.0;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(half_texel.x * 2.0, 0.0)).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(half_texel.x, -half_texel.y)).rgb * 2.0;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(0.0, -half_texel.y * 2.0)).rgb;
+    color = color + textureSample(source_texture, source_sampler, uv + vec2<f32>(-half_texel.x, -half_texel.y)).rgb * 2.0;
+    return color / 12.0;
+}
+
+[[stage(fragment)]]
+fn fs_prefilter(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(threshold(downsample(in.uv)), 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_downsample(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(downsample(in.uv), 1.0);
+}
+
+// added onto the next larger mip.
+[[stage(fragment)]]
+fn fs_upsample(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(upsample(in.uv), 1.0);
+}
+
+// added onto the HDR scene.
+[[stage(fragment)]]
+fn fs_composite(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    return vec4<f32>(upsample(in.uv) * uniforms.parameters.z, 1.0);
+}
@@ -0,0 +1,13 @@
+// offsets the red and blue channels away from each other, more towards the edges of the frame.
+// the first parameter is the offset at the corners relative to the frame's size.
+
+#include "include/fullscreen.wgsl"
+
+[[stage(fragment)]]
+fn fs_main(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    let offset = (in.uv - vec2<f32>(0.5)) * 2.0 * uniforms.parameters.x;
+    let red = textureSample(source_texture, source_sampler, in.uv + offset).r;
+    let green = textureSample(source_texture, source_sampler, in.uv).g;
+    let blue = textureSample(source_texture, source_sampler, in.uv - offset).b;
+    return vec4<f32>(red, green, blue, 1.0);
+}
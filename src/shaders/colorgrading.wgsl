@@ -0,0 +1,29 @@
+// remaps the tone mapped colors with a 3D lookup table, the table is indexed with sRGB encoded colors like .cube files are.
+// the parameters are the strength and the size of the table.
+
+#include "include/fullscreen.wgsl"
+[[group(0)
+,binding(3)]]
+var lut_texture: texture_3d<f32>;
+
+fn linear_to_srgb(color: vec3<f32>) -> vec3<f32> {
+    let low = color * 12.92;
+    let high = 1.055 * pow(color, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, color <= vec3<f32>(0.0031308));
+}
+
+fn srgb_to_linear(color: vec3<f32>) -> vec3<f32> {
+    let low = color / 12.92;
+    let high = pow((color + 0.055) / 1.055, vec3<f32>(2.4));
+    return select(high, low, color <= vec3<f32>(0.04045));
+}
+
+[[stage(fragment)]]
+fn fs_main(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    let color = textureSample(source_texture, source_sampler, in.uv).rgb;
+    let size = uniforms.parameters.y;
+    // samples between the centers of the first and the last texels.
+    let coordinates = linear_to_srgb(clamp(color, vec3<f32>(0.0), vec3<f32>(1.0))) * (size - 1.0) / size + 0.5 / size;
+    let graded = srgb_to_linear(textureSample(lut_texture, source_sampler, coordinates).rgb);
+    return vec4<f32>(mix(color, graded, uniforms.parameters.x), 1.0);
+}
@@ -0,0 +1,49 @@
+// builds the hierarchical depth buffer of the occlusion culling, every texel keeps the farthest depth it covers.
+// the depth is reversed so the farthest depth is the smallest one.
+
+[[group(0)
+,binding(0)]]
+var depth_texture: texture_depth_2d;
+[[group(0)
+,binding(1)]]
+var pyramid_mip: texture_storage_2d<r32float,write>;
+[[group(0)
+,binding(2)]]
+var previous_mip: texture_2d<f32>;
+
+// the first mip is the largest power of two that fits the depth texture, a texel can cover parts of up to three depth texels per axis.
+[[stage(compute),workgroup_size(8,8,1)]]
+fn reduce_depth([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let size = textureDimensions(pyramid_mip);
+    let coords = vec2<i32>(id.xy);
+    if (coords.x >= size.x || coords.y >= size.y) {
+        return;
+    }
+    let depth_size = textureDimensions(depth_texture);
+    let begin = coords * depth_size / size;
+    let end = min(((coords + vec2<i32>(1)) * depth_size + size - vec2<i32>(1)) / size, depth_size);
+    var depth = 1.0;
+    for (var y: i32 = begin.y; y < end.y; y = y + 1) {
+        for (var x: i32 = begin.x; x < end.x; x = x + 1) {
+            depth = min(depth, textureLoad(depth_texture, vec2<i32>(x, y), 0));
+        }
+    }
+    textureStore(pyramid_mip, coords, vec4<f32>(depth, 0.0, 0.0, 0.0));
+}
+
+[[stage(compute),workgroup_size(8,8,1)]]
+fn reduce_mip([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let size = textureDimensions(pyramid_mip);
+    let coords = vec2<i32>(id.xy);
+    if (coords.x >= size.x || coords.y >= size.y) {
+        return;
+    }
+    // a side which is already a single texel isn't halved anymore.
+    let last = textureDimensions(previous_mip) - vec2<i32>(1);
+    let source = coords * 2;
+    let depth = min(
+        min(textureLoad(previous_mip, min(source, last), 0).x, textureLoad(previous_mip, min(source + vec2<i32>(1, 0), last), 0).x),
+        min(textureLoad(previous_mip, min(source + vec2<i32>(0, 1), last), 0).x, textureLoad(previous_mip, min(source + vec2<i32>(1, 1), last), 0).x)
+    );
+    textureStore(pyramid_mip, coords, vec4<f32>(depth, 0.0, 0.0, 0.0));
+}
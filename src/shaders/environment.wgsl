@@ -0,0 +1,185 @@
+// precomputes the image based lighting maps from the skybox cubemap.
+// every invocation writes a single texel, the z component of the invocation id selects the cubemap face.
+
+struct EnvironmentUniforms {
+    roughness: f32;
+    size: u32;
+    sample_count: u32;
+    padding: u32;
+};
+
+[[group(0)
+,binding(0)]]
+var skybox_texture: texture_cube<f32>;
+[[group(0)
+,binding(1)]]
+var skybox_sampler: sampler;
+[[group(0)
+,binding(2)]]
+var<uniform> environment: EnvironmentUniforms;
+[[group(0)
+,binding(3)]]
+var output_texture: texture_storage_2d_array<rgba16float,write>;
+
+let PI: f32 = 3.14159265359;
+
+// direction of a texel on the given cubemap face, following the face order of cubemap textures.
+fn cube_direction(face: u32, uv: vec2<f32>) -> vec3<f32> {
+    let c = uv * 2.0 - vec2<f32>(1.0);
+    var direction: vec3<f32>;
+    if (face == 0u) {
+        direction = vec3<f32>(1.0, -c.y, -c.x);
+    } else if (face == 1u) {
+        direction = vec3<f32>(-1.0, -c.y, c.x);
+    } else if (face == 2u) {
+        direction = vec3<f32>(c.x, 1.0, c.y);
+    } else if (face == 3u) {
+        direction = vec3<f32>(c.x, -1.0, -c.y);
+    } else if (face == 4u) {
+        direction = vec3<f32>(c.x, -c.y, 1.0);
+    } else {
+        direction = vec3<f32>(-c.x, -c.y, -1.0);
+    }
+    return normalize(direction);
+}
+
+fn radical_inverse(bits: u32) -> f32 {
+    var b = bits;
+    b = (b << 16u) | (b >> 16u);
+    b = ((b & 0x55555555u) << 1u) | ((b & 0xAAAAAAAAu) >> 1u);
+    b = ((b & 0x33333333u) << 2u) | ((b & 0xCCCCCCCCu) >> 2u);
+    b = ((b & 0x0F0F0F0Fu) << 4u) | ((b & 0xF0F0F0F0u) >> 4u);
+    b = ((b & 0x00FF00FFu) << 8u) | ((b & 0xFF00FF00u) >> 8u);
+    return f32(b) * 2.3283064365386963e-10;
+}
+
+fn hammersley(i: u32, count: u32) -> vec2<f32> {
+    return vec2<f32>(f32(i) / f32(count), radical_inverse(i));
+}
+
+// samples a half vector around the normal following the GGX distribution.
+fn importance_sample_ggx(xi: vec2<f32>, normal: vec3<f32>, roughness: f32) -> vec3<f32> {
+    let a = roughness * roughness;
+    let phi = 2.0 * PI * xi.x;
+    let cos_theta = sqrt((1.0 - xi.y) / (1.0 + (a * a - 1.0) * xi.y));
+    let sin_theta = sqrt(1.0 - cos_theta * cos_theta);
+    let h = vec3<f32>(cos(phi) * sin_theta, sin(phi) * sin_theta, cos_theta);
+
+    var up = vec3<f32>(1.0, 0.0, 0.0);
+    if (abs(normal.z) < 0.999) {
+        up = vec3<f32>(0.0, 0.0, 1.0);
+    }
+    let tangent = normalize(cross(up, normal));
+    let bitangent = cross(normal, tangent);
+    return normalize(tangent * h.x + bitangent * h.y + normal * h.z);
+}
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * denom * denom);
+}
+
+// image based lighting uses k = a^2 / 2 instead of the direct lighting remapping.
+fn geometry_smith_ibl(n_dot_v: f32, n_dot_l: f32, roughness: f32) -> f32 {
+    let k = roughness * roughness / 2.0;
+    let ggx_v = n_dot_v / (n_dot_v * (1.0 - k) + k);
+    let ggx_l = n_dot_l / (n_dot_l * (1.0 - k) + k);
+    return ggx_v * ggx_l;
+}
+
+fn texel_uv(id: vec3<u32>) -> vec2<f32> {
+    return (vec2<f32>(id.xy) + vec2<f32>(0.5)) / f32(environment.size);
+}
+
+[[stage(compute),workgroup_size(8,8,1)]]
+fn irradiance_main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    if (id.x >= environment.size || id.y >= environment.size) {
+        return;
+    }
+    let normal = cube_direction(id.z, texel_uv(id));
+    var up = vec3<f32>(0.0, 1.0, 0.0);
+    if (abs(normal.y) > 0.999) {
+        up = vec3<f32>(0.0, 0.0, 1.0);
+    }
+    let right = normalize(cross(up, normal));
+    up = cross(normal, right);
+
+    // uniform steps over the hemisphere, lower mips of the skybox keep the sum from aliasing.
+    let sample_delta = 0.05;
+    var irradiance = vec3<f32>(0.0);
+    var sample_count = 0.0;
+    for (var phi: f32 = 0.0; phi < 2.0 * PI; phi = phi + sample_delta) {
+        for (var theta: f32 = 0.0; theta < 0.5 * PI; theta = theta + sample_delta) {
+            let tangent_sample = vec3<f32>(sin(theta) * cos(phi), sin(theta) * sin(phi), cos(theta));
+            let direction = tangent_sample.x * right + tangent_sample.y * up + tangent_sample.z * normal;
+            irradiance = irradiance + textureSampleLevel(skybox_texture, skybox_sampler, direction, 4.0).rgb * cos(theta) * sin(theta);
+            sample_count = sample_count + 1.0;
+        }
+    }
+    irradiance = PI * irradiance / sample_count;
+    textureStore(output_texture, vec2<i32>(id.xy), i32(id.z), vec4<f32>(irradiance, 1.0));
+}
+
+[[stage(compute),workgroup_size(8,8,1)]]
+fn prefilter_main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    if (id.x >= environment.size || id.y >= environment.size) {
+        return;
+    }
+    // the view direction is assumed to be the same as the normal.
+    let normal = cube_direction(id.z, texel_uv(id));
+    let source_size = f32(textureDimensions(skybox_texture).x);
+    let texel_solid_angle = 4.0 * PI / (6.0 * source_size * source_size);
+    var color = vec3<f32>(0.0);
+    var total_weight = 0.0;
+    for (var i: u32 = 0u; i < environment.sample_count; i = i + 1u) {
+        let h = importance_sample_ggx(hammersley(i, environment.sample_count), normal, environment.roughness);
+        let l = normalize(2.0 * dot(normal, h) * h - normal);
+        let n_dot_l = dot(normal, l);
+        if (n_dot_l > 0.0) {
+            // samples with a low probability read from lower mips to reduce bright spots.
+            let n_dot_h = max(dot(normal, h), 0.0);
+            let pdf = distribution_ggx(n_dot_h, environment.roughness) / 4.0 + 0.0001;
+            let sample_solid_angle = 1.0 / (f32(environment.sample_count) * pdf + 0.0001);
+            var level = 0.0;
+            if (environment.roughness > 0.0) {
+                level = 0.5 * log2(sample_solid_angle / texel_solid_angle);
+            }
+            color = color + textureSampleLevel(skybox_texture, skybox_sampler, l, level).rgb * n_dot_l;
+            total_weight = total_weight + n_dot_l;
+        }
+    }
+    textureStore(output_texture, vec2<i32>(id.xy), i32(id.z), vec4<f32>(color / max(total_weight, 0.0001), 1.0));
+}
+
+[[stage(compute),workgroup_size(8,8,1)]]
+fn brdf_lut_main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    if (id.x >= environment.size || id.y >= environment.size) {
+        return;
+    }
+    // x is the angle between the normal and the view direction, y is the roughness.
+    let uv = texel_uv(id);
+    let n_dot_v = uv.x;
+    let roughness = uv.y;
+    let v = vec3<f32>(sqrt(1.0 - n_dot_v * n_dot_v), 0.0, n_dot_v);
+    let normal = vec3<f32>(0.0, 0.0, 1.0);
+    var scale = 0.0;
+    var bias = 0.0;
+    for (var i: u32 = 0u; i < environment.sample_count; i = i + 1u) {
+        let h = importance_sample_ggx(hammersley(i, environment.sample_count), normal, roughness);
+        let l = normalize(2.0 * dot(v, h) * h - v);
+        let n_dot_l = max(l.z, 0.0);
+        let n_dot_h = max(h.z, 0.0);
+        let v_dot_h = max(dot(v, h), 0.0);
+        if (n_dot_l > 0.0) {
+            let g = geometry_smith_ibl(n_dot_v, n_dot_l, roughness);
+            let g_vis = g * v_dot_h / (n_dot_h * n_dot_v);
+            let fc = pow(1.0 - v_dot_h, 5.0);
+            scale = scale + (1.0 - fc) * g_vis;
+            bias = bias + fc * g_vis;
+        }
+    }
+    let count = f32(environment.sample_count);
+    textureStore(output_texture, vec2<i32>(id.xy), 0, vec4<f32>(scale / count, bias / count, 0.0, 1.0));
+}
@@ -0,0 +1,196 @@
+
+struct VertexOutput {
+[[builtin(position)]] fragPos: vec4<f32>;
+[[location(0)]] fragUV:vec2<f32>;
+};
+
+[[stage(vertex)]]
+fn vs_main([[location(0)]] pos: vec4<f32>,[[location(1)]] uv:vec2<f32>) -> VertexOutput {
+    var vertex_output: VertexOutput;
+        vertex_output.fragUV = uv;
+        vertex_output.fragPos = pos;
+    return vertex_output;
+}
+
+struct Globals {
+    u_view_position: vec4<f32>;
+    u_view_proj: mat4x4<f32>;
+    lights_num: vec4<u32>;
+};
+
+// determines the number of lights a tile can be influenced by.
+let num_tile_light_slot:u32 = TILE_LIGHT_SLOTS; // needs to be a constant as you can't create a runtime array with an atomic next to it. pipeline overrides would solve this issue this being a constant but they are not implemeneted.
+
+struct TileLightData {
+    light_count: atomic<u32>;
+   light_ids: array<u32,num_tile_light_slot>;
+};
+struct Tiles {
+    data: array<TileLightData>;
+};
+struct TileInfo {
+     tile_size: i32;
+     tile_count_x: i32;
+     tile_count_y: i32;
+     num_tiles: u32;
+     num_tile_light_slot: u32;
+};
+
+
+[[group(0)
+,binding(0)]]
+var  texture_sampler: sampler;
+
+[[group(0)
+,binding(1)]]
+var positions:texture_2d<f32>;
+[[group(0)
+,binding(2)]]
+var normals: texture_2d<f32>;
+[[group(0),binding(3)]]
+var specular:texture_2d<f32>;
+[[group(0)
+,binding(4)]]
+var albedo: texture_2d<f32>;
+
+struct CanvasSize {
+     canvasConstants: vec2<f32>;
+};
+[[group(0)
+,binding(5)]]
+var<uniform> canvasSize: CanvasSize;
+[[group(0),binding(6)]]
+var<storage,read_write> tile_light_data:Tiles;
+
+[[group(0),
+binding(7)]]
+var<uniform> tile_info:TileInfo;
+// the screen-space ambient occlusion, white when it's disabled.
+[[group(0),binding(8)]]
+var ambient_occlusion: texture_2d<f32>;
+
+[[group(1)
+,binding(0)]]
+var<uniform> globals: Globals;
+
+// the shadows share the group of the globals.
+#define SHADOW_GROUP 1
+#include "include/lighting.wgsl"
+
+fn get_tile_id(coordinates: vec2<f32>) -> u32
+{
+    // calculate tile position
+    //let V = normalize(globals.u_view_position.xyz - position);
+    var tile_scale = vec2<f32>(1.0 / f32(tile_info.tile_count_x),1.0 / f32(tile_info.tile_count_y));
+    var uv_flip = vec2<f32>(coordinates.x,1.0-coordinates.y);
+    var tile_coord = vec2<u32>(floor(uv_flip/tile_scale));
+                                                    // tile_counts are i32
+    return tile_coord.x + tile_coord.y * u32(tile_info.tile_count_x);
+}
+
+// the tiles store the ids of both light types, ids past the point light count refer to spot lights.
+fn addLightContributions(position:vec3<f32>,coordinates:vec2<f32>,object_normal:vec3<f32>,view_direction:vec3<f32>,base_color:vec3<f32>,metallic:f32,roughness:f32) -> vec3<f32>
+{
+    var result = vec3<f32>(0.0);
+    let tile_id = get_tile_id(coordinates);
+    let count = atomicLoad(&tile_light_data.data[tile_id].light_count);
+    for(var i: u32 = 0u; i < num_tile_light_slot; i = i + 1u)
+    {
+        if(i >= count)
+        {
+            break;
+        }
+        let light_id = tile_light_data.data[tile_id].light_ids[i];
+        if(light_id >= globals.lights_num.x)
+        {
+            result = result + calcSpotLightContribution(spotLights.elements[light_id - globals.lights_num.x],position,object_normal,view_direction,base_color,metallic,roughness);
+            continue;
+        }
+        var light =  pointLights.elements[light_id];
+        var dist = length(light.position.xyz - position);
+        if(dist > light.radius)
+        {
+            continue;
+        }
+
+        result = result + calcPointLightContribution(light,position,object_normal,view_direction,base_color,metallic,roughness);
+    }
+    return result;
+}
+
+#ifndef TARGET_WEB
+[[stage(fragment)]]
+fn fs_main(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let coordinates = vec2<i32>(floor(in.fragPos.xy));
+
+    // the gbuffer stores metallic next to the position, roughness next to the normal
+    // and the ambient occlusion next to the emissive color.
+    let position_metallic = textureLoad(positions,coordinates,0);
+    let position = position_metallic.xyz;
+    if(position.z > 10000.0)
+    {
+        discard;
+    }
+    let normal_roughness = textureLoad(normals,coordinates,0);
+    let object_normal = normal_roughness.xyz;
+    let emissive_occlusion = textureLoad(specular,coordinates,0);
+    let object_color = textureLoad(albedo,coordinates,0).xyz;
+    let metallic = clamp(position_metallic.w, 0.0, 1.0);
+    // fully smooth surfaces would turn the highlights into single points.
+    let roughness = clamp(normal_roughness.w, 0.04, 1.0);
+    let view_direction = normalize(globals.u_view_position.xyz - position);
+
+    let n_dot_l = max(dot(object_normal, normalize(dirLight.direction.xyz)), 0.0);
+    let shadow = get_shadow_value(vec4<f32>(position,1.0), n_dot_l);
+    // the material's occlusion is combined with the screen-space one.
+    let occlusion = emissive_occlusion.w * textureLoad(ambient_occlusion,coordinates,0).r;
+    var result = calcAmbientContribution(object_normal,view_direction,object_color,metallic,roughness,occlusion);
+    result = result + calcDirLightContribution(object_normal,view_direction,object_color,metallic,roughness,shadow);
+    result = result + addLightContributions(position,in.fragUV,object_normal,view_direction,object_color,metallic,roughness);
+    result = result + emissive_occlusion.xyz;
+
+    if(shadow_settings.show_cascades != 0u)
+    {
+        result = result * get_cascade_tint(get_cascade_index(get_view_depth(vec4<f32>(position,1.0))));
+    }
+
+    return vec4<f32>(result,1.0);
+}
+#endif
+[[stage(fragment)]]
+fn fs_main_web(in: VertexOutput) -> [[location(0)]] vec4<f32> {
+    let coordinates = vec2<i32>(floor(in.fragPos.xy));
+
+    // the gbuffer stores metallic next to the position, roughness next to the normal
+    // and the ambient occlusion next to the emissive color.
+    let position_metallic = textureLoad(positions,coordinates,0);
+    let position = position_metallic.xyz;
+    if(position.z > 10000.0)
+    {
+        discard;
+    }
+    let normal_roughness = textureLoad(normals,coordinates,0);
+    let object_normal = normal_roughness.xyz;
+    let emissive_occlusion = textureLoad(specular,coordinates,0);
+    let object_color = textureLoad(albedo,coordinates,0).xyz;
+    let metallic = clamp(position_metallic.w, 0.0, 1.0);
+    // fully smooth surfaces would turn the highlights into single points.
+    let roughness = clamp(normal_roughness.w, 0.04, 1.0);
+    let view_direction = normalize(globals.u_view_position.xyz - position);
+
+#ifdef TARGET_WEB
+    // the shadows of the web build are disabled for now.
+    let shadow = 1.0;
+#else
+    let n_dot_l = max(dot(object_normal, normalize(dirLight.direction.xyz)), 0.0);
+    let shadow = get_shadow_value_web(cascade_transforms.elements[0]* vec4<f32>(position,1.0), n_dot_l);
+#endif
+    // the material's occlusion is combined with the screen-space one.
+    let occlusion = emissive_occlusion.w * textureLoad(ambient_occlusion,coordinates,0).r;
+    var result = calcAmbientContribution(object_normal,view_direction,object_color,metallic,roughness,occlusion);
+    result = result + calcDirLightContribution(object_normal,view_direction,object_color,metallic,roughness,shadow);
+    result = result + addLightContributions(position,in.fragUV,object_normal,view_direction,object_color,metallic,roughness);
+    result = result + emissive_occlusion.xyz;
+
+    return vec4<f32>(result,1.0);
+}
@@ -0,0 +1,228 @@
+// culls every instance against the frustum of each view and appends the visible ones to the view's indirect draws.
+// the y component of the invocation id selects the view: the camera, the shadow cascades and the unculled view of the shadow atlas.
+// instances in the camera's view are also tested against the depth pyramid of the previous frame.
+// every visible instance is drawn with the level of detail matching its size on the camera's screen.
+
+struct Frustum {
+    planes: array<vec4<f32>,6>;
+};
+let VIEW_COUNT: u32 = FRUSTUM_VIEW_COUNT;
+
+let CAMERA_VIEW: u32 = 0u;
+// the visible instances hold the level of detail plus one above this bit when the levels are shown.
+let LOD_LEVEL_SHIFT: u32 = 28u;
+
+struct FrustumCullingUniforms {
+    views: array<Frustum,VIEW_COUNT>;
+    // view matrix and projection (x and y scale, near plane) of the camera which rendered the depth pyramid.
+    occlusion_view: mat4x4<f32>;
+    occlusion_projection: vec4<f32>;
+    // camera position selecting the levels of detail and its vertical projection scale including the bias.
+    lod_camera: vec4<f32>;
+    instance_count: u32;
+    draw_count: u32;
+    // zero disables the occlusion culling.
+    pyramid_mip_count: u32;
+    // size of every view's range of the visible instances.
+    slot_count: u32;
+    show_lod_levels: u32;
+    padding1: u32;
+    padding2: u32;
+    padding3: u32;
+};
+struct Transforms {
+    elements: array<mat4x4<f32>>;
+};
+// bounds.xyz is the center of the mesh's bounding sphere, a negative radius is never culled.
+struct CullingInstance {
+    bounds: vec4<f32>;
+    group: u32;
+    padding1: u32;
+    padding2: u32;
+    padding3: u32;
+};
+struct CullingInstances {
+    elements: array<CullingInstance>;
+};
+// instances of the same model mesh, every primitive of the mesh has its own draw for each level of detail.
+struct CullingGroup {
+    // screen sizes below which the next level of detail replaces the previous one.
+    lod_screen_sizes: vec4<f32>;
+    first_draw: u32;
+    primitive_count: u32;
+    lod_count: u32;
+    // every level of detail has a slot for each instance of the group.
+    first_slot: u32;
+    instance_count: u32;
+    padding1: u32;
+    padding2: u32;
+    padding3: u32;
+};
+struct CullingGroups {
+    elements: array<CullingGroup>;
+};
+struct DrawIndexedIndirect {
+    index_count: u32;
+    instance_count: atomic<u32>;
+    first_index: u32;
+    base_vertex: i32;
+    first_instance: u32;
+};
+struct IndirectDraws {
+    elements: array<DrawIndexedIndirect>;
+};
+struct VisibleInstances {
+    elements: array<u32>;
+};
+// instances of the camera's view, read back for the debug stats.
+struct CullingStats {
+    frustum_culled: atomic<u32>;
+    occlusion_culled: atomic<u32>;
+    visible: atomic<u32>;
+    padding: u32;
+};
+
+[[group(0)
+,binding(0)]]
+var<uniform> culling: FrustumCullingUniforms;
+[[group(0)
+,binding(1)]]
+var<storage,read> transform: Transforms;
+[[group(0)
+,binding(2)]]
+var<storage,read> culling_instances: CullingInstances;
+[[group(0)
+,binding(3)]]
+var<storage,read> culling_groups: CullingGroups;
+[[group(0)
+,binding(4)]]
+var<storage,read_write> indirect_draws: IndirectDraws;
+[[group(0)
+,binding(5)]]
+var<storage,read_write> visible_instances: VisibleInstances;
+[[group(0)
+,binding(6)]]
+var depth_pyramid: texture_2d<f32>;
+[[group(0)
+,binding(7)]]
+var<storage,read_write> culling_stats: CullingStats;
+
+// the instance's bounding sphere in world space, the radius stays negative for instances which are never culled.
+fn world_bounds(bounds: vec4<f32>, model_matrix: mat4x4<f32>) -> vec4<f32> {
+    if (bounds.w < 0.0) {
+        return bounds;
+    }
+    let center = model_matrix * vec4<f32>(bounds.xyz, 1.0);
+    let scale = max(length(model_matrix[0].xyz), max(length(model_matrix[1].xyz), length(model_matrix[2].xyz)));
+    return vec4<f32>(center.xyz, bounds.w * scale);
+}
+
+fn is_visible(view: u32, sphere: vec4<f32>) -> bool {
+    if (sphere.w < 0.0) {
+        return true;
+    }
+    for (var i: u32 = 0u; i < 6u; i = i + 1u) {
+        let plane = culling.views[view].planes[i];
+        if (dot(plane.xyz, sphere.xyz) + plane.w < -sphere.w) {
+            return false;
+        }
+    }
+    return true;
+}
+
+// x and y bounds in normalized device coordinates of a sphere along one axis, the center's z points away from the camera.
+fn project_sphere_axis(center: vec2<f32>, radius: f32, scale: f32) -> vec2<f32> {
+    let tangent = sqrt(dot(center, center) - radius * radius);
+    let first = vec2<f32>(tangent * center.x - radius * center.y, radius * center.x + tangent * center.y);
+    let second = vec2<f32>(tangent * center.x + radius * center.y, -radius * center.x + tangent * center.y);
+    let first_ndc = first.x / first.y * scale;
+    let second_ndc = second.x / second.y * scale;
+    return vec2<f32>(min(first_ndc, second_ndc), max(first_ndc, second_ndc));
+}
+
+fn is_occluded(sphere: vec4<f32>) -> bool {
+    if (culling.pyramid_mip_count == 0u || sphere.w < 0.0) {
+        return false;
+    }
+    let view_center = culling.occlusion_view * vec4<f32>(sphere.xyz, 1.0);
+    let center = vec3<f32>(view_center.x, view_center.y, -view_center.z);
+    let radius = sphere.w;
+    let z_near = culling.occlusion_projection.z;
+    // spheres crossing the near plane cover the whole screen.
+    if (center.z < radius + z_near) {
+        return false;
+    }
+    let x_bounds = project_sphere_axis(center.xz, radius, culling.occlusion_projection.x);
+    let y_bounds = project_sphere_axis(center.yz, radius, culling.occlusion_projection.y);
+    // texture coordinates grow downwards.
+    let uv_min = clamp(vec2<f32>(x_bounds.x, -y_bounds.y) * 0.5 + vec2<f32>(0.5), vec2<f32>(0.0), vec2<f32>(1.0));
+    let uv_max = clamp(vec2<f32>(x_bounds.y, -y_bounds.x) * 0.5 + vec2<f32>(0.5), vec2<f32>(0.0), vec2<f32>(1.0));
+
+    // the mip where the bounds fit into a texel, so they overlap at most 2x2 texels.
+    let extent = (uv_max - uv_min) * vec2<f32>(textureDimensions(depth_pyramid, 0));
+    let mip = min(i32(ceil(log2(max(max(extent.x, extent.y), 1.0)))), i32(culling.pyramid_mip_count) - 1);
+    let last = textureDimensions(depth_pyramid, mip) - vec2<i32>(1);
+    let mip_size = vec2<f32>(last + vec2<i32>(1));
+    let min_texel = clamp(vec2<i32>(uv_min * mip_size), vec2<i32>(0), last);
+    let max_texel = clamp(vec2<i32>(uv_max * mip_size), vec2<i32>(0), last);
+    let depth = min(
+        min(textureLoad(depth_pyramid, min_texel, mip).x, textureLoad(depth_pyramid, vec2<i32>(max_texel.x, min_texel.y), mip).x),
+        min(textureLoad(depth_pyramid, vec2<i32>(min_texel.x, max_texel.y), mip).x, textureLoad(depth_pyramid, max_texel, mip).x)
+    );
+    // reversed depth of the sphere's closest point.
+    let sphere_depth = z_near / (center.z - radius);
+    return sphere_depth < depth;
+}
+
+// height of the sphere relative to the screen's decides the level, unbounded instances use the base level.
+fn select_lod(group: CullingGroup, sphere: vec4<f32>) -> u32 {
+    if (sphere.w < 0.0) {
+        return 0u;
+    }
+    let distance = max(length(sphere.xyz - culling.lod_camera.xyz), 0.0001);
+    let screen_size = sphere.w * culling.lod_camera.w / distance;
+    var lod = 0u;
+    for (var i: u32 = 1u; i < group.lod_count; i = i + 1u) {
+        if (screen_size < group.lod_screen_sizes[i - 1u]) {
+            lod = i;
+        }
+    }
+    return lod;
+}
+
+[[stage(compute),workgroup_size(64,1,1)]]
+fn main([[builtin(global_invocation_id)]] id: vec3<u32>) {
+    let index = id.x;
+    let view = id.y;
+    if (index >= culling.instance_count || view >= VIEW_COUNT) {
+        return;
+    }
+    let instance = culling_instances.elements[index];
+    let sphere = world_bounds(instance.bounds, transform.elements[index]);
+    if (!is_visible(view, sphere)) {
+        if (view == CAMERA_VIEW) {
+            atomicAdd(&culling_stats.frustum_culled, 1u);
+        }
+        return;
+    }
+    if (view == CAMERA_VIEW) {
+        if (is_occluded(sphere)) {
+            atomicAdd(&culling_stats.occlusion_culled, 1u);
+            return;
+        }
+        atomicAdd(&culling_stats.visible, 1u);
+    }
+    let group = culling_groups.elements[instance.group];
+    let lod = select_lod(group, sphere);
+    let first_draw = view * culling.draw_count + group.first_draw + lod * group.primitive_count;
+    // the first primitive's count decides the slot, the other primitives draw the same instances.
+    let slot = atomicAdd(&indirect_draws.elements[first_draw].instance_count, 1u);
+    for (var i: u32 = 1u; i < group.primitive_count; i = i + 1u) {
+        atomicAdd(&indirect_draws.elements[first_draw + i].instance_count, 1u);
+    }
+    var visible = index;
+    if (culling.show_lod_levels != 0u) {
+        visible = index | ((lod + 1u) << LOD_LEVEL_SHIFT);
+    }
+    visible_instances.elements[view * culling.slot_count + group.first_slot + lod * group.instance_count + slot] = visible;
+}
@@ -0,0 +1,47 @@
+// fast approximate anti-aliasing of the tone mapped frame, blurs along the edges found from the luma of the neighbouring pixels.
+// the parameters are the longest span searched, the minimal and the relative reduction of the edge direction.
+
+#include "include/fullscreen.wgsl"
+
+// the frame is stored linear, the edges are found on perceptual luma.
+fn luma(color: vec3<f32>) -> f32 {
+    return sqrt(dot(color, vec3<f32>(0.299, 0.587, 0.114)));
+}
+
+fn sample_color(uv: vec2<f32>) -> vec3<f32> {
+    return textureSample(source_texture, source_sampler, uv).rgb;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    let span_max = uniforms.parameters.x;
+    let reduce_min = uniforms.parameters.y;
+    let reduce_mul = uniforms.parameters.z;
+    let texel = 1.0 / vec2<f32>(textureDimensions(source_texture));
+    let luma_nw = luma(sample_color(in.uv + vec2<f32>(-1.0, -1.0) * texel));
+    let luma_ne = luma(sample_color(in.uv + vec2<f32>(1.0, -1.0) * texel));
+    let luma_sw = luma(sample_color(in.uv + vec2<f32>(-1.0, 1.0) * texel));
+    let luma_se = luma(sample_color(in.uv + vec2<f32>(1.0, 1.0) * texel));
+    let color_m = sample_color(in.uv);
+    let luma_m = luma(color_m);
+    let luma_min = min(luma_m, min(min(luma_nw, luma_ne), min(luma_sw, luma_se)));
+    let luma_max = max(luma_m, max(max(luma_nw, luma_ne), max(luma_sw, luma_se)));
+
+    // the direction runs along the edge, perpendicular to the luma gradient.
+    var direction = vec2<f32>(
+        -((luma_nw + luma_ne) - (luma_sw + luma_se)),
+        (luma_nw + luma_sw) - (luma_ne + luma_se)
+    );
+    let direction_reduce = max((luma_nw + luma_ne + luma_sw + luma_se) * 0.25 * reduce_mul, reduce_min);
+    let inverse_direction_min = 1.0 / (min(abs(direction.x), abs(direction.y)) + direction_reduce);
+    direction = clamp(direction * inverse_direction_min, vec2<f32>(-span_max), vec2<f32>(span_max)) * texel;
+
+    let color_a = 0.5 * (sample_color(in.uv + direction * (1.0 / 3.0 - 0.5)) + sample_color(in.uv + direction * (2.0 / 3.0 - 0.5)));
+    let color_b = color_a * 0.5 + 0.25 * (sample_color(in.uv - direction * 0.5) + sample_color(in.uv + direction * 0.5));
+    let luma_b = luma(color_b);
+    // the wider blur crossed another edge.
+    if (luma_b < luma_min || luma_b > luma_max) {
+        return vec4<f32>(color_a, 1.0);
+    }
+    return vec4<f32>(color_b, 1.0);
+}
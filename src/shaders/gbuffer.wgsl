@@ -1,79 +1,13 @@
-
-struct GBufferInputs {
-[[location(0)]] a_pos: vec3<f32>;
-[[location(1)]] a_normal: vec3<f32>;
-[[location(2)]] tangent: vec4<f32>;
-[[location(3)]] tex_coord: vec2<f32>;
-[[location(4)]] vertex_color: u32;
-[[location(5)]] joint_weight: vec4<f32>;
-[[location(6)]] joint_id: u32;
-[[builtin(instance_index)]] instance_index: u32;
-};
-
-struct VertexOutputs {
-    [[builtin(position)]] pos: vec4<f32>;
-    [[location(0)]] v_tex_coord: vec2<f32>;
-    [[location(1)]] world_frag_pos: vec3<f32>;
-    [[location(2)]] tangent: vec4<f32>;
-    [[location(3)]] normal: vec3<f32>;
-};
-
-
-struct GBufferOutputs {
-    [[location(0)]] position: vec4<f32>;
-    [[location(1)]] normal: vec4<f32>;
-    [[location(2)]] specular:vec4<f32>;
-    [[location(3)]] albedo: vec4<f32>;
-};
-struct Globals {
-    u_view_position: vec4<f32>;
-    u_view_proj: mat4x4<f32>;
-    lights_num: vec4<u32>;
-};
+#include "include/gbuffervertex.wgsl"
 
 struct MaterialUniforms {
     base_color_factor: vec4<f32>;
     roughness_metallic_double_sided: vec4<f32>;
     emissive_color: vec4<f32>;
+    // alpha cutoff, alpha mode (0 opaque, 1 mask, 2 blend) and transmission factor.
+    alpha_cutoff_mode_transmission: vec4<f32>;
 };
 
-[[group(0)
-,binding(0)]]
-var<uniform> globals: Globals;
-
-struct Transforms {
- elements: array<mat4x4<f32> >;
-}; 
-struct Normals {
-    elements: array<mat4x4<f32> >;
-};
-
-[[group(0)
-,binding(1)]]
-var<storage,read> transform: Transforms;
-
-[[group(0)
-,binding(2)]]
-var<storage,read> normals: Normals;
-
-[[stage(vertex)]]
-fn vs_main(in: GBufferInputs) -> VertexOutputs {
-    var output: VertexOutputs;
-    output.v_tex_coord = in.tex_coord; 
-    var model_matrix: mat4x4<f32> = transform.elements[in.instance_index];
-    var normal: mat4x4<f32> = normals.elements[in.instance_index];
-    let frag_tangent = normalize(normal * in.tangent);
-    output.normal = normalize(vec3<f32>((normal * vec4<f32>(in.a_normal,0.0)).xyz));
-    output.tangent = frag_tangent;   
-    var model_space: vec4<f32>  = model_matrix * vec4<f32>(in.a_pos,1.0);
-    output.world_frag_pos = model_space.xyz;    
-    output.pos= globals.u_view_proj* model_space;
-    return output;
-}
-
-
-
-
 [[group(1),binding(0)]]
 var t_texture: texture_2d<f32>;
 [[group(1),binding(1)]]
@@ -98,6 +32,11 @@ fn fs_main(in: VertexOutputs, [[builtin(front_facing)]] front_facing: bool) -> G
     let occulison = textureSample(t_occlusion,t_sampler,in.v_tex_coord).r;
     albedo = vec4<f32>(material_uniforms.base_color_factor.xyz * texture_color.xyz,1.0);
     out.albedo = albedo;
+    if (in.lod_level != 0u) {
+        // red, green, blue and yellow for the levels of detail, same order as the debug window.
+        var tints = array<vec3<f32>,4>(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(1.0, 1.0, 0.0));
+        out.albedo = vec4<f32>(mix(albedo.xyz, tints[min(in.lod_level - 1u, 3u)], 0.6), albedo.w);
+    }
     out.position = vec4<f32>(in.world_frag_pos,textureSample(t_roughness,t_sampler,in.v_tex_coord).b * material_uniforms.roughness_metallic_double_sided.y);
 
       var normal_map:vec3<f32> = textureSample(t_normal,t_sampler,in.v_tex_coord).xyz;
@@ -114,7 +53,13 @@ fn fs_main(in: VertexOutputs, [[builtin(front_facing)]] front_facing: bool) -> G
     var bitangent: vec3<f32> = cross(tangent,frag_normal); // * in.tangent.w;
     //tangent = normalize(tangent - dot(tangent,frag_normal) * frag_normal);
     normal_map= normalize(mat3x3<f32>(tangent,bitangent,frag_normal) * normal_map);
-    out.specular = vec4<f32>((textureSample(t_emissive,t_sampler,in.v_tex_coord).xyz*material_uniforms.emissive_color.xyz) ,1.0);
-    out.normal = vec4<f32>(normal_map,textureSample(t_roughness,t_sampler,in.v_tex_coord).r * material_uniforms.roughness_metallic_double_sided.x);
+    out.specular = vec4<f32>((textureSample(t_emissive,t_sampler,in.v_tex_coord).xyz*material_uniforms.emissive_color.xyz) ,occulison);
+    // glTF stores roughness in the green and metalness in the blue channel.
+    out.normal = vec4<f32>(normal_map,textureSample(t_roughness,t_sampler,in.v_tex_coord).g * material_uniforms.roughness_metallic_double_sided.x);
+    // masked texels are cut out after sampling, the derivatives stay in uniform control flow.
+    let alpha = material_uniforms.alpha_cutoff_mode_transmission;
+    if (alpha.y == 1.0 && material_uniforms.base_color_factor.w * texture_color.w < alpha.x) {
+        discard;
+    }
     return out;
 }
\ No newline at end of file
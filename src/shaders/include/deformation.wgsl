@@ -0,0 +1,71 @@
+// morph targets and skinning of the vertex stages, the deformation buffers are bindings 7 to 10 of group 0.
+
+struct JointMatrices {
+    elements: array<mat4x4<f32> >;
+};
+struct InstanceDeformation {
+    joint_offset: u32;
+    morph_weight_offset: u32;
+    morph_target_count: u32;
+    padding: u32;
+};
+struct InstanceDeformations {
+    elements: array<InstanceDeformation>;
+};
+struct MorphTargetDelta {
+    position: vec4<f32>;
+    normal: vec4<f32>;
+};
+struct MorphTargets {
+    elements: array<MorphTargetDelta>;
+};
+struct MorphWeights {
+    elements: array<f32>;
+};
+
+[[group(0)
+,binding(7)]]
+var<storage,read> joint_matrices: JointMatrices;
+
+[[group(0)
+,binding(8)]]
+var<storage,read> instance_deformations: InstanceDeformations;
+
+[[group(0)
+,binding(9)]]
+var<storage,read> morph_targets: MorphTargets;
+
+[[group(0)
+,binding(10)]]
+var<storage,read> morph_weights: MorphWeights;
+
+// the deltas of a vertex are next to each other, vertices without morph targets have the maximum index.
+fn morph(morph_index: u32, index: u32, v: vec3<f32>, is_normal: bool) -> vec3<f32> {
+    let deformation = instance_deformations.elements[index];
+    if (morph_index == 0xffffffffu) {
+        return v;
+    }
+    var morphed = v;
+    for (var i: u32 = 0u; i < deformation.morph_target_count; i = i + 1u) {
+        let delta = morph_targets.elements[morph_index + i];
+        let weight = morph_weights.elements[deformation.morph_weight_offset + i];
+        if (is_normal) {
+            morphed = morphed + delta.normal.xyz * weight;
+        } else {
+            morphed = morphed + delta.position.xyz * weight;
+        }
+    }
+    return morphed;
+}
+
+// joint indices are packed as 4 bytes, unskinned instances have the maximum offset.
+fn skin(joint_id: u32, joint_weight: vec4<f32>, index: u32, v: vec4<f32>) -> vec4<f32> {
+    let joint_offset = instance_deformations.elements[index].joint_offset;
+    if (joint_offset == 0xffffffffu) {
+        return v;
+    }
+    return (joint_matrices.elements[joint_offset + (joint_id & 0xffu)] * v) * joint_weight.x
+        + (joint_matrices.elements[joint_offset + ((joint_id >> 8u) & 0xffu)] * v) * joint_weight.y
+        + (joint_matrices.elements[joint_offset + ((joint_id >> 16u) & 0xffu)] * v) * joint_weight.z
+        + (joint_matrices.elements[joint_offset + ((joint_id >> 24u) & 0xffu)] * v) * joint_weight.w;
+}
@@ -0,0 +1,29 @@
+// fullscreen triangle and the bindings shared by the post-processing effects.
+struct FullscreenOutput {
+    [[builtin(position)]] position: vec4<f32>;
+    [[location(0)]] uv: vec2<f32>;
+};
+struct PostProcessUniforms {
+    parameters: vec4<f32>;
+};
+
+[[group(0)
+,binding(0)]]
+var source_texture: texture_2d<f32>;
+[[group(0)
+,binding(1)]]
+var source_sampler: sampler;
+[[group(0)
+,binding(2)]]
+var<uniform> uniforms: PostProcessUniforms;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> FullscreenOutput {
+    // one triangle covering the screen.
+    let x = f32(i32(vertex_index) / 2) * 4.0 - 1.0;
+    let y = f32(i32(vertex_index) & 1) * 4.0 - 1.0;
+    var out: FullscreenOutput;
+    out.position = vec4<f32>(x, y, 0.0, 1.0);
+    out.uv = vec2<f32>(x * 0.5 + 0.5, 0.5 - y * 0.5);
+    return out;
+}
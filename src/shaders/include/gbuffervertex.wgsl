@@ -0,0 +1,90 @@
+// vertex stage of the gbuffer, shared by the glTF and the custom materials.
+
+struct GBufferInputs {
+[[location(0)]] a_pos: vec3<f32>;
+[[location(1)]] a_normal: vec3<f32>;
+[[location(2)]] tangent: vec4<f32>;
+[[location(3)]] tex_coord: vec2<f32>;
+[[location(4)]] vertex_color: u32;
+[[location(5)]] joint_weight: vec4<f32>;
+[[location(6)]] joint_id: u32;
+[[location(7)]] morph_index: u32;
+[[builtin(instance_index)]] instance_index: u32;
+};
+
+struct VertexOutputs {
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] v_tex_coord: vec2<f32>;
+    [[location(1)]] world_frag_pos: vec3<f32>;
+    [[location(2)]] tangent: vec4<f32>;
+    [[location(3)]] normal: vec3<f32>;
+    // level of detail plus one when the levels are shown, zero otherwise.
+    [[location(4), interpolate(flat)]] lod_level: u32;
+};
+
+
+struct GBufferOutputs {
+    [[location(0)]] position: vec4<f32>;
+    [[location(1)]] normal: vec4<f32>;
+    [[location(2)]] specular:vec4<f32>;
+    [[location(3)]] albedo: vec4<f32>;
+};
+struct Globals {
+    u_view_position: vec4<f32>;
+    u_view_proj: mat4x4<f32>;
+    lights_num: vec4<u32>;
+};
+
+[[group(0)
+,binding(0)]]
+var<uniform> globals: Globals;
+
+struct Transforms {
+ elements: array<mat4x4<f32> >;
+}; 
+struct Normals {
+    elements: array<mat4x4<f32> >;
+};
+
+[[group(0)
+,binding(1)]]
+var<storage,read> transform: Transforms;
+
+[[group(0)
+,binding(2)]]
+var<storage,read> normals: Normals;
+
+#include "include/deformation.wgsl"
+
+struct VisibleInstances {
+    elements: array<u32>;
+};
+// the culled draws index into the visible instances which hold the index of the instance's transform.
+[[group(0)
+,binding(12)]]
+var<storage,read> visible_instances: VisibleInstances;
+// the frustum culling stores the level of detail above the instance's index when the levels are shown.
+let LOD_LEVEL_SHIFT: u32 = 28u;
+
+[[stage(vertex)]]
+fn vs_main(in: GBufferInputs) -> VertexOutputs {
+    var output: VertexOutputs;
+    let visible = visible_instances.elements[in.instance_index];
+    let index = visible & ((1u << LOD_LEVEL_SHIFT) - 1u);
+    output.lod_level = visible >> LOD_LEVEL_SHIFT;
+    output.v_tex_coord = in.tex_coord; 
+    var model_matrix: mat4x4<f32> = transform.elements[index];
+    var normal: mat4x4<f32> = normals.elements[index];
+    // morph targets are applied in the mesh's bind pose, before skinning.
+    let morphed_position = morph(in.morph_index, index, in.a_pos, false);
+    let morphed_normal = morph(in.morph_index, index, in.a_normal, true);
+    let skinned_tangent = skin(in.joint_id, in.joint_weight, index, vec4<f32>(in.tangent.xyz,0.0));
+    let skinned_normal = skin(in.joint_id, in.joint_weight, index, vec4<f32>(morphed_normal,0.0));
+    let frag_tangent = normalize(normal * vec4<f32>(skinned_tangent.xyz,in.tangent.w));
+    output.normal = normalize(vec3<f32>((normal * skinned_normal).xyz));
+    output.tangent = frag_tangent;   
+    var model_space: vec4<f32>  = model_matrix * skin(in.joint_id, in.joint_weight, index, vec4<f32>(morphed_position,1.0));
+    output.world_frag_pos = model_space.xyz;    
+    output.pos= globals.u_view_proj* model_space;
+    return output;
+}
@@ -0,0 +1,131 @@
+// physically based shading of the lights and the environment, shared by the deferred and the transparent shading.
+// The lights are bound to group 2 and the environment to group 3, see `include/shadows.wgsl` for the shadows.
+
+#include "include/lights.wgsl"
+
+struct DirectionalLight {
+    direction: vec4<f32>;
+    color: vec4<f32>;
+};
+
+struct PointLightContainer {
+    elements: array<PointLight>;
+};
+
+struct SpotLightContainer {
+    elements: array<SpotLight>;
+};
+
+[[group(2)
+,binding(0)]]
+var<uniform> dirLight: DirectionalLight;
+
+[[group(2)
+,binding(1)]]
+var<storage,read> pointLights: PointLightContainer;
+
+[[group(2)
+,binding(2)]]
+var<storage,read> spotLights: SpotLightContainer;
+
+#include "include/shadows.wgsl"
+
+[[group(3)
+,binding(0)]]
+var irradiance_map: texture_cube<f32>;
+[[group(3)
+,binding(1)]]
+var prefiltered_map: texture_cube<f32>;
+[[group(3)
+,binding(2)]]
+var brdf_lut: texture_2d<f32>;
+[[group(3)
+,binding(3)]]
+var environment_sampler: sampler;
+
+let PI: f32 = 3.14159265359;
+// roughness of the last mip level of the prefiltered environment map.
+let MAX_REFLECTION_LOD: f32 = 4.0;
+
+fn distribution_ggx(n_dot_h: f32, roughness: f32) -> f32 {
+    let a = roughness * roughness;
+    let a2 = a * a;
+    let denom = n_dot_h * n_dot_h * (a2 - 1.0) + 1.0;
+    return a2 / (PI * denom * denom);
+}
+
+fn geometry_schlick_ggx(n_dot_x: f32, roughness: f32) -> f32 {
+    let r = roughness + 1.0;
+    let k = r * r / 8.0;
+    return n_dot_x / (n_dot_x * (1.0 - k) + k);
+}
+
+fn fresnel_schlick(cos_theta: f32, f0: vec3<f32>) -> vec3<f32> {
+    return f0 + (vec3<f32>(1.0) - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+fn fresnel_schlick_roughness(cos_theta: f32, f0: vec3<f32>, roughness: f32) -> vec3<f32> {
+    return f0 + (max(vec3<f32>(1.0 - roughness), f0) - f0) * pow(clamp(1.0 - cos_theta, 0.0, 1.0), 5.0);
+}
+
+// dielectrics reflect 4% at normal incidence, metals tint the reflection with their base color.
+fn base_reflectivity(base_color: vec3<f32>, metallic: f32) -> vec3<f32> {
+    return mix(vec3<f32>(0.04), base_color, vec3<f32>(metallic));
+}
+
+// Cook-Torrance reflectance of a single light, the radiance already contains the light's attenuation.
+fn calcBrdf(normal: vec3<f32>, view_dir: vec3<f32>, light_dir: vec3<f32>, radiance: vec3<f32>, base_color: vec3<f32>, metallic: f32, roughness: f32) -> vec3<f32> {
+    let half_dir = normalize(view_dir + light_dir);
+    let n_dot_l = max(dot(normal, light_dir), 0.0);
+    let n_dot_v = max(dot(normal, view_dir), 0.0001);
+    let f = fresnel_schlick(max(dot(half_dir, view_dir), 0.0), base_reflectivity(base_color, metallic));
+    let d = distribution_ggx(max(dot(normal, half_dir), 0.0), roughness);
+    let g = geometry_schlick_ggx(n_dot_v, roughness) * geometry_schlick_ggx(n_dot_l, roughness);
+    let specular_term = d * g * f / (4.0 * n_dot_v * n_dot_l + 0.0001);
+    let k_d = (vec3<f32>(1.0) - f) * (1.0 - metallic);
+    return (k_d * base_color / PI + specular_term) * radiance * n_dot_l;
+}
+
+fn calcPointLightContribution(light: PointLight, position: vec3<f32>, normal: vec3<f32>, view_dir: vec3<f32>, base_color: vec3<f32>, metallic: f32, roughness: f32) -> vec3<f32> {
+    let light_direction = normalize(light.position.xyz - position);
+    let dist = length(light.position.xyz - position);
+    // falls off to zero at the light's radius, the same radius is used for culling.
+    let attenuation = pow(1.0 - dist / light.radius, 2.0) * get_point_light_shadow(light, position, normal);
+    return calcBrdf(normal, view_dir, light_direction, light.color * attenuation, base_color, metallic, roughness);
+}
+fn calcSpotLightContribution(light: SpotLight, position: vec3<f32>, normal: vec3<f32>, view_dir: vec3<f32>, base_color: vec3<f32>, metallic: f32, roughness: f32) -> vec3<f32> {
+    let light_direction = normalize(light.position.xyz - position);
+    let dist = length(light.position.xyz - position);
+    if(dist > light.radius)
+    {
+        return vec3<f32>(0.0);
+    }
+    let attenuation = pow(1.0 - dist / light.radius, 2.0);
+    // fades out between the inner and outer cone, the cutoffs are cosines.
+    let theta = dot(-light_direction, normalize(light.direction.xyz));
+    let intensity = clamp((theta - light.cutoffs.y) / max(light.cutoffs.x - light.cutoffs.y, 0.0001), 0.0, 1.0);
+    var shadow = 1.0;
+    if(light.shadow_index >= 0)
+    {
+        shadow = get_atlas_shadow_value(light.shadow_index, position, normal, dist);
+    }
+    return calcBrdf(normal, view_dir, light_direction, light.color * attenuation * intensity * shadow, base_color, metallic, roughness);
+}
+fn calcDirLightContribution(normal: vec3<f32>, view_direction: vec3<f32>, base_color: vec3<f32>, metallic: f32, roughness: f32, shadow: f32) -> vec3<f32> {
+    let light_direction = normalize(dirLight.direction.xyz);
+    return calcBrdf(normal, view_direction, light_direction, dirLight.color.xyz * shadow, base_color, metallic, roughness);
+}
+
+// image based lighting from the skybox, the diffuse part comes from the irradiance map
+// and the specular part from the prefiltered map combined with the BRDF lookup table.
+fn calcAmbientContribution(normal: vec3<f32>, view_direction: vec3<f32>, base_color: vec3<f32>, metallic: f32, roughness: f32, occlusion: f32) -> vec3<f32> {
+    let n_dot_v = max(dot(normal, view_direction), 0.0);
+    let f = fresnel_schlick_roughness(n_dot_v, base_reflectivity(base_color, metallic), roughness);
+    let k_d = (vec3<f32>(1.0) - f) * (1.0 - metallic);
+    let diffuse = textureSampleLevel(irradiance_map, environment_sampler, normal, 0.0).rgb * base_color;
+    let reflection = reflect(-view_direction, normal);
+    let prefiltered = textureSampleLevel(prefiltered_map, environment_sampler, reflection, roughness * MAX_REFLECTION_LOD).rgb;
+    let brdf = textureSampleLevel(brdf_lut, environment_sampler, vec2<f32>(n_dot_v, roughness), 0.0).rg;
+    let specular_term = prefiltered * (f * brdf.x + vec3<f32>(brdf.y));
+    return (k_d * diffuse + specular_term) * occlusion;
+}
@@ -0,0 +1,22 @@
+// the light layouts of the storage buffers, they have to match `SpotLightRaw` and `PointLightRaw`.
+struct SpotLight {
+    position: vec4<f32>;
+    direction: vec4<f32>;
+    color: vec3<f32>;
+    radius:f32;
+    cutoffs: vec4<f32>;  // X inner , Y outer
+    shadow_index: i32; // first tile in the shadow atlas, -1 without shadows
+    padding1: u32;
+    padding2: u32;
+    padding3: u32;
+};
+
+struct PointLight {
+    position: vec4<f32>;
+    color: vec3<f32>;
+    radius: f32;
+    shadow_index: i32; // first tile in the shadow atlas, -1 without shadows
+    padding1: u32;
+    padding2: u32;
+    padding3: u32;
+};
@@ -0,0 +1,214 @@
+// cascaded shadows of the directional light and the shadow atlas of the point and spot lights.
+// The including shader declares `globals` and defines SHADOW_GROUP, the group of its cascade bindings.
+
+#include "include/lights.wgsl"
+
+[[group(SHADOW_GROUP)
+,binding(3)]]
+var t_shadow: texture_depth_2d_array;
+[[group(SHADOW_GROUP)
+,binding(3)]]
+var t_shadow_single: texture_depth_2d;
+[[group(SHADOW_GROUP)
+,binding(4)]]
+var s_shadow: sampler_comparison;
+
+struct CascadeTransforms{
+    elements: array<mat4x4<f32>>;
+};
+
+struct CascadeLengths {
+    elements: array<f32>;
+};
+
+[[group(SHADOW_GROUP)
+,binding(5)]]
+var<storage,read> cascade_transforms: CascadeTransforms;
+[[group(SHADOW_GROUP)
+,binding(6)]]
+var<storage,read> cascade_lengths: CascadeLengths;
+
+struct ShadowSettings {
+    cascade_count: u32;
+    pcf_kernel: u32;
+    bias: f32;
+    // part of the shadow texture covered by a cascade, they are rendered into its top left corner.
+    resolution_scale: f32;
+    show_cascades: u32;
+    padding1: u32;
+    padding2: u32;
+    padding3: u32;
+};
+[[group(SHADOW_GROUP)
+,binding(11)]]
+var<uniform> shadow_settings: ShadowSettings;
+
+struct ShadowAtlasTile {
+    view_proj: mat4x4<f32>;
+    // offset and size of the tile in texture coordinates.
+    atlas_rect: vec4<f32>;
+};
+struct ShadowAtlasTiles {
+    elements: array<ShadowAtlasTile>;
+};
+[[group(2)
+,binding(3)]]
+var<storage,read> shadow_atlas_tiles: ShadowAtlasTiles;
+[[group(2)
+,binding(4)]]
+var shadow_atlas: texture_depth_2d;
+
+// the camera's projection stores the view space depth in w.
+fn get_view_depth(coords: vec4<f32>) -> f32
+{
+    return (globals.u_view_proj * coords).w;
+}
+
+fn get_cascade_index(view_depth: f32) -> i32
+{
+    let count = i32(shadow_settings.cascade_count);
+    for(var i:i32 = 0; i < count; i = i + 1)
+    {
+        if(view_depth < cascade_lengths.elements[i])
+        {
+            return i;
+        }
+    }
+    return count - 1;
+}
+
+// steeper surfaces need a larger bias to avoid shadow acne.
+fn get_shadow_bias(n_dot_l: f32) -> f32
+{
+    return max(shadow_settings.bias * (1.0 - n_dot_l), shadow_settings.bias * 0.1);
+}
+
+fn get_shadow_uv(light_coords: vec4<f32>) -> vec2<f32>
+{
+    let flip = vec2<f32>(0.5,-0.5);
+    let light_local = light_coords.xy * flip / light_coords.w + vec2<f32>(0.5,0.5);
+    return light_local * shadow_settings.resolution_scale;
+}
+
+fn get_cascade_tint(layer: i32) -> vec3<f32>
+{
+    var tints = array<vec3<f32>,4>(
+        vec3<f32>(1.0, 0.25, 0.25),
+        vec3<f32>(0.25, 1.0, 0.25),
+        vec3<f32>(0.25, 0.25, 1.0),
+        vec3<f32>(1.0, 1.0, 0.25)
+    );
+    return tints[layer % 4];
+}
+
+fn get_shadow_value(coords:vec4<f32>, n_dot_l: f32) -> f32
+{
+    let view_depth = get_view_depth(coords);
+    let layer = get_cascade_index(view_depth);
+    // no shadows past the shadow distance.
+    if(view_depth > cascade_lengths.elements[layer])
+    {
+        return 1.0;
+    }
+    let light_coords = cascade_transforms.elements[layer] * coords;
+    if(light_coords.w <= 0.0)
+    {
+        return 1.0;
+    }
+    let texel_size = 1.0 / vec2<f32>(textureDimensions(t_shadow));
+    let uv = get_shadow_uv(light_coords);
+    let depth = light_coords.z / light_coords.w - get_shadow_bias(n_dot_l);
+    // samples outside of the rendered part of the layer would always be lit.
+    let max_uv = vec2<f32>(shadow_settings.resolution_scale) - texel_size;
+    let kernel = i32(shadow_settings.pcf_kernel);
+    var shadow = 0.0;
+    for(var x:i32 = -kernel; x <= kernel; x = x + 1)
+    {
+        for(var y:i32 = -kernel; y <= kernel; y = y + 1)
+        {
+            let offset_uv = clamp(uv + vec2<f32>(f32(x), f32(y)) * texel_size, vec2<f32>(0.0), max_uv);
+            shadow = shadow + textureSampleCompareLevel(t_shadow,s_shadow,offset_uv,layer,depth);
+        }
+    }
+    return shadow / f32((2 * kernel + 1) * (2 * kernel + 1));
+}
+
+fn get_shadow_value_web(light_coords:vec4<f32>, n_dot_l: f32) -> f32
+{
+    if(light_coords.w <= 0.0)
+    {
+        return 1.0;
+    }
+    let texel_size = 1.0 / vec2<f32>(textureDimensions(t_shadow_single));
+    let uv = get_shadow_uv(light_coords);
+    let depth = light_coords.z / light_coords.w - get_shadow_bias(n_dot_l);
+    let max_uv = vec2<f32>(shadow_settings.resolution_scale) - texel_size;
+    let kernel = i32(shadow_settings.pcf_kernel);
+    var shadow = 0.0;
+    for(var x:i32 = -kernel; x <= kernel; x = x + 1)
+    {
+        for(var y:i32 = -kernel; y <= kernel; y = y + 1)
+        {
+            let offset_uv = clamp(uv + vec2<f32>(f32(x), f32(y)) * texel_size, vec2<f32>(0.0), max_uv);
+            shadow = shadow + textureSampleCompareLevel(t_shadow_single,s_shadow,offset_uv,depth);
+        }
+    }
+    return shadow / f32((2 * kernel + 1) * (2 * kernel + 1));
+}
+// the position is moved along the normal by the size of a texel at the light's distance
+// so surfaces don't shadow themselves.
+fn get_atlas_shadow_value(tile_index: i32, position: vec3<f32>, normal: vec3<f32>, light_distance: f32) -> f32
+{
+    let tile = shadow_atlas_tiles.elements[tile_index];
+    let atlas_size = vec2<f32>(textureDimensions(shadow_atlas));
+    let texel_size = 1.0 / atlas_size;
+    let texel_world_size = 2.0 * light_distance / (tile.atlas_rect.z * atlas_size.x);
+    let light_coords = tile.view_proj * vec4<f32>(position + normal * texel_world_size * 1.5, 1.0);
+    if(light_coords.w <= 0.0)
+    {
+        return 1.0;
+    }
+    let flip = vec2<f32>(0.5,-0.5);
+    let light_local = light_coords.xy * flip / light_coords.w + vec2<f32>(0.5,0.5);
+    let uv = tile.atlas_rect.xy + light_local * tile.atlas_rect.zw;
+    let depth = light_coords.z / light_coords.w;
+    // the kernel can't reach into the neighbouring tiles.
+    let min_uv = tile.atlas_rect.xy + texel_size * 0.5;
+    let max_uv = tile.atlas_rect.xy + tile.atlas_rect.zw - texel_size * 0.5;
+    let kernel = i32(shadow_settings.pcf_kernel);
+    var shadow = 0.0;
+    for(var x:i32 = -kernel; x <= kernel; x = x + 1)
+    {
+        for(var y:i32 = -kernel; y <= kernel; y = y + 1)
+        {
+            let offset_uv = clamp(uv + vec2<f32>(f32(x), f32(y)) * texel_size, min_uv, max_uv);
+            shadow = shadow + textureSampleCompareLevel(shadow_atlas,s_shadow,offset_uv,depth);
+        }
+    }
+    return shadow / f32((2 * kernel + 1) * (2 * kernel + 1));
+}
+
+fn get_point_light_shadow(light: PointLight, position: vec3<f32>, normal: vec3<f32>) -> f32
+{
+    if(light.shadow_index < 0)
+    {
+        return 1.0;
+    }
+    // the cube faces follow each other in the +X, -X, +Y, -Y, +Z, -Z order.
+    let to_position = position - light.position.xyz;
+    let distances = abs(to_position);
+    var face: i32 = 0;
+    if(distances.x >= distances.y && distances.x >= distances.z)
+    {
+        face = select(1, 0, to_position.x > 0.0);
+    }
+    else if(distances.y >= distances.z)
+    {
+        face = select(3, 2, to_position.y > 0.0);
+    }
+    else
+    {
+        face = select(5, 4, to_position.z > 0.0);
+    }
+    return get_atlas_shadow_value(light.shadow_index + face, position, normal, length(to_position));
+}
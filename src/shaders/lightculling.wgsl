@@ -1,19 +1,7 @@
 
 
 
-struct SpotLight {
-    position: vec4<f32>;
-    direction: vec4<f32>;
-    color: vec3<f32>;
-    radius:f32;
-    cutoffs: vec4<f32>;  // X inner , Y outer
-};
-
-struct PointLight {
-    position: vec4<f32>;
-    color: vec3<f32>;
-    radius: f32;
-};
+#include "include/lights.wgsl"
 
 struct TileInfo {
      tile_size: i32;
@@ -24,7 +12,7 @@ struct TileInfo {
 };
 
 // determines the number of lights a tile can be influenced by.
-let num_tile_light_slot:u32 = 128u; // needs to be a constant as you can't create a runtime array with an atomic next to it. pipeline overrides would solve this issue this being a constant but they are not implemeneted.
+let num_tile_light_slot:u32 = TILE_LIGHT_SLOTS; // needs to be a constant as you can't create a runtime array with an atomic next to it. pipeline overrides would solve this issue this being a constant but they are not implemeneted.
 
 // Not possible as of 22/10/2021 use uniforms instead
 // @override(0)
@@ -89,24 +77,49 @@ var<storage,read_write> tile_light_data: Tiles;
  [[stage(compute),workgroup_size(64,1,1)]]
 fn main([[builtin(global_invocation_id)]] Global_Invocation_Id: vec3<u32>) {
      let index = Global_Invocation_Id.x;
-     if(index >= globals.lights_num.x)
+     // spot lights follow the point lights, the tiles store the ids in the same order.
+     if(index >= globals.lights_num.x + globals.lights_num.y)
      {
          return;
      }
      let proj = light_culling_uniforms.u_proj;
 
 
+#ifdef TARGET_WEB
+     let view_near =  -proj[3][2] / (-1.0 + proj[2][2]);
+#else
      let view_near =  -proj[3][2] / (proj[2][2]);
+#endif
      let view_far = -proj[3][2] / (1.0 + proj[2][2]);
 
-    var lightPos = pointLights.elements[index].position;
+    var lightPos: vec4<f32>;
+    var lightRadius: f32;
+    if(index < globals.lights_num.x)
+    {
+        lightPos = pointLights.elements[index].position;
+        lightRadius = pointLights.elements[index].radius;
+    }
+    else
+    {
+        // bounding sphere of the cone centered halfway along its axis, the farthest points are either the apex or the rim.
+        let spot_light = spotLights.elements[index - globals.lights_num.x];
+        let direction = normalize(spot_light.direction.xyz);
+        let cos_outer = spot_light.cutoffs.y;
+        let sin_outer = sqrt(max(1.0 - cos_outer * cos_outer, 0.0));
+        let half_length = spot_light.radius * 0.5;
+        lightPos = vec4<f32>(spot_light.position.xyz + direction * half_length, 1.0);
+        lightRadius = max(half_length, spot_light.radius * length(vec2<f32>(cos_outer - 0.5, sin_outer)));
+    }
         lightPos = light_culling_uniforms.u_view * lightPos;
         lightPos = lightPos / lightPos.w;
-    var lightRadius: f32 = pointLights.elements[index].radius;
     var bounding_box_min = lightPos - vec4<f32>(vec3<f32>(lightRadius),0.0);
     var bounding_box_max = lightPos + vec4<f32>(vec3<f32>(lightRadius),0.0);
     var frustums: array<vec4<f32>,6>;
+#ifdef TARGET_WEB
+    frustums[4] = vec4<f32>(0.0,0.0,-1.0,view_near); // near plane
+#else
     frustums[4] = vec4<f32>(0.0,0.0,0.0,-view_near); // near
+#endif
     frustums[5] = vec4<f32>(0.0,0.0,1.0,-view_far); // far 
 
     for(var y: i32 = 0; y < tileInfo.tile_count_y; y= y +1)
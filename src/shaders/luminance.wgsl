@@ -0,0 +1,84 @@
+// computes the automatic exposure from a histogram of the HDR scene's luminance.
+// the first bin counts the black pixels, the others split the log2 luminance range evenly.
+
+struct ToneMappingUniforms {
+    min_log_luminance: f32;
+    log_luminance_range: f32;
+    // blend factor between the previous and the current average luminance.
+    adaptation: f32;
+    exposure: f32;
+    pixel_count: u32;
+    tone_operator: u32;
+    auto_exposure: u32;
+    padding: u32;
+};
+let BIN_COUNT: u32 = HISTOGRAM_BIN_COUNT;
+
+struct Histogram {
+    bins: array<atomic<u32>,BIN_COUNT>;
+};
+struct AdaptedLuminance {
+    value: f32;
+};
+
+[[group(0)
+,binding(0)]]
+var hdr_scene: texture_2d<f32>;
+[[group(0)
+,binding(1)]]
+var<uniform> uniforms: ToneMappingUniforms;
+[[group(0)
+,binding(2)]]
+var<storage,read_write> histogram: Histogram;
+[[group(0)
+,binding(3)]]
+var<storage,read_write> adapted_luminance: AdaptedLuminance;
+
+var<workgroup> local_bins: array<atomic<u32>,BIN_COUNT>;
+var<workgroup> weighted_counts: array<f32,BIN_COUNT>;
+
+fn luminance_bin(color: vec3<f32>) -> u32 {
+    let luminance = dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+    if (luminance < 0.0001) {
+        return 0u;
+    }
+    let log_luminance = clamp((log2(luminance) - uniforms.min_log_luminance) / uniforms.log_luminance_range, 0.0, 1.0);
+    return u32(log_luminance * 254.0 + 1.0);
+}
+
+[[stage(compute),workgroup_size(16,16,1)]]
+fn build_histogram([[builtin(global_invocation_id)]] id: vec3<u32>, [[builtin(local_invocation_index)]] local_index: u32) {
+    atomicStore(&local_bins[local_index], 0u);
+    workgroupBarrier();
+    let size = textureDimensions(hdr_scene);
+    let coords = vec2<i32>(id.xy);
+    if (coords.x < size.x && coords.y < size.y) {
+        let color = textureLoad(hdr_scene, coords, 0).rgb;
+        atomicAdd(&local_bins[luminance_bin(color)], 1u);
+    }
+    workgroupBarrier();
+    atomicAdd(&histogram.bins[local_index], atomicLoad(&local_bins[local_index]));
+}
+
+// a single workgroup with one invocation per bin, the bins are cleared for the next frame.
+[[stage(compute),workgroup_size(256,1,1)]]
+fn average_luminance([[builtin(local_invocation_index)]] local_index: u32) {
+    let count = atomicExchange(&histogram.bins[local_index], 0u);
+    weighted_counts[local_index] = f32(count) * f32(local_index);
+    workgroupBarrier();
+    for (var stride: u32 = BIN_COUNT / 2u; stride > 0u; stride = stride / 2u) {
+        if (local_index < stride) {
+            weighted_counts[local_index] = weighted_counts[local_index] + weighted_counts[local_index + stride];
+        }
+        workgroupBarrier();
+    }
+    if (local_index == 0u) {
+        // the black pixels don't take part in the average.
+        let lit_pixels = max(f32(uniforms.pixel_count) - f32(count), 1.0);
+        let average_bin = weighted_counts[0] / lit_pixels - 1.0;
+        let average_log_luminance = average_bin / 254.0 * uniforms.log_luminance_range + uniforms.min_log_luminance;
+        let average = exp2(average_log_luminance);
+        let previous = adapted_luminance.value;
+        adapted_luminance.value = previous + (average - previous) * uniforms.adaptation;
+    }
+}
@@ -0,0 +1,66 @@
+// gbuffer shader of the custom materials, the engine puts the material's bindings and snippet between the markers.
+// the snippet defines `material`, which turns a `MaterialInput` into the surface written to the gbuffer.
+// the material's bindings are in group 1: the parameters are `material_parameters` at binding 0,
+// `material_sampler` is at binding 1 and the textures follow it in the order they were declared.
+#include "include/gbuffervertex.wgsl"
+
+struct MaterialInput {
+    uv: vec2<f32>;
+    world_position: vec3<f32>;
+    // the interpolated normal, flipped on back faces.
+    normal: vec3<f32>;
+    tangent: vec4<f32>;
+};
+
+struct MaterialOutput {
+    albedo: vec3<f32>;
+    // world space normal of the surface.
+    normal: vec3<f32>;
+    roughness: f32;
+    metallic: f32;
+    emissive: vec3<f32>;
+    occlusion: f32;
+};
+
+// a white dielectric surface, materials can start from it and change what they need.
+fn default_material_output(input: MaterialInput) -> MaterialOutput {
+    var output: MaterialOutput;
+    output.albedo = vec3<f32>(1.0, 1.0, 1.0);
+    output.normal = input.normal;
+    output.roughness = 1.0;
+    output.metallic = 0.0;
+    output.emissive = vec3<f32>(0.0, 0.0, 0.0);
+    output.occlusion = 1.0;
+    return output;
+}
+
+// MATERIAL_BEGIN
+fn material(input: MaterialInput) -> MaterialOutput {
+    return default_material_output(input);
+}
+// MATERIAL_END
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutputs, [[builtin(front_facing)]] front_facing: bool) -> GBufferOutputs {
+    var input: MaterialInput;
+    input.uv = in.v_tex_coord;
+    input.world_position = in.world_frag_pos;
+    input.normal = in.normal;
+    if (!front_facing) {
+        input.normal = -in.normal;
+    }
+    input.tangent = in.tangent;
+    let surface = material(input);
+
+    var out: GBufferOutputs;
+    out.albedo = vec4<f32>(surface.albedo, 1.0);
+    if (in.lod_level != 0u) {
+        // red, green, blue and yellow for the levels of detail, same order as the debug window.
+        var tints = array<vec3<f32>,4>(vec3<f32>(1.0, 0.0, 0.0), vec3<f32>(0.0, 1.0, 0.0), vec3<f32>(0.0, 0.0, 1.0), vec3<f32>(1.0, 1.0, 0.0));
+        out.albedo = vec4<f32>(mix(surface.albedo, tints[min(in.lod_level - 1u, 3u)], 0.6), 1.0);
+    }
+    out.position = vec4<f32>(in.world_frag_pos, surface.metallic);
+    out.normal = vec4<f32>(normalize(surface.normal), surface.roughness);
+    out.specular = vec4<f32>(surface.emissive, surface.occlusion);
+    return out;
+}
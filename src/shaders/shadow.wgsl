@@ -0,0 +1,132 @@
+struct ViewProj {
+    u_view_proj: mat4x4<f32>;
+};
+
+struct ShadowOutputs {
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] tex_coord: vec2<f32>;
+};
+
+struct MaterialUniforms {
+    base_color_factor: vec4<f32>;
+    roughness_metallic_double_sided: vec4<f32>;
+    emissive_color: vec4<f32>;
+    // alpha cutoff, alpha mode (0 opaque, 1 mask, 2 blend) and transmission factor.
+    alpha_cutoff_mode_transmission: vec4<f32>;
+};
+
+struct Transforms {
+ elements: array<mat4x4<f32>>;
+}; 
+struct JointMatrices {
+    elements: array<mat4x4<f32>>;
+};
+struct InstanceDeformation {
+    joint_offset: u32;
+    morph_weight_offset: u32;
+    morph_target_count: u32;
+    padding: u32;
+};
+struct InstanceDeformations {
+    elements: array<InstanceDeformation>;
+};
+struct MorphTargetDelta {
+    position: vec4<f32>;
+    normal: vec4<f32>;
+};
+struct MorphTargets {
+    elements: array<MorphTargetDelta>;
+};
+struct MorphWeights {
+    elements: array<f32>;
+};
+[[group(0)
+,binding(1)]]
+var<storage,read> transform: Transforms;
+
+[[group(0)
+,binding(0)]]
+var<uniform> viewProj: ViewProj;
+
+[[group(0)
+,binding(2)]]
+var<storage,read> joint_matrices: JointMatrices;
+
+[[group(0)
+,binding(3)]]
+var<storage,read> instance_deformations: InstanceDeformations;
+
+[[group(0)
+,binding(4)]]
+var<storage,read> morph_targets: MorphTargets;
+
+[[group(0)
+,binding(5)]]
+var<storage,read> morph_weights: MorphWeights;
+
+struct VisibleInstances {
+    elements: array<u32>;
+};
+// the culled draws index into the visible instances which hold the index of the instance's transform.
+[[group(0)
+,binding(6)]]
+var<storage,read> visible_instances: VisibleInstances;
+// the frustum culling stores the level of detail above the instance's index when the levels are shown.
+let LOD_LEVEL_SHIFT: u32 = 28u;
+
+// the deltas of a vertex are next to each other, vertices without morph targets have the maximum index.
+fn morph(morph_index: u32, index: u32, v: vec3<f32>, is_normal: bool) -> vec3<f32> {
+    let deformation = instance_deformations.elements[index];
+    if (morph_index == 0xffffffffu) {
+        return v;
+    }
+    var morphed = v;
+    for (var i: u32 = 0u; i < deformation.morph_target_count; i = i + 1u) {
+        let delta = morph_targets.elements[morph_index + i];
+        let weight = morph_weights.elements[deformation.morph_weight_offset + i];
+        if (is_normal) {
+            morphed = morphed + delta.normal.xyz * weight;
+        } else {
+            morphed = morphed + delta.position.xyz * weight;
+        }
+    }
+    return morphed;
+}
+
+// joint indices are packed as 4 bytes, unskinned instances have the maximum offset.
+fn skin(joint_id: u32, joint_weight: vec4<f32>, index: u32, v: vec4<f32>) -> vec4<f32> {
+    let joint_offset = instance_deformations.elements[index].joint_offset;
+    if (joint_offset == 0xffffffffu) {
+        return v;
+    }
+    return (joint_matrices.elements[joint_offset + (joint_id & 0xffu)] * v) * joint_weight.x
+        + (joint_matrices.elements[joint_offset + ((joint_id >> 8u) & 0xffu)] * v) * joint_weight.y
+        + (joint_matrices.elements[joint_offset + ((joint_id >> 16u) & 0xffu)] * v) * joint_weight.z
+        + (joint_matrices.elements[joint_offset + ((joint_id >> 24u) & 0xffu)] * v) * joint_weight.w;
+}
+
+[[stage(vertex)]]
+fn vs_main([[location(0)]] a_pos: vec3<f32>, [[location(3)]] tex_coord: vec2<f32>, [[location(5)]] joint_weight: vec4<f32>, [[location(6)]] joint_id: u32, [[location(7)]] morph_index: u32, [[builtin(instance_index)]] instance_index: u32) -> ShadowOutputs {
+    var output: ShadowOutputs;
+    let index = visible_instances.elements[instance_index] & ((1u << LOD_LEVEL_SHIFT) - 1u);
+    output.pos = viewProj.u_view_proj * transform.elements[index] * skin(joint_id, joint_weight, index, vec4<f32>(morph(morph_index, index, a_pos, false),1.0));
+    output.tex_coord = tex_coord;
+    return output;
+}
+
+[[group(1),binding(0)]]
+var t_texture: texture_2d<f32>;
+[[group(1),binding(1)]]
+var t_sampler: sampler;
+[[group(1),binding(6)]]
+var<uniform> material_uniforms: MaterialUniforms;
+
+// masked texels don't cast shadows, the other alpha modes write every texel's depth.
+[[stage(fragment)]]
+fn fs_main(in: ShadowOutputs) {
+    let alpha = material_uniforms.alpha_cutoff_mode_transmission;
+    let texture_alpha = textureSample(t_texture, t_sampler, in.tex_coord).a;
+    if (alpha.y == 1.0 && material_uniforms.base_color_factor.w * texture_alpha < alpha.x) {
+        discard;
+    }
+}
\ No newline at end of file
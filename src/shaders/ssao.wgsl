@@ -0,0 +1,91 @@
+// screen-space ambient occlusion from the world space positions and normals of the gbuffer.
+// the samples of a hemisphere around the normal are projected to the screen and compared
+// against the distance of the gbuffer's position there, the kernel is rotated in a 4x4 pattern the blur removes.
+
+let KERNEL_SIZE: u32 = SSAO_KERNEL_SIZE;
+
+struct SsaoUniforms {
+    // hemisphere samples in tangent space, closer to the center towards the start.
+    kernel: array<vec4<f32>,KERNEL_SIZE>;
+    radius: f32;
+    bias: f32;
+    intensity: f32;
+    sample_count: u32;
+};
+struct Globals {
+    u_view_position: vec4<f32>;
+    u_view_proj: mat4x4<f32>;
+    lights_num: vec4<u32>;
+};
+
+[[group(0)
+,binding(0)]]
+var positions: texture_2d<f32>;
+[[group(0)
+,binding(1)]]
+var normals: texture_2d<f32>;
+[[group(0)
+,binding(2)]]
+var<uniform> uniforms: SsaoUniforms;
+[[group(0)
+,binding(3)]]
+var<uniform> globals: Globals;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> [[builtin(position)]] vec4<f32> {
+    // one triangle covering the screen.
+    let x = f32(i32(vertex_index) / 2) * 4.0 - 1.0;
+    let y = f32(i32(vertex_index) & 1) * 4.0 - 1.0;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+// rotation of the kernel as a fraction of a turn, neighbouring pixels get distant angles.
+fn get_rotation(coordinates: vec2<i32>) -> f32 {
+    var bayer = array<f32,16>(0.0, 8.0, 2.0, 10.0, 12.0, 4.0, 14.0, 6.0, 3.0, 11.0, 1.0, 9.0, 15.0, 7.0, 13.0, 5.0);
+    return bayer[(coordinates.x & 3) + (coordinates.y & 3) * 4] / 16.0;
+}
+
+[[stage(fragment)]]
+fn fs_main([[builtin(position)]] frag_pos: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    let coordinates = vec2<i32>(floor(frag_pos.xy));
+    let position = textureLoad(positions, coordinates, 0).xyz;
+    // nothing was rendered here.
+    if (position.z > 10000.0) {
+        return vec4<f32>(1.0);
+    }
+    let normal = normalize(textureLoad(normals, coordinates, 0).xyz);
+    let angle = get_rotation(coordinates) * 6.28318530718;
+    var random = vec3<f32>(cos(angle), sin(angle), 0.0);
+    if (abs(dot(random, normal)) > 0.99) {
+        random = vec3<f32>(0.0, cos(angle), sin(angle));
+    }
+    let tangent = normalize(random - normal * dot(random, normal));
+    let tbn = mat3x3<f32>(tangent, cross(normal, tangent), normal);
+
+    let size = vec2<f32>(textureDimensions(positions));
+    let frag_distance = distance(globals.u_view_position.xyz, position);
+    let sample_count = min(uniforms.sample_count, KERNEL_SIZE);
+    var occlusion = 0.0;
+    for (var i: u32 = 0u; i < sample_count; i = i + 1u) {
+        let sample_position = position + tbn * uniforms.kernel[i].xyz * uniforms.radius;
+        let clip = globals.u_view_proj * vec4<f32>(sample_position, 1.0);
+        if (clip.w <= 0.0) {
+            continue;
+        }
+        let ndc = clip.xy / clip.w;
+        let uv = vec2<f32>(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+        if (uv.x < 0.0 || uv.x >= 1.0 || uv.y < 0.0 || uv.y >= 1.0) {
+            continue;
+        }
+        let scene_position = textureLoad(positions, vec2<i32>(uv * size), 0).xyz;
+        let scene_distance = distance(globals.u_view_position.xyz, scene_position);
+        let sample_distance = distance(globals.u_view_position.xyz, sample_position);
+        // geometry far in front of the fragment doesn't occlude it.
+        let range = clamp(uniforms.radius / max(abs(frag_distance - scene_distance), 0.0001), 0.0, 1.0);
+        if (scene_distance <= sample_distance - uniforms.bias) {
+            occlusion = occlusion + range * range * (3.0 - 2.0 * range);
+        }
+    }
+    let ambient_occlusion = pow(1.0 - occlusion / f32(max(sample_count, 1u)), uniforms.intensity);
+    return vec4<f32>(ambient_occlusion, ambient_occlusion, ambient_occlusion, 1.0);
+}
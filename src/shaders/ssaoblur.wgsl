@@ -0,0 +1,28 @@
+// averages the ambient occlusion over the 4x4 pixels the kernel rotations repeat in.
+
+[[group(0)
+,binding(0)]]
+var ambient_occlusion: texture_2d<f32>;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> [[builtin(position)]] vec4<f32> {
+    // one triangle covering the screen.
+    let x = f32(i32(vertex_index) / 2) * 4.0 - 1.0;
+    let y = f32(i32(vertex_index) & 1) * 4.0 - 1.0;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+[[stage(fragment)]]
+fn fs_main([[builtin(position)]] frag_pos: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    let coordinates = vec2<i32>(floor(frag_pos.xy));
+    let max_coordinates = vec2<i32>(textureDimensions(ambient_occlusion)) - vec2<i32>(1);
+    var sum = 0.0;
+    for (var y: i32 = -2; y < 2; y = y + 1) {
+        for (var x: i32 = -2; x < 2; x = x + 1) {
+            let texel = clamp(coordinates + vec2<i32>(x, y), vec2<i32>(0), max_coordinates);
+            sum = sum + textureLoad(ambient_occlusion, texel, 0).r;
+        }
+    }
+    let blurred = sum / 16.0;
+    return vec4<f32>(blurred, blurred, blurred, 1.0);
+}
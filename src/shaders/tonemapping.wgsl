@@ -0,0 +1,107 @@
+// exposes the HDR scene and maps it to the surface with the selected operator.
+
+struct ToneMappingUniforms {
+    min_log_luminance: f32;
+    log_luminance_range: f32;
+    adaptation: f32;
+    // in stops, compensates the automatic exposure when it's enabled.
+    exposure: f32;
+    pixel_count: u32;
+    // has to match the order of `ToneMappingOperator`.
+    tone_operator: u32;
+    auto_exposure: u32;
+    padding: u32;
+};
+struct AdaptedLuminance {
+    value: f32;
+};
+
+[[group(0)
+,binding(0)]]
+var hdr_scene: texture_2d<f32>;
+[[group(0)
+,binding(1)]]
+var<uniform> uniforms: ToneMappingUniforms;
+[[group(0)
+,binding(3)]]
+var<storage,read_write> adapted_luminance: AdaptedLuminance;
+
+let OPERATOR_ACES: u32 = 0u;
+let OPERATOR_REINHARD: u32 = 1u;
+// the average luminance is exposed to middle grey.
+let MIDDLE_GREY: f32 = 0.18;
+
+[[stage(vertex)]]
+fn vs_main([[builtin(vertex_index)]] vertex_index: u32) -> [[builtin(position)]] vec4<f32> {
+    // one triangle covering the screen.
+    let x = f32(i32(vertex_index) / 2) * 4.0 - 1.0;
+    let y = f32(i32(vertex_index) & 1) * 4.0 - 1.0;
+    return vec4<f32>(x, y, 0.0, 1.0);
+}
+
+// Stephen Hill's fit of the ACES reference rendering and output transforms.
+fn aces(color: vec3<f32>) -> vec3<f32> {
+    let input_matrix = mat3x3<f32>(
+        vec3<f32>(0.59719, 0.07600, 0.02840),
+        vec3<f32>(0.35458, 0.90834, 0.13383),
+        vec3<f32>(0.04823, 0.01566, 0.83777)
+    );
+    let output_matrix = mat3x3<f32>(
+        vec3<f32>(1.60475, -0.10208, -0.00327),
+        vec3<f32>(-0.53108, 1.10813, -0.07276),
+        vec3<f32>(-0.07367, -0.00605, 1.07602)
+    );
+    let v = input_matrix * color;
+    let a = v * (v + 0.0245786) - 0.000090537;
+    let b = v * (0.983729 * v + 0.4329510) + 0.238081;
+    return clamp(output_matrix * (a / b), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn reinhard(color: vec3<f32>) -> vec3<f32> {
+    return color / (vec3<f32>(1.0) + color);
+}
+
+// polynomial approximation of the AgX base contrast curve.
+fn agx_contrast(x: vec3<f32>) -> vec3<f32> {
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    return 15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x - 0.00232;
+}
+
+fn agx(color: vec3<f32>) -> vec3<f32> {
+    let inset = mat3x3<f32>(
+        vec3<f32>(0.842479062253094, 0.0423282422610123, 0.0423756549057051),
+        vec3<f32>(0.0784335999999992, 0.878468636469772, 0.0784336),
+        vec3<f32>(0.0792237451477643, 0.0791661274605434, 0.879142973793104)
+    );
+    let outset = mat3x3<f32>(
+        vec3<f32>(1.19687900512017, -0.0528968517574562, -0.0529716355144438),
+        vec3<f32>(-0.0980208811401368, 1.15190312990417, -0.0980434501171241),
+        vec3<f32>(-0.0990297440797205, -0.0989611768448433, 1.15107367264116)
+    );
+    let min_ev = -12.47393;
+    let max_ev = 4.026069;
+    let log_color = clamp(log2(max(inset * color, vec3<f32>(0.0000000001))), vec3<f32>(min_ev), vec3<f32>(max_ev));
+    let curve = agx_contrast((log_color - min_ev) / (max_ev - min_ev));
+    // the curve ends up gamma encoded, the surface expects linear colors.
+    return pow(clamp(outset * curve, vec3<f32>(0.0), vec3<f32>(1.0)), vec3<f32>(2.2));
+}
+
+[[stage(fragment)]]
+fn fs_main([[builtin(position)]] position: vec4<f32>) -> [[location(0)]] vec4<f32> {
+    let color = textureLoad(hdr_scene, vec2<i32>(position.xy), 0).rgb;
+    var exposure = exp2(uniforms.exposure);
+    if (uniforms.auto_exposure != 0u) {
+        exposure = exposure * MIDDLE_GREY / max(adapted_luminance.value, 0.0001);
+    }
+    let exposed = color * exposure;
+    var mapped: vec3<f32>;
+    if (uniforms.tone_operator == OPERATOR_ACES) {
+        mapped = aces(exposed);
+    } else if (uniforms.tone_operator == OPERATOR_REINHARD) {
+        mapped = reinhard(exposed);
+    } else {
+        mapped = agx(exposed);
+    }
+    return vec4<f32>(mapped, 1.0);
+}
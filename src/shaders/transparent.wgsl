@@ -0,0 +1,236 @@
+struct GBufferInputs {
+[[location(0)]] a_pos: vec3<f32>;
+[[location(1)]] a_normal: vec3<f32>;
+[[location(2)]] tangent: vec4<f32>;
+[[location(3)]] tex_coord: vec2<f32>;
+[[location(4)]] vertex_color: u32;
+[[location(5)]] joint_weight: vec4<f32>;
+[[location(6)]] joint_id: u32;
+[[location(7)]] morph_index: u32;
+[[builtin(instance_index)]] instance_index: u32;
+};
+
+struct VertexOutputs {
+    [[builtin(position)]] pos: vec4<f32>;
+    [[location(0)]] v_tex_coord: vec2<f32>;
+    [[location(1)]] world_frag_pos: vec3<f32>;
+    [[location(2)]] tangent: vec4<f32>;
+    [[location(3)]] normal: vec3<f32>;
+};
+
+
+struct Globals {
+    u_view_position: vec4<f32>;
+    u_view_proj: mat4x4<f32>;
+    lights_num: vec4<u32>;
+};
+
+struct MaterialUniforms {
+    base_color_factor: vec4<f32>;
+    roughness_metallic_double_sided: vec4<f32>;
+    emissive_color: vec4<f32>;
+    // alpha cutoff, alpha mode (0 opaque, 1 mask, 2 blend) and transmission factor.
+    alpha_cutoff_mode_transmission: vec4<f32>;
+};
+
+[[group(0)
+,binding(0)]]
+var<uniform> globals: Globals;
+
+struct Transforms {
+ elements: array<mat4x4<f32> >;
+}; 
+struct Normals {
+    elements: array<mat4x4<f32> >;
+};
+
+[[group(0)
+,binding(1)]]
+var<storage,read> transform: Transforms;
+
+[[group(0)
+,binding(2)]]
+var<storage,read> normal_matrices: Normals;
+
+#include "include/deformation.wgsl"
+
+// the shadows share the group of the globals.
+#define SHADOW_GROUP 0
+#include "include/lighting.wgsl"
+
+[[group(1)
+,binding(0)]]
+var t_texture: texture_2d<f32>;
+[[group(1)
+,binding(1)]]
+var t_sampler: sampler;
+[[group(1)
+,binding(2)]]
+var t_roughness: texture_2d<f32>;
+[[group(1)
+,binding(3)]]
+var t_normal: texture_2d<f32>;
+[[group(1)
+,binding(4)]]
+var t_occlusion: texture_2d<f32>;
+[[group(1)
+,binding(5)]]
+var t_emissive: texture_2d<f32>;
+[[group(1)
+,binding(6)]]
+var<uniform> material_uniforms: MaterialUniforms;
+
+// the lit opaque scene, copied before the transparent primitives are drawn.
+[[group(3)
+,binding(4)]]
+var scene_color: texture_2d<f32>;
+
+// transparent instances aren't culled on the GPU, the instance index points straight at the transform.
+[[stage(vertex)]]
+fn vs_main(in: GBufferInputs) -> VertexOutputs {
+    var output: VertexOutputs;
+    let index = in.instance_index;
+    output.v_tex_coord = in.tex_coord;
+    let model_matrix = transform.elements[index];
+    let normal = normal_matrices.elements[index];
+    // morph targets are applied in the mesh's bind pose, before skinning.
+    let morphed_position = morph(in.morph_index, index, in.a_pos, false);
+    let morphed_normal = morph(in.morph_index, index, in.a_normal, true);
+    let skinned_tangent = skin(in.joint_id, in.joint_weight, index, vec4<f32>(in.tangent.xyz,0.0));
+    let skinned_normal = skin(in.joint_id, in.joint_weight, index, vec4<f32>(morphed_normal,0.0));
+    output.tangent = normalize(normal * vec4<f32>(skinned_tangent.xyz,in.tangent.w));
+    output.normal = normalize((normal * skinned_normal).xyz);
+    let model_space = model_matrix * skin(in.joint_id, in.joint_weight, index, vec4<f32>(morphed_position,1.0));
+    output.world_frag_pos = model_space.xyz;
+    output.pos = globals.u_view_proj * model_space;
+    return output;
+}
+
+// transparent primitives aren't part of the light tiles, every light is tested instead.
+fn addLightContributions(position:vec3<f32>,object_normal:vec3<f32>,view_direction:vec3<f32>,base_color:vec3<f32>,metallic:f32,roughness:f32) -> vec3<f32>
+{
+    var result = vec3<f32>(0.0);
+    for(var i: u32 = 0u; i < globals.lights_num.x; i = i + 1u)
+    {
+        let light = pointLights.elements[i];
+        if(length(light.position.xyz - position) > light.radius)
+        {
+            continue;
+        }
+        result = result + calcPointLightContribution(light,position,object_normal,view_direction,base_color,metallic,roughness);
+    }
+    for(var i: u32 = 0u; i < globals.lights_num.y; i = i + 1u)
+    {
+        result = result + calcSpotLightContribution(spotLights.elements[i],position,object_normal,view_direction,base_color,metallic,roughness);
+    }
+    return result;
+}
+
+struct Surface {
+    base_color: vec4<f32>;
+    normal: vec3<f32>;
+    metallic: f32;
+    roughness: f32;
+    emissive: vec3<f32>;
+    occlusion: f32;
+};
+
+// index of refraction of glass, the refracted ray is followed this far before sampling the scene.
+let IOR: f32 = 1.5;
+let REFRACTION_DISTANCE: f32 = 0.1;
+
+fn get_surface(in: VertexOutputs, front_facing: bool) -> Surface {
+    var surface: Surface;
+    surface.base_color = material_uniforms.base_color_factor * textureSample(t_texture,t_sampler,in.v_tex_coord);
+    // glTF stores roughness in the green and metalness in the blue channel.
+    let metallic_roughness = textureSample(t_roughness,t_sampler,in.v_tex_coord);
+    surface.metallic = clamp(metallic_roughness.b * material_uniforms.roughness_metallic_double_sided.y, 0.0, 1.0);
+    surface.roughness = clamp(metallic_roughness.g * material_uniforms.roughness_metallic_double_sided.x, 0.04, 1.0);
+    surface.emissive = textureSample(t_emissive,t_sampler,in.v_tex_coord).xyz * material_uniforms.emissive_color.xyz;
+    surface.occlusion = textureSample(t_occlusion,t_sampler,in.v_tex_coord).r;
+    var frag_normal = in.normal;
+    if(!front_facing)
+    {
+        frag_normal = normalize(in.normal * material_uniforms.roughness_metallic_double_sided.z);
+    }
+    let normal_map = 2.0 * textureSample(t_normal,t_sampler,in.v_tex_coord).xyz - vec3<f32>(1.0);
+    let bitangent = cross(in.tangent.xyz,frag_normal);
+    surface.normal = normalize(mat3x3<f32>(in.tangent.xyz,bitangent,frag_normal) * normal_map);
+    return surface;
+}
+
+// snell's law for the incident direction, total internal reflection keeps the incident direction.
+fn refract_direction(incident: vec3<f32>, normal: vec3<f32>, eta: f32) -> vec3<f32> {
+    let n_dot_i = dot(normal, incident);
+    let k = 1.0 - eta * eta * (1.0 - n_dot_i * n_dot_i);
+    if(k < 0.0)
+    {
+        return incident;
+    }
+    return eta * incident - (eta * n_dot_i + sqrt(k)) * normal;
+}
+
+// thin surface refraction, the scene behind the surface is sampled where the refracted view ray ends up on the screen.
+fn get_transmitted_color(position: vec3<f32>, normal: vec3<f32>, view_direction: vec3<f32>) -> vec3<f32> {
+    let refracted = refract_direction(-view_direction, normal, 1.0 / IOR);
+    let clip = globals.u_view_proj * vec4<f32>(position + refracted * REFRACTION_DISTANCE, 1.0);
+    let uv = clip.xy / max(clip.w, 0.0001) * vec2<f32>(0.5,-0.5) + vec2<f32>(0.5);
+    return textureSampleLevel(scene_color, environment_sampler, clamp(uv, vec2<f32>(0.0), vec2<f32>(1.0)), 0.0).rgb;
+}
+
+fn shade(in: VertexOutputs, surface: Surface, shadow: f32) -> vec4<f32> {
+    let position = in.world_frag_pos;
+    let base_color = surface.base_color.rgb;
+    let view_direction = normalize(globals.u_view_position.xyz - position);
+    var result = calcAmbientContribution(surface.normal,view_direction,base_color,surface.metallic,surface.roughness,surface.occlusion);
+    result = result + calcDirLightContribution(surface.normal,view_direction,base_color,surface.metallic,surface.roughness,shadow);
+    result = result + addLightContributions(position,surface.normal,view_direction,base_color,surface.metallic,surface.roughness);
+    // the transmitted light replaces the part which isn't reflected.
+    let alpha_mode = material_uniforms.alpha_cutoff_mode_transmission;
+    let transmission = alpha_mode.z;
+    if(transmission > 0.0)
+    {
+        let n_dot_v = max(dot(surface.normal, view_direction), 0.0);
+        let reflected = fresnel_schlick(n_dot_v, base_reflectivity(base_color, surface.metallic));
+        let transmitted = get_transmitted_color(position, surface.normal, view_direction) * base_color;
+        result = mix(result, transmitted, transmission * (1.0 - reflected) * (1.0 - surface.metallic));
+    }
+    result = result + surface.emissive;
+    // only blended materials are see-through, masked and transmissive ones cover what's behind them.
+    var alpha = 1.0;
+    if(alpha_mode.y == 2.0)
+    {
+        alpha = surface.base_color.a;
+    }
+    return vec4<f32>(result, alpha);
+}
+
+fn is_masked(surface: Surface) -> bool {
+    let alpha_mode = material_uniforms.alpha_cutoff_mode_transmission;
+    return alpha_mode.y == 1.0 && surface.base_color.a < alpha_mode.x;
+}
+
+[[stage(fragment)]]
+fn fs_main(in: VertexOutputs, [[builtin(front_facing)]] front_facing: bool) -> [[location(0)]] vec4<f32> {
+    let surface = get_surface(in, front_facing);
+    let n_dot_l = max(dot(surface.normal, normalize(dirLight.direction.xyz)), 0.0);
+    let shadow = get_shadow_value(vec4<f32>(in.world_frag_pos,1.0), n_dot_l);
+    let color = shade(in, surface, shadow);
+    if(is_masked(surface))
+    {
+        discard;
+    }
+    return color;
+}
+[[stage(fragment)]]
+fn fs_main_web(in: VertexOutputs, [[builtin(front_facing)]] front_facing: bool) -> [[location(0)]] vec4<f32> {
+    let surface = get_surface(in, front_facing);
+    let n_dot_l = max(dot(surface.normal, normalize(dirLight.direction.xyz)), 0.0);
+    let shadow = get_shadow_value_web(cascade_transforms.elements[0] * vec4<f32>(in.world_frag_pos,1.0), n_dot_l);
+    let color = shade(in, surface, shadow);
+    if(is_masked(surface))
+    {
+        discard;
+    }
+    return color;
+}
@@ -0,0 +1,16 @@
+// darkens the frame towards its corners.
+// the parameters are the intensity, the radius where the darkening ends and the width of the transition.
+
+#include "include/fullscreen.wgsl"
+
+[[stage(fragment)]]
+fn fs_main(in: FullscreenOutput) -> [[location(0)]] vec4<f32> {
+    let color = textureSample(source_texture, source_sampler, in.uv).rgb;
+    // zero at the center and one at the corners.
+    let distance = length(in.uv - vec2<f32>(0.5)) * sqrt(2.0);
+    let radius = uniforms.parameters.y;
+    // smoothstep, which this version of naga lacks.
+    let t = clamp((distance - radius + uniforms.parameters.z) / uniforms.parameters.z, 0.0, 1.0);
+    let vignette = 1.0 - t * t * (3.0 - 2.0 * t);
+    return vec4<f32>(color * mix(1.0, vignette, uniforms.parameters.x), 1.0);
+}
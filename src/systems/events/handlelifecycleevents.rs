@@ -1,4 +1,5 @@
 use crate::components::scriptingcallback::{CallbackArgs, ExecuteFunction, ScriptingCallback};
+use crate::resources::finishedanimations::FinishedAnimations;
 use crate::scripting::scriptevent::ScriptEvent;
 use crate::scripting::util::horizonentity::HorizonEntity;
 use crate::systems::physics::PhysicsWorld;
@@ -148,3 +149,39 @@ impl<'a> System<'a> for InvokeEntityCollisionHandlers {
         }
     }
 }
+
+/// Invokes the `AnimationFinished` callbacks for the clips which finished during the frame.
+pub struct InvokeAnimationFinishedHandlers;
+
+impl<'a> System<'a> for InvokeAnimationFinishedHandlers {
+    type SystemData = (
+        Write<'a, FinishedAnimations>,
+        WriteExpect<'a, HorizonScriptingEngine>,
+        ReadStorage<'a, ScriptingCallback>,
+        ReadStorage<'a, ScriptEvent>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            mut finished_animations,
+            mut scripting_engine,
+            callbacks,
+            script_events,
+        ): Self::SystemData,
+    ) {
+        for (entity, animation) in finished_animations.events.drain(..) {
+            for (callback, script_event) in (&callbacks, &script_events).join() {
+                if let ScriptEvent::AnimationFinished = script_event {
+                    callback.execute_with_args(
+                        &mut scripting_engine,
+                        CallbackArgs::AnimationFinished(
+                            HorizonEntity::from_entity_id(entity.id()),
+                            animation.clone(),
+                        ),
+                    );
+                }
+            }
+        }
+    }
+}
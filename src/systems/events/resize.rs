@@ -1,8 +1,19 @@
 use specs::{Entities, Join, ReadStorage, System, WriteExpect, WriteStorage};
 
+use crate::renderer::bindgroups::frustumculling::FrustumCullingBindGroup;
+use crate::renderer::bindgroups::ssao::SsaoBindGroup;
+use crate::renderer::bindgroups::tonemapping::ToneMappingBindGroup;
+use crate::renderer::bindgroups::transparent::TransparentBindGroup;
+use crate::renderer::postprocessing::PostProcessStack;
 use crate::renderer::primitives::uniforms::TileInfo;
+use crate::renderer::utils::depthpyramidgenerator::DepthPyramidGenerator;
 use crate::resources::commandencoder::HorizonCommandEncoder;
-use crate::BufferTypes::{LightCulling, LightId};
+use crate::BufferTypes::{
+    AdaptedLuminance, CullingGroups, CullingInstances, CullingStats, FrustumCullingUniform,
+    IndirectDraws, Instances, LightCulling, LightId, LuminanceHistogram, SsaoUniform,
+    ToneMappingUniform, Uniform, VisibleInstances,
+};
+use crate::SamplerTypes;
 use crate::TextureViewTypes::DeferredSpecular;
 use crate::{
     renderer::{
@@ -13,7 +24,7 @@ use crate::{
     },
     resources::{bindingresourcecontainer::BindingResourceContainer, windowevents::ResizeEvent},
     CanvasSize, DeferredAlbedo, DeferredNormals, DeferredPosition, DeferredTexture, Projection,
-    Tiling, TilingBindGroup,
+    TextureViewTypes, Tiling, TilingBindGroup,
 };
 
 pub struct Resize;
@@ -27,6 +38,11 @@ impl<'a> System<'a> for Resize {
         WriteExpect<'a, Projection>,
         ReadStorage<'a, DeferredBindGroup>,
         ReadStorage<'a, TilingBindGroup>,
+        ReadStorage<'a, FrustumCullingBindGroup>,
+        ReadStorage<'a, TransparentBindGroup>,
+        ReadStorage<'a, ToneMappingBindGroup>,
+        WriteExpect<'a, PostProcessStack>,
+        ReadStorage<'a, SsaoBindGroup>,
     );
 
     fn run(
@@ -39,6 +55,11 @@ impl<'a> System<'a> for Resize {
             mut proj,
             deferred_bind_group,
             tiling_bind_group,
+            frustum_culling_bind_group,
+            transparent_bind_group,
+            tone_mapping_bind_group,
+            mut post_process_stack,
+            ssao_bind_group,
         ): Self::SystemData,
     ) {
         if resize_event.handled {
@@ -59,8 +80,37 @@ impl<'a> System<'a> for Resize {
         state.depth_texture =
             Texture::create_depth_texture(&state.device, &state.sc_descriptor, "depth_texture");
         GBuffer::generate_g_buffers(&state.device, &state.sc_descriptor, &mut resource_container);
+        DepthPyramidGenerator::create_pyramid(
+            &state.device,
+            state.sc_descriptor.width,
+            state.sc_descriptor.height,
+            &mut resource_container,
+        );
+        ToneMappingBindGroup::create_hdr_target(
+            &state.device,
+            &state.sc_descriptor,
+            &mut resource_container,
+        );
+        TransparentBindGroup::create_scene_color(
+            &state.device,
+            &state.sc_descriptor,
+            &mut resource_container,
+        );
+        PostProcessStack::create_targets(
+            &state.device,
+            &state.sc_descriptor,
+            &mut resource_container,
+        );
+        post_process_stack.resize(&state.device, &state.sc_descriptor);
+        SsaoBindGroup::create_targets(&state.device, &state.sc_descriptor, &mut resource_container);
 
-        state.surface.configure(&state.device, &state.sc_descriptor);
+        if let Some(ref surface) = state.surface {
+            surface.configure(&state.device, &state.sc_descriptor);
+        } else {
+            let offscreen_texture =
+                State::create_offscreen_texture(&state.device, &state.sc_descriptor);
+            state.offscreen_texture = Some(offscreen_texture);
+        }
         state.queue.write_buffer(
             resource_container.buffers[CanvasSize].as_ref().unwrap(),
             0,
@@ -78,7 +128,9 @@ impl<'a> System<'a> for Resize {
                 state.sc_descriptor.width as f32,
                 state.sc_descriptor.height as f32,
             ),
-            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+            usage: wgpu::BufferUsages::STORAGE
+                | wgpu::BufferUsages::COPY_DST
+                | wgpu::BufferUsages::COPY_SRC,
             label: Some("Light ids buffer"),
         });
         state.queue.write_buffer(
@@ -114,6 +166,9 @@ impl<'a> System<'a> for Resize {
                     resource_container.buffers[CanvasSize].as_ref().unwrap(),
                     resource_container.buffers[LightId].as_ref().unwrap(),
                     resource_container.buffers[Tiling].as_ref().unwrap(),
+                    resource_container.texture_views[TextureViewTypes::AmbientOcclusionBlurred]
+                        .as_ref()
+                        .unwrap(),
                 ),
             );
         }
@@ -134,6 +189,105 @@ impl<'a> System<'a> for Resize {
             );
         }
 
+        {
+            let (_, frustum_culling) = (&frustum_culling_bind_group, &mut bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+            *frustum_culling = FrustumCullingBindGroup::create_container(
+                &state.device,
+                (
+                    resource_container.buffers[FrustumCullingUniform]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[Instances].as_ref().unwrap(),
+                    resource_container.buffers[CullingInstances]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[CullingGroups].as_ref().unwrap(),
+                    resource_container.buffers[IndirectDraws].as_ref().unwrap(),
+                    resource_container.buffers[VisibleInstances]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.texture_views[TextureViewTypes::DepthPyramid]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[CullingStats].as_ref().unwrap(),
+                ),
+            );
+        }
+
+        {
+            let (_, transparent) = (&transparent_bind_group, &mut bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+            *transparent = TransparentBindGroup::create_container(
+                &state.device,
+                (
+                    resource_container.texture_views[TextureViewTypes::Irradiance]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.texture_views[TextureViewTypes::PrefilteredEnvironment]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.texture_views[TextureViewTypes::BrdfLut]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.samplers[SamplerTypes::Environment]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.texture_views[TextureViewTypes::SceneColor]
+                        .as_ref()
+                        .unwrap(),
+                ),
+            );
+        }
+
+        {
+            let (_, tone_mapping) = (&tone_mapping_bind_group, &mut bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+            *tone_mapping = ToneMappingBindGroup::create_container(
+                &state.device,
+                (
+                    resource_container.texture_views[TextureViewTypes::HdrScene]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[ToneMappingUniform]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[LuminanceHistogram]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[AdaptedLuminance]
+                        .as_ref()
+                        .unwrap(),
+                ),
+            );
+        }
+
+        {
+            let (_, ssao) = (&ssao_bind_group, &mut bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+            *ssao = SsaoBindGroup::create_container(
+                &state.device,
+                (
+                    resource_container.texture_views[DeferredPosition]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.texture_views[DeferredNormals]
+                        .as_ref()
+                        .unwrap(),
+                    resource_container.buffers[SsaoUniform].as_ref().unwrap(),
+                    resource_container.buffers[Uniform].as_ref().unwrap(),
+                ),
+            );
+        }
+
         log::info!("resize has occurred!");
 
         resize_event.handled = true;
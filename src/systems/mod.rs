@@ -1,4 +1,6 @@
 pub mod events;
 pub mod physics;
+pub mod propagatetransforms;
 pub mod rendering;
+pub mod updateanimations;
 pub mod util;
@@ -1,6 +1,6 @@
 use std::borrow::BorrowMut;
 
-use rapier3d::na::{vector, UnitQuaternion, Vector3};
+use rapier3d::na::{vector, Isometry3, Matrix4, Point3, UnitQuaternion, Vector3};
 use rapier3d::prelude::{CCDSolver, IslandManager, RigidBodyType};
 use rapier3d::{
     crossbeam::{self, channel::Receiver},
@@ -11,8 +11,9 @@ use rapier3d::{
     },
     pipeline::{ChannelEventCollector, PhysicsPipeline},
 };
-use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect, WriteStorage};
 
+use crate::components::hierarchy::Parent;
 use crate::components::{physicshandle::PhysicsHandle, transform::Transform};
 use crate::resources::scriptingstate::ScriptingState;
 use crate::ui::debugstats::DebugStats;
@@ -93,6 +94,14 @@ impl PhysicsWorld {
             &self.event_handler,
         );
     }
+    pub fn delete_collider(&mut self, collider_handle: ColliderHandle) {
+        self.collider_set.remove(
+            collider_handle,
+            &mut self.island_manager,
+            &mut self.body_set,
+            true,
+        );
+    }
     pub fn delete_rigid_body(&mut self, rigid_body_handle: RigidBodyHandle) {
         self.body_set.remove(
             rigid_body_handle,
@@ -112,10 +121,11 @@ impl<'a> System<'a> for Physics {
         Entities<'a>,
         ReadExpect<'a, DeltaTime>,
         ReadExpect<'a, ScriptingState>,
+        ReadStorage<'a, Parent>,
     );
 
     fn run(&mut self, data: Self::SystemData) {
-        let (mut world, handles, mut transforms, entities, dt, scripting_state) = data;
+        let (mut world, handles, mut transforms, entities, dt, scripting_state, parents) = data;
         // perform simulation
         if !scripting_state.run_physics_simulation {
             return;
@@ -123,19 +133,21 @@ impl<'a> System<'a> for Physics {
         world.step(dt.delta);
         for rigid_body_handle in world.island_manager.active_dynamic_bodies() {
             let body = world.body_set.get(*rigid_body_handle).unwrap();
-            let mut transform = transforms
-                .get_mut(entities.entity(body.user_data as u32))
-                .unwrap();
-            transform.position = body.position().translation.vector;
-            transform.rotation = body.position().rotation;
+            Self::write_body_position(
+                body,
+                entities.entity(body.user_data as u32),
+                &parents,
+                &mut transforms,
+            );
         }
         for rigid_body_handle in world.island_manager.active_kinematic_bodies() {
             let body = world.body_set.get(*rigid_body_handle).unwrap();
-            let mut transform = transforms
-                .get_mut(entities.entity(body.user_data as u32))
-                .unwrap();
-            transform.position = body.position().translation.vector;
-            transform.rotation = body.position().rotation;
+            Self::write_body_position(
+                body,
+                entities.entity(body.user_data as u32),
+                &parents,
+                &mut transforms,
+            );
         }
         for handle in (&handles).join() {
             let body = world.body_set.get_mut(handle.rigid_body_handle).unwrap();
@@ -145,3 +157,49 @@ impl<'a> System<'a> for Physics {
         }
     }
 }
+
+impl Physics {
+    /// The world space pose of an entity's body, the transform of an entity with a parent is relative to the parent's.
+    pub fn body_isometry(
+        transform: &Transform,
+        parent_matrix: Option<Matrix4<f32>>,
+    ) -> Isometry3<f32> {
+        match parent_matrix {
+            Some(parent_matrix) => Isometry3::from_parts(
+                parent_matrix
+                    .transform_point(&Point3::from(transform.position))
+                    .coords
+                    .into(),
+                Self::matrix_rotation(&parent_matrix) * transform.rotation,
+            ),
+            None => Isometry3::from_parts(transform.position.into(), transform.rotation),
+        }
+    }
+    /// Moves the simulated pose into the parent's space of the last propagated frame for entities with a parent.
+    fn write_body_position(
+        body: &RigidBody,
+        entity: Entity,
+        parents: &ReadStorage<Parent>,
+        transforms: &mut WriteStorage<Transform>,
+    ) {
+        let mut position = body.position().translation.vector;
+        let mut rotation = body.position().rotation;
+        let parent_matrix = parents
+            .get(entity)
+            .and_then(|parent| transforms.get(parent.0))
+            .map(|parent| parent.world_matrix);
+        if let Some(parent_matrix) = parent_matrix {
+            if let Some(inverse) = parent_matrix.try_inverse() {
+                position = inverse.transform_point(&Point3::from(position)).coords;
+                rotation = Self::matrix_rotation(&parent_matrix).inverse() * rotation;
+            }
+        }
+        let transform = transforms.get_mut(entity).unwrap();
+        transform.position = position;
+        transform.rotation = rotation;
+    }
+    /// The rotation of a world matrix without its scale.
+    fn matrix_rotation(matrix: &Matrix4<f32>) -> UnitQuaternion<f32> {
+        UnitQuaternion::from_matrix(&matrix.fixed_slice::<3, 3>(0, 0).into_owned())
+    }
+}
@@ -0,0 +1,120 @@
+use rapier3d::na::Matrix4;
+use specs::{BitSet, Entities, Entity, Join, ReadStorage, System, WriteStorage};
+
+use crate::components::hierarchy::{Children, Parent};
+use crate::components::modelnode::ModelNode;
+use crate::components::transform::Transform;
+
+/// Computes the world matrix of every transform by walking the hierarchy from the root entities.
+/// A parent cycle is logged and broken by removing the `Parent` of the entity closing it.
+pub struct PropagateTransforms;
+
+impl<'a> System<'a> for PropagateTransforms {
+    type SystemData = (
+        Entities<'a>,
+        ReadStorage<'a, ModelNode>,
+        WriteStorage<'a, Parent>,
+        WriteStorage<'a, Children>,
+        WriteStorage<'a, Transform>,
+    );
+
+    fn run(
+        &mut self,
+        (entities, model_nodes, mut parents, mut children, mut transforms): Self::SystemData,
+    ) {
+        // model nodes are owned by their instance, they are removed along with it.
+        for (node_ent, node) in (&entities, &model_nodes).join() {
+            if !entities.is_alive(node.instance) {
+                entities.delete(node_ent).unwrap();
+            }
+        }
+        let orphans = (&entities, &parents)
+            .join()
+            .filter(|(_, parent)| !entities.is_alive(parent.0))
+            .map(|(ent, _)| ent)
+            .collect::<Vec<_>>();
+        for orphan in orphans {
+            parents.remove(orphan);
+        }
+        for child_list in (&mut children).join() {
+            child_list.0.clear();
+        }
+        for (ent, parent) in (&entities, &parents).join() {
+            if let Some(child_list) = children.get_mut(parent.0) {
+                child_list.0.push(ent);
+            } else {
+                children.insert(parent.0, Children(vec![ent])).unwrap();
+            }
+        }
+
+        // entities whose parent has no transform are treated as roots as well.
+        let roots: Vec<(Entity, Matrix4<f32>)> = (&entities, &transforms)
+            .join()
+            .filter(|(ent, _)| match parents.get(*ent) {
+                Some(parent) => !transforms.contains(parent.0),
+                None => true,
+            })
+            .map(|(ent, _)| (ent, Matrix4::identity()))
+            .collect();
+        let mut visited = BitSet::new();
+        Self::propagate(roots, &children, &mut transforms, &mut visited);
+        // the entities left are part of a parent cycle or below one, the cycle is broken where it closes.
+        let unvisited = (&entities, &transforms)
+            .join()
+            .map(|(ent, _)| ent)
+            .filter(|ent| !visited.contains(ent.id()))
+            .collect::<Vec<_>>();
+        for ent in unvisited {
+            if visited.contains(ent.id()) {
+                continue;
+            }
+            let mut ancestors = BitSet::new();
+            let mut cycle_ent = ent;
+            while !ancestors.add(cycle_ent.id()) {
+                cycle_ent = parents.get(cycle_ent).unwrap().0;
+            }
+            let parent = parents.remove(cycle_ent).unwrap().0;
+            if let Some(child_list) = children.get_mut(parent) {
+                child_list.0.retain(|child| *child != cycle_ent);
+            }
+            log::warn!(
+                target: "hierarchy",
+                "entity {} is its own ancestor, its parent {} is removed",
+                cycle_ent.id(),
+                parent.id()
+            );
+            Self::propagate(
+                vec![(cycle_ent, Matrix4::identity())],
+                &children,
+                &mut transforms,
+                &mut visited,
+            );
+        }
+    }
+}
+
+impl PropagateTransforms {
+    /// Walks down from the given entities and their parents' world matrices.
+    fn propagate(
+        mut stack: Vec<(Entity, Matrix4<f32>)>,
+        children: &WriteStorage<Children>,
+        transforms: &mut WriteStorage<Transform>,
+        visited: &mut BitSet,
+    ) {
+        while let Some((ent, parent_matrix)) = stack.pop() {
+            visited.add(ent.id());
+            let transform = transforms.get_mut(ent).unwrap();
+            transform.world_matrix = parent_matrix * transform.local_matrix();
+            let world_matrix = transform.world_matrix;
+            if let Some(child_list) = children.get(ent) {
+                stack.extend(
+                    child_list
+                        .0
+                        .iter()
+                        .filter(|child| transforms.contains(**child))
+                        .map(|child| (*child, world_matrix)),
+                );
+            }
+        }
+    }
+}
@@ -9,14 +9,20 @@ impl<'a> System<'a> for AcquireTexture {
     type SystemData = (WriteExpect<'a,SurfaceTexture>,ReadExpect<'a,State>,Write<'a,RenderResult>);
 
     fn run(&mut self, (mut surface_texture,state,mut render_result): Self::SystemData) {
-        let frame_result = state.surface.get_current_texture();
-        let frame;
-        if let Ok(f) = frame_result {
-            frame = f;
+        if let Some(ref surface) = state.surface {
+            let frame_result = surface.get_current_texture();
+            let frame;
+            if let Ok(f) = frame_result {
+                frame = f;
+            } else {
+                render_result.result = frame_result.err();
+                return;
+            }
+            surface_texture.view = Some(frame.texture.create_view(&wgpu::TextureViewDescriptor::default()));
+            surface_texture.texture = Some(frame);
         } else {
-            render_result.result = frame_result.err();
-            return;
+            let offscreen_texture = state.offscreen_texture.as_ref().unwrap();
+            surface_texture.view = Some(offscreen_texture.create_view(&wgpu::TextureViewDescriptor::default()));
         }
-        surface_texture.texture = Some(frame);
     }
-}
\ No newline at end of file
+}
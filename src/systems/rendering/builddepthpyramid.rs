@@ -0,0 +1,61 @@
+use specs::{ReadExpect, System, WriteExpect};
+
+use crate::renderer::state::State;
+use crate::renderer::utils::depthpyramidgenerator::DepthPyramidGenerator;
+use crate::resources::bindingresourcecontainer::BindingResourceContainer;
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::frustumculling::{FrustumCulling, OcclusionView};
+use crate::{Camera, Projection};
+
+/// Reduces the depth of the G-buffer pass into the depth pyramid,
+/// the frustum culling of the next frame skips the instances hidden behind it.
+pub struct BuildDepthPyramid;
+
+impl<'a> System<'a> for BuildDepthPyramid {
+    type SystemData = (
+        ReadExpect<'a, State>,
+        WriteExpect<'a, HorizonCommandEncoder>,
+        ReadExpect<'a, BindingResourceContainer>,
+        ReadExpect<'a, DepthPyramidGenerator>,
+        ReadExpect<'a, Camera>,
+        ReadExpect<'a, Projection>,
+        WriteExpect<'a, FrustumCulling>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            state,
+            mut encoder,
+            binding_resource_container,
+            generator,
+            camera,
+            projection,
+            mut frustum_culling,
+        ): Self::SystemData,
+    ) {
+        let pyramid_size = DepthPyramidGenerator::pyramid_size(
+            state.sc_descriptor.width,
+            state.sc_descriptor.height,
+        );
+        generator.generate(
+            &state.device,
+            encoder.get_encoder(),
+            &state.depth_texture.view,
+            pyramid_size,
+            &binding_resource_container,
+        );
+        let scale = 1.0 / (projection.fov_y * 0.5).tan();
+        frustum_culling.occlusion = Some(OcclusionView {
+            view: camera.get_view_matrix(),
+            projection: [
+                scale / projection.aspect_ratio,
+                scale,
+                projection.z_near,
+                0.0,
+            ],
+            pyramid_size,
+            pyramid_mip_count: DepthPyramidGenerator::mip_count(pyramid_size),
+        });
+    }
+}
@@ -0,0 +1,60 @@
+use specs::{Read, ReadExpect, System, WriteExpect};
+
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::framecapture::{FrameCapture, FrameCaptureError, FrameReadback};
+use crate::resources::surfacetexture::SurfaceTexture;
+use crate::{RenderResult, State};
+
+/// Copies the composited frame into a mappable buffer and hands the decoded image to every pending request.
+pub struct CaptureFrame;
+
+impl<'a> System<'a> for CaptureFrame {
+    type SystemData = (
+        WriteExpect<'a, FrameCapture>,
+        ReadExpect<'a, SurfaceTexture>,
+        ReadExpect<'a, State>,
+        WriteExpect<'a, HorizonCommandEncoder>,
+        Read<'a, RenderResult>,
+    );
+
+    fn run(
+        &mut self,
+        (mut frame_capture, surface_texture, state, mut command_encoder, render_result): Self::SystemData,
+    ) {
+        if frame_capture.requests.is_empty() || render_result.result.is_some() {
+            return;
+        }
+        let texture = match surface_texture.texture.as_ref() {
+            Some(surface_texture) => &surface_texture.texture,
+            None => state.offscreen_texture.as_ref().unwrap(),
+        };
+        let readback = FrameReadback::new(
+            &state.device,
+            state.sc_descriptor.width,
+            state.sc_descriptor.height,
+            state.sc_descriptor.format,
+        );
+        readback.copy_from_texture(command_encoder.get_encoder(), texture, 0);
+        command_encoder.finish(&state.device, &state.queue);
+
+        let requests = std::mem::take(&mut frame_capture.requests);
+        let map_future = readback.buffer.slice(..).map_async(wgpu::MapMode::Read);
+        let deliver = move |map_result: Result<(), wgpu::BufferAsyncError>| {
+            let image = map_result
+                .map_err(|_| FrameCaptureError::BufferMapFailed)
+                .and_then(|_| readback.to_image());
+            for request in requests {
+                let _ = request.send(image.clone());
+            }
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            state.device.poll(wgpu::Maintain::Wait);
+            deliver(futures::executor::block_on(map_future));
+        }
+        #[cfg(target_arch = "wasm32")]
+        wasm_bindgen_futures::spawn_local(async move {
+            deliver(map_future.await);
+        });
+    }
+}
@@ -0,0 +1,439 @@
+use specs::{Entities, Entity, Join, ReadExpect, ReadStorage, System, WriteExpect};
+
+use crate::components::custommaterial::CustomMaterial;
+use crate::components::materialoverride::MaterialOverride;
+use crate::components::modelnode::ModelNode;
+use crate::components::morphweights::MorphWeights;
+use crate::components::skin::Skin;
+use crate::components::transform::{Transform, TransformRaw};
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::bindgroups::frustumculling::FrustumCullingBindGroup;
+use crate::renderer::pipelines::frustumcullingpipeline::FrustumCullingPipeline;
+use crate::renderer::state::State;
+use crate::renderer::utils::depthpyramidgenerator::DepthPyramidGenerator;
+use crate::resources::bindingresourcecontainer::BindingResourceContainer;
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::frustumculling::{
+    CullingGroupRaw, CullingInstanceRaw, CullingStatsRaw, DrawIndexedIndirectRaw, FrustumCulling,
+    FrustumCullingUniforms, IndirectDraw, TransparentInstance,
+};
+use crate::resources::gpuquerysets::GpuQuerySetContainer;
+use crate::resources::lodsettings::LodSettings;
+use crate::resources::shadowsettings::ShadowSettings;
+use crate::ui::debugstats::DebugStats;
+use crate::ui::gpustats::Passes;
+use crate::{
+    BufferTypes, Camera, DirectionalLight, Globals, Instances, Normals, Projection, RawModel,
+};
+
+/// Writes the instances of every model mesh and culls them against the camera and the shadow cascades,
+/// the shadow and gbuffer passes draw the visible instances with indirect draws.
+/// The camera's instances are also culled against the depth pyramid of the previous frame,
+/// the visible ones are drawn with the level of detail matching their size on the screen.
+pub struct ComputeFrustumCulling;
+
+impl<'a> System<'a> for ComputeFrustumCulling {
+    type SystemData = (
+        ReadExpect<'a, State>,
+        WriteExpect<'a, HorizonCommandEncoder>,
+        ReadExpect<'a, BindingResourceContainer>,
+        Entities<'a>,
+        ReadStorage<'a, RawModel>,
+        ReadStorage<'a, Transform>,
+        ReadStorage<'a, ModelNode>,
+        ReadStorage<'a, Skin>,
+        ReadStorage<'a, MorphWeights>,
+        ReadStorage<'a, BindGroupContainer>,
+        ReadStorage<'a, FrustumCullingBindGroup>,
+        ReadExpect<'a, FrustumCullingPipeline>,
+        ReadExpect<'a, Globals>,
+        ReadExpect<'a, DirectionalLight>,
+        ReadExpect<'a, Camera>,
+        ReadExpect<'a, Projection>,
+        ReadExpect<'a, ShadowSettings>,
+        ReadExpect<'a, LodSettings>,
+        WriteExpect<'a, FrustumCulling>,
+        WriteExpect<'a, GpuQuerySetContainer>,
+        WriteExpect<'a, DebugStats>,
+        ReadStorage<'a, CustomMaterial>,
+        ReadStorage<'a, MaterialOverride>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            state,
+            mut encoder,
+            binding_resource_container,
+            entities,
+            models,
+            transforms,
+            model_nodes,
+            skins,
+            morph_weights,
+            bind_group_container,
+            frustum_culling_bind_group,
+            pipeline,
+            globals,
+            dir_light,
+            camera,
+            projection,
+            shadow_settings,
+            lod_settings,
+            mut frustum_culling,
+            mut query_sets,
+            mut debug_stats,
+            custom_materials,
+            material_overrides,
+        ): Self::SystemData,
+    ) {
+        let stats_buffer = binding_resource_container.buffers[BufferTypes::CullingStats]
+            .as_ref()
+            .unwrap();
+        let stats_readback_buffer = binding_resource_container.buffers
+            [BufferTypes::CullingStatsReadback]
+            .as_ref()
+            .unwrap();
+        // the stats of the previous frame have been submitted by now.
+        if frustum_culling.stats_pending {
+            let _ = stats_readback_buffer
+                .slice(..)
+                .map_async(wgpu::MapMode::Read);
+            state.device.poll(wgpu::Maintain::Wait);
+            let stats_view = stats_readback_buffer.slice(..).get_mapped_range();
+            let stats: CullingStatsRaw = *bytemuck::from_bytes(&*stats_view);
+            drop(stats_view);
+            stats_readback_buffer.unmap();
+            debug_stats.frustum_culled_instances = stats.frustum_culled;
+            debug_stats.occlusion_culled_instances = stats.occlusion_culled;
+            debug_stats.visible_instances = stats.visible;
+            frustum_culling.stats_pending = false;
+        }
+        let max_instances = State::MAX_ENTITY_COUNT as usize / std::mem::size_of::<TransformRaw>();
+        let mut instances = Vec::new();
+        let mut deformations = Vec::new();
+        let mut culling_instances = Vec::new();
+        let mut groups = Vec::new();
+        let mut draws = Vec::new();
+        // (index count, first index, first slot) of every primitive's draw for each level of detail.
+        let mut draw_args = Vec::new();
+        let mut slot_count = 0;
+        let mut transparent_instances = Vec::new();
+        let camera_planes = FrustumCulling::frustum_planes(&globals.get_view_proj());
+        // every view selects the levels of detail with the camera so the shadows match the drawn meshes.
+        let lod_camera = [
+            camera.position.x,
+            camera.position.y,
+            camera.position.z,
+            lod_settings.projection_scale(&projection),
+        ];
+        for (model, model_ent) in (&models, &*entities).join() {
+            for mesh_index in 0..model.mesh_count() {
+                let (node_entities, mesh_instances, mesh_deformations) = RawModel::mesh_instances(
+                    model_ent,
+                    mesh_index,
+                    &entities,
+                    &transforms,
+                    &model_nodes,
+                    &skins,
+                    &morph_weights,
+                );
+                // instances drawn with different materials get their own culling group and draws.
+                let mut material_groups: Vec<((Option<Entity>, Option<Entity>), Vec<_>, Vec<_>)> =
+                    Vec::new();
+                for ((node_ent, instance), deformation) in node_entities
+                    .into_iter()
+                    .zip(mesh_instances)
+                    .zip(mesh_deformations)
+                {
+                    let materials = (
+                        CustomMaterial::material_entity(
+                            node_ent,
+                            model_ent,
+                            &model_nodes,
+                            &custom_materials,
+                        ),
+                        MaterialOverride::override_entity(
+                            node_ent,
+                            model_ent,
+                            &model_nodes,
+                            &material_overrides,
+                        ),
+                    );
+                    match material_groups
+                        .iter_mut()
+                        .find(|(group_materials, _, _)| *group_materials == materials)
+                    {
+                        Some((_, instances, deformations)) => {
+                            instances.push(instance);
+                            deformations.push(deformation);
+                        }
+                        None => {
+                            material_groups.push((materials, vec![instance], vec![deformation]))
+                        }
+                    }
+                }
+                for (
+                    (material_entity, material_override),
+                    instance_buffer,
+                    instance_deformations,
+                ) in material_groups
+                {
+                    let primitives = model.mesh_primitives(mesh_index).collect::<Vec<_>>();
+                    let lod_count = model.mesh_lod_count(mesh_index);
+                    if instances.len() + instance_buffer.len() > max_instances
+                        || draw_args.len() + primitives.len() * lod_count
+                            > State::MAX_INDIRECT_DRAWS
+                    {
+                        log::warn!(
+                            target: "frustum_culling",
+                            "instance or indirect draw buffer is full, mesh #{} is not drawn",
+                            mesh_index
+                        );
+                        continue;
+                    }
+                    let group = groups.len() as u32;
+                    let first_instance = instances.len() as u32;
+                    let first_draw = draw_args.len() as u32;
+                    let bounds = model.mesh_bounds(mesh_index);
+                    culling_instances.extend(instance_deformations.iter().map(|deformation| {
+                        if deformation.is_deformed() {
+                            CullingInstanceRaw::new(None, group)
+                        } else {
+                            CullingInstanceRaw::new(bounds, group)
+                        }
+                    }));
+                    let lod_screen_sizes = model.mesh_lod_screen_sizes(mesh_index);
+                    groups.push(CullingGroupRaw::new(
+                        lod_screen_sizes,
+                        first_draw,
+                        primitives.len() as u32,
+                        lod_count as u32,
+                        slot_count,
+                        instance_buffer.len() as u32,
+                    ));
+                    for lod in 0..lod_count {
+                        let first_slot = slot_count + (lod * instance_buffer.len()) as u32;
+                        draw_args.extend(primitives.iter().map(|mesh| {
+                            let mesh_lod = mesh.lod(lod);
+                            (mesh_lod.index_count, mesh_lod.first_index, first_slot)
+                        }));
+                    }
+                    slot_count += (lod_count * instance_buffer.len()) as u32;
+                    // transparent instances are culled on the CPU, they need sorting anyway.
+                    if model.mesh_is_transparent(mesh_index) {
+                        transparent_instances.extend(
+                            instance_buffer
+                                .iter()
+                                .zip(&instance_deformations)
+                                .enumerate()
+                                .filter_map(|(index, (transform, deformation))| {
+                                    let matrix = transform.matrix();
+                                    let (center, lod) = match bounds {
+                                        Some(bounds) if !deformation.is_deformed() => {
+                                            let bounds = bounds.transform(&matrix);
+                                            if !FrustumCulling::sphere_visible(
+                                                &camera_planes,
+                                                &bounds,
+                                            ) {
+                                                return None;
+                                            }
+                                            let lod = FrustumCulling::select_lod(
+                                                &lod_screen_sizes,
+                                                lod_count,
+                                                &bounds,
+                                                &lod_camera,
+                                            );
+                                            (bounds.center, lod)
+                                        }
+                                        // like on the GPU, instances without bounds use the base level.
+                                        _ => (matrix.column(3).xyz(), 0),
+                                    };
+                                    Some(TransparentInstance {
+                                        model: model_ent,
+                                        mesh_index,
+                                        instance: first_instance + index as u32,
+                                        lod,
+                                        material_override,
+                                        distance: (center - camera.position.coords).norm_squared(),
+                                    })
+                                }),
+                        );
+                    }
+                    draws.push(IndirectDraw {
+                        model: model_ent,
+                        mesh_index,
+                        first_draw,
+                        material_entity,
+                        material_override,
+                    });
+                    instances.extend(instance_buffer);
+                    deformations.extend(instance_deformations);
+                }
+            }
+        }
+        let instance_count = instances.len() as u32;
+        let draw_count = draw_args.len() as u32;
+        transparent_instances.sort_by(|a, b| {
+            b.distance
+                .partial_cmp(&a.distance)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        frustum_culling.draws = draws;
+        frustum_culling.transparent_instances = transparent_instances;
+        frustum_culling.instance_count = instance_count;
+        frustum_culling.draw_count = draw_count;
+        frustum_culling.slot_count = slot_count;
+        if instances.is_empty() {
+            debug_stats.frustum_culled_instances = 0;
+            debug_stats.occlusion_culled_instances = 0;
+            debug_stats.visible_instances = 0;
+            return;
+        }
+
+        let normal_matrices = instances
+            .iter()
+            .map(TransformRaw::get_normal_matrix)
+            .collect::<Vec<_>>();
+        state.queue.write_buffer(
+            binding_resource_container.buffers[Instances]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&instances),
+        );
+        state.queue.write_buffer(
+            binding_resource_container.buffers[Normals]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&normal_matrices),
+        );
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::InstanceDeformations]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&deformations),
+        );
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::CullingInstances]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&culling_instances),
+        );
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::CullingGroups]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&groups),
+        );
+        // every view draws into its own range of the visible instances, the counts start at zero.
+        let indirect_draws = (0..FrustumCulling::VIEW_COUNT)
+            .flat_map(|view| {
+                draw_args
+                    .iter()
+                    .map(move |(index_count, first_index, first_slot)| {
+                        DrawIndexedIndirectRaw::new(
+                            *index_count,
+                            *first_index,
+                            view as u32 * slot_count + first_slot,
+                        )
+                    })
+            })
+            .collect::<Vec<_>>();
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::IndirectDraws]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&indirect_draws),
+        );
+
+        let mut views = [[[0.0; 4]; 6]; FrustumCulling::VIEW_COUNT];
+        views[FrustumCulling::CAMERA_VIEW] = camera_planes;
+        for (cascade, (_, view_proj)) in dir_light
+            .get_view_and_proj_matrices(&camera, &projection, &shadow_settings)
+            .iter()
+            .enumerate()
+        {
+            views[FrustumCulling::cascade_view(cascade)] =
+                FrustumCulling::frustum_planes(view_proj);
+        }
+        // the pyramid of the previous frame is only usable if it wasn't recreated since.
+        let pyramid_size = DepthPyramidGenerator::pyramid_size(
+            state.sc_descriptor.width,
+            state.sc_descriptor.height,
+        );
+        let occlusion = frustum_culling.occlusion.filter(|occlusion| {
+            debug_stats.occlusion_culling && occlusion.pyramid_size == pyramid_size
+        });
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::FrustumCullingUniform]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::bytes_of(&FrustumCullingUniforms::new(
+                views,
+                occlusion.as_ref(),
+                lod_camera,
+                debug_stats.show_lod_levels,
+                instance_count,
+                draw_count,
+                slot_count,
+            )),
+        );
+
+        let (_, culling_bind_group_container) =
+            (&frustum_culling_bind_group, &bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+        let command_encoder = encoder.get_encoder();
+        command_encoder.clear_buffer(stats_buffer, 0, None);
+        let mut compute_pass = command_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+            label: Some("Frustum Culling pass"),
+        });
+        if let Some(ref query_set) = query_sets.container {
+            compute_pass
+                .write_timestamp(&query_set.timestamp_queries, query_set.next_query_index * 2); // use manual indexing for now
+            compute_pass.begin_pipeline_statistics_query(
+                &query_set.pipeline_queries,
+                query_set.next_query_index,
+            );
+        }
+        compute_pass.set_pipeline(&pipeline.0);
+        compute_pass.set_bind_group(0, &culling_bind_group_container.bind_group, &[]);
+        // one invocation per instance and view.
+        compute_pass.dispatch(
+            f32::ceil(instance_count as f32 / 64.0) as u32,
+            FrustumCulling::VIEW_COUNT as u32,
+            1,
+        );
+        if let Some(ref mut query_set) = query_sets.container {
+            compute_pass.write_timestamp(
+                &query_set.timestamp_queries,
+                query_set.next_query_index * 2 + 1,
+            ); // use manual indexing for now
+            compute_pass.end_pipeline_statistics_query();
+            query_set
+                .pass_indices
+                .insert(Passes::FrustumCulling, query_set.next_query_index);
+            query_set.next_query_index += 1;
+        }
+        drop(compute_pass);
+        // reading the stats back waits for the GPU, which isn't possible on the web.
+        if !cfg!(target_arch = "wasm32") {
+            command_encoder.copy_buffer_to_buffer(
+                stats_buffer,
+                0,
+                stats_readback_buffer,
+                0,
+                std::mem::size_of::<CullingStatsRaw>() as wgpu::BufferAddress,
+            );
+            frustum_culling.stats_pending = true;
+        }
+    }
+}
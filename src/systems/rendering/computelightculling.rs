@@ -85,8 +85,11 @@ impl<'a> System<'a> for ComputeLightCulling {
         compute_pass.set_bind_group(0, &light_bind_group_container.bind_group, &[]);
         compute_pass.set_bind_group(1, &uniform_bind_group_container.bind_group, &[]);
         compute_pass.set_bind_group(2, &tiling_bind_group_container.bind_group, &[]);
+        // one invocation per light, the spot lights are culled after the point lights.
         compute_pass.dispatch(
-            (f32::ceil(globals.get_point_light_count() as f32 / 64.0)) as u32,
+            (f32::ceil(
+                (globals.get_point_light_count() + globals.get_spot_light_count()) as f32 / 64.0,
+            )) as u32,
             1,
             1,
         );
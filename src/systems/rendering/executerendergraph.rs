@@ -0,0 +1,14 @@
+use specs::{RunNow, World, WorldExt};
+
+use crate::renderer::rendergraph::RenderGraph;
+
+/// Runs the passes of the `RenderGraph` in the order the graph resolved from their resources.
+pub struct ExecuteRenderGraph;
+
+impl<'a> RunNow<'a> for ExecuteRenderGraph {
+    fn run_now(&mut self, world: &'a World) {
+        world.write_resource::<RenderGraph>().execute(world);
+    }
+
+    fn setup(&mut self, _world: &mut World) {}
+}
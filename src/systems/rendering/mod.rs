@@ -1,12 +1,26 @@
 pub mod acquiretexture;
+pub mod builddepthpyramid;
+pub mod captureframe;
+pub mod computefrustumculling;
 pub mod computelightculling;
+pub mod executerendergraph;
+pub mod presentframe;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod reloadshaders;
 pub mod rendercollision;
 pub mod renderforwardpass;
+pub mod renderpostprocessing;
 pub mod rendershadowpass;
 pub mod renderskybox;
+pub mod renderssao;
 pub mod renderuipass;
+pub mod rendertonemapping;
+pub mod rendertransparentpass;
 pub mod resolvequerysets;
 pub mod updatebuffers;
 pub mod updatecamera;
+pub mod updatejointmatrices;
+pub mod updatematerials;
+pub mod updatemorphweights;
 pub mod updateuniformbuffers;
 pub mod writegbuffer;
@@ -0,0 +1,16 @@
+use specs::{System, WriteExpect};
+
+use crate::resources::surfacetexture::SurfaceTexture;
+
+/// Presents the swapchain texture, headless states have nothing to present as the frame stays in the offscreen texture.
+pub struct PresentFrame;
+
+impl<'a> System<'a> for PresentFrame {
+    type SystemData = WriteExpect<'a, SurfaceTexture>;
+
+    fn run(&mut self, mut surface_texture: Self::SystemData) {
+        if let Some(output) = surface_texture.texture.take() {
+            output.present();
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use specs::{RunNow, World, WorldExt};
+
+use crate::renderer::shaderlibrary::ShaderLibrary;
+use crate::resources::shadererrors::{ShaderError, ShaderErrors};
+use crate::resources::shaderhotreload::ShaderHotReload;
+use crate::ui::menu::Menu;
+
+/// Reloads the shaders whose native variant changed while the hot reload is enabled in the menu.
+/// A shader which fails to validate or to build one of its pipelines keeps the last good set of pipelines,
+/// its errors are shown in the ui.
+pub struct ReloadShaders;
+
+impl<'a> RunNow<'a> for ReloadShaders {
+    fn run_now(&mut self, world: &'a World) {
+        let enabled = world.read_resource::<Menu>().hot_reload_shaders;
+        let changed = {
+            let mut hot_reload = world.write_resource::<ShaderHotReload>();
+            if !enabled {
+                hot_reload.stop();
+                return;
+            }
+            hot_reload.poll(&world.read_resource::<ShaderErrors>())
+        };
+        if changed.is_empty() {
+            return;
+        }
+        let hot_reload = world.read_resource::<ShaderHotReload>();
+        let mut shader_errors = world.write_resource::<ShaderErrors>();
+        for (file_name, result) in changed {
+            let shader = match result {
+                Ok(shader) => shader,
+                Err(error) => {
+                    shader_errors.set_errors(file_name.as_str(), vec![error]);
+                    continue;
+                }
+            };
+            let mut errors = ShaderHotReload::validate(file_name.as_str(), &shader);
+            if errors.is_empty() {
+                let previous = ShaderLibrary::set_override(file_name.as_str(), shader.source);
+                let mut swaps = Vec::new();
+                for reloader in hot_reload.reloaders(file_name.as_str()) {
+                    match reloader(world) {
+                        Ok(swap) => swaps.push(swap),
+                        Err(message) => errors.push(ShaderError {
+                            file_name: file_name.clone(),
+                            location: None,
+                            message,
+                        }),
+                    }
+                }
+                // the pipelines built so far are dropped when one of them failed.
+                if errors.is_empty() {
+                    for swap in swaps {
+                        swap(world);
+                    }
+                    log::info!(target: "shader_hot_reload", "reloaded {}", file_name);
+                } else {
+                    ShaderLibrary::restore_override(file_name.as_str(), previous);
+                }
+            }
+            shader_errors.set_errors(file_name.as_str(), errors);
+        }
+    }
+
+    fn setup(&mut self, _world: &mut World) {}
+}
@@ -3,10 +3,10 @@ use crate::components::physicshandle::PhysicsHandle;
 use crate::components::transform::Transform;
 use crate::renderer::bindgroups::debugcollision::DebugCollisionBindGroup;
 use crate::renderer::pipelines::debugcollision::DebugCollisionPipeline;
+use crate::resources::bindingresourcecontainer::TextureViewTypes;
 use crate::resources::commandencoder::HorizonCommandEncoder;
 use crate::resources::gpuquerysets::GpuQuerySetContainer;
 use crate::resources::scriptingstate::ScriptingState;
-use crate::resources::surfacetexture::SurfaceTexture;
 use crate::systems::physics::PhysicsWorld;
 use crate::ui::debugstats::DebugStats;
 use crate::ui::gpustats::Passes;
@@ -18,7 +18,6 @@ pub struct RenderCollision;
 
 impl<'a> System<'a> for RenderCollision {
     type SystemData = (
-        ReadExpect<'a, SurfaceTexture>,
         WriteExpect<'a, HorizonCommandEncoder>,
         ReadExpect<'a, State>,
         ReadStorage<'a, PhysicsHandle>,
@@ -37,7 +36,6 @@ impl<'a> System<'a> for RenderCollision {
     fn run(
         &mut self,
         (
-            surface_texture,
             mut cmd_encoder,
             state,
             physics_handles,
@@ -57,14 +55,13 @@ impl<'a> System<'a> for RenderCollision {
             return;
         }
         let encoder = cmd_encoder.get_encoder();
-        let surface_text = surface_texture.texture.as_ref().unwrap();
-        let surface_view = surface_text
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let hdr_view = binding_resource_container.texture_views[TextureViewTypes::HdrScene]
+            .as_ref()
+            .unwrap();
         let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[wgpu::RenderPassColorAttachment {
                 resolve_target: None,
-                view: &surface_view,
+                view: hdr_view,
 
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
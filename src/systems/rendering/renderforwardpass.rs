@@ -2,7 +2,8 @@ use crate::{
     renderer::{
         bindgroupcontainer::BindGroupContainer,
         bindgroups::{
-            deferred::DeferredBindGroup, lighting::LightBindGroup, uniforms::UniformBindGroup,
+            deferred::DeferredBindGroup, environment::EnvironmentBindGroup,
+            lighting::LightBindGroup, uniforms::UniformBindGroup,
         },
         pipelines::forwardpipeline::ForwardPipeline,
         state::State,
@@ -14,8 +15,9 @@ use crate::{
 };
 
 use crate::resources::bindingresourcecontainer::BufferTypes::DeferredVao;
+use crate::resources::bindingresourcecontainer::{TextureTypes, TextureViewTypes};
+use crate::resources::framecapture::{CaptureTarget, FrameCapture};
 use crate::resources::gpuquerysets::GpuQuerySetContainer;
-use crate::resources::surfacetexture::SurfaceTexture;
 use crate::ui::gpustats::Passes;
 use specs::prelude::*;
 
@@ -32,8 +34,9 @@ impl<'a> System<'a> for RenderForwardPass {
         ReadExpect<'a, ForwardPipeline>,
         Write<'a, RenderResult>,
         ReadStorage<'a, DeferredBindGroup>,
-        ReadExpect<'a, SurfaceTexture>,
         WriteExpect<'a, GpuQuerySetContainer>,
+        ReadStorage<'a, EnvironmentBindGroup>,
+        WriteExpect<'a, FrameCapture>,
     );
 
     fn run(
@@ -48,8 +51,9 @@ impl<'a> System<'a> for RenderForwardPass {
             forward_pipeline,
             render_result,
             deferred_bind_group,
-            surface_texture,
             mut query_sets,
+            environment_bind_group,
+            mut frame_capture,
         ): Self::SystemData,
     ) {
         if render_result.result.is_some() {
@@ -58,17 +62,14 @@ impl<'a> System<'a> for RenderForwardPass {
 
         let cmd_encoder = encoder.get_encoder();
 
-        let view = surface_texture
-            .texture
+        let view = binding_resource_container.texture_views[TextureViewTypes::HdrScene]
             .as_ref()
-            .unwrap()
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+            .unwrap();
 
         let mut render_pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("forward pass"),
             color_attachments: &[wgpu::RenderPassColorAttachment {
-                view: &view,
+                view,
                 resolve_target: None,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Clear(wgpu::Color {
@@ -103,9 +104,15 @@ impl<'a> System<'a> for RenderForwardPass {
             .join()
             .next()
             .unwrap();
+        let (_, environment_bind_group_container) =
+            (&environment_bind_group, &bind_group_containers)
+                .join()
+                .next()
+                .unwrap();
         render_pass.set_bind_group(0, &deffered_bind_group_container.bind_group, &[]);
         render_pass.set_bind_group(1, &uniform_bind_group_container.bind_group, &[]);
         render_pass.set_bind_group(2, &light_bind_group_container.bind_group, &[]);
+        render_pass.set_bind_group(3, &environment_bind_group_container.bind_group, &[]);
 
         render_pass.set_vertex_buffer(
             0,
@@ -127,5 +134,15 @@ impl<'a> System<'a> for RenderForwardPass {
             query_set.next_query_index += 1;
         }
         drop(render_pass);
+        frame_capture.store_pass_output(
+            &state.device,
+            cmd_encoder,
+            CaptureTarget::Forward,
+            binding_resource_container.textures[TextureTypes::HdrScene]
+                .as_ref()
+                .unwrap(),
+            state.sc_descriptor.width,
+            state.sc_descriptor.height,
+        );
     }
 }
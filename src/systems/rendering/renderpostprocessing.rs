@@ -0,0 +1,23 @@
+use specs::{RunNow, World, WorldExt};
+
+use crate::renderer::postprocessing::{PostProcessStack, PostProcessStage};
+use crate::RenderResult;
+
+/// Records the enabled effects of one stage of the `PostProcessStack`.
+/// The effects read their settings from the world so this runs with the world instead of fixed system data.
+pub struct RenderPostProcessing(pub PostProcessStage);
+
+impl<'a> RunNow<'a> for RenderPostProcessing {
+    fn run_now(&mut self, world: &'a World) {
+        if world.read_resource::<RenderResult>().result.is_some() {
+            return;
+        }
+        let mut stack = world.write_resource::<PostProcessStack>();
+        if self.0 == PostProcessStage::Hdr {
+            stack.prepare(world);
+        }
+        stack.render(world, self.0);
+    }
+
+    fn setup(&mut self, _world: &mut World) {}
+}
@@ -1,22 +1,25 @@
-use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect};
+use specs::{Join, ReadExpect, ReadStorage, System, WriteExpect};
 use wgpu::BufferAddress;
 
 use crate::{
-    components::transform::Transform,
     renderer::{
         bindgroupcontainer::BindGroupContainer, bindgroups::shadow::ShadowBindGroup,
-        model::HorizonModel, pipelines::shadowpipeline::ShadowPipeline, state::State,
+        pipelines::shadowpipeline::ShadowPipeline, state::State,
     },
     resources::{
         bindingresourcecontainer::BindingResourceContainer, commandencoder::HorizonCommandEncoder,
     },
-    BufferTypes, Camera, DirectionalLight, Instances, Projection, RawModel, ShadowUniform,
+    BufferTypes, Camera, DirectionalLight, Projection, RawModel, ShadowUniform,
 };
 
-use crate::components::transform::TransformRaw;
+use crate::components::materialoverride::MaterialOverride;
 use crate::renderer::primitives::uniforms::ShadowUniforms;
 use crate::resources::bindingresourcecontainer::*;
+use crate::resources::frustumculling::{DrawIndexedIndirectRaw, FrustumCulling};
 use crate::resources::gpuquerysets::GpuQuerySetContainer;
+use crate::resources::shadowatlas::{ShadowAtlas, ShadowAtlasTileRaw};
+use crate::resources::shadowsettings::ShadowSettings;
+use crate::ui::debugstats::DebugStats;
 use crate::ui::gpustats::Passes;
 
 pub struct RenderShadowPass;
@@ -25,15 +28,19 @@ impl<'a> System<'a> for RenderShadowPass {
         ReadExpect<'a, State>,
         WriteExpect<'a, HorizonCommandEncoder>,
         ReadExpect<'a, BindingResourceContainer>,
-        Entities<'a>,
         ReadStorage<'a, RawModel>,
-        ReadStorage<'a, Transform>,
         ReadStorage<'a, BindGroupContainer>,
         ReadStorage<'a, ShadowBindGroup>,
         ReadExpect<'a, ShadowPipeline>,
         ReadExpect<'a, DirectionalLight>,
         ReadExpect<'a, Camera>,
         WriteExpect<'a, GpuQuerySetContainer>,
+        ReadExpect<'a, Projection>,
+        ReadExpect<'a, ShadowSettings>,
+        WriteExpect<'a, DebugStats>,
+        ReadExpect<'a, ShadowAtlas>,
+        ReadExpect<'a, FrustumCulling>,
+        ReadStorage<'a, MaterialOverride>,
     );
 
     fn run(
@@ -42,15 +49,19 @@ impl<'a> System<'a> for RenderShadowPass {
             state,
             mut encoder,
             binding_resource_container,
-            entities,
             models,
-            transforms,
             bind_group_container,
             shadow_bind_group,
             shadow_pipeline,
             dir_light,
             camera,
             mut query_sets,
+            projection,
+            shadow_settings,
+            mut debug_stats,
+            shadow_atlas,
+            frustum_culling,
+            material_overrides,
         ): Self::SystemData,
     ) {
         let cmd_encoder = encoder.get_encoder();
@@ -58,11 +69,17 @@ impl<'a> System<'a> for RenderShadowPass {
         let shadow_uniform_buf = binding_resource_container.buffers[ShadowUniform]
             .as_ref()
             .unwrap();
-        let raw_dir_lights = dir_light.get_view_and_proj_matrices(
-            &camera,
-            State::CASCADE_DISTS.0,
-            State::CASCADE_DISTS.1,
+        let raw_dir_lights =
+            dir_light.get_view_and_proj_matrices(&camera, &projection, &shadow_settings);
+        debug_stats.shadow_cascade_splits = raw_dir_lights.iter().map(|v| v.0).collect();
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::ShadowSettingsUniform]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&[shadow_settings.to_raw(debug_stats.show_shadow_cascades)]),
         );
+        let resolution = shadow_settings.resolution() as f32;
 
         let shadow_cascade_buffer = binding_resource_container.buffers[BufferTypes::ShadowCascade]
             .as_ref()
@@ -93,9 +110,18 @@ impl<'a> System<'a> for RenderShadowPass {
                     .as_slice(),
             ),
         );
+        let indirect_buffer = binding_resource_container.buffers[BufferTypes::IndirectDraws]
+            .as_ref()
+            .unwrap();
+        let (_, sh_pass_bind_group) = (&shadow_bind_group, &bind_group_container)
+            .join()
+            .next()
+            .unwrap();
+
         for (index, cascade) in binding_resource_container.texture_array_views
             [TextureArrayViewTypes::Shadow]
             .iter()
+            .take(raw_dir_lights.len())
             .enumerate()
         {
             let format = format!("shadow pass for cascade: #{}", index);
@@ -126,42 +152,18 @@ impl<'a> System<'a> for RenderShadowPass {
                 );
             }
             pass.insert_debug_marker("render_entities");
+            // lower resolutions only use the top left part of the cascade's layer.
+            pass.set_viewport(0.0, 0.0, resolution, resolution, 0.0, 1.0);
             pass.set_pipeline(&shadow_pipeline.0);
-            let (_, sh_pass_bind_group) = (&shadow_bind_group, &bind_group_container)
-                .join()
-                .next()
-                .unwrap();
             pass.set_bind_group(0, &sh_pass_bind_group.bind_group, &[]);
-            let mut begin_instance_index: u32 = 0;
-            for (model, model_ent) in (&models, &*entities).join() {
-                let mut instance_buffer = Vec::new();
-                for transform in transforms.join() {
-                    if let Some(model) = transform.model {
-                        if model_ent == model {
-                            instance_buffer.push(transform.to_raw());
-                        }
-                    }
-                }
-                state.queue.write_buffer(
-                    binding_resource_container.buffers[Instances]
-                        .as_ref()
-                        .unwrap(),
-                    (std::mem::size_of::<TransformRaw>() * begin_instance_index as usize)
-                        as BufferAddress,
-                    bytemuck::cast_slice(&instance_buffer),
-                );
-                for mesh in &model.meshes {
-                    pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
-                    pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
-                    pass.draw_indexed(
-                        0..mesh.index_buffer_len,
-                        0,
-                        begin_instance_index..begin_instance_index + instance_buffer.len() as u32,
-                    );
-                }
-
-                begin_instance_index += instance_buffer.len() as u32;
-            }
+            draw_shadow_casters(
+                &mut pass,
+                &models,
+                &material_overrides,
+                &frustum_culling,
+                indirect_buffer,
+                FrustumCulling::cascade_view(index),
+            );
             if let Some(ref mut query_set) = query_sets.container {
                 pass.write_timestamp(
                     &query_set.timestamp_queries,
@@ -175,5 +177,103 @@ impl<'a> System<'a> for RenderShadowPass {
                 query_set.next_query_index += 1;
             }
         }
+
+        // every point light face and spot light is rendered into its own tile of the atlas.
+        let atlas_tiles_buffer = binding_resource_container.buffers[BufferTypes::ShadowAtlasTiles]
+            .as_ref()
+            .unwrap();
+        let atlas_view = binding_resource_container.texture_views[TextureViewTypes::ShadowAtlas]
+            .as_ref()
+            .unwrap();
+        for (index, tile) in shadow_atlas.tiles.iter().enumerate() {
+            cmd_encoder.copy_buffer_to_buffer(
+                atlas_tiles_buffer,
+                (index * std::mem::size_of::<ShadowAtlasTileRaw>()) as wgpu::BufferAddress,
+                shadow_uniform_buf,
+                0,
+                64,
+            );
+            let mut pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("shadow atlas pass"),
+                color_attachments: &[],
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: atlas_view,
+                    depth_ops: Some(wgpu::Operations {
+                        // the first tile clears the whole atlas.
+                        load: if index == 0 {
+                            wgpu::LoadOp::Clear(1.0f32)
+                        } else {
+                            wgpu::LoadOp::Load
+                        },
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+            pass.set_viewport(
+                tile.x as f32,
+                tile.y as f32,
+                tile.size as f32,
+                tile.size as f32,
+                0.0,
+                1.0,
+            );
+            pass.set_scissor_rect(tile.x, tile.y, tile.size, tile.size);
+            pass.set_pipeline(&shadow_pipeline.0);
+            pass.set_bind_group(0, &sh_pass_bind_group.bind_group, &[]);
+            draw_shadow_casters(
+                &mut pass,
+                &models,
+                &material_overrides,
+                &frustum_culling,
+                indirect_buffer,
+                FrustumCulling::UNCULLED_VIEW,
+            );
+        }
+    }
+}
+
+/// Draws every culled model mesh with the indirect draws of the given view,
+/// the material is bound for the alpha test of masked primitives.
+/// Instances with a custom material have their own draws and keep the alpha test of their glTF material.
+fn draw_shadow_casters<'a>(
+    pass: &mut wgpu::RenderPass<'a>,
+    models: &'a ReadStorage<RawModel>,
+    material_overrides: &'a ReadStorage<MaterialOverride>,
+    frustum_culling: &FrustumCulling,
+    indirect_buffer: &'a wgpu::Buffer,
+    view: usize,
+) {
+    for draw in &frustum_culling.draws {
+        let model = match models.get(draw.model) {
+            Some(model) => model,
+            None => continue,
+        };
+        let material_override = draw
+            .material_override
+            .and_then(|entity| material_overrides.get(entity));
+        let indirect_offset = frustum_culling.indirect_offset(view, draw);
+        let primitive_count = model.mesh_primitives(draw.mesh_index).count();
+        for lod in 0..model.mesh_lod_count(draw.mesh_index) {
+            for (index, mesh) in model.mesh_primitives(draw.mesh_index).enumerate() {
+                pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                pass.set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                pass.set_bind_group(
+                    1,
+                    &model
+                        .material(mesh.material_index, material_override)
+                        .bind_group_container
+                        .bind_group,
+                    &[],
+                );
+                pass.draw_indexed_indirect(
+                    indirect_buffer,
+                    indirect_offset
+                        + ((lod * primitive_count + index)
+                            * std::mem::size_of::<DrawIndexedIndirectRaw>())
+                            as BufferAddress,
+                );
+            }
+        }
     }
 }
@@ -1,7 +1,10 @@
 use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, TextureTypes, TextureViewTypes,
+};
 use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::framecapture::{CaptureTarget, FrameCapture};
 use crate::resources::gpuquerysets::GpuQuerySetContainer;
-use crate::resources::surfacetexture::SurfaceTexture;
 use crate::ui::gpustats::Passes;
 use crate::{LightBindGroup, RenderResult, SkyboxBindGroup, SkyboxPipeline, State};
 use specs::{Join, ReadExpect, ReadStorage, System, Write, WriteExpect};
@@ -11,7 +14,7 @@ pub struct RenderSkyBox;
 
 impl<'a> System<'a> for RenderSkyBox {
     type SystemData = (
-        ReadExpect<'a, SurfaceTexture>,
+        ReadExpect<'a, BindingResourceContainer>,
         ReadExpect<'a, RenderResult>,
         ReadStorage<'a, BindGroupContainer>,
         ReadStorage<'a, SkyboxBindGroup>,
@@ -19,12 +22,13 @@ impl<'a> System<'a> for RenderSkyBox {
         ReadExpect<'a, State>,
         WriteExpect<'a, HorizonCommandEncoder>,
         WriteExpect<'a, GpuQuerySetContainer>,
+        WriteExpect<'a, FrameCapture>,
     );
 
     fn run(
         &mut self,
         (
-            surface_texture,
+            binding_resource_container,
             render_result,
             bind_group_container,
             skybox_bind_group,
@@ -32,6 +36,7 @@ impl<'a> System<'a> for RenderSkyBox {
             state,
             mut command_encoder,
             mut query_sets,
+            mut frame_capture,
         ): Self::SystemData,
     ) {
         if render_result.result.is_some() {
@@ -39,12 +44,9 @@ impl<'a> System<'a> for RenderSkyBox {
         }
 
         let cmd_encoder = command_encoder.get_encoder();
-        let view = surface_texture
-            .texture
+        let view = binding_resource_container.texture_views[TextureViewTypes::HdrScene]
             .as_ref()
-            .unwrap()
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+            .unwrap();
 
         let mut render_pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("SkyboxRenderPass"),
@@ -54,7 +56,7 @@ impl<'a> System<'a> for RenderSkyBox {
                     load: wgpu::LoadOp::Load,
                     store: true,
                 },
-                view: &view,
+                view,
             }],
             depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
                 view: &state.depth_texture.view,
@@ -88,5 +90,16 @@ impl<'a> System<'a> for RenderSkyBox {
                 .insert(Passes::Skybox, query_set.next_query_index);
             query_set.next_query_index += 1;
         }
+        drop(render_pass);
+        frame_capture.store_pass_output(
+            &state.device,
+            cmd_encoder,
+            CaptureTarget::Skybox,
+            binding_resource_container.textures[TextureTypes::HdrScene]
+                .as_ref()
+                .unwrap(),
+            state.sc_descriptor.width,
+            state.sc_descriptor.height,
+        );
     }
 }
@@ -0,0 +1,173 @@
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::bindgroups::ssao::{SsaoBindGroup, SsaoBlurBindGroup};
+use crate::renderer::pipelines::ssaopipeline::{SsaoBlurPipeline, SsaoPipeline};
+use crate::renderer::rendergraph::{
+    RenderGraphPass, RenderGraphResource, TransientTarget, TransientTargets,
+};
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, BufferTypes, TextureViewTypes,
+};
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::gpuquerysets::GpuQuerySetContainer;
+use crate::ui::debugstats::DebugStats;
+use crate::ui::gpustats::Passes;
+use crate::{HorizonBindGroup, RenderResult, State};
+use specs::{Join, ReadExpect, ReadStorage, World, WriteExpect};
+use wgpu::RenderPassColorAttachment;
+
+type SsaoData<'a> = (
+    ReadExpect<'a, RenderResult>,
+    ReadExpect<'a, BindingResourceContainer>,
+    ReadStorage<'a, BindGroupContainer>,
+    ReadStorage<'a, SsaoBindGroup>,
+    ReadExpect<'a, SsaoPipeline>,
+    ReadExpect<'a, SsaoBlurPipeline>,
+    ReadExpect<'a, DebugStats>,
+    ReadExpect<'a, State>,
+    WriteExpect<'a, HorizonCommandEncoder>,
+    WriteExpect<'a, GpuQuerySetContainer>,
+);
+
+/// Computes the ambient occlusion from the gbuffer and blurs it into the target the lighting reads,
+/// the target is cleared to white when the ambient occlusion is disabled.
+/// The noisy ambient occlusion is a transient target of the render graph, the blur bind group follows its allocation.
+#[derive(Default)]
+pub struct RenderSsao {
+    blur_bind_group: Option<BindGroupContainer>,
+    blur_target_generation: u64,
+}
+
+impl RenderGraphPass for RenderSsao {
+    fn name(&self) -> &'static str {
+        "ssao"
+    }
+    fn reads(&self) -> Vec<RenderGraphResource> {
+        vec![
+            RenderGraphResource::Texture(TextureViewTypes::DeferredPosition),
+            RenderGraphResource::Texture(TextureViewTypes::DeferredNormals),
+        ]
+    }
+    fn writes(&self) -> Vec<RenderGraphResource> {
+        vec![
+            RenderGraphResource::Transient(SsaoBindGroup::RAW_TARGET),
+            RenderGraphResource::Texture(TextureViewTypes::AmbientOcclusionBlurred),
+        ]
+    }
+    fn transient_targets(&self) -> Vec<TransientTarget> {
+        vec![TransientTarget::new(
+            SsaoBindGroup::RAW_TARGET,
+            SsaoBindGroup::AMBIENT_OCCLUSION_FORMAT,
+        )]
+    }
+
+    fn run(&mut self, world: &World, targets: &TransientTargets) {
+        let (
+            render_result,
+            binding_resource_container,
+            bind_group_container,
+            ssao_bind_group,
+            ssao_pipeline,
+            ssao_blur_pipeline,
+            debug_stats,
+            state,
+            mut command_encoder,
+            mut query_sets,
+        ) = world.system_data::<SsaoData>();
+        if render_result.result.is_some() {
+            return;
+        }
+        let raw_view = targets.view(SsaoBindGroup::RAW_TARGET).unwrap();
+        if self.blur_bind_group.is_none() || self.blur_target_generation != targets.generation() {
+            self.blur_bind_group = Some(SsaoBlurBindGroup::create_container(
+                &state.device,
+                raw_view,
+            ));
+            self.blur_target_generation = targets.generation();
+        }
+        let cmd_encoder = command_encoder.get_encoder();
+        let blurred_view = binding_resource_container.texture_views
+            [TextureViewTypes::AmbientOcclusionBlurred]
+            .as_ref()
+            .unwrap();
+        let settings = debug_stats.ssao;
+        if !settings.enabled {
+            cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("Ssao clear pass"),
+                color_attachments: &[RenderPassColorAttachment {
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                        store: true,
+                    },
+                    view: blurred_view,
+                }],
+                depth_stencil_attachment: None,
+            });
+            return;
+        }
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::SsaoUniform]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::bytes_of(&settings.to_raw()),
+        );
+        let (_, ssao_bind_group_container) = (&ssao_bind_group, &bind_group_container)
+            .join()
+            .next()
+            .unwrap();
+        let ssao_blur_bind_group_container = self.blur_bind_group.as_ref().unwrap();
+
+        let mut render_pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Ssao render pass"),
+            color_attachments: &[RenderPassColorAttachment {
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                    store: true,
+                },
+                view: raw_view,
+            }],
+            depth_stencil_attachment: None,
+        });
+        if let Some(ref query_set) = query_sets.container {
+            render_pass
+                .write_timestamp(&query_set.timestamp_queries, query_set.next_query_index * 2);
+            render_pass.begin_pipeline_statistics_query(
+                &query_set.pipeline_queries,
+                query_set.next_query_index,
+            );
+        }
+        render_pass.set_pipeline(&ssao_pipeline.0);
+        render_pass.set_bind_group(0, &ssao_bind_group_container.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        if let Some(ref mut query_set) = query_sets.container {
+            render_pass.write_timestamp(
+                &query_set.timestamp_queries,
+                query_set.next_query_index * 2 + 1,
+            );
+            render_pass.end_pipeline_statistics_query();
+            query_set
+                .pass_indices
+                .insert(Passes::AmbientOcclusion, query_set.next_query_index);
+            query_set.next_query_index += 1;
+        }
+        drop(render_pass);
+
+        let mut render_pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Ssao blur render pass"),
+            color_attachments: &[RenderPassColorAttachment {
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::WHITE),
+                    store: true,
+                },
+                view: blurred_view,
+            }],
+            depth_stencil_attachment: None,
+        });
+        render_pass.set_pipeline(&ssao_blur_pipeline.0);
+        render_pass.set_bind_group(0, &ssao_blur_bind_group_container.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+    }
+}
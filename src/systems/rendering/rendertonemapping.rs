@@ -0,0 +1,157 @@
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::bindgroups::tonemapping::ToneMappingBindGroup;
+use crate::renderer::pipelines::luminancepipeline::{
+    AverageLuminancePipeline, LuminanceHistogramPipeline,
+};
+use crate::renderer::pipelines::tonemappingpipeline::ToneMappingPipeline;
+use crate::renderer::postprocessing::{PostProcessStack, PostProcessStage};
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, BufferTypes, TextureViewTypes,
+};
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::deltatime::DeltaTime;
+use crate::resources::gpuquerysets::GpuQuerySetContainer;
+use crate::resources::surfacetexture::SurfaceTexture;
+use crate::ui::debugstats::DebugStats;
+use crate::ui::gpustats::Passes;
+use crate::{RenderResult, State};
+use specs::{Join, ReadExpect, ReadStorage, System, WriteExpect};
+use wgpu::RenderPassColorAttachment;
+
+/// Maps the HDR scene to the surface, the automatic exposure is computed from a histogram of the scene's luminance first.
+/// When LDR post-processing effects are enabled the result goes to their first target instead.
+pub struct RenderToneMapping;
+
+impl<'a> System<'a> for RenderToneMapping {
+    type SystemData = (
+        ReadExpect<'a, SurfaceTexture>,
+        ReadExpect<'a, RenderResult>,
+        ReadExpect<'a, BindingResourceContainer>,
+        ReadStorage<'a, BindGroupContainer>,
+        ReadStorage<'a, ToneMappingBindGroup>,
+        ReadExpect<'a, LuminanceHistogramPipeline>,
+        ReadExpect<'a, AverageLuminancePipeline>,
+        ReadExpect<'a, ToneMappingPipeline>,
+        ReadExpect<'a, PostProcessStack>,
+        ReadExpect<'a, DebugStats>,
+        ReadExpect<'a, DeltaTime>,
+        ReadExpect<'a, State>,
+        WriteExpect<'a, HorizonCommandEncoder>,
+        WriteExpect<'a, GpuQuerySetContainer>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            surface_texture,
+            render_result,
+            binding_resource_container,
+            bind_group_container,
+            tone_mapping_bind_group,
+            histogram_pipeline,
+            average_pipeline,
+            tone_mapping_pipeline,
+            post_process_stack,
+            debug_stats,
+            delta_time,
+            state,
+            mut command_encoder,
+            mut query_sets,
+        ): Self::SystemData,
+    ) {
+        if render_result.result.is_some() {
+            return;
+        }
+        let (width, height) = (state.sc_descriptor.width, state.sc_descriptor.height);
+        let settings = debug_stats.tone_mapping;
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::ToneMappingUniform]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::bytes_of(&settings.to_raw(width * height, delta_time.delta)),
+        );
+        let (_, tone_mapping_bind_group_container) =
+            (&tone_mapping_bind_group, &bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+        let cmd_encoder = command_encoder.get_encoder();
+
+        if settings.auto_exposure {
+            let mut compute_pass = cmd_encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+                label: Some("Luminance histogram pass"),
+            });
+            if let Some(ref query_set) = query_sets.container {
+                compute_pass
+                    .write_timestamp(&query_set.timestamp_queries, query_set.next_query_index * 2);
+                compute_pass.begin_pipeline_statistics_query(
+                    &query_set.pipeline_queries,
+                    query_set.next_query_index,
+                );
+            }
+            compute_pass.set_bind_group(0, &tone_mapping_bind_group_container.bind_group, &[]);
+            compute_pass.set_pipeline(&histogram_pipeline.0);
+            compute_pass.dispatch(
+                f32::ceil(width as f32 / 16.0) as u32,
+                f32::ceil(height as f32 / 16.0) as u32,
+                1,
+            );
+            compute_pass.set_pipeline(&average_pipeline.0);
+            compute_pass.dispatch(1, 1, 1);
+            if let Some(ref mut query_set) = query_sets.container {
+                compute_pass.write_timestamp(
+                    &query_set.timestamp_queries,
+                    query_set.next_query_index * 2 + 1,
+                );
+                compute_pass.end_pipeline_statistics_query();
+                query_set
+                    .pass_indices
+                    .insert(Passes::LuminanceHistogram, query_set.next_query_index);
+                query_set.next_query_index += 1;
+            }
+        }
+
+        let view = if post_process_stack.has_enabled(PostProcessStage::Ldr) {
+            binding_resource_container.texture_views[TextureViewTypes::PostProcessPing]
+                .as_ref()
+                .unwrap()
+        } else {
+            surface_texture.view.as_ref().unwrap()
+        };
+        let mut render_pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("ToneMappingRenderPass"),
+            color_attachments: &[RenderPassColorAttachment {
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                    store: true,
+                },
+                view,
+            }],
+            depth_stencil_attachment: None,
+        });
+        if let Some(ref query_set) = query_sets.container {
+            render_pass
+                .write_timestamp(&query_set.timestamp_queries, query_set.next_query_index * 2);
+            render_pass.begin_pipeline_statistics_query(
+                &query_set.pipeline_queries,
+                query_set.next_query_index,
+            );
+        }
+        render_pass.set_pipeline(&tone_mapping_pipeline.0);
+        render_pass.set_bind_group(0, &tone_mapping_bind_group_container.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        if let Some(ref mut query_set) = query_sets.container {
+            render_pass.write_timestamp(
+                &query_set.timestamp_queries,
+                query_set.next_query_index * 2 + 1,
+            );
+            render_pass.end_pipeline_statistics_query();
+            query_set
+                .pass_indices
+                .insert(Passes::ToneMapping, query_set.next_query_index);
+            query_set.next_query_index += 1;
+        }
+    }
+}
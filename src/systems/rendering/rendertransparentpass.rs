@@ -0,0 +1,159 @@
+use crate::components::materialoverride::MaterialOverride;
+use crate::renderer::bindgroupcontainer::BindGroupContainer;
+use crate::renderer::bindgroups::lighting::LightBindGroup;
+use crate::renderer::bindgroups::transparent::TransparentBindGroup;
+use crate::renderer::bindgroups::uniforms::UniformBindGroup;
+use crate::renderer::pipelines::transparentpipeline::TransparentPipeline;
+use crate::resources::bindingresourcecontainer::{
+    BindingResourceContainer, TextureTypes, TextureViewTypes,
+};
+use crate::resources::commandencoder::HorizonCommandEncoder;
+use crate::resources::frustumculling::FrustumCulling;
+use crate::resources::gpuquerysets::GpuQuerySetContainer;
+use crate::ui::gpustats::Passes;
+use crate::{RawModel, RenderResult, State};
+use specs::{Join, ReadExpect, ReadStorage, System, WriteExpect};
+use wgpu::{RenderPassColorAttachment, RenderPassDepthStencilAttachment};
+
+/// Draws the transparent primitives over the lit opaque scene, sorted from back to front.
+/// The scene is copied first so transmissive materials can refract it.
+pub struct RenderTransparentPass;
+
+impl<'a> System<'a> for RenderTransparentPass {
+    type SystemData = (
+        ReadExpect<'a, RenderResult>,
+        ReadExpect<'a, BindingResourceContainer>,
+        ReadStorage<'a, BindGroupContainer>,
+        ReadStorage<'a, UniformBindGroup>,
+        ReadStorage<'a, LightBindGroup>,
+        ReadStorage<'a, TransparentBindGroup>,
+        ReadStorage<'a, RawModel>,
+        ReadExpect<'a, FrustumCulling>,
+        ReadExpect<'a, TransparentPipeline>,
+        ReadExpect<'a, State>,
+        WriteExpect<'a, HorizonCommandEncoder>,
+        WriteExpect<'a, GpuQuerySetContainer>,
+        ReadStorage<'a, MaterialOverride>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            render_result,
+            binding_resource_container,
+            bind_group_container,
+            uniform_bind_group,
+            light_bind_group,
+            transparent_bind_group,
+            models,
+            frustum_culling,
+            pipeline,
+            state,
+            mut command_encoder,
+            mut query_sets,
+            material_overrides,
+        ): Self::SystemData,
+    ) {
+        if render_result.result.is_some() || frustum_culling.transparent_instances.is_empty() {
+            return;
+        }
+
+        let cmd_encoder = command_encoder.get_encoder();
+        cmd_encoder.copy_texture_to_texture(
+            binding_resource_container.textures[TextureTypes::HdrScene]
+                .as_ref()
+                .unwrap()
+                .as_image_copy(),
+            binding_resource_container.textures[TextureTypes::SceneColor]
+                .as_ref()
+                .unwrap()
+                .as_image_copy(),
+            wgpu::Extent3d {
+                width: state.sc_descriptor.width,
+                height: state.sc_descriptor.height,
+                depth_or_array_layers: 1,
+            },
+        );
+        let view = binding_resource_container.texture_views[TextureViewTypes::HdrScene]
+            .as_ref()
+            .unwrap();
+        let mut render_pass = cmd_encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("TransparentRenderPass"),
+            color_attachments: &[RenderPassColorAttachment {
+                resolve_target: None,
+                ops: wgpu::Operations {
+                    load: wgpu::LoadOp::Load,
+                    store: true,
+                },
+                view,
+            }],
+            depth_stencil_attachment: Some(RenderPassDepthStencilAttachment {
+                view: &state.depth_texture.view,
+                depth_ops: None,
+                stencil_ops: None,
+            }),
+        });
+        if let Some(ref query_set) = query_sets.container {
+            render_pass
+                .write_timestamp(&query_set.timestamp_queries, query_set.next_query_index * 2);
+            render_pass.begin_pipeline_statistics_query(
+                &query_set.pipeline_queries,
+                query_set.next_query_index,
+            );
+        }
+        let (_, uniform_bind_group_container) = (&uniform_bind_group, &bind_group_container)
+            .join()
+            .next()
+            .unwrap();
+        let (_, light_bind_group_container) = (&light_bind_group, &bind_group_container)
+            .join()
+            .next()
+            .unwrap();
+        let (_, transparent_bind_group_container) =
+            (&transparent_bind_group, &bind_group_container)
+                .join()
+                .next()
+                .unwrap();
+        render_pass.set_pipeline(&pipeline.0);
+        render_pass.set_bind_group(0, &uniform_bind_group_container.bind_group, &[]);
+        render_pass.set_bind_group(2, &light_bind_group_container.bind_group, &[]);
+        render_pass.set_bind_group(3, &transparent_bind_group_container.bind_group, &[]);
+        // every instance is a separate draw so the instances of a mesh are blended in order too.
+        for transparent_instance in &frustum_culling.transparent_instances {
+            let model = match models.get(transparent_instance.model) {
+                Some(model) => model,
+                None => continue,
+            };
+            let material_override = transparent_instance
+                .material_override
+                .and_then(|entity| material_overrides.get(entity));
+            for mesh in model.mesh_primitives(transparent_instance.mesh_index) {
+                let material = model.material(mesh.material_index, material_override);
+                if !material.transparent {
+                    continue;
+                }
+                render_pass.set_vertex_buffer(0, mesh.vertex_buffer.slice(..));
+                render_pass
+                    .set_index_buffer(mesh.index_buffer.slice(..), wgpu::IndexFormat::Uint32);
+                render_pass.set_bind_group(1, &material.bind_group_container.bind_group, &[]);
+                let lod = mesh.lod(transparent_instance.lod);
+                render_pass.draw_indexed(
+                    lod.first_index..lod.first_index + lod.index_count,
+                    0,
+                    transparent_instance.instance..transparent_instance.instance + 1,
+                );
+            }
+        }
+        if let Some(ref mut query_set) = query_sets.container {
+            render_pass.write_timestamp(
+                &query_set.timestamp_queries,
+                query_set.next_query_index * 2 + 1,
+            );
+            render_pass.end_pipeline_statistics_query();
+            query_set
+                .pass_indices
+                .insert(Passes::Transparent, query_set.next_query_index);
+            query_set.next_query_index += 1;
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use egui_wgpu_backend::ScreenDescriptor;
 use epi::{IntegrationInfo, WebInfo};
-use specs::{Join, ReadExpect, ReadStorage, System, Write, WriteExpect, WriteStorage};
+use specs::{Join, Read, ReadExpect, ReadStorage, System, Write, WriteExpect, WriteStorage};
 use wgpu::util::DeviceExt;
 use wgpu::{FilterMode, Texture, TextureView};
 
@@ -8,11 +8,18 @@ use crate::renderer::utils::texturerenderer::TextureRenderer;
 use crate::resources::commandencoder::HorizonCommandEncoder;
 use crate::resources::eguicontainer::EguiContainer;
 use crate::resources::gpuquerysets::{GpuQuerySet, GpuQuerySetContainer};
+use crate::resources::postprocesssettings::{
+    BloomSettings, ChromaticAberrationSettings, ColorGradingSettings, FxaaSettings,
+    VignetteSettings,
+};
+use crate::resources::shadererrors::ShaderErrors;
 use crate::resources::surfacetexture::SurfaceTexture;
 use crate::ui::debugstats::DebugStats;
 use crate::ui::gpustats::Passes;
 use crate::ui::menu::Menu;
+use crate::ui::postprocessing::PostProcessingWindow;
 use crate::ui::scriptingconsole::ScriptingConsole;
+use crate::ui::shadererrors::ShaderErrorsWindow;
 use crate::ui::UiComponent;
 use crate::{
     renderer::state::State, BindGroupContainer, BindingResourceContainer, BufferTypes,
@@ -36,6 +43,14 @@ impl<'a> System<'a> for RenderUIPass {
         Write<'a, Menu>,
         Write<'a, ScriptingConsole>,
         WriteExpect<'a, GpuQuerySetContainer>,
+        (
+            WriteExpect<'a, BloomSettings>,
+            WriteExpect<'a, FxaaSettings>,
+            WriteExpect<'a, VignetteSettings>,
+            WriteExpect<'a, ChromaticAberrationSettings>,
+            WriteExpect<'a, ColorGradingSettings>,
+        ),
+        Read<'a, ShaderErrors>,
     );
 
     fn run(
@@ -53,6 +68,8 @@ impl<'a> System<'a> for RenderUIPass {
             mut menu_ui,
             mut console,
             mut query_sets,
+            (mut bloom, mut fxaa, mut vignette, mut chromatic_aberration, mut color_grading),
+            shader_errors,
         ): Self::SystemData,
     ) {
         let encoder = command_encoder.get_encoder();
@@ -163,6 +180,22 @@ impl<'a> System<'a> for RenderUIPass {
         if menu_ui.show_scripting_console {
             console.show(&egui_container.context, &mut true);
         }
+        if menu_ui.show_post_processing_window {
+            PostProcessingWindow {
+                bloom: &mut bloom,
+                fxaa: &mut fxaa,
+                vignette: &mut vignette,
+                chromatic_aberration: &mut chromatic_aberration,
+                color_grading: &mut color_grading,
+            }
+            .show(&egui_container.context, &mut true);
+        }
+        if !shader_errors.errors.is_empty() {
+            ShaderErrorsWindow {
+                shader_errors: &shader_errors,
+            }
+            .show(&egui_container.context, &mut true);
+        }
         menu_ui.show(&egui_container.context, &mut true);
         let output = egui_container.context.end_frame();
         let paint_jobs = egui_container.context.tessellate(output.shapes);
@@ -182,10 +215,7 @@ impl<'a> System<'a> for RenderUIPass {
             .remove_textures(output.textures_delta)
             .unwrap();
         egui_render_pass.update_buffers(&state.device, &state.queue, &paint_jobs, &screen_desc);
-        let output = surface_texture.texture.take().unwrap();
-        let color_attachment = output
-            .texture
-            .create_view(&wgpu::TextureViewDescriptor::default());
+        let color_attachment = surface_texture.view.take().unwrap();
         let mut horizon_render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             label: Some("Ui render pass"),
             color_attachments: &[wgpu::RenderPassColorAttachment {
@@ -223,6 +253,5 @@ impl<'a> System<'a> for RenderUIPass {
         drop(horizon_render_pass);
 
         command_encoder.finish(&state.device, &state.queue);
-        output.present();
     }
 }
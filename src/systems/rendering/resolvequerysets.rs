@@ -70,10 +70,15 @@ impl<'a> System<'a> for ResolveQuerySets {
             let pass_data = match key {
                 Passes::ShadowPassWithCascade(i) => format!("Shadow cascade #{}", i),
                 Passes::GBuffer => String::from("G Buffer "),
+                Passes::FrustumCulling => String::from("Frustum culling"),
                 Passes::LightCulling => String::from("Light culling"),
+                Passes::AmbientOcclusion => String::from("Ambient occlusion"),
                 Passes::Forward => String::from("Forward"),
                 Passes::Collision => String::from("Collision"),
                 Passes::Skybox => String::from("Skybox"),
+                Passes::Transparent => String::from("Transparent"),
+                Passes::LuminanceHistogram => String::from("Luminance histogram"),
+                Passes::ToneMapping => String::from("Tone mapping"),
                 Passes::Ui => String::from("Ui"),
             };
             let timestamp_data = timestamp_queries[*values as usize];
@@ -3,6 +3,8 @@ use specs::{Join, ReadExpect, ReadStorage, System, WriteExpect};
 
 use crate::components::transform::Transform;
 use crate::renderer::primitives::uniforms::{LightCullingUniforms, SkyboxUniform};
+use crate::resources::shadowatlas::ShadowAtlas;
+use crate::resources::shadowsettings::ShadowSettings;
 use crate::{
     renderer::{
         primitives::{
@@ -30,6 +32,8 @@ impl<'a> System<'a> for UpdateBuffers {
         ReadStorage<'a, PointLight>,
         ReadStorage<'a, Transform>,
         ReadStorage<'a, SpotLight>,
+        ReadExpect<'a, ShadowSettings>,
+        WriteExpect<'a, ShadowAtlas>,
     );
 
     fn run(
@@ -44,6 +48,8 @@ impl<'a> System<'a> for UpdateBuffers {
             point_lights,
             transforms,
             spot_lights,
+            shadow_settings,
+            mut shadow_atlas,
         ): Self::SystemData,
     ) {
         globals.update_view_proj_matrix(&cam, &proj);
@@ -81,23 +87,79 @@ impl<'a> System<'a> for UpdateBuffers {
             0,
             bytemuck::bytes_of(&dir_light.to_raw()),
         );
-        let point_light_raw = (&transforms, &point_lights)
+        let point_lights = (&transforms, &point_lights)
             .join()
-            .map(|(transform, pl)| {
-                if let Some(attached_id) = pl.attached_to {
-                    if let Some(ent) = transforms.get(attached_id) {
-                        pl.to_raw(ent.position)
-                    } else {
-                        pl.to_raw(transform.position)
-                    }
-                } else {
-                    pl.to_raw(transform.position)
-                }
+            .map(|(transform, pl)| (transform.get_world_position(), pl))
+            .collect::<Vec<_>>();
+        let spot_lights = (&transforms, &spot_lights)
+            .join()
+            .map(|(transform, sl)| {
+                (
+                    transform.get_world_position(),
+                    SpotLight::get_direction(&transform.world_matrix),
+                    sl,
+                )
             })
+            .take(State::MAX_SPOT_LIGHTS)
+            .collect::<Vec<_>>();
+
+        // the closest shadow casters get tiles in the shadow atlas, the rest are rendered without shadows.
+        shadow_atlas.clear();
+        let tile_size = shadow_settings.atlas_tile_size();
+        let camera_position = cam.position.coords;
+        let mut point_light_shadows = vec![-1; point_lights.len()];
+        for index in ShadowAtlas::closest_casters(
+            &camera_position,
+            point_lights
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, pl))| pl.casts_shadow)
+                .map(|(index, (position, _))| (index, *position)),
+            shadow_settings.max_point_light_shadows,
+        ) {
+            let (position, pl) = point_lights[index];
+            if let Some(shadow_index) =
+                shadow_atlas.allocate(tile_size, &pl.get_shadow_matrices(position))
+            {
+                point_light_shadows[index] = shadow_index;
+            }
+        }
+        let mut spot_light_shadows = vec![-1; spot_lights.len()];
+        for index in ShadowAtlas::closest_casters(
+            &camera_position,
+            spot_lights
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, _, sl))| sl.casts_shadow)
+                .map(|(index, (position, _, _))| (index, *position)),
+            shadow_settings.max_spot_light_shadows,
+        ) {
+            let (position, direction, sl) = spot_lights[index];
+            if let Some(shadow_index) =
+                shadow_atlas.allocate(tile_size, &[sl.get_shadow_matrix(position, direction)])
+            {
+                spot_light_shadows[index] = shadow_index;
+            }
+        }
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::ShadowAtlasTiles]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&shadow_atlas.to_raw()),
+        );
+
+        let point_light_raw = point_lights
+            .iter()
+            .zip(point_light_shadows)
+            .map(|((position, pl), shadow_index)| pl.to_raw(*position, shadow_index))
             .collect::<Vec<_>>();
         let spot_light_raw = spot_lights
-            .join()
-            .map(SpotLight::to_raw)
+            .iter()
+            .zip(spot_light_shadows)
+            .map(|((position, direction, sl), shadow_index)| {
+                sl.to_raw(*position, *direction, shadow_index)
+            })
             .collect::<Vec<_>>();
         globals.set_point_light_count(point_light_raw.len() as u32);
         globals.set_spot_light_count(spot_light_raw.len() as u32);
@@ -26,7 +26,7 @@ impl<'a> System<'a> for UpdateCamera {
     ) {
         if let Some(target_ent) = camera.follow_target {
             if let Some(transform) = transforms.get(target_ent) {
-                camera.set_follow_target_pos(Point3::from(transform.position))
+                camera.set_follow_target_pos(Point3::from(transform.get_world_position()))
             }
         }
         let dt = delta_time.delta;
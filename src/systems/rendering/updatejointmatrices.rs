@@ -0,0 +1,60 @@
+use rapier3d::na::Matrix4;
+use specs::{Join, ReadExpect, ReadStorage, System, WriteStorage};
+
+use crate::components::skin::Skin;
+use crate::components::transform::Transform;
+use crate::renderer::state::State;
+use crate::resources::bindingresourcecontainer::BindingResourceContainer;
+use crate::BufferTypes;
+
+/// Writes the joint matrices of every skin into a single buffer and stores where each skin's matrices begin.
+pub struct UpdateJointMatrices;
+
+impl<'a> System<'a> for UpdateJointMatrices {
+    type SystemData = (
+        ReadExpect<'a, BindingResourceContainer>,
+        ReadExpect<'a, State>,
+        ReadStorage<'a, Transform>,
+        WriteStorage<'a, Skin>,
+    );
+
+    fn run(
+        &mut self,
+        (binding_resource_container, state, transforms, mut skins): Self::SystemData,
+    ) {
+        let mut joint_matrices: Vec<[[f32; 4]; 4]> = Vec::new();
+        for (skin, transform) in (&mut skins, &transforms).join() {
+            if joint_matrices.len() + skin.joints.len() > State::MAX_JOINT_MATRICES {
+                skin.joint_offset = None;
+                continue;
+            }
+            // the skinned node's own transform is applied in the shader, so it's removed from the joints.
+            let inverse_node_matrix = transform
+                .world_matrix
+                .try_inverse()
+                .unwrap_or_else(Matrix4::identity);
+            skin.joint_offset = Some(joint_matrices.len() as u32);
+            for (joint, inverse_bind_matrix) in
+                skin.joints.iter().zip(skin.inverse_bind_matrices.iter())
+            {
+                let joint_matrix = match joint.and_then(|joint| transforms.get(joint)) {
+                    Some(joint_transform) => {
+                        inverse_node_matrix * joint_transform.world_matrix * inverse_bind_matrix
+                    }
+                    None => Matrix4::identity(),
+                };
+                joint_matrices.push(joint_matrix.into());
+            }
+        }
+        if joint_matrices.is_empty() {
+            return;
+        }
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::JointMatrices]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&joint_matrices),
+        );
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashSet;
+
+use specs::{Entities, Join, ReadExpect, ReadStorage, System, Write, WriteExpect, WriteStorage};
+
+use crate::components::custommaterial::CustomMaterial;
+use crate::components::gltfmodel::{RawMaterial, RawModel};
+use crate::components::materialoverride::MaterialOverride;
+use crate::renderer::model::HorizonModel;
+use crate::renderer::pipelines::gbufferpipeline::GBufferPipeline;
+use crate::renderer::pipelines::materialpipeline::MaterialPipeline;
+use crate::renderer::primitives::materialdefinition::MaterialDefinition;
+use crate::renderer::state::State;
+use crate::resources::defaulttexturecontainer::DefaultTextureContainer;
+use crate::resources::materiallibrary::MaterialLibrary;
+use crate::resources::shadererrors::{ShaderError, ShaderErrors};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::resources::shaderhotreload::ShaderHotReload;
+
+/// Builds the pipelines of the registered materials, creates the bind groups of the models using them
+/// and writes the parameters which changed.
+/// Also uploads the glTF materials changed by scripts and creates the materials of the entities overriding them.
+pub struct UpdateMaterials;
+
+impl UpdateMaterials {
+    /// On native the shader is validated first, so its errors point at the material's snippet.
+    fn build(
+        state: &State,
+        definition: &MaterialDefinition,
+        uniform_layout: &wgpu::BindGroupLayout,
+        revision: u32,
+    ) -> Result<MaterialPipeline, Vec<ShaderError>> {
+        let shader = definition.shader_source(MaterialPipeline::template().as_str());
+        let file_name = definition.shader_name();
+        let error = |message: String| {
+            vec![ShaderError {
+                file_name: file_name.clone(),
+                location: None,
+                message,
+            }]
+        };
+        let create = || {
+            MaterialPipeline::new(
+                &state.device,
+                &state.queue,
+                definition,
+                uniform_layout,
+                shader.source.as_str(),
+                revision,
+            )
+        };
+        #[cfg(not(target_arch = "wasm32"))]
+        let pipeline = {
+            let errors = ShaderHotReload::validate(file_name.as_str(), &shader);
+            if !errors.is_empty() {
+                return Err(errors);
+            }
+            ShaderHotReload::try_create(&state.device, create).map_err(error)?
+        };
+        #[cfg(target_arch = "wasm32")]
+        let pipeline = create();
+        pipeline.map_err(|e| error(e.to_string()))
+    }
+}
+
+impl<'a> System<'a> for UpdateMaterials {
+    type SystemData = (
+        ReadExpect<'a, State>,
+        ReadExpect<'a, GBufferPipeline>,
+        WriteExpect<'a, MaterialLibrary>,
+        Write<'a, ShaderErrors>,
+        WriteStorage<'a, CustomMaterial>,
+        ReadExpect<'a, DefaultTextureContainer>,
+        WriteStorage<'a, RawModel>,
+        ReadStorage<'a, HorizonModel>,
+        Entities<'a>,
+        WriteStorage<'a, MaterialOverride>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            state,
+            gbuffer_pipeline,
+            mut material_library,
+            mut shader_errors,
+            mut custom_materials,
+            default_textures,
+            mut raw_models,
+            horizon_models,
+            entities,
+            mut material_overrides,
+        ): Self::SystemData,
+    ) {
+        let pending = material_library.take_pending();
+        if !pending.is_empty() {
+            let uniform_layout = gbuffer_pipeline.0.get_bind_group_layout(0);
+            for name in pending {
+                let revision = material_library.next_revision();
+                let definition = match material_library.definition(name.as_str()) {
+                    Some(definition) => definition,
+                    None => continue,
+                };
+                let file_name = definition.shader_name();
+                let pipeline = match Self::build(&state, definition, &uniform_layout, revision) {
+                    Ok(pipeline) => {
+                        shader_errors.set_errors(file_name.as_str(), Vec::new());
+                        Some(pipeline)
+                    }
+                    Err(errors) => {
+                        log::warn!(target: "materials", "failed to build material {}", name);
+                        shader_errors.set_errors(file_name.as_str(), errors);
+                        None
+                    }
+                };
+                material_library.set_pipeline(name.as_str(), pipeline);
+            }
+        }
+
+        for material in (&mut custom_materials).join() {
+            let (definition, pipeline) = match (
+                material_library.definition(material.name.as_str()),
+                material_library.pipeline(material.name.as_str()),
+            ) {
+                (Some(definition), Some(pipeline)) => (definition, pipeline),
+                _ => {
+                    material.bind_group = None;
+                    material.pipeline_revision = None;
+                    continue;
+                }
+            };
+            if material.pipeline_revision != Some(pipeline.revision) {
+                let buffer = state.device.create_buffer(&wgpu::BufferDescriptor {
+                    label: Some("material parameters"),
+                    size: pipeline.uniform_size,
+                    usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+                    mapped_at_creation: false,
+                });
+                material.bind_group = Some(pipeline.create_bind_group(&state.device, &buffer));
+                material.buffer = Some(buffer);
+                material.pipeline_revision = Some(pipeline.revision);
+                material.dirty = true;
+            }
+            if material.dirty {
+                if let Some(buffer) = material.buffer.as_ref() {
+                    state.queue.write_buffer(
+                        buffer,
+                        0,
+                        bytemuck::cast_slice(&definition.pack_parameters(&material.parameters)),
+                    );
+                }
+                material.dirty = false;
+            }
+        }
+
+        let mut changed_models = HashSet::new();
+        for (model_ent, raw_model, horizon_model) in
+            (&entities, &mut raw_models, &horizon_models).join()
+        {
+            let RawModel {
+                materials,
+                textures,
+                ..
+            } = raw_model;
+            for (index, raw_material) in materials.iter_mut() {
+                if !raw_material.uniform_dirty && !raw_material.textures_dirty {
+                    continue;
+                }
+                let material = match horizon_model.materials.get(index) {
+                    Some(material) => material,
+                    None => continue,
+                };
+                changed_models.insert(model_ent);
+                if raw_material.textures_dirty {
+                    material.upload_material_textures_to_gpu(
+                        &state.device,
+                        &state.queue,
+                        &horizon_model.textures,
+                        textures,
+                    );
+                    raw_material.bind_group_container = material.register_bind_group(
+                        &state.device,
+                        textures,
+                        &default_textures.elements,
+                        &raw_material.uniform_buffer,
+                    );
+                }
+                if raw_material.uniform_dirty {
+                    state.queue.write_buffer(
+                        &raw_material.uniform_buffer,
+                        0,
+                        bytemuck::bytes_of(&material.to_raw_material()),
+                    );
+                }
+                raw_material.transparent = material.is_transparent();
+                raw_material.uniform_dirty = false;
+                raw_material.textures_dirty = false;
+            }
+        }
+
+        // overrides are made from the model's materials, so they follow its changes too.
+        for material_override in (&mut material_overrides).join() {
+            if changed_models.contains(&material_override.model) {
+                material_override.textures_dirty = true;
+            }
+            if !material_override.uniform_dirty && !material_override.textures_dirty {
+                continue;
+            }
+            let (raw_model, horizon_model) = match (
+                raw_models.get_mut(material_override.model),
+                horizon_models.get(material_override.model),
+            ) {
+                (Some(raw_model), Some(horizon_model)) => (raw_model, horizon_model),
+                _ => continue,
+            };
+            for (index, model_material) in horizon_model.materials.iter() {
+                let material = material_override.apply(model_material);
+                if material_override.textures_dirty {
+                    material.upload_material_textures_to_gpu(
+                        &state.device,
+                        &state.queue,
+                        &horizon_model.textures,
+                        &mut raw_model.textures,
+                    );
+                    let uniform_buffer = material.create_uniform_buffer(&state.device);
+                    let bind_group_container = material.register_bind_group(
+                        &state.device,
+                        &raw_model.textures,
+                        &default_textures.elements,
+                        &uniform_buffer,
+                    );
+                    material_override.materials.insert(
+                        *index,
+                        RawMaterial::new(
+                            bind_group_container,
+                            uniform_buffer,
+                            material.is_transparent(),
+                        ),
+                    );
+                } else if let Some(raw_material) = material_override.materials.get(index) {
+                    state.queue.write_buffer(
+                        &raw_material.uniform_buffer,
+                        0,
+                        bytemuck::bytes_of(&material.to_raw_material()),
+                    );
+                }
+            }
+            material_override.uniform_dirty = false;
+            material_override.textures_dirty = false;
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use specs::{Join, ReadExpect, System, WriteStorage};
+
+use crate::components::morphweights::MorphWeights;
+use crate::renderer::state::State;
+use crate::resources::bindingresourcecontainer::BindingResourceContainer;
+use crate::BufferTypes;
+
+/// Writes the morph target weights of every node into a single buffer and stores where each node's weights begin.
+pub struct UpdateMorphWeights;
+
+impl<'a> System<'a> for UpdateMorphWeights {
+    type SystemData = (
+        ReadExpect<'a, BindingResourceContainer>,
+        ReadExpect<'a, State>,
+        WriteStorage<'a, MorphWeights>,
+    );
+
+    fn run(&mut self, (binding_resource_container, state, mut morph_weights): Self::SystemData) {
+        let mut weights: Vec<f32> = Vec::new();
+        for node_weights in (&mut morph_weights).join() {
+            if weights.len() + node_weights.weights.len() > State::MAX_MORPH_WEIGHTS {
+                node_weights.weight_offset = None;
+                continue;
+            }
+            node_weights.weight_offset = Some(weights.len() as u32);
+            weights.extend_from_slice(&node_weights.weights);
+        }
+        if weights.is_empty() {
+            return;
+        }
+        state.queue.write_buffer(
+            binding_resource_container.buffers[BufferTypes::MorphWeights]
+                .as_ref()
+                .unwrap(),
+            0,
+            bytemuck::cast_slice(&weights),
+        );
+    }
+}
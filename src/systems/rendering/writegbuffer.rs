@@ -1,14 +1,17 @@
-use specs::{Entities, Join, ReadExpect, ReadStorage, System, WriteExpect};
-use wgpu::{BufferAddress, LoadOp, PipelineStatisticsTypes, QueryType};
+use specs::{Join, ReadExpect, ReadStorage, System, WriteExpect};
+use wgpu::{LoadOp, PipelineStatisticsTypes, QueryType};
 
+use crate::components::custommaterial::CustomMaterial;
 use crate::components::gltfmodel::DrawModel;
+use crate::components::materialoverride::MaterialOverride;
+use crate::resources::frustumculling::{FrustumCulling, IndirectDraw};
 use crate::resources::gpuquerysets::{
     GpuQuerySet, GpuQuerySetContainer, PipelineStatisticsQueries, TimestampQueries,
 };
+use crate::resources::materiallibrary::MaterialLibrary;
 use crate::ui::gpustats::Passes;
 use crate::TextureViewTypes::DeferredSpecular;
 use crate::{
-    components::transform::{Transform, TransformRaw},
     renderer::{
         bindgroupcontainer::BindGroupContainer, bindgroups::uniforms::UniformBindGroup,
         pipelines::gbufferpipeline::GBufferPipeline, state::State,
@@ -16,7 +19,7 @@ use crate::{
     resources::{
         bindingresourcecontainer::BindingResourceContainer, commandencoder::HorizonCommandEncoder,
     },
-    DeferredAlbedo, DeferredNormals, DeferredPosition, EguiContainer, Instances, Normals, RawModel,
+    BufferTypes, DeferredAlbedo, DeferredNormals, DeferredPosition, EguiContainer, RawModel,
 };
 
 pub struct WriteGBuffer;
@@ -28,11 +31,13 @@ impl<'a> System<'a> for WriteGBuffer {
         WriteExpect<'a, HorizonCommandEncoder>,
         ReadStorage<'a, UniformBindGroup>,
         ReadStorage<'a, BindGroupContainer>,
-        ReadStorage<'a, Transform>,
         ReadStorage<'a, RawModel>,
         ReadExpect<'a, GBufferPipeline>,
-        Entities<'a>,
         WriteExpect<'a, GpuQuerySetContainer>,
+        ReadExpect<'a, FrustumCulling>,
+        ReadExpect<'a, MaterialLibrary>,
+        ReadStorage<'a, CustomMaterial>,
+        ReadStorage<'a, MaterialOverride>,
     );
 
     fn run(
@@ -43,11 +48,13 @@ impl<'a> System<'a> for WriteGBuffer {
             mut encoder,
             uniform_bind_group,
             bind_group_container,
-            transforms,
             models,
             gbuffer_pipeline,
-            entities,
             mut query_sets,
+            frustum_culling,
+            material_library,
+            custom_materials,
+            material_overrides,
         ): Self::SystemData,
     ) {
         let cmd_encoder = encoder.get_encoder();
@@ -131,7 +138,6 @@ impl<'a> System<'a> for WriteGBuffer {
             .unwrap();
         render_pass.set_bind_group(0, &uniform_bind_group_container.bind_group, &[]);
         render_pass.set_pipeline(&gbuffer_pipeline.0);
-        let mut begin_instance_index: u32 = 0;
         if let Some(ref query_set) = query_sets.container {
             render_pass
                 .write_timestamp(&query_set.timestamp_queries, query_set.next_query_index * 2);
@@ -141,43 +147,46 @@ impl<'a> System<'a> for WriteGBuffer {
             );
         }
 
-        for (model, model_ent) in (&models, &*entities).join() {
-            let mut instance_buffer: Vec<TransformRaw> = Vec::new();
-            for transform in transforms.join() {
-                if let Some(model) = transform.model {
-                    if model_ent == model {
-                        instance_buffer.push(transform.to_raw());
-                    }
-                }
+        let indirect_buffer = binding_resource_container.buffers[BufferTypes::IndirectDraws]
+            .as_ref()
+            .unwrap();
+        // instances whose custom material isn't built yet keep their glTF materials.
+        let custom_material = |draw: &IndirectDraw| {
+            custom_materials
+                .get(draw.material_entity?)
+                .and_then(|material: &CustomMaterial| {
+                    let pipeline = material_library.pipeline(material.name.as_str())?;
+                    Some((pipeline, material.bind_group.as_ref()?))
+                })
+        };
+        for draw in &frustum_culling.draws {
+            if custom_material(draw).is_some() {
+                continue;
+            }
+            if let Some(model) = models.get(draw.model) {
+                render_pass.draw_model_mesh_indirect(
+                    model,
+                    draw.mesh_index,
+                    draw.material_override
+                        .and_then(|entity| material_overrides.get(entity)),
+                    indirect_buffer,
+                    frustum_culling.indirect_offset(FrustumCulling::CAMERA_VIEW, draw),
+                );
+            }
+        }
+        for draw in &frustum_culling.draws {
+            if let (Some(model), Some((pipeline, bind_group))) =
+                (models.get(draw.model), custom_material(draw))
+            {
+                render_pass.set_pipeline(&pipeline.pipeline);
+                render_pass.draw_model_mesh_indirect_with_material(
+                    model,
+                    draw.mesh_index,
+                    bind_group,
+                    indirect_buffer,
+                    frustum_culling.indirect_offset(FrustumCulling::CAMERA_VIEW, draw),
+                );
             }
-
-            state.queue.write_buffer(
-                binding_resource_container.buffers[Instances]
-                    .as_ref()
-                    .unwrap(),
-                (std::mem::size_of::<TransformRaw>() * begin_instance_index as usize)
-                    as BufferAddress,
-                bytemuck::cast_slice(&instance_buffer),
-            );
-
-            let normal_matrices = instance_buffer
-                .iter()
-                .map(TransformRaw::get_normal_matrix)
-                .collect::<Vec<_>>();
-            state.queue.write_buffer(
-                binding_resource_container.buffers[Normals]
-                    .as_ref()
-                    .unwrap(),
-                (std::mem::size_of::<TransformRaw>() * begin_instance_index as usize)
-                    as BufferAddress,
-                bytemuck::cast_slice(&normal_matrices),
-            );
-
-            render_pass.draw_model_instanced(
-                model,
-                begin_instance_index..begin_instance_index + instance_buffer.len() as u32,
-            );
-            begin_instance_index += instance_buffer.len() as u32;
         }
         if let Some(ref mut query_set) = query_sets.container {
             render_pass.write_timestamp(
@@ -0,0 +1,146 @@
+use std::collections::HashMap;
+use std::mem::Discriminant;
+
+use specs::{Entities, Entity, Join, Read, ReadStorage, System, Write, WriteStorage};
+
+use crate::components::animator::{AnimationPlayback, Animator};
+use crate::components::gltfmodel::{ChannelSample, GltfAnimation};
+use crate::components::morphweights::MorphWeights;
+use crate::components::transform::Transform;
+use crate::renderer::model::HorizonModel;
+use crate::resources::deltatime::DeltaTime;
+use crate::resources::finishedanimations::FinishedAnimations;
+
+/// Advances the animators and writes the sampled keyframes into the transforms of the animated nodes.
+pub struct UpdateAnimations;
+
+impl<'a> System<'a> for UpdateAnimations {
+    type SystemData = (
+        Entities<'a>,
+        Read<'a, DeltaTime>,
+        ReadStorage<'a, HorizonModel>,
+        WriteStorage<'a, Animator>,
+        WriteStorage<'a, Transform>,
+        WriteStorage<'a, MorphWeights>,
+        Write<'a, FinishedAnimations>,
+    );
+
+    fn run(
+        &mut self,
+        (
+            entities,
+            delta_time,
+            models,
+            mut animators,
+            mut transforms,
+            mut morph_weights,
+            mut finished,
+        ): Self::SystemData,
+    ) {
+        for (instance, animator) in (&entities, &mut animators).join() {
+            let model = match models.get(animator.model) {
+                Some(model) => model,
+                None => continue,
+            };
+            if animator.previous.is_some() {
+                animator.blend_time += delta_time.delta;
+                if animator.blend_time >= animator.blend_duration {
+                    animator.previous = None;
+                }
+            }
+            let weight = animator.blend_weight();
+            let mut previous_pose = HashMap::new();
+            if let Some(previous) = animator.previous.as_mut() {
+                if let Some(animation) = model.animations.get(previous.animation) {
+                    previous.advance(delta_time.delta, animation.duration);
+                    previous_pose = Self::sample_pose(animation, previous);
+                }
+            }
+            let mut current_pose = HashMap::new();
+            if let Some(current) = animator.current.as_mut() {
+                if let Some(animation) = model.animations.get(current.animation) {
+                    if current.advance(delta_time.delta, animation.duration) {
+                        finished.events.push((instance, animation.name.clone()));
+                    }
+                    current_pose = Self::sample_pose(animation, current);
+                }
+            }
+            // both poses are sampled and blended, a property only one clip animates is blended with the node's own value.
+            for (key, previous_sample) in previous_pose.iter() {
+                if !current_pose.contains_key(key) {
+                    let rest = Self::rest_sample(model, key.0, previous_sample);
+                    current_pose.insert(*key, rest);
+                }
+            }
+            for (key, sample) in current_pose.iter() {
+                let sample = match previous_pose.get(key) {
+                    Some(previous_sample) if weight < 1.0 => previous_sample.lerp(sample, weight),
+                    None if weight < 1.0 => {
+                        Self::rest_sample(model, key.0, sample).lerp(sample, weight)
+                    }
+                    _ => sample.clone(),
+                };
+                if let Some(node_ent) = animator.nodes.get(&key.0) {
+                    Self::apply(sample, *node_ent, &mut transforms, &mut morph_weights);
+                }
+            }
+        }
+    }
+}
+
+impl UpdateAnimations {
+    /// The value of every node property the clip animates at its playback position.
+    fn sample_pose(
+        animation: &GltfAnimation,
+        playback: &AnimationPlayback,
+    ) -> HashMap<(usize, Discriminant<ChannelSample>), ChannelSample> {
+        animation
+            .channels
+            .iter()
+            .filter_map(|channel| {
+                let sample = channel.sample(playback.time)?;
+                Some(((channel.node, std::mem::discriminant(&sample)), sample))
+            })
+            .collect()
+    }
+    /// The node's own value of the sampled property, the pose it has when it isn't animated.
+    fn rest_sample(model: &HorizonModel, node: usize, sample: &ChannelSample) -> ChannelSample {
+        let node = &model.nodes[node];
+        match sample {
+            ChannelSample::Translation(_) => ChannelSample::Translation(node.translation),
+            ChannelSample::Rotation(_) => ChannelSample::Rotation(node.rotation),
+            ChannelSample::Scale(_) => ChannelSample::Scale(node.scale),
+            ChannelSample::Weights(weights) => ChannelSample::Weights(
+                node.weights
+                    .clone()
+                    .or_else(|| node.mesh.map(|mesh| model.meshes[mesh].weights.clone()))
+                    .unwrap_or_else(|| vec![0.0; weights.len()]),
+            ),
+        }
+    }
+    fn apply(
+        sample: ChannelSample,
+        node_ent: Entity,
+        transforms: &mut WriteStorage<Transform>,
+        morph_weights: &mut WriteStorage<MorphWeights>,
+    ) {
+        if let ChannelSample::Weights(weights) = sample {
+            if let Some(morph_weights) = morph_weights.get_mut(node_ent) {
+                for (current, target) in morph_weights.weights.iter_mut().zip(weights) {
+                    *current = target;
+                }
+            }
+            return;
+        }
+        let transform = match transforms.get_mut(node_ent) {
+            Some(transform) => transform,
+            None => return,
+        };
+        match sample {
+            ChannelSample::Translation(translation) => transform.set_position(translation),
+            ChannelSample::Rotation(rotation) => transform.set_rotation(rotation),
+            ChannelSample::Scale(scale) => transform.set_scale(scale),
+            ChannelSample::Weights(_) => {}
+        }
+    }
+}
@@ -1,3 +1,6 @@
+use crate::renderer::primitives::uniforms::SSAO_KERNEL_SIZE;
+use crate::resources::ssaosettings::SsaoSettings;
+use crate::resources::tonemappingsettings::{ToneMappingOperator, ToneMappingSettings};
 use crate::ui::{UiComponent, ViewComponent};
 use crate::TextureViewTypes;
 use egui::{Context, TextureId, Ui};
@@ -18,6 +21,22 @@ pub struct DebugStats {
     pub selected_material: usize,
     pub selected_texture: usize,
     pub show_collision_wireframes: bool,
+    /// Tints the lit frame by the shadow cascade every fragment samples.
+    pub show_shadow_cascades: bool,
+    /// Far view space depth of every rendered shadow cascade.
+    pub shadow_cascade_splits: Vec<f32>,
+    /// Tests the camera's instances against the depth pyramid of the previous frame.
+    pub occlusion_culling: bool,
+    /// Instances of the camera's view in the last read back frame.
+    pub frustum_culled_instances: u32,
+    pub occlusion_culled_instances: u32,
+    pub visible_instances: u32,
+    /// Tints the G-buffer's albedo by the level of detail every instance is drawn with.
+    pub show_lod_levels: bool,
+    /// Operator and exposure of the tone mapping pass, scripts can change them too.
+    pub tone_mapping: ToneMappingSettings,
+    /// Screen-space ambient occlusion multiplied into the materials' occlusion.
+    pub ssao: SsaoSettings,
 }
 
 impl UiComponent for DebugStats {
@@ -52,6 +71,64 @@ impl ViewComponent for DebugStats {
                 ));
                 ui.end_row();
                 ui.checkbox(&mut self.show_collision_wireframes, "Show collisions");
+                ui.checkbox(&mut self.show_shadow_cascades, "Show shadow cascades");
+                if self.show_shadow_cascades {
+                    // same order as the tints in the forward shader.
+                    let tints = ["red", "green", "blue", "yellow"];
+                    let mut last_split = 0.0;
+                    for (index, split) in self.shadow_cascade_splits.iter().enumerate() {
+                        ui.label(format!(
+                            "Cascade #{} ({}): {:.2} - {:.2}",
+                            index,
+                            tints[index % tints.len()],
+                            last_split,
+                            split
+                        ));
+                        last_split = *split;
+                    }
+                }
+                ui.checkbox(&mut self.occlusion_culling, "Occlusion culling");
+                ui.label(format!(
+                    "Instances visible: {}, frustum culled: {}, occlusion culled: {}",
+                    self.visible_instances,
+                    self.frustum_culled_instances,
+                    self.occlusion_culled_instances
+                ));
+                ui.checkbox(&mut self.show_lod_levels, "Show LOD levels");
+                if self.show_lod_levels {
+                    // same order as the tints in the gbuffer shader.
+                    ui.label("LOD 0: red, LOD 1: green, LOD 2: blue, LOD 3: yellow");
+                }
+                ui.separator();
+                egui::ComboBox::from_label("Tone mapping")
+                    .selected_text(self.tone_mapping.operator.name())
+                    .show_ui(ui, |ui| {
+                        for operator in ToneMappingOperator::ALL {
+                            ui.selectable_value(
+                                &mut self.tone_mapping.operator,
+                                operator,
+                                operator.name(),
+                            );
+                        }
+                    });
+                ui.checkbox(&mut self.tone_mapping.auto_exposure, "Auto exposure");
+                ui.add(
+                    egui::Slider::new(&mut self.tone_mapping.exposure, -8.0..=8.0)
+                        .text("Exposure (stops)"),
+                );
+                ui.separator();
+                ui.checkbox(&mut self.ssao.enabled, "Ambient occlusion");
+                if self.ssao.enabled {
+                    ui.add(egui::Slider::new(&mut self.ssao.radius, 0.05..=2.0).text("Radius"));
+                    ui.add(egui::Slider::new(&mut self.ssao.bias, 0.0..=0.1).text("Bias"));
+                    ui.add(
+                        egui::Slider::new(&mut self.ssao.intensity, 0.5..=4.0).text("Intensity"),
+                    );
+                    ui.add(
+                        egui::Slider::new(&mut self.ssao.sample_count, 1..=SSAO_KERNEL_SIZE as u32)
+                            .text("Samples"),
+                    );
+                }
                 ui.separator();
                 egui::ComboBox::from_label("Select debug texture!")
                     .selected_text(format!("{:?}", &self.selected_texture_name))
@@ -81,6 +158,11 @@ impl ViewComponent for DebugStats {
                             TextureViewTypes::Shadow,
                             "Shadow texture View",
                         );
+                        ui.selectable_value(
+                            &mut self.selected_texture_name,
+                            TextureViewTypes::AmbientOcclusionBlurred,
+                            "Ambient occlusion Texture View",
+                        );
                     });
                 if let Some(tex_id) = self.texture_id {
                     ui.image(tex_id, egui::Vec2::new(480.0, 320.0));
@@ -10,10 +10,15 @@
 pub enum Passes {
     ShadowPassWithCascade(u32),
     GBuffer,
+    FrustumCulling,
     LightCulling,
+    AmbientOcclusion,
     Forward,
     Collision,
     Skybox,
+    Transparent,
+    LuminanceHistogram,
+    ToneMapping,
     Ui,
 }
 // impl ViewComponent for GpuStats {
@@ -6,6 +6,9 @@ pub struct Menu {
     pub window_should_close: bool,
     pub show_debug_window: bool,
     pub show_scripting_console: bool,
+    pub show_post_processing_window: bool,
+    /// Only has an effect on native, the web build embeds its shaders.
+    pub hot_reload_shaders: bool,
 }
 
 impl ViewComponent for Menu {
@@ -19,6 +22,13 @@ impl ViewComponent for Menu {
             ui.menu_button("View", |ui| {
                 ui.checkbox(&mut self.show_scripting_console, "Show Scripting console");
                 ui.checkbox(&mut self.show_debug_window, "Show debug window");
+                ui.checkbox(
+                    &mut self.show_post_processing_window,
+                    "Show post processing",
+                );
+                if cfg!(not(target_arch = "wasm32")) {
+                    ui.checkbox(&mut self.hot_reload_shaders, "Hot reload shaders");
+                }
             });
         });
     }
@@ -1,7 +1,9 @@
 pub mod debugstats;
 pub mod gpustats;
 pub mod menu;
+pub mod postprocessing;
 pub mod scriptingconsole;
+pub mod shadererrors;
 
 /// Something that can show some ui
 pub trait UiComponent {
@@ -0,0 +1,78 @@
+use egui::{Context, Ui};
+
+use crate::resources::postprocesssettings::{
+    BloomSettings, ChromaticAberrationSettings, ColorGradingSettings, FxaaSettings,
+    VignetteSettings,
+};
+use crate::ui::{UiComponent, ViewComponent};
+
+/// Toggles and tunes the post-processing effects, borrows their settings for the frame.
+pub struct PostProcessingWindow<'a> {
+    pub bloom: &'a mut BloomSettings,
+    pub fxaa: &'a mut FxaaSettings,
+    pub vignette: &'a mut VignetteSettings,
+    pub chromatic_aberration: &'a mut ChromaticAberrationSettings,
+    pub color_grading: &'a mut ColorGradingSettings,
+}
+
+impl<'a> ViewComponent for PostProcessingWindow<'a> {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.checkbox(&mut self.bloom.enabled, "Bloom");
+        if self.bloom.enabled {
+            ui.add(egui::Slider::new(&mut self.bloom.threshold, 0.0..=10.0).text("Threshold"));
+            ui.add(egui::Slider::new(&mut self.bloom.knee, 0.0..=1.0).text("Knee"));
+            ui.add(egui::Slider::new(&mut self.bloom.intensity, 0.0..=1.0).text("Intensity"));
+        }
+        ui.separator();
+        ui.checkbox(&mut self.color_grading.enabled, "Color grading");
+        if self.color_grading.enabled {
+            ui.add(egui::Slider::new(&mut self.color_grading.strength, 0.0..=1.0).text("Strength"));
+            let size = self.color_grading.lut().size;
+            ui.label(format!("LUT size: {}x{}x{}", size, size, size));
+        }
+        ui.separator();
+        ui.checkbox(&mut self.fxaa.enabled, "FXAA");
+        if self.fxaa.enabled {
+            ui.add(egui::Slider::new(&mut self.fxaa.span_max, 1.0..=16.0).text("Span max"));
+            ui.add(
+                egui::Slider::new(&mut self.fxaa.reduce_min, 0.0..=0.1)
+                    .logarithmic(true)
+                    .text("Reduce min"),
+            );
+            ui.add(egui::Slider::new(&mut self.fxaa.reduce_mul, 0.0..=0.5).text("Reduce mul"));
+        }
+        ui.separator();
+        ui.checkbox(
+            &mut self.chromatic_aberration.enabled,
+            "Chromatic aberration",
+        );
+        if self.chromatic_aberration.enabled {
+            ui.add(
+                egui::Slider::new(&mut self.chromatic_aberration.intensity, 0.0..=0.05)
+                    .text("Intensity"),
+            );
+        }
+        ui.separator();
+        ui.checkbox(&mut self.vignette.enabled, "Vignette");
+        if self.vignette.enabled {
+            ui.add(egui::Slider::new(&mut self.vignette.intensity, 0.0..=1.0).text("Intensity"));
+            ui.add(egui::Slider::new(&mut self.vignette.radius, 0.0..=1.5).text("Radius"));
+            ui.add(egui::Slider::new(&mut self.vignette.smoothness, 0.0..=1.0).text("Smoothness"));
+        }
+    }
+}
+
+impl<'a> UiComponent for PostProcessingWindow<'a> {
+    fn name(&self) -> &'static str {
+        "Post processing"
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .collapsible(true)
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.ui(ui);
+            });
+    }
+}
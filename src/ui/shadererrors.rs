@@ -0,0 +1,47 @@
+use egui::{Context, Ui};
+
+use crate::resources::shadererrors::ShaderErrors;
+use crate::ui::{UiComponent, ViewComponent};
+
+/// Lists the errors of the reloaded shaders, shown while there are any.
+pub struct ShaderErrorsWindow<'a> {
+    pub shader_errors: &'a ShaderErrors,
+}
+
+impl<'a> ViewComponent for ShaderErrorsWindow<'a> {
+    fn ui(&mut self, ui: &mut Ui) {
+        ui.label("The last working version of these shaders is still in use.");
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for error in self.shader_errors.errors.iter() {
+                ui.separator();
+                let heading = match &error.location {
+                    Some((source_file, line, column)) if *source_file == error.file_name => {
+                        format!("{}:{}:{}", source_file, line, column)
+                    }
+                    Some((source_file, line, column)) => format!(
+                        "{}:{}:{} (included by {})",
+                        source_file, line, column, error.file_name
+                    ),
+                    None => error.file_name.clone(),
+                };
+                ui.strong(heading);
+                ui.monospace(error.message.as_str());
+            }
+        });
+    }
+}
+
+impl<'a> UiComponent for ShaderErrorsWindow<'a> {
+    fn name(&self) -> &'static str {
+        "Shader errors"
+    }
+
+    fn show(&mut self, ctx: &Context, open: &mut bool) {
+        egui::Window::new(self.name())
+            .collapsible(true)
+            .resizable(true)
+            .show(ctx, |ui| {
+                self.ui(ui);
+            });
+    }
+}
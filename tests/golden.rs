@@ -0,0 +1,180 @@
+//! Golden image tests for the render passes.
+//! The scene is rendered headlessly (falling back to a software adapter) and every pass target
+//! is compared against the reference images in `tests/golden`.
+//! A missing reference fails the test, set `HORIZON_UPDATE_GOLDEN=1` to write the references from the current output.
+//! The test is ignored until the references are committed, run it with `cargo test --test golden -- --ignored`.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use horizon::CaptureTarget;
+use image::RgbaImage;
+use std::path::{Path, PathBuf};
+
+const WIDTH: u32 = 320;
+const HEIGHT: u32 = 240;
+/// Per channel difference which is still considered a match, software rasterizers differ slightly from gpus.
+const CHANNEL_TOLERANCE: u8 = 8;
+/// Ratio of mismatching pixels allowed before a pass is reported.
+const PIXEL_TOLERANCE: f64 = 0.005;
+
+struct PassDiff {
+    name: String,
+    mismatched_pixels: usize,
+    total_pixels: usize,
+    max_channel_diff: u8,
+}
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn diff_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("target")
+        .join("golden-diffs")
+}
+
+fn compare(name: &str, actual: &RgbaImage, expected: &RgbaImage) -> Option<PassDiff> {
+    if actual.dimensions() != expected.dimensions() {
+        return Some(PassDiff {
+            name: format!(
+                "{} (size {:?} instead of {:?})",
+                name,
+                actual.dimensions(),
+                expected.dimensions()
+            ),
+            mismatched_pixels: (actual.width() * actual.height()) as usize,
+            total_pixels: (actual.width() * actual.height()) as usize,
+            max_channel_diff: 255,
+        });
+    }
+    let mut diff_image = RgbaImage::new(actual.width(), actual.height());
+    let mut mismatched_pixels = 0;
+    let mut max_channel_diff = 0;
+    for (x, y, actual_pixel) in actual.enumerate_pixels() {
+        let expected_pixel = expected.get_pixel(x, y);
+        let channel_diff = actual_pixel
+            .0
+            .iter()
+            .zip(expected_pixel.0.iter())
+            .map(|(a, e)| (*a as i16 - *e as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap();
+        max_channel_diff = max_channel_diff.max(channel_diff);
+        if channel_diff > CHANNEL_TOLERANCE {
+            mismatched_pixels += 1;
+            diff_image.put_pixel(x, y, image::Rgba([255, 0, 255, 255]));
+        } else {
+            let gray = expected_pixel.0[0] / 4 + expected_pixel.0[1] / 4 + expected_pixel.0[2] / 4;
+            diff_image.put_pixel(x, y, image::Rgba([gray, gray, gray, 255]));
+        }
+    }
+    let total_pixels = (actual.width() * actual.height()) as usize;
+    if mismatched_pixels as f64 / total_pixels as f64 <= PIXEL_TOLERANCE {
+        return None;
+    }
+    std::fs::create_dir_all(diff_dir()).unwrap();
+    actual
+        .save(diff_dir().join(format!("{}.actual.png", name)))
+        .unwrap();
+    diff_image
+        .save(diff_dir().join(format!("{}.diff.png", name)))
+        .unwrap();
+    Some(PassDiff {
+        name: name.to_string(),
+        mismatched_pixels,
+        total_pixels,
+        max_channel_diff,
+    })
+}
+
+fn setup_scene() {
+    futures::executor::block_on(horizon::setup_headless(WIDTH, HEIGHT));
+    let scene_path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("assets")
+        .join("golden_scene.gltf");
+    let model = futures::executor::block_on(horizon::load_model_headless(
+        scene_path.to_str().unwrap(),
+    ))
+    .unwrap();
+    horizon::create_entity_headless(&format!(
+        r#"{{"transform":{{"componentType":"transform","position":{{"x":0.0,"y":0.0,"z":0.0}},"rotation":{{"x":0.0,"y":0.0,"z":0.0}},"scale":{{"x":1.0,"y":1.0,"z":1.0}},"model":{}}},"components":[]}}"#,
+        model
+    ))
+    .unwrap();
+    for (position, color) in [("1.0,1.0,1.0", "1.0,0.8,0.6"), ("-1.0,0.5,-1.0", "0.2,0.4,1.0")] {
+        let position: Vec<&str> = position.split(',').collect();
+        let color: Vec<&str> = color.split(',').collect();
+        horizon::create_entity_headless(&format!(
+            r#"{{"transform":{{"componentType":"transform","position":{{"x":{},"y":{},"z":{}}},"rotation":{{"x":0.0,"y":0.0,"z":0.0}},"scale":{{"x":1.0,"y":1.0,"z":1.0}}}},"components":[{{"componentType":"pointLight","radius":5.0,"color":{{"x":{},"y":{},"z":{}}}}}]}}"#,
+            position[0], position[1], position[2], color[0], color[1], color[2]
+        ))
+        .unwrap();
+    }
+}
+
+#[test]
+#[ignore = "the golden images in tests/golden aren't committed yet"]
+fn render_passes_match_golden_images() {
+    setup_scene();
+    horizon::keep_headless_pass_outputs(true);
+    // the first frame handles the initial resize, the second one renders with the final targets.
+    horizon::render_headless_frame();
+    horizon::render_headless_frame();
+
+    let targets = [
+        ("shadow_cascade_0", CaptureTarget::ShadowCascade(0)),
+        ("gbuffer_position", CaptureTarget::DeferredPosition),
+        ("gbuffer_normals", CaptureTarget::DeferredNormals),
+        ("gbuffer_specular", CaptureTarget::DeferredSpecular),
+        ("gbuffer_albedo", CaptureTarget::DeferredAlbedo),
+        ("light_culling_tiles", CaptureTarget::LightCullingTiles),
+        // the hdr scene before the skybox is drawn into it.
+        ("forward", CaptureTarget::Forward),
+        ("skybox", CaptureTarget::Skybox),
+        // tone mapped and post processed with the ui on top.
+        ("frame", CaptureTarget::Frame),
+    ];
+    let update_references = std::env::var("HORIZON_UPDATE_GOLDEN").is_ok();
+    let mut diffs = Vec::new();
+    let mut missing_references = Vec::new();
+    for (name, target) in targets {
+        let actual = horizon::capture_headless_target(target).unwrap();
+        let reference_path = golden_dir().join(format!("{}.png", name));
+        if update_references {
+            std::fs::create_dir_all(golden_dir()).unwrap();
+            actual.save(&reference_path).unwrap();
+            continue;
+        }
+        if !reference_path.exists() {
+            missing_references.push(reference_path);
+            continue;
+        }
+        let expected = image::open(&reference_path).unwrap().to_rgba8();
+        if let Some(diff) = compare(name, &actual, &expected) {
+            diffs.push(diff);
+        }
+    }
+    assert!(
+        missing_references.is_empty(),
+        "missing golden images {:?}, run the test with HORIZON_UPDATE_GOLDEN=1 to write them",
+        missing_references
+    );
+    let report = diffs
+        .iter()
+        .map(|diff| {
+            format!(
+                "{}: {}/{} pixels differ (max channel difference {})",
+                diff.name, diff.mismatched_pixels, diff.total_pixels, diff.max_channel_diff
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    assert!(
+        diffs.is_empty(),
+        "render passes differ from the golden images, see {:?}\n{}",
+        diff_dir(),
+        report
+    );
+}